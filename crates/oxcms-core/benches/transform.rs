@@ -351,6 +351,52 @@ fn bench_rgb8_conversion(c: &mut Criterion) {
     group.finish();
 }
 
+// ============================================================================
+// CMYK LUT Benchmarks
+// ============================================================================
+
+fn bench_cmyk8_lut(c: &mut Criterion) {
+    use oxcms_core::pipeline::{ClutData, LutPipeline, Pipeline, TransformContext};
+
+    let mut group = c.benchmark_group("cmyk8_lut");
+
+    let source = LutPipeline::from_clut(ClutData::sample(4, 17, 3, |p| {
+        (0..3).map(|i| (1.0 - p[i]) * (1.0 - p[3])).collect()
+    }));
+    let direct = Pipeline::ChainedLut {
+        source,
+        destination: LutPipeline::identity(3, 3),
+    };
+    let precalculated = direct.precalculate(&TransformContext::new()).unwrap();
+
+    for pixel_count in [1000, 10000].iter() {
+        let src: Vec<u8> = (0..pixel_count * 4)
+            .map(|i| ((i * 37) % 256) as u8)
+            .collect();
+        let mut dst = vec![0u8; pixel_count * 3];
+
+        group.throughput(Throughput::Elements(*pixel_count as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("direct", pixel_count),
+            pixel_count,
+            |b, _| b.iter(|| direct.transform_cmyk8_to_rgb8(black_box(&src), black_box(&mut dst))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("precalculated", pixel_count),
+            pixel_count,
+            |b, _| {
+                b.iter(|| {
+                    precalculated.transform_cmyk8_to_rgb8(black_box(&src), black_box(&mut dst))
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_matrix_single,
@@ -361,6 +407,7 @@ criterion_group!(
     bench_lut1d,
     bench_rgb8_batch_transform,
     bench_rgb8_conversion,
+    bench_cmyk8_lut,
 );
 
 criterion_main!(benches);
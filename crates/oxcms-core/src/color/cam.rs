@@ -0,0 +1,514 @@
+//! CIECAM02 and CAM16 Color Appearance Models
+//!
+//! Appearance models predict how a stimulus looks under given viewing
+//! conditions, so a color can be carried between environments as different
+//! as a dim cinema and a bright office while keeping its appearance.
+//!
+//! - J: lightness, Q: brightness
+//! - C: chroma, M: colorfulness, s: saturation
+//! - h: hue angle in degrees
+//!
+//! XYZ values use the crate-wide scale where the white has Y = 1.
+//!
+//! References:
+//! - CIE 159:2004, A colour appearance model for colour management systems
+//! - Li et al., Comprehensive color solutions: CAM16, CAT16, and CAM16-UCS (2017)
+
+use crate::color::Xyz;
+use crate::math::Matrix3x3;
+use crate::math::chromatic_adaptation::{CAT02_XYZ_TO_LMS, CAT16_XYZ_TO_LMS, degree_of_adaptation};
+
+/// Hunt–Pointer–Estévez matrix used by CIECAM02's nonlinear stage
+const HPE_XYZ_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [0.38971, 0.68898, -0.07868],
+    [-0.22981, 1.18340, 0.04641],
+    [0.00000, 0.00000, 1.00000],
+]);
+
+/// Largest post-adaptation cone response the inverse accepts, just below
+/// the asymptote of the compression
+const MAX_RESPONSE: f64 = 399.99;
+
+/// Which appearance model to evaluate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CamModel {
+    /// CIECAM02 (CAT02 adaptation, Hunt–Pointer–Estévez cone space)
+    Ciecam02,
+    /// CAM16 (CAT16 for both adaptation and the nonlinear stage)
+    #[default]
+    Cam16,
+}
+
+/// Surround of the viewing field relative to the adopted white
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Surround {
+    /// Surface colors, surround brighter than 20% of white
+    #[default]
+    Average,
+    /// Television or monitor viewing, surround 0–20% of white
+    Dim,
+    /// Projection in a dark room
+    Dark,
+}
+
+impl Surround {
+    /// The (F, c, Nc) parameters of the surround
+    fn parameters(self) -> (f64, f64, f64) {
+        match self {
+            Surround::Average => (1.0, 0.69, 1.0),
+            Surround::Dim => (0.9, 0.59, 0.9),
+            Surround::Dark => (0.8, 0.525, 0.8),
+        }
+    }
+}
+
+/// Viewing conditions of an appearance model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewingConditions {
+    /// Adopted white (Y = 1)
+    pub white: Xyz,
+    /// Luminance of the adapting field in cd/m² (L_A)
+    pub adapting_luminance: f64,
+    /// Relative luminance of the background, 0–100 (Y_b)
+    pub background_luminance: f64,
+    /// Surround of the viewing field
+    pub surround: Surround,
+    /// Assume full adaptation to the white (D = 1), as for reflective prints
+    pub discounting: bool,
+}
+
+impl ViewingConditions {
+    /// Viewing conditions for a white, with the sRGB reference environment
+    ///
+    /// L_A is 64 lux / π / 5 ≈ 4.07 cd/m², Y_b is 20 and the surround average.
+    pub fn new(white: Xyz) -> Self {
+        Self {
+            white,
+            adapting_luminance: 64.0 / std::f64::consts::PI / 5.0,
+            background_luminance: 20.0,
+            surround: Surround::Average,
+            discounting: false,
+        }
+    }
+
+    /// Set the adapting luminance in cd/m²
+    pub fn with_adapting_luminance(mut self, la: f64) -> Self {
+        self.adapting_luminance = la;
+        self
+    }
+
+    /// Set the relative background luminance (0–100)
+    pub fn with_background_luminance(mut self, yb: f64) -> Self {
+        self.background_luminance = yb;
+        self
+    }
+
+    /// Set the surround
+    pub fn with_surround(mut self, surround: Surround) -> Self {
+        self.surround = surround;
+        self
+    }
+
+    /// Discount the illuminant (full adaptation)
+    pub fn with_discounting(mut self, discounting: bool) -> Self {
+        self.discounting = discounting;
+        self
+    }
+}
+
+/// Appearance correlates of a color
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CamAppearance {
+    /// Lightness J
+    pub j: f64,
+    /// Chroma C
+    pub c: f64,
+    /// Hue angle h in degrees (0–360)
+    pub h: f64,
+    /// Brightness Q
+    pub q: f64,
+    /// Colorfulness M
+    pub m: f64,
+    /// Saturation s
+    pub s: f64,
+}
+
+/// CAM16-UCS (or CAM02-UCS) uniform coordinates J'a'b'
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Cam16Ucs {
+    /// Uniform lightness J'
+    pub j: f64,
+    /// Red-green coordinate a'
+    pub a: f64,
+    /// Yellow-blue coordinate b'
+    pub b: f64,
+}
+
+impl Cam16Ucs {
+    /// Euclidean distance, the UCS color difference ΔE'
+    pub fn distance(&self, other: &Self) -> f64 {
+        ((self.j - other.j).powi(2) + (self.a - other.a).powi(2) + (self.b - other.b).powi(2))
+            .sqrt()
+    }
+}
+
+/// An appearance model initialized for one set of viewing conditions
+///
+/// Like lcms2's `cmsCIECAM02Init`, everything that depends only on the
+/// viewing conditions is computed once here.
+#[derive(Debug, Clone)]
+pub struct ColorAppearanceModel {
+    model: CamModel,
+    conditions: ViewingConditions,
+    /// XYZ → adaptation space
+    cat: Matrix3x3,
+    /// Adaptation space → XYZ
+    cat_inv: Matrix3x3,
+    /// Adapted adaptation space → nonlinear cone space
+    to_cone: Matrix3x3,
+    /// Nonlinear cone space → adapted adaptation space
+    from_cone: Matrix3x3,
+    /// Per-channel von Kries gains including the degree of adaptation
+    d_rgb: [f64; 3],
+    fl: f64,
+    n: f64,
+    z: f64,
+    nbb: f64,
+    ncb: f64,
+    c: f64,
+    nc: f64,
+    aw: f64,
+}
+
+impl ColorAppearanceModel {
+    /// Initialize a model for the given viewing conditions
+    pub fn new(model: CamModel, conditions: ViewingConditions) -> Self {
+        let cat = match model {
+            CamModel::Ciecam02 => CAT02_XYZ_TO_LMS,
+            CamModel::Cam16 => CAT16_XYZ_TO_LMS,
+        };
+        let cat_inv = cat.inverse().unwrap_or_else(Matrix3x3::identity);
+        let (to_cone, from_cone) = match model {
+            CamModel::Ciecam02 => {
+                let m = HPE_XYZ_TO_LMS.multiply(&cat_inv);
+                (m, m.inverse().unwrap_or_else(Matrix3x3::identity))
+            }
+            CamModel::Cam16 => (Matrix3x3::identity(), Matrix3x3::identity()),
+        };
+
+        let (f, c, nc) = conditions.surround.parameters();
+        let la = conditions.adapting_luminance;
+        let white = scale100(conditions.white);
+
+        let k = 1.0 / (5.0 * la + 1.0);
+        let k4 = k.powi(4);
+        let fl = 0.2 * k4 * (5.0 * la) + 0.1 * (1.0 - k4).powi(2) * (5.0 * la).cbrt();
+
+        let n = conditions.background_luminance / white[1];
+        let z = 1.48 + n.sqrt();
+        let nbb = 0.725 * n.powf(-0.2);
+
+        let d = if conditions.discounting {
+            1.0
+        } else {
+            degree_of_adaptation(la, f)
+        };
+
+        let rgb_w = cat.multiply_vec(white);
+        let d_rgb = rgb_w.map(|v| d * white[1] / v + 1.0 - d);
+
+        let mut cam = Self {
+            model,
+            conditions,
+            cat,
+            cat_inv,
+            to_cone,
+            from_cone,
+            d_rgb,
+            fl,
+            n,
+            z,
+            nbb,
+            ncb: nbb,
+            c,
+            nc,
+            aw: 0.0,
+        };
+        let rgb_aw = cam.adapted_response(white);
+        cam.aw = cam.achromatic(rgb_aw);
+        cam
+    }
+
+    /// CIECAM02 for the given viewing conditions
+    pub fn ciecam02(conditions: ViewingConditions) -> Self {
+        Self::new(CamModel::Ciecam02, conditions)
+    }
+
+    /// CAM16 for the given viewing conditions
+    pub fn cam16(conditions: ViewingConditions) -> Self {
+        Self::new(CamModel::Cam16, conditions)
+    }
+
+    /// The model being evaluated
+    pub fn model(&self) -> CamModel {
+        self.model
+    }
+
+    /// The viewing conditions the model was initialized with
+    pub fn conditions(&self) -> &ViewingConditions {
+        &self.conditions
+    }
+
+    /// Appearance correlates of an XYZ color (Y = 1 scale)
+    pub fn forward(&self, xyz: Xyz) -> CamAppearance {
+        let [ra, ga, ba] = self.adapted_response(scale100(xyz));
+
+        let a = ra - 12.0 * ga / 11.0 + ba / 11.0;
+        let b = (ra + ga - 2.0 * ba) / 9.0;
+        let h = b.atan2(a).to_degrees().rem_euclid(360.0);
+
+        let et = eccentricity(h);
+        let achromatic = self.achromatic([ra, ga, ba]);
+        let j = 100.0 * (achromatic / self.aw).max(0.0).powf(self.c * self.z);
+        let q = (4.0 / self.c) * (j / 100.0).sqrt() * (self.aw + 4.0) * self.fl.powf(0.25);
+
+        let t =
+            (50000.0 / 13.0 * self.nc * self.ncb * et * a.hypot(b)) / (ra + ga + 21.0 / 20.0 * ba);
+        let chroma =
+            t.max(0.0).powf(0.9) * (j / 100.0).sqrt() * (1.64 - 0.29f64.powf(self.n)).powf(0.73);
+        let m = chroma * self.fl.powf(0.25);
+        let s = if q > 0.0 { 100.0 * (m / q).sqrt() } else { 0.0 };
+
+        CamAppearance {
+            j,
+            c: chroma,
+            h,
+            q,
+            m,
+            s,
+        }
+    }
+
+    /// XYZ color (Y = 1 scale) with the given lightness, chroma and hue
+    ///
+    /// Only J, C and h are read; the other correlates follow from them.
+    pub fn inverse(&self, appearance: &CamAppearance) -> Xyz {
+        let CamAppearance {
+            j, c: chroma, h, ..
+        } = *appearance;
+        if j <= 0.0 {
+            return Xyz::new(0.0, 0.0, 0.0);
+        }
+
+        let t = (chroma / ((j / 100.0).sqrt() * (1.64 - 0.29f64.powf(self.n)).powf(0.73)))
+            .powf(1.0 / 0.9);
+        let et = eccentricity(h);
+        let achromatic = self.aw * (j / 100.0).powf(1.0 / (self.c * self.z));
+
+        let p2 = achromatic / self.nbb + 0.305;
+        let p3 = 21.0 / 20.0;
+        let (sin_h, cos_h) = h.to_radians().sin_cos();
+
+        let (a, b) = if t <= 0.0 {
+            (0.0, 0.0)
+        } else {
+            let p1 = (50000.0 / 13.0 * self.nc * self.ncb) * et / t;
+            if sin_h.abs() >= cos_h.abs() {
+                let p4 = p1 / sin_h;
+                let b = p2 * (2.0 + p3) * (460.0 / 1403.0)
+                    / (p4 + (2.0 + p3) * (220.0 / 1403.0) * (cos_h / sin_h) - 27.0 / 1403.0
+                        + p3 * (6300.0 / 1403.0));
+                (b * cos_h / sin_h, b)
+            } else {
+                let p5 = p1 / cos_h;
+                let a = p2 * (2.0 + p3) * (460.0 / 1403.0)
+                    / (p5 + (2.0 + p3) * (220.0 / 1403.0)
+                        - (27.0 / 1403.0 - p3 * (6300.0 / 1403.0)) * (sin_h / cos_h));
+                (a, a * sin_h / cos_h)
+            }
+        };
+
+        let ra = (460.0 * p2 + 451.0 * a + 288.0 * b) / 1403.0;
+        let ga = (460.0 * p2 - 891.0 * a - 261.0 * b) / 1403.0;
+        let ba = (460.0 * p2 - 220.0 * a - 6300.0 * b) / 1403.0;
+
+        let cone = [ra, ga, ba].map(|v| self.uncompress(v));
+        let adapted = self.from_cone.multiply_vec(cone);
+        let rgb = [
+            adapted[0] / self.d_rgb[0],
+            adapted[1] / self.d_rgb[1],
+            adapted[2] / self.d_rgb[2],
+        ];
+        let [x, y, z] = self.cat_inv.multiply_vec(rgb);
+        Xyz::new(x / 100.0, y / 100.0, z / 100.0)
+    }
+
+    /// CAM16-UCS coordinates of an XYZ color
+    pub fn to_ucs(&self, xyz: Xyz) -> Cam16Ucs {
+        let appearance = self.forward(xyz);
+        let m = (1.0 + 0.0228 * appearance.m).ln() / 0.0228;
+        let (sin_h, cos_h) = appearance.h.to_radians().sin_cos();
+        Cam16Ucs {
+            j: 1.7 * appearance.j / (1.0 + 0.007 * appearance.j),
+            a: m * cos_h,
+            b: m * sin_h,
+        }
+    }
+
+    /// XYZ color of CAM16-UCS coordinates
+    pub fn from_ucs(&self, ucs: &Cam16Ucs) -> Xyz {
+        let j = ucs.j / (1.7 - 0.007 * ucs.j);
+        let m = ((ucs.a.hypot(ucs.b) * 0.0228).exp() - 1.0) / 0.0228;
+        let h = ucs.b.atan2(ucs.a).to_degrees().rem_euclid(360.0);
+        self.inverse(&CamAppearance {
+            j,
+            c: m / self.fl.powf(0.25),
+            h,
+            ..Default::default()
+        })
+    }
+
+    /// Carry a color seen under this model's conditions to another's
+    ///
+    /// The result has the same J, C and h under `target` as `xyz` has here.
+    pub fn match_appearance(&self, xyz: Xyz, target: &ColorAppearanceModel) -> Xyz {
+        target.inverse(&self.forward(xyz))
+    }
+
+    /// Adapted, compressed cone responses of XYZ on the 0–100 scale
+    fn adapted_response(&self, xyz: [f64; 3]) -> [f64; 3] {
+        let rgb = self.cat.multiply_vec(xyz);
+        let adapted = [
+            rgb[0] * self.d_rgb[0],
+            rgb[1] * self.d_rgb[1],
+            rgb[2] * self.d_rgb[2],
+        ];
+        self.to_cone.multiply_vec(adapted).map(|v| self.compress(v))
+    }
+
+    /// Post-adaptation nonlinear response compression
+    fn compress(&self, v: f64) -> f64 {
+        let x = (self.fl * v.abs() / 100.0).powf(0.42);
+        v.signum() * 400.0 * x / (x + 27.13) + 0.1
+    }
+
+    /// Inverse of [`Self::compress`]
+    ///
+    /// Responses at or beyond the ±400 asymptote, which out-of-range
+    /// correlates can ask for, are clamped just inside it.
+    fn uncompress(&self, v: f64) -> f64 {
+        let x = (v - 0.1).clamp(-MAX_RESPONSE, MAX_RESPONSE);
+        let base = (27.13 * x.abs() / (400.0 - x.abs())).max(0.0);
+        x.signum() * 100.0 / self.fl * base.powf(1.0 / 0.42)
+    }
+
+    /// Achromatic response A
+    fn achromatic(&self, [ra, ga, ba]: [f64; 3]) -> f64 {
+        (2.0 * ra + ga + ba / 20.0 - 0.305) * self.nbb
+    }
+}
+
+/// Eccentricity factor e_t of a hue in degrees
+fn eccentricity(h: f64) -> f64 {
+    0.25 * ((h.to_radians() + 2.0).cos() + 3.8)
+}
+
+/// XYZ on the 0–100 scale used by the model equations
+fn scale100(xyz: Xyz) -> [f64; 3] {
+    [xyz.x * 100.0, xyz.y * 100.0, xyz.z * 100.0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference_conditions() -> ViewingConditions {
+        ViewingConditions::new(Xyz::new(0.9505, 1.0, 1.0888))
+            .with_adapting_luminance(318.31)
+            .with_background_luminance(20.0)
+    }
+
+    #[test]
+    fn test_ciecam02_reference() {
+        let cam = ColorAppearanceModel::ciecam02(reference_conditions());
+        let jch = cam.forward(Xyz::new(0.1901, 0.2000, 0.2178));
+        assert!((jch.j - 41.7311).abs() < 1e-3, "J = {}", jch.j);
+        assert!((jch.c - 0.1047).abs() < 1e-3, "C = {}", jch.c);
+        assert!((jch.h - 219.0484).abs() < 0.1, "h = {}", jch.h);
+        assert!((jch.q - 195.3713).abs() < 1e-2, "Q = {}", jch.q);
+        assert!((jch.s - 2.3592).abs() < 1e-2, "s = {}", jch.s);
+    }
+
+    #[test]
+    fn test_cam16_reference() {
+        let cam = ColorAppearanceModel::cam16(reference_conditions());
+        let jch = cam.forward(Xyz::new(0.1901, 0.2000, 0.2178));
+        assert!((jch.j - 41.7312).abs() < 1e-3, "J = {}", jch.j);
+        assert!((jch.c - 0.1034).abs() < 1e-3, "C = {}", jch.c);
+        assert!((jch.h - 217.0680).abs() < 0.1, "h = {}", jch.h);
+        assert!((jch.m - 0.1074).abs() < 1e-3, "M = {}", jch.m);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let samples = [
+            Xyz::new(0.1901, 0.2000, 0.2178),
+            Xyz::new(0.4124, 0.2126, 0.0193),
+            Xyz::new(0.3576, 0.7152, 0.1192),
+            Xyz::new(0.1805, 0.0722, 0.9505),
+            Xyz::new(0.9505, 1.0, 1.0888),
+        ];
+        for model in [CamModel::Ciecam02, CamModel::Cam16] {
+            for surround in [Surround::Average, Surround::Dim, Surround::Dark] {
+                let vc = reference_conditions().with_surround(surround);
+                let cam = ColorAppearanceModel::new(model, vc);
+                for xyz in samples {
+                    let back = cam.inverse(&cam.forward(xyz));
+                    assert!(
+                        back.approx_eq(&xyz, 1e-6),
+                        "{:?}: {:?} vs {:?}",
+                        model,
+                        xyz,
+                        back
+                    );
+                    let back = cam.from_ucs(&cam.to_ucs(xyz));
+                    assert!(back.approx_eq(&xyz, 1e-6), "UCS {:?}: {:?}", model, back);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_uncompress_past_asymptote() {
+        let cam = ColorAppearanceModel::cam16(reference_conditions());
+        for v in [400.1, 1e6, f64::INFINITY] {
+            let up = cam.uncompress(v);
+            assert!(up.is_finite() && up > 0.0, "{}: {}", v, up);
+            assert_eq!(cam.uncompress(0.2 - v), -up);
+        }
+        assert_eq!(cam.uncompress(400.1), cam.uncompress(MAX_RESPONSE + 0.1));
+    }
+
+    #[test]
+    fn test_white_is_achromatic() {
+        let vc = reference_conditions().with_discounting(true);
+        let jch = ColorAppearanceModel::cam16(vc).forward(vc.white);
+        assert!((jch.j - 100.0).abs() < 1e-9);
+        assert!(jch.c < 1e-6, "C = {}", jch.c);
+    }
+
+    #[test]
+    fn test_dark_surround_needs_more_contrast() {
+        let xyz = Xyz::new(0.1901, 0.2000, 0.2178);
+        let bright = ColorAppearanceModel::cam16(reference_conditions());
+        let dark = ColorAppearanceModel::cam16(
+            reference_conditions()
+                .with_surround(Surround::Dark)
+                .with_adapting_luminance(10.0),
+        );
+        let matched = bright.match_appearance(xyz, &dark);
+        // Same J in a dark room takes a darker stimulus
+        assert!(matched.y < xyz.y, "{:?}", matched);
+        assert!((dark.forward(matched).j - bright.forward(xyz).j).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,412 @@
+//! Color Difference Metrics
+//!
+//! ΔE formulas for comparing two colors:
+//! - CIE76: Euclidean distance in L*a*b*
+//! - CIE94: graphic arts and textiles weightings
+//! - CMC l:c (BS 6923)
+//! - CIEDE2000, with parametric kL/kC/kH
+//! - ΔE_ITP (ITU-R BT.2124) for HDR content in ICtCp
+//! - CAM16-UCS ΔE'
+//!
+//! CIE94 and CMC are asymmetric: the first argument is the reference.
+//!
+//! References:
+//! - Sharma, Wu & Dalal, The CIEDE2000 color-difference formula:
+//!   implementation notes, supplementary test data, and mathematical
+//!   observations (2005)
+//! - Lindbloom: http://www.brucelindbloom.com/index.html?ColorDifferenceCalc.html
+
+use crate::color::{Cam16Ucs, Lab};
+
+/// Weightings of the CIE94 formula
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Cie94Application {
+    /// kL = 1, K1 = 0.045, K2 = 0.015
+    #[default]
+    GraphicArts,
+    /// kL = 2, K1 = 0.048, K2 = 0.014
+    Textiles,
+}
+
+/// A color difference formula over L*a*b*
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DeltaEMetric {
+    /// CIE76
+    Cie76,
+    /// CIE94
+    Cie94(Cie94Application),
+    /// CMC l:c, usually 2:1 for acceptability and 1:1 for perceptibility
+    Cmc {
+        /// Lightness weight l
+        l: f64,
+        /// Chroma weight c
+        c: f64,
+    },
+    /// CIEDE2000 with kL = kC = kH = 1
+    #[default]
+    Ciede2000,
+}
+
+impl DeltaEMetric {
+    /// The difference between a reference and a sample color
+    pub fn compute(&self, reference: Lab, sample: Lab) -> f64 {
+        match *self {
+            DeltaEMetric::Cie76 => delta_e_76(reference, sample),
+            DeltaEMetric::Cie94(application) => delta_e_94(reference, sample, application),
+            DeltaEMetric::Cmc { l, c } => delta_e_cmc(reference, sample, l, c),
+            DeltaEMetric::Ciede2000 => delta_e_2000(reference, sample),
+        }
+    }
+}
+
+/// Calculate CIE76 color difference (Euclidean distance in L*a*b*)
+pub fn delta_e_76(lab1: Lab, lab2: Lab) -> f64 {
+    ((lab1.l - lab2.l).powi(2) + (lab1.a - lab2.a).powi(2) + (lab1.b - lab2.b).powi(2)).sqrt()
+}
+
+/// Calculate CIE94 color difference
+pub fn delta_e_94(reference: Lab, sample: Lab, application: Cie94Application) -> f64 {
+    let (k_l, k1, k2) = match application {
+        Cie94Application::GraphicArts => (1.0, 0.045, 0.015),
+        Cie94Application::Textiles => (2.0, 0.048, 0.014),
+    };
+
+    let c1 = reference.chroma();
+    let c2 = sample.chroma();
+    let delta_l = reference.l - sample.l;
+    let delta_c = c1 - c2;
+    let delta_h_sq = ((reference.a - sample.a).powi(2) + (reference.b - sample.b).powi(2)
+        - delta_c * delta_c)
+        .max(0.0);
+
+    let s_c = 1.0 + k1 * c1;
+    let s_h = 1.0 + k2 * c1;
+
+    ((delta_l / k_l).powi(2) + (delta_c / s_c).powi(2) + delta_h_sq / (s_h * s_h)).sqrt()
+}
+
+/// Calculate CMC l:c color difference
+pub fn delta_e_cmc(reference: Lab, sample: Lab, l: f64, c: f64) -> f64 {
+    let c1 = reference.chroma();
+    let c2 = sample.chroma();
+    let delta_l = reference.l - sample.l;
+    let delta_c = c1 - c2;
+    let delta_h_sq = ((reference.a - sample.a).powi(2) + (reference.b - sample.b).powi(2)
+        - delta_c * delta_c)
+        .max(0.0);
+
+    let h1 = reference.hue_degrees();
+    let s_l = if reference.l < 16.0 {
+        0.511
+    } else {
+        0.040975 * reference.l / (1.0 + 0.01765 * reference.l)
+    };
+    let s_c = 0.0638 * c1 / (1.0 + 0.0131 * c1) + 0.638;
+    let c1_4 = c1.powi(4);
+    let f = (c1_4 / (c1_4 + 1900.0)).sqrt();
+    let t = if (164.0..=345.0).contains(&h1) {
+        0.56 + (0.2 * (h1 + 168.0).to_radians().cos()).abs()
+    } else {
+        0.36 + (0.4 * (h1 + 35.0).to_radians().cos()).abs()
+    };
+    let s_h = s_c * (f * t + 1.0 - f);
+
+    ((delta_l / (l * s_l)).powi(2) + (delta_c / (c * s_c)).powi(2) + delta_h_sq / (s_h * s_h))
+        .sqrt()
+}
+
+/// Calculate CIEDE2000 color difference
+///
+/// This is the industry-standard color difference formula.
+/// A difference of 1.0 is approximately the just-noticeable difference.
+pub fn delta_e_2000(lab1: Lab, lab2: Lab) -> f64 {
+    delta_e_2000_weighted(lab1, lab2, 1.0, 1.0, 1.0)
+}
+
+/// Calculate CIEDE2000 with parametric weighting factors kL, kC, kH
+///
+/// kL = 2 is common for textiles; the reference conditions use 1:1:1.
+pub fn delta_e_2000_weighted(lab1: Lab, lab2: Lab, k_l: f64, k_c: f64, k_h: f64) -> f64 {
+    // CIEDE2000 implementation
+    // Reference: http://www.brucelindbloom.com/index.html?Eqn_DeltaE_CIE2000.html
+
+    let l1 = lab1.l;
+    let a1 = lab1.a;
+    let b1 = lab1.b;
+    let l2 = lab2.l;
+    let a2 = lab2.a;
+    let b2 = lab2.b;
+
+    // Step 1: Calculate C and h
+    let c1 = (a1 * a1 + b1 * b1).sqrt();
+    let c2 = (a2 * a2 + b2 * b2).sqrt();
+    let c_bar = (c1 + c2) / 2.0;
+
+    let c_bar_7 = c_bar.powi(7);
+    let g = 0.5 * (1.0 - (c_bar_7 / (c_bar_7 + 25.0_f64.powi(7))).sqrt());
+
+    let a1_prime = a1 * (1.0 + g);
+    let a2_prime = a2 * (1.0 + g);
+
+    let c1_prime = (a1_prime * a1_prime + b1 * b1).sqrt();
+    let c2_prime = (a2_prime * a2_prime + b2 * b2).sqrt();
+
+    let h1_prime = if a1_prime == 0.0 && b1 == 0.0 {
+        0.0
+    } else {
+        let h = b1.atan2(a1_prime).to_degrees();
+        if h < 0.0 { h + 360.0 } else { h }
+    };
+
+    let h2_prime = if a2_prime == 0.0 && b2 == 0.0 {
+        0.0
+    } else {
+        let h = b2.atan2(a2_prime).to_degrees();
+        if h < 0.0 { h + 360.0 } else { h }
+    };
+
+    // Step 2: Calculate deltas
+    let delta_l_prime = l2 - l1;
+    let delta_c_prime = c2_prime - c1_prime;
+
+    let delta_h_prime = if c1_prime * c2_prime == 0.0 {
+        0.0
+    } else {
+        let diff = h2_prime - h1_prime;
+        if diff.abs() <= 180.0 {
+            diff
+        } else if diff > 180.0 {
+            diff - 360.0
+        } else {
+            diff + 360.0
+        }
+    };
+
+    let delta_big_h_prime =
+        2.0 * (c1_prime * c2_prime).sqrt() * (delta_h_prime.to_radians() / 2.0).sin();
+
+    // Step 3: Calculate CIEDE2000
+    let l_bar_prime = (l1 + l2) / 2.0;
+    let c_bar_prime = (c1_prime + c2_prime) / 2.0;
+
+    let h_bar_prime = if c1_prime * c2_prime == 0.0 {
+        h1_prime + h2_prime
+    } else if (h1_prime - h2_prime).abs() <= 180.0 {
+        (h1_prime + h2_prime) / 2.0
+    } else if h1_prime + h2_prime < 360.0 {
+        (h1_prime + h2_prime + 360.0) / 2.0
+    } else {
+        (h1_prime + h2_prime - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar_prime - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar_prime).to_radians().cos()
+        + 0.32 * (3.0 * h_bar_prime + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar_prime - 63.0).to_radians().cos();
+
+    let delta_theta = 30.0 * (-((h_bar_prime - 275.0) / 25.0).powi(2)).exp();
+    let c_bar_prime_7 = c_bar_prime.powi(7);
+    let r_c = 2.0 * (c_bar_prime_7 / (c_bar_prime_7 + 25.0_f64.powi(7))).sqrt();
+    let s_l =
+        1.0 + (0.015 * (l_bar_prime - 50.0).powi(2)) / (20.0 + (l_bar_prime - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar_prime;
+    let s_h = 1.0 + 0.015 * c_bar_prime * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let term1 = delta_l_prime / (k_l * s_l);
+    let term2 = delta_c_prime / (k_c * s_c);
+    let term3 = delta_big_h_prime / (k_h * s_h);
+
+    (term1 * term1 + term2 * term2 + term3 * term3 + r_t * term2 * term3).sqrt()
+}
+
+/// Calculate ΔE_ITP (ITU-R BT.2124) from two ICtCp colors
+///
+/// Inputs are `[I, Ct, Cp]` with PQ-encoded intensity; 1.0 is roughly a
+/// just-noticeable difference.
+pub fn delta_e_itp(ictcp1: [f64; 3], ictcp2: [f64; 3]) -> f64 {
+    let delta_i = ictcp1[0] - ictcp2[0];
+    let delta_t = 0.5 * (ictcp1[1] - ictcp2[1]);
+    let delta_p = ictcp1[2] - ictcp2[2];
+    720.0 * (delta_i * delta_i + delta_t * delta_t + delta_p * delta_p).sqrt()
+}
+
+/// Calculate CAM16-UCS color difference ΔE'
+pub fn delta_e_cam16_ucs(ucs1: &Cam16Ucs, ucs2: &Cam16Ucs) -> f64 {
+    ucs1.distance(ucs2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sharma, Wu & Dalal (2005), table 1: L1 a1 b1, L2 a2 b2, ΔE00
+    const SHARMA: [([f64; 3], [f64; 3], f64); 34] = [
+        ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+        ([50.0, 3.1571, -77.2803], [50.0, 0.0, -82.7485], 2.8615),
+        ([50.0, 2.8361, -74.0200], [50.0, 0.0, -82.7485], 3.4412),
+        ([50.0, -1.3802, -84.2814], [50.0, 0.0, -82.7485], 1.0000),
+        ([50.0, -1.1848, -84.8006], [50.0, 0.0, -82.7485], 1.0000),
+        ([50.0, -0.9009, -85.5211], [50.0, 0.0, -82.7485], 1.0000),
+        ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+        ([50.0, -1.0, 2.0], [50.0, 0.0, 0.0], 2.3669),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0009], 7.1792),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0010], 7.1792),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0011], 7.2195),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0012], 7.2195),
+        ([50.0, -0.0010, 2.4900], [50.0, 0.0009, -2.4900], 4.8045),
+        ([50.0, -0.0010, 2.4900], [50.0, 0.0010, -2.4900], 4.8045),
+        ([50.0, -0.0010, 2.4900], [50.0, 0.0011, -2.4900], 4.7461),
+        ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+        ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+        ([50.0, 2.5, 0.0], [61.0, -5.0, 29.0], 22.8977),
+        ([50.0, 2.5, 0.0], [56.0, -27.0, -3.0], 31.9030),
+        ([50.0, 2.5, 0.0], [58.0, 24.0, 15.0], 19.4535),
+        ([50.0, 2.5, 0.0], [50.0, 3.1736, 0.5854], 1.0000),
+        ([50.0, 2.5, 0.0], [50.0, 3.2972, 0.0], 1.0000),
+        ([50.0, 2.5, 0.0], [50.0, 1.8634, 0.5757], 1.0000),
+        ([50.0, 2.5, 0.0], [50.0, 3.2592, 0.3350], 1.0000),
+        (
+            [60.2574, -34.0099, 36.2677],
+            [60.4626, -34.1751, 39.4387],
+            1.2644,
+        ),
+        (
+            [63.0109, -31.0961, -5.8663],
+            [62.8187, -29.7946, -4.0864],
+            1.2630,
+        ),
+        (
+            [61.2901, 3.7196, -5.3901],
+            [61.4292, 2.2480, -4.9620],
+            1.8731,
+        ),
+        (
+            [35.0831, -44.1164, 3.7933],
+            [35.0232, -40.0716, 1.5901],
+            1.8645,
+        ),
+        (
+            [22.7233, 20.0904, -46.6940],
+            [23.0331, 14.9730, -42.5619],
+            2.0373,
+        ),
+        (
+            [36.4612, 47.8580, 18.3852],
+            [36.2715, 50.5065, 21.2231],
+            1.4146,
+        ),
+        (
+            [90.8027, -2.0831, 1.4410],
+            [91.1528, -1.6435, 0.0447],
+            1.4441,
+        ),
+        (
+            [90.9257, -0.5406, -0.9208],
+            [88.6381, -0.8985, -0.7239],
+            1.5381,
+        ),
+        (
+            [6.7747, -0.2908, -2.4247],
+            [5.8714, -0.0985, -2.2286],
+            0.6377,
+        ),
+        (
+            [2.0776, 0.0795, -1.1350],
+            [0.9033, -0.0636, -0.5514],
+            0.9082,
+        ),
+    ];
+
+    #[test]
+    fn test_ciede2000_sharma() {
+        for (i, (lab1, lab2, expected)) in SHARMA.iter().enumerate() {
+            let lab1 = Lab::from_array(*lab1);
+            let lab2 = Lab::from_array(*lab2);
+            let de = delta_e_2000(lab1, lab2);
+            assert!(
+                (de - expected).abs() < 1e-4,
+                "pair {}: {} vs {}",
+                i + 1,
+                de,
+                expected
+            );
+            // Symmetric
+            assert!((delta_e_2000(lab2, lab1) - de).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_ciede2000_weights() {
+        let lab1 = Lab::new(50.0, 0.0, 0.0);
+        let lab2 = Lab::new(60.0, 0.0, 0.0);
+        let de = delta_e_2000(lab1, lab2);
+        let half = delta_e_2000_weighted(lab1, lab2, 2.0, 1.0, 1.0);
+        assert!((half - de / 2.0).abs() < 1e-12);
+    }
+
+    // Reference values from the colour-science package
+    const LAB1: Lab = Lab::new(100.0, 21.57210357, 272.2281935);
+    const LAB2: Lab = Lab::new(100.0, 426.67945353, 72.39590835);
+
+    #[test]
+    fn test_cie76_and_cie94() {
+        assert!((delta_e_76(LAB1, LAB2) - 451.7133019).abs() < 1e-4);
+        let graphic = delta_e_94(LAB1, LAB2, Cie94Application::GraphicArts);
+        assert!((graphic - 83.7792255).abs() < 1e-4, "{}", graphic);
+        let textiles = delta_e_94(LAB1, LAB2, Cie94Application::Textiles);
+        assert!((textiles - 88.3355530).abs() < 1e-4, "{}", textiles);
+    }
+
+    #[test]
+    fn test_cmc() {
+        let de = delta_e_cmc(LAB1, LAB2, 2.0, 1.0);
+        assert!((de - 172.7047712).abs() < 1e-4, "{}", de);
+        assert_eq!(DeltaEMetric::Cmc { l: 2.0, c: 1.0 }.compute(LAB1, LAB2), de);
+        // 1:1 weighs lightness differences more
+        let lab1 = Lab::new(50.0, 10.0, 10.0);
+        let lab2 = Lab::new(52.0, 10.0, 10.0);
+        assert!(delta_e_cmc(lab1, lab2, 1.0, 1.0) > delta_e_cmc(lab1, lab2, 2.0, 1.0));
+    }
+
+    #[test]
+    fn test_itp() {
+        let a = [0.5, 0.01, -0.02];
+        assert_eq!(delta_e_itp(a, a), 0.0);
+        let b = [0.5, 0.03, -0.02];
+        // Ct differences are halved
+        assert!((delta_e_itp(a, b) - 720.0 * 0.01).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cam16_ucs() {
+        use crate::color::Xyz;
+        use crate::color::cam::{ColorAppearanceModel, ViewingConditions};
+
+        // colour-science's delta_E_CAM16UCS example, whose first color is
+        // the CIECAM02 test sample in UCS coordinates (L_A = 318.31 cd/m²,
+        // Y_b = 20, average surround); the UCS formulas are shared
+        let conditions = ViewingConditions::new(Xyz::new(0.9505, 1.0, 1.0888))
+            .with_adapting_luminance(318.31)
+            .with_background_luminance(20.0);
+        let ucs1 =
+            ColorAppearanceModel::ciecam02(conditions).to_ucs(Xyz::new(0.1901, 0.2000, 0.2178));
+        for (got, expected) in
+            [ucs1.j, ucs1.a, ucs1.b]
+                .into_iter()
+                .zip([54.90433134, -0.08450395, -0.06854831])
+        {
+            assert!((got - expected).abs() < 1e-3, "{:?}", ucs1);
+        }
+
+        let ucs2 = Cam16Ucs {
+            j: 54.80352754,
+            a: -3.96940084,
+            b: -13.57591013,
+        };
+        let de = delta_e_cam16_ucs(&ucs1, &ucs2);
+        assert!((de - 14.0555464).abs() < 1e-3, "{}", de);
+        assert_eq!(delta_e_cam16_ucs(&ucs2, &ucs1), de);
+        assert_eq!(delta_e_cam16_ucs(&ucs1, &ucs1), 0.0);
+    }
+}
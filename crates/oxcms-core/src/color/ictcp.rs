@@ -0,0 +1,203 @@
+//! ICtCp Color Space
+//!
+//! ICtCp (ITU-R BT.2100) is the HDR color encoding built from linear BT.2020
+//! RGB: a crosstalk LMS matrix, the PQ nonlinearity, then an opponent
+//! matrix.
+//!
+//! - I: Intensity (PQ encoded, 1.0 = 10000 cd/m²)
+//! - Ct: Tritan axis (blue-yellow)
+//! - Cp: Protan axis (red-green)
+//!
+//! Inputs are relative (Y = 1 for the reference white) and every conversion
+//! takes the luminance of that white in cd/m². Color differences in ICtCp
+//! are measured with [`delta_e_itp`].
+
+use crate::color::Xyz;
+use crate::color::delta_e::delta_e_itp;
+use crate::color::xyz::convert_buffer;
+use crate::math::Matrix3x3;
+use crate::math::gamma::{pq_decode, pq_encode};
+use crate::math::matrix::{BT2020_TO_XYZ, XYZ_TO_BT2020};
+
+/// Luminance PQ is normalized to, in cd/m²
+const PEAK_LUMINANCE: f64 = 10000.0;
+
+/// Linear BT.2020 RGB to LMS
+const RGB_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [1688.0 / 4096.0, 2146.0 / 4096.0, 262.0 / 4096.0],
+    [683.0 / 4096.0, 2951.0 / 4096.0, 462.0 / 4096.0],
+    [99.0 / 4096.0, 309.0 / 4096.0, 3688.0 / 4096.0],
+]);
+
+/// Inverse of RGB_TO_LMS
+const LMS_TO_RGB: Matrix3x3 = Matrix3x3::new([
+    [3.4366066943, -2.5064521187, 0.0698454243],
+    [-0.7913295556, 1.9836004518, -0.1922708962],
+    [-0.0259498997, -0.0989137147, 1.1248636144],
+]);
+
+/// PQ encoded LMS to ICtCp
+const LMS_TO_ICTCP: Matrix3x3 = Matrix3x3::new([
+    [0.5, 0.5, 0.0],
+    [6610.0 / 4096.0, -13613.0 / 4096.0, 7003.0 / 4096.0],
+    [17933.0 / 4096.0, -17390.0 / 4096.0, -543.0 / 4096.0],
+]);
+
+/// Inverse of LMS_TO_ICTCP
+const ICTCP_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [1.0, 0.0086090370, 0.1110296250],
+    [1.0, -0.0086090370, -0.1110296250],
+    [1.0, 0.5600313357, -0.3206271750],
+]);
+
+/// ICtCp color coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Ictcp {
+    /// Intensity (0 to 1)
+    pub i: f64,
+    /// Blue-yellow axis
+    pub ct: f64,
+    /// Red-green axis
+    pub cp: f64,
+}
+
+impl Ictcp {
+    /// Create a new ICtCp color
+    #[inline]
+    pub const fn new(i: f64, ct: f64, cp: f64) -> Self {
+        Self { i, ct, cp }
+    }
+
+    /// Create ICtCp from an array
+    #[inline]
+    pub const fn from_array(arr: [f64; 3]) -> Self {
+        Self {
+            i: arr[0],
+            ct: arr[1],
+            cp: arr[2],
+        }
+    }
+
+    /// Convert to array
+    #[inline]
+    pub const fn to_array(&self) -> [f64; 3] {
+        [self.i, self.ct, self.cp]
+    }
+
+    /// Convert from linear BT.2020 RGB whose white has the given luminance in cd/m²
+    pub fn from_linear_bt2020(rgb: [f64; 3], white_luminance: f64) -> Self {
+        let scale = white_luminance / PEAK_LUMINANCE;
+        let lms = RGB_TO_LMS.multiply_vec(rgb).map(|v| pq_encode(v * scale));
+        Self::from_array(LMS_TO_ICTCP.multiply_vec(lms))
+    }
+
+    /// Convert to linear BT.2020 RGB whose white has the given luminance in cd/m²
+    pub fn to_linear_bt2020(&self, white_luminance: f64) -> [f64; 3] {
+        let scale = PEAK_LUMINANCE / white_luminance;
+        let lms = ICTCP_TO_LMS
+            .multiply_vec(self.to_array())
+            .map(|v| pq_decode(v) * scale);
+        LMS_TO_RGB.multiply_vec(lms)
+    }
+
+    /// Convert from D65 XYZ whose white (Y = 1) has the given luminance in cd/m²
+    pub fn from_xyz(xyz: Xyz, white_luminance: f64) -> Self {
+        Self::from_linear_bt2020(XYZ_TO_BT2020.multiply_vec(xyz.to_array()), white_luminance)
+    }
+
+    /// Convert to D65 XYZ whose white (Y = 1) has the given luminance in cd/m²
+    pub fn to_xyz(&self, white_luminance: f64) -> Xyz {
+        Xyz::from_array(BT2020_TO_XYZ.multiply_vec(self.to_linear_bt2020(white_luminance)))
+    }
+
+    /// ΔE_ITP (ITU-R BT.2124) to another ICtCp color
+    #[inline]
+    pub fn delta_e(&self, other: &Self) -> f64 {
+        delta_e_itp(self.to_array(), other.to_array())
+    }
+
+    /// Check if approximately equal to another ICtCp color
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.i - other.i).abs() < epsilon
+            && (self.ct - other.ct).abs() < epsilon
+            && (self.cp - other.cp).abs() < epsilon
+    }
+}
+
+/// Transform a buffer of D65 XYZ f32 values to ICtCp f32 values
+///
+/// Both buffers should be triplets (length must be divisible by 3)
+pub fn xyz_to_ictcp_buffer(xyz_data: &[f32], ictcp_out: &mut [f32], white_luminance: f64) {
+    convert_buffer(xyz_data, ictcp_out, |xyz| {
+        Ictcp::from_xyz(Xyz::from_array(xyz), white_luminance).to_array()
+    });
+}
+
+/// Transform a buffer of ICtCp f32 values to D65 XYZ f32 values
+pub fn ictcp_to_xyz_buffer(ictcp_data: &[f32], xyz_out: &mut [f32], white_luminance: f64) {
+    convert_buffer(ictcp_data, xyz_out, |ictcp| {
+        Ictcp::from_array(ictcp).to_xyz(white_luminance).to_array()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neutrals_have_no_chroma() {
+        // 100 cd/m² encodes to PQ 0.508 and 10000 cd/m² to 1.0
+        let white = Ictcp::from_linear_bt2020([1.0, 1.0, 1.0], 100.0);
+        assert!(
+            white.approx_eq(&Ictcp::new(0.508_078, 0.0, 0.0), 1e-6),
+            "{:?}",
+            white
+        );
+
+        let peak = Ictcp::from_linear_bt2020([1.0, 1.0, 1.0], 10000.0);
+        assert!(
+            peak.approx_eq(&Ictcp::new(1.0, 0.0, 0.0), 1e-9),
+            "{:?}",
+            peak
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for xyz in [
+            Xyz::new(0.9505, 1.0, 1.089),
+            Xyz::new(0.3, 0.4, 0.2),
+            Xyz::new(0.05, 0.02, 0.4),
+        ] {
+            // Limited by the 7 digit BT.2020 matrices
+            for luminance in [100.0, 203.0, 1000.0] {
+                let back = Ictcp::from_xyz(xyz, luminance).to_xyz(luminance);
+                assert!(xyz.approx_eq(&back, 1e-6), "{:?} vs {:?}", xyz, back);
+            }
+        }
+    }
+
+    #[test]
+    fn test_delta_e() {
+        let a = Ictcp::from_xyz(Xyz::new(0.3, 0.4, 0.2), 100.0);
+        let b = Ictcp::from_xyz(Xyz::new(0.31, 0.4, 0.2), 100.0);
+        assert_eq!(a.delta_e(&a), 0.0);
+        let de = a.delta_e(&b);
+        assert!(de > 0.5 && de < 10.0, "{}", de);
+    }
+
+    #[test]
+    fn test_buffer_transform() {
+        let xyz_in = [0.3f32, 0.4, 0.2, 0.05, 0.02, 0.4];
+        let mut ictcp = [0.0f32; 6];
+        let mut xyz_back = [0.0f32; 6];
+
+        xyz_to_ictcp_buffer(&xyz_in, &mut ictcp, 203.0);
+        ictcp_to_xyz_buffer(&ictcp, &mut xyz_back, 203.0);
+
+        for i in 0..6 {
+            assert!((xyz_in[i] - xyz_back[i]).abs() < 1e-4);
+        }
+    }
+}
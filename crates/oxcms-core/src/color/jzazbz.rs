@@ -0,0 +1,299 @@
+//! Jzazbz Color Space
+//!
+//! Jzazbz (Safdar et al., 2017) is a perceptually uniform space for HDR and
+//! wide gamut content. It uses a PQ-style nonlinearity on a modified LMS
+//! response, so it stays uniform from deep shadows to 10000 cd/m².
+//!
+//! - Jz: Lightness
+//! - az: Green-red axis
+//! - bz: Blue-yellow axis
+//!
+//! Jzazbz works on absolute luminance. XYZ inputs here are D65 adapted with
+//! Y = 1 for the reference white, and every conversion takes the luminance
+//! of that white in cd/m². [`Jzczhz`] is its cylindrical form.
+
+use crate::color::Xyz;
+use crate::color::lch::{from_polar, hue_difference, to_polar};
+use crate::color::xyz::convert_buffer;
+use crate::math::Matrix3x3;
+
+const B: f64 = 1.15;
+const G: f64 = 0.66;
+const D: f64 = -0.56;
+const D0: f64 = 1.629_549_953_282_156_6e-11;
+
+const C1: f64 = 3424.0 / 4096.0;
+const C2: f64 = 2413.0 / 128.0;
+const C3: f64 = 2392.0 / 128.0;
+const N: f64 = 2610.0 / 16384.0;
+/// PQ exponent, 1.7 times the ST 2084 m2
+const P: f64 = 1.7 * 2523.0 / 32.0;
+
+/// Luminance the nonlinearity is normalized to, in cd/m²
+const PEAK_LUMINANCE: f64 = 10000.0;
+
+/// Modified XYZ to LMS
+const XYZ_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [0.41478972, 0.579999, 0.0146480],
+    [-0.2015100, 1.120649, 0.0531008],
+    [-0.0166008, 0.264800, 0.6684799],
+]);
+
+/// Inverse of XYZ_TO_LMS
+const LMS_TO_XYZ: Matrix3x3 = Matrix3x3::new([
+    [1.9242264358, -1.0047923126, 0.0376514040],
+    [0.3503167621, 0.7264811939, -0.0653844229],
+    [-0.0909828110, -0.3127282905, 1.5227665613],
+]);
+
+/// Nonlinear LMS to Iz, az, bz
+const LMS_TO_IAB: Matrix3x3 = Matrix3x3::new([
+    [0.5, 0.5, 0.0],
+    [3.524000, -4.066708, 0.542708],
+    [0.199076, 1.096799, -1.295875],
+]);
+
+/// Inverse of LMS_TO_IAB
+const IAB_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [1.0, 0.1386050433, 0.0580473162],
+    [1.0, -0.1386050433, -0.0580473162],
+    [1.0, -0.0960192420, -0.8118918961],
+]);
+
+/// Jzazbz color coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Jzazbz {
+    /// Lightness (0 to 1, about 0.17 at 100 cd/m²)
+    pub jz: f64,
+    /// Green-red axis
+    pub az: f64,
+    /// Blue-yellow axis
+    pub bz: f64,
+}
+
+impl Jzazbz {
+    /// Create a new Jzazbz color
+    #[inline]
+    pub const fn new(jz: f64, az: f64, bz: f64) -> Self {
+        Self { jz, az, bz }
+    }
+
+    /// Create Jzazbz from an array
+    #[inline]
+    pub const fn from_array(arr: [f64; 3]) -> Self {
+        Self {
+            jz: arr[0],
+            az: arr[1],
+            bz: arr[2],
+        }
+    }
+
+    /// Convert to array
+    #[inline]
+    pub const fn to_array(&self) -> [f64; 3] {
+        [self.jz, self.az, self.bz]
+    }
+
+    /// Convert from D65 XYZ whose white (Y = 1) has the given luminance in cd/m²
+    pub fn from_xyz(xyz: Xyz, white_luminance: f64) -> Self {
+        let [x, y, z] = xyz.scale(white_luminance).to_array();
+        let xp = B * x - (B - 1.0) * z;
+        let yp = G * y - (G - 1.0) * x;
+
+        let lms = XYZ_TO_LMS.multiply_vec([xp, yp, z]);
+        let [iz, az, bz] = LMS_TO_IAB.multiply_vec(lms.map(pq_encode));
+        let jz = (1.0 + D) * iz / (1.0 + D * iz) - D0;
+
+        Self { jz, az, bz }
+    }
+
+    /// Convert to D65 XYZ whose white (Y = 1) has the given luminance in cd/m²
+    pub fn to_xyz(&self, white_luminance: f64) -> Xyz {
+        let jz = self.jz + D0;
+        let iz = jz / (1.0 + D - D * jz);
+
+        let lms = IAB_TO_LMS.multiply_vec([iz, self.az, self.bz]);
+        let [xp, yp, z] = LMS_TO_XYZ.multiply_vec(lms.map(pq_decode));
+        let x = (xp + (B - 1.0) * z) / B;
+        let y = (yp + (G - 1.0) * x) / G;
+
+        Xyz::new(x, y, z).scale(1.0 / white_luminance)
+    }
+
+    /// Convert to the cylindrical JzCzhz form
+    pub fn to_jzczhz(&self) -> Jzczhz {
+        Jzczhz::from_jzazbz(*self)
+    }
+
+    /// Check if approximately equal to another Jzazbz color
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.jz - other.jz).abs() < epsilon
+            && (self.az - other.az).abs() < epsilon
+            && (self.bz - other.bz).abs() < epsilon
+    }
+}
+
+/// JzCzhz color coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Jzczhz {
+    /// Lightness
+    pub jz: f64,
+    /// Chroma
+    pub cz: f64,
+    /// Hue angle in degrees (0 to 360)
+    pub hz: f64,
+}
+
+impl Jzczhz {
+    /// Create a new JzCzhz color
+    #[inline]
+    pub const fn new(jz: f64, cz: f64, hz: f64) -> Self {
+        Self { jz, cz, hz }
+    }
+
+    /// Create JzCzhz from an array
+    #[inline]
+    pub const fn from_array(arr: [f64; 3]) -> Self {
+        Self {
+            jz: arr[0],
+            cz: arr[1],
+            hz: arr[2],
+        }
+    }
+
+    /// Convert to array
+    #[inline]
+    pub const fn to_array(&self) -> [f64; 3] {
+        [self.jz, self.cz, self.hz]
+    }
+
+    /// Convert from Jzazbz
+    pub fn from_jzazbz(jab: Jzazbz) -> Self {
+        let (cz, hz) = to_polar(jab.az, jab.bz);
+        Self { jz: jab.jz, cz, hz }
+    }
+
+    /// Convert to Jzazbz
+    pub fn to_jzazbz(&self) -> Jzazbz {
+        let (az, bz) = from_polar(self.cz, self.hz);
+        Jzazbz::new(self.jz, az, bz)
+    }
+
+    /// Convert from D65 XYZ whose white (Y = 1) has the given luminance in cd/m²
+    pub fn from_xyz(xyz: Xyz, white_luminance: f64) -> Self {
+        Self::from_jzazbz(Jzazbz::from_xyz(xyz, white_luminance))
+    }
+
+    /// Convert to D65 XYZ whose white (Y = 1) has the given luminance in cd/m²
+    pub fn to_xyz(&self, white_luminance: f64) -> Xyz {
+        self.to_jzazbz().to_xyz(white_luminance)
+    }
+
+    /// Check if approximately equal to another JzCzhz color
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.jz - other.jz).abs() < epsilon
+            && (self.cz - other.cz).abs() < epsilon
+            && hue_difference(self.hz, other.hz).abs() < epsilon
+    }
+}
+
+impl From<Jzazbz> for Jzczhz {
+    fn from(jab: Jzazbz) -> Self {
+        Self::from_jzazbz(jab)
+    }
+}
+
+impl From<Jzczhz> for Jzazbz {
+    fn from(jch: Jzczhz) -> Self {
+        jch.to_jzazbz()
+    }
+}
+
+/// Jzazbz PQ curve: absolute luminance (cd/m²) → encoded
+#[inline]
+fn pq_encode(v: f64) -> f64 {
+    let x = (v.max(0.0) / PEAK_LUMINANCE).powf(N);
+    ((C1 + C2 * x) / (1.0 + C3 * x)).powf(P)
+}
+
+/// Inverse of [`pq_encode`]
+#[inline]
+fn pq_decode(v: f64) -> f64 {
+    let x = v.max(0.0).powf(1.0 / P);
+    let num = (C1 - x).min(0.0);
+    PEAK_LUMINANCE * (num / (C3 * x - C2)).powf(1.0 / N)
+}
+
+/// Transform a buffer of D65 XYZ f32 values to Jzazbz f32 values
+///
+/// Both buffers should be triplets (length must be divisible by 3)
+pub fn xyz_to_jzazbz_buffer(xyz_data: &[f32], jzazbz_out: &mut [f32], white_luminance: f64) {
+    convert_buffer(xyz_data, jzazbz_out, |xyz| {
+        Jzazbz::from_xyz(Xyz::from_array(xyz), white_luminance).to_array()
+    });
+}
+
+/// Transform a buffer of Jzazbz f32 values to D65 XYZ f32 values
+pub fn jzazbz_to_xyz_buffer(jzazbz_data: &[f32], xyz_out: &mut [f32], white_luminance: f64) {
+    convert_buffer(jzazbz_data, xyz_out, |jab| {
+        Jzazbz::from_array(jab).to_xyz(white_luminance).to_array()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_value() {
+        // colour-science XYZ_to_Jzazbz, which takes absolute XYZ in cd/m²
+        let xyz = Xyz::new(0.206_540_08, 0.121_972_25, 0.051_369_52);
+        let jab = Jzazbz::from_xyz(xyz, 1.0);
+        let expected = Jzazbz::new(0.005_350_48, 0.009_243_02, 0.005_260_07);
+        assert!(jab.approx_eq(&expected, 1e-8), "{:?}", jab);
+    }
+
+    #[test]
+    fn test_white_is_near_neutral() {
+        let white = Xyz::new(0.9505, 1.0, 1.089);
+        let jab = Jzazbz::from_xyz(white, 100.0);
+        assert!((jab.jz - 0.167_17).abs() < 1e-4, "{:?}", jab);
+        assert!(jab.to_jzczhz().cz < 1e-3);
+
+        // Brighter whites are lighter
+        assert!(Jzazbz::from_xyz(white, 1000.0).jz > jab.jz);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for xyz in [
+            Xyz::new(0.9505, 1.0, 1.089),
+            Xyz::new(0.3, 0.4, 0.2),
+            Xyz::new(0.05, 0.02, 0.4),
+        ] {
+            for luminance in [1.0, 203.0, 4000.0] {
+                let back = Jzazbz::from_xyz(xyz, luminance).to_xyz(luminance);
+                assert!(xyz.approx_eq(&back, 1e-9), "{:?} vs {:?}", xyz, back);
+
+                let back = Jzczhz::from_xyz(xyz, luminance).to_xyz(luminance);
+                assert!(xyz.approx_eq(&back, 1e-9), "{:?} vs {:?}", xyz, back);
+            }
+        }
+    }
+
+    #[test]
+    fn test_buffer_transform() {
+        let xyz_in = [0.3f32, 0.4, 0.2, 0.05, 0.02, 0.4];
+        let mut jab = [0.0f32; 6];
+        let mut xyz_back = [0.0f32; 6];
+
+        xyz_to_jzazbz_buffer(&xyz_in, &mut jab, 203.0);
+        jzazbz_to_xyz_buffer(&jab, &mut xyz_back, 203.0);
+
+        for i in 0..6 {
+            assert!((xyz_in[i] - xyz_back[i]).abs() < 1e-4);
+        }
+    }
+}
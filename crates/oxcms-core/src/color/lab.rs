@@ -9,6 +9,8 @@
 
 use crate::color::{D50, WhitePoint, Xyz};
 
+pub use super::delta_e::delta_e_2000;
+
 /// CIELAB color coordinates
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Lab {
@@ -136,109 +138,6 @@ fn lab_f_inv(t: f64) -> f64 {
     }
 }
 
-/// Calculate CIEDE2000 color difference
-///
-/// This is the industry-standard color difference formula.
-/// A difference of 1.0 is approximately the just-noticeable difference.
-pub fn delta_e_2000(lab1: Lab, lab2: Lab) -> f64 {
-    // CIEDE2000 implementation
-    // Reference: http://www.brucelindbloom.com/index.html?Eqn_DeltaE_CIE2000.html
-
-    let l1 = lab1.l;
-    let a1 = lab1.a;
-    let b1 = lab1.b;
-    let l2 = lab2.l;
-    let a2 = lab2.a;
-    let b2 = lab2.b;
-
-    // Step 1: Calculate C and h
-    let c1 = (a1 * a1 + b1 * b1).sqrt();
-    let c2 = (a2 * a2 + b2 * b2).sqrt();
-    let c_bar = (c1 + c2) / 2.0;
-
-    let c_bar_7 = c_bar.powi(7);
-    let g = 0.5 * (1.0 - (c_bar_7 / (c_bar_7 + 25.0_f64.powi(7))).sqrt());
-
-    let a1_prime = a1 * (1.0 + g);
-    let a2_prime = a2 * (1.0 + g);
-
-    let c1_prime = (a1_prime * a1_prime + b1 * b1).sqrt();
-    let c2_prime = (a2_prime * a2_prime + b2 * b2).sqrt();
-
-    let h1_prime = if a1_prime == 0.0 && b1 == 0.0 {
-        0.0
-    } else {
-        let h = b1.atan2(a1_prime).to_degrees();
-        if h < 0.0 { h + 360.0 } else { h }
-    };
-
-    let h2_prime = if a2_prime == 0.0 && b2 == 0.0 {
-        0.0
-    } else {
-        let h = b2.atan2(a2_prime).to_degrees();
-        if h < 0.0 { h + 360.0 } else { h }
-    };
-
-    // Step 2: Calculate deltas
-    let delta_l_prime = l2 - l1;
-    let delta_c_prime = c2_prime - c1_prime;
-
-    let delta_h_prime = if c1_prime * c2_prime == 0.0 {
-        0.0
-    } else {
-        let diff = h2_prime - h1_prime;
-        if diff.abs() <= 180.0 {
-            diff
-        } else if diff > 180.0 {
-            diff - 360.0
-        } else {
-            diff + 360.0
-        }
-    };
-
-    let delta_big_h_prime =
-        2.0 * (c1_prime * c2_prime).sqrt() * (delta_h_prime.to_radians() / 2.0).sin();
-
-    // Step 3: Calculate CIEDE2000
-    let l_bar_prime = (l1 + l2) / 2.0;
-    let c_bar_prime = (c1_prime + c2_prime) / 2.0;
-
-    let h_bar_prime = if c1_prime * c2_prime == 0.0 {
-        h1_prime + h2_prime
-    } else if (h1_prime - h2_prime).abs() <= 180.0 {
-        (h1_prime + h2_prime) / 2.0
-    } else if h1_prime + h2_prime < 360.0 {
-        (h1_prime + h2_prime + 360.0) / 2.0
-    } else {
-        (h1_prime + h2_prime - 360.0) / 2.0
-    };
-
-    let t = 1.0 - 0.17 * (h_bar_prime - 30.0).to_radians().cos()
-        + 0.24 * (2.0 * h_bar_prime).to_radians().cos()
-        + 0.32 * (3.0 * h_bar_prime + 6.0).to_radians().cos()
-        - 0.20 * (4.0 * h_bar_prime - 63.0).to_radians().cos();
-
-    let delta_theta = 30.0 * (-((h_bar_prime - 275.0) / 25.0).powi(2)).exp();
-    let c_bar_prime_7 = c_bar_prime.powi(7);
-    let r_c = 2.0 * (c_bar_prime_7 / (c_bar_prime_7 + 25.0_f64.powi(7))).sqrt();
-    let s_l =
-        1.0 + (0.015 * (l_bar_prime - 50.0).powi(2)) / (20.0 + (l_bar_prime - 50.0).powi(2)).sqrt();
-    let s_c = 1.0 + 0.045 * c_bar_prime;
-    let s_h = 1.0 + 0.015 * c_bar_prime * t;
-    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
-
-    // Weighting factors (commonly 1:1:1)
-    let k_l = 1.0;
-    let k_c = 1.0;
-    let k_h = 1.0;
-
-    let term1 = delta_l_prime / (k_l * s_l);
-    let term2 = delta_c_prime / (k_c * s_c);
-    let term3 = delta_big_h_prime / (k_h * s_h);
-
-    (term1 * term1 + term2 * term2 + term3 * term3 + r_t * term2 * term3).sqrt()
-}
-
 impl From<[f64; 3]> for Lab {
     fn from(arr: [f64; 3]) -> Self {
         Self::from_array(arr)
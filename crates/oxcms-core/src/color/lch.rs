@@ -0,0 +1,183 @@
+//! CIE LCh(ab) Color Space
+//!
+//! The cylindrical form of CIELAB:
+//!
+//! - L: Lightness (same as L*)
+//! - C: Chroma, the distance from the neutral axis
+//! - h: Hue angle in degrees (0-360, 0 = +a*, 90 = +b*)
+
+use crate::color::xyz::convert_buffer;
+use crate::color::{D50, Lab, WhitePoint, Xyz};
+
+/// CIE LCh(ab) color coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Lch {
+    /// Lightness (0 to 100)
+    pub l: f64,
+    /// Chroma (0 and up)
+    pub c: f64,
+    /// Hue angle in degrees (0 to 360)
+    pub h: f64,
+}
+
+impl Lch {
+    /// Create a new LCh color
+    #[inline]
+    pub const fn new(l: f64, c: f64, h: f64) -> Self {
+        Self { l, c, h }
+    }
+
+    /// Create LCh from an array
+    #[inline]
+    pub const fn from_array(arr: [f64; 3]) -> Self {
+        Self {
+            l: arr[0],
+            c: arr[1],
+            h: arr[2],
+        }
+    }
+
+    /// Convert to array
+    #[inline]
+    pub const fn to_array(&self) -> [f64; 3] {
+        [self.l, self.c, self.h]
+    }
+
+    /// Convert from Lab
+    pub fn from_lab(lab: Lab) -> Self {
+        let (c, h) = to_polar(lab.a, lab.b);
+        Self { l: lab.l, c, h }
+    }
+
+    /// Convert to Lab
+    pub fn to_lab(&self) -> Lab {
+        let (a, b) = from_polar(self.c, self.h);
+        Lab::new(self.l, a, b)
+    }
+
+    /// Convert from XYZ with D50 white point (ICC PCS)
+    pub fn from_xyz(xyz: Xyz) -> Self {
+        Self::from_xyz_with_white(xyz, &D50)
+    }
+
+    /// Convert from XYZ with a specific white point
+    pub fn from_xyz_with_white(xyz: Xyz, white: &WhitePoint) -> Self {
+        Self::from_lab(Lab::from_xyz_with_white(xyz, white))
+    }
+
+    /// Convert to XYZ with D50 white point (ICC PCS)
+    pub fn to_xyz(&self) -> Xyz {
+        self.to_xyz_with_white(&D50)
+    }
+
+    /// Convert to XYZ with a specific white point
+    pub fn to_xyz_with_white(&self, white: &WhitePoint) -> Xyz {
+        self.to_lab().to_xyz_with_white(white)
+    }
+
+    /// Check if approximately equal to another LCh color
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.l - other.l).abs() < epsilon
+            && (self.c - other.c).abs() < epsilon
+            && hue_difference(self.h, other.h).abs() < epsilon
+    }
+}
+
+impl From<Lab> for Lch {
+    fn from(lab: Lab) -> Self {
+        Self::from_lab(lab)
+    }
+}
+
+impl From<Lch> for Lab {
+    fn from(lch: Lch) -> Self {
+        lch.to_lab()
+    }
+}
+
+/// Rectangular (a, b) to polar (chroma, hue in degrees 0-360)
+#[inline]
+pub(crate) fn to_polar(a: f64, b: f64) -> (f64, f64) {
+    let c = (a * a + b * b).sqrt();
+    let h = b.atan2(a).to_degrees();
+    (c, if h < 0.0 { h + 360.0 } else { h })
+}
+
+/// Polar (chroma, hue in degrees) to rectangular (a, b)
+#[inline]
+pub(crate) fn from_polar(c: f64, h: f64) -> (f64, f64) {
+    let (sin, cos) = h.to_radians().sin_cos();
+    (c * cos, c * sin)
+}
+
+/// Signed difference between two hue angles in degrees, in (-180, 180]
+#[inline]
+pub(crate) fn hue_difference(h1: f64, h2: f64) -> f64 {
+    let d = (h1 - h2).rem_euclid(360.0);
+    if d > 180.0 { d - 360.0 } else { d }
+}
+
+/// Transform a buffer of XYZ f32 values to LCh(ab) f32 values (D50 white)
+///
+/// Both buffers should be triplets (length must be divisible by 3)
+pub fn xyz_to_lch_buffer(xyz_data: &[f32], lch_out: &mut [f32]) {
+    convert_buffer(xyz_data, lch_out, |xyz| {
+        Lch::from_xyz(Xyz::from_array(xyz)).to_array()
+    });
+}
+
+/// Transform a buffer of LCh(ab) f32 values to XYZ f32 values (D50 white)
+pub fn lch_to_xyz_buffer(lch_data: &[f32], xyz_out: &mut [f32]) {
+    convert_buffer(lch_data, xyz_out, |lch| {
+        Lch::from_array(lch).to_xyz().to_array()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_lab() {
+        let lch = Lch::from_lab(Lab::new(50.0, 3.0, 4.0));
+        assert!((lch.c - 5.0).abs() < 1e-12);
+        assert!((lch.h - 53.130_102_354_155_98).abs() < 1e-9);
+
+        // Negative b lands in the upper half of the hue circle
+        let lch = Lch::from_lab(Lab::new(50.0, 0.0, -10.0));
+        assert!((lch.h - 270.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let original = Lab::new(62.0, -31.5, 18.25);
+        let back = Lch::from_lab(original).to_lab();
+        assert!(original.approx_eq(&back, 1e-9));
+
+        let xyz = Xyz::new(0.3, 0.4, 0.2);
+        let back = Lch::from_xyz(xyz).to_xyz();
+        assert!(xyz.approx_eq(&back, 1e-9));
+    }
+
+    #[test]
+    fn test_hue_difference_wraps() {
+        assert!((hue_difference(359.0, 1.0) + 2.0).abs() < 1e-12);
+        assert!((hue_difference(1.0, 359.0) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_buffer_transform() {
+        let xyz_in = [0.3f32, 0.4, 0.2, 0.9642, 1.0, 0.8251];
+        let mut lch = [0.0f32; 6];
+        let mut xyz_back = [0.0f32; 6];
+
+        xyz_to_lch_buffer(&xyz_in, &mut lch);
+        lch_to_xyz_buffer(&lch, &mut xyz_back);
+
+        assert!((lch[3] - 100.0).abs() < 1e-3 && lch[4] < 1e-3);
+        for i in 0..6 {
+            assert!((xyz_in[i] - xyz_back[i]).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! CIELUV (L*u*v*) Color Space
+//!
+//! CIE 1976 L*u*v* shares L* with CIELAB but measures chromaticity in the
+//! (u', v') uniform chromaticity scale, so additive mixtures of lights lie
+//! on straight lines.
+//!
+//! - L*: Lightness (0 = black, 100 = white)
+//! - u*: Roughly green-red
+//! - v*: Roughly blue-yellow
+//!
+//! [`Lchuv`] is its cylindrical form, LCh(uv).
+
+use crate::color::lch::{from_polar, hue_difference, to_polar};
+use crate::color::xyz::convert_buffer;
+use crate::color::{D50, WhitePoint, Xyz};
+
+/// CIE ε: (6/29)³
+const EPSILON: f64 = 216.0 / 24389.0;
+/// CIE κ: (29/3)³
+const KAPPA: f64 = 24389.0 / 27.0;
+
+/// CIELUV color coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Luv {
+    /// Lightness (0 to 100)
+    pub l: f64,
+    /// Green-red axis
+    pub u: f64,
+    /// Blue-yellow axis
+    pub v: f64,
+}
+
+impl Luv {
+    /// Create a new Luv color
+    #[inline]
+    pub const fn new(l: f64, u: f64, v: f64) -> Self {
+        Self { l, u, v }
+    }
+
+    /// Create Luv from an array
+    #[inline]
+    pub const fn from_array(arr: [f64; 3]) -> Self {
+        Self {
+            l: arr[0],
+            u: arr[1],
+            v: arr[2],
+        }
+    }
+
+    /// Convert to array
+    #[inline]
+    pub const fn to_array(&self) -> [f64; 3] {
+        [self.l, self.u, self.v]
+    }
+
+    /// Convert from XYZ with D50 white point (ICC PCS)
+    pub fn from_xyz(xyz: Xyz) -> Self {
+        Self::from_xyz_with_white(xyz, &D50)
+    }
+
+    /// Convert from XYZ with a specific white point
+    pub fn from_xyz_with_white(xyz: Xyz, white: &WhitePoint) -> Self {
+        let yr = xyz.y / white.xyz.y;
+        let l = if yr > EPSILON {
+            116.0 * yr.cbrt() - 16.0
+        } else {
+            KAPPA * yr
+        };
+
+        let (up, vp) = uv_prime(&xyz);
+        let (un, vn) = uv_prime(&white.xyz);
+
+        Self {
+            l,
+            u: 13.0 * l * (up - un),
+            v: 13.0 * l * (vp - vn),
+        }
+    }
+
+    /// Convert to XYZ with D50 white point (ICC PCS)
+    pub fn to_xyz(&self) -> Xyz {
+        self.to_xyz_with_white(&D50)
+    }
+
+    /// Convert to XYZ with a specific white point
+    pub fn to_xyz_with_white(&self, white: &WhitePoint) -> Xyz {
+        if self.l <= 0.0 {
+            return Xyz::new(0.0, 0.0, 0.0);
+        }
+
+        let yr = if self.l > KAPPA * EPSILON {
+            ((self.l + 16.0) / 116.0).powi(3)
+        } else {
+            self.l / KAPPA
+        };
+        let y = yr * white.xyz.y;
+
+        let (un, vn) = uv_prime(&white.xyz);
+        let up = self.u / (13.0 * self.l) + un;
+        let vp = self.v / (13.0 * self.l) + vn;
+        if vp == 0.0 {
+            return Xyz::new(0.0, y, 0.0);
+        }
+
+        let x = y * 9.0 * up / (4.0 * vp);
+        let z = y * (12.0 - 3.0 * up - 20.0 * vp) / (4.0 * vp);
+        Xyz::new(x, y, z)
+    }
+
+    /// Convert to the cylindrical LCh(uv) form
+    pub fn to_lchuv(&self) -> Lchuv {
+        Lchuv::from_luv(*self)
+    }
+
+    /// Check if approximately equal to another Luv color
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.l - other.l).abs() < epsilon
+            && (self.u - other.u).abs() < epsilon
+            && (self.v - other.v).abs() < epsilon
+    }
+}
+
+/// CIE LCh(uv) color coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Lchuv {
+    /// Lightness (0 to 100)
+    pub l: f64,
+    /// Chroma (0 and up)
+    pub c: f64,
+    /// Hue angle in degrees (0 to 360)
+    pub h: f64,
+}
+
+impl Lchuv {
+    /// Create a new LCh(uv) color
+    #[inline]
+    pub const fn new(l: f64, c: f64, h: f64) -> Self {
+        Self { l, c, h }
+    }
+
+    /// Create LCh(uv) from an array
+    #[inline]
+    pub const fn from_array(arr: [f64; 3]) -> Self {
+        Self {
+            l: arr[0],
+            c: arr[1],
+            h: arr[2],
+        }
+    }
+
+    /// Convert to array
+    #[inline]
+    pub const fn to_array(&self) -> [f64; 3] {
+        [self.l, self.c, self.h]
+    }
+
+    /// Convert from Luv
+    pub fn from_luv(luv: Luv) -> Self {
+        let (c, h) = to_polar(luv.u, luv.v);
+        Self { l: luv.l, c, h }
+    }
+
+    /// Convert to Luv
+    pub fn to_luv(&self) -> Luv {
+        let (u, v) = from_polar(self.c, self.h);
+        Luv::new(self.l, u, v)
+    }
+
+    /// Convert from XYZ with D50 white point (ICC PCS)
+    pub fn from_xyz(xyz: Xyz) -> Self {
+        Self::from_luv(Luv::from_xyz(xyz))
+    }
+
+    /// Convert to XYZ with D50 white point (ICC PCS)
+    pub fn to_xyz(&self) -> Xyz {
+        self.to_luv().to_xyz()
+    }
+
+    /// Saturation s(uv) = C / L
+    #[inline]
+    pub fn saturation(&self) -> f64 {
+        if self.l > 0.0 { self.c / self.l } else { 0.0 }
+    }
+
+    /// Check if approximately equal to another LCh(uv) color
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.l - other.l).abs() < epsilon
+            && (self.c - other.c).abs() < epsilon
+            && hue_difference(self.h, other.h).abs() < epsilon
+    }
+}
+
+impl From<Luv> for Lchuv {
+    fn from(luv: Luv) -> Self {
+        Self::from_luv(luv)
+    }
+}
+
+impl From<Lchuv> for Luv {
+    fn from(lch: Lchuv) -> Self {
+        lch.to_luv()
+    }
+}
+
+/// CIE 1976 UCS chromaticity (u', v')
+#[inline]
+fn uv_prime(xyz: &Xyz) -> (f64, f64) {
+    let denom = xyz.x + 15.0 * xyz.y + 3.0 * xyz.z;
+    if denom > 0.0 {
+        (4.0 * xyz.x / denom, 9.0 * xyz.y / denom)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// Transform a buffer of XYZ f32 values to Luv f32 values (D50 white)
+///
+/// Both buffers should be triplets (length must be divisible by 3)
+pub fn xyz_to_luv_buffer(xyz_data: &[f32], luv_out: &mut [f32]) {
+    convert_buffer(xyz_data, luv_out, |xyz| {
+        Luv::from_xyz(Xyz::from_array(xyz)).to_array()
+    });
+}
+
+/// Transform a buffer of Luv f32 values to XYZ f32 values (D50 white)
+pub fn luv_to_xyz_buffer(luv_data: &[f32], xyz_out: &mut [f32]) {
+    convert_buffer(luv_data, xyz_out, |luv| {
+        Luv::from_array(luv).to_xyz().to_array()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d65_xy() -> WhitePoint {
+        WhitePoint::from_xyz(Xyz::from_xyy(0.3127, 0.3290, 1.0))
+    }
+
+    #[test]
+    fn test_reference_value() {
+        // colour-science XYZ_to_Luv with the D65 chromaticity
+        let xyz = Xyz::new(0.206_540_08, 0.121_972_25, 0.051_369_52);
+        let luv = Luv::from_xyz_with_white(xyz, &d65_xy());
+        let expected = Luv::new(41.527_875_2, 96.836_260_5, 17.752_101_4);
+        assert!(luv.approx_eq(&expected, 1e-6), "{:?}", luv);
+
+        let lch = luv.to_lchuv();
+        assert!((lch.c - 98.449_949_2).abs() < 1e-3, "{:?}", lch);
+        assert!((lch.h - 10.388_009_9).abs() < 1e-3, "{:?}", lch);
+    }
+
+    #[test]
+    fn test_white_and_black() {
+        let white = Luv::from_xyz(D50.xyz);
+        assert!(white.approx_eq(&Luv::new(100.0, 0.0, 0.0), 1e-9));
+
+        let black = Luv::from_xyz(Xyz::new(0.0, 0.0, 0.0));
+        assert!(black.approx_eq(&Luv::default(), 1e-12));
+        assert!(black.to_xyz().approx_eq(&Xyz::default(), 1e-12));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let white = d65_xy();
+        for xyz in [
+            Xyz::new(0.3, 0.4, 0.2),
+            Xyz::new(0.05, 0.02, 0.4),
+            Xyz::new(0.001, 0.002, 0.001),
+        ] {
+            let back = Luv::from_xyz_with_white(xyz, &white).to_xyz_with_white(&white);
+            assert!(xyz.approx_eq(&back, 1e-12), "{:?} vs {:?}", xyz, back);
+
+            let back = Lchuv::from_xyz(xyz).to_xyz();
+            assert!(xyz.approx_eq(&back, 1e-12), "{:?} vs {:?}", xyz, back);
+        }
+    }
+
+    #[test]
+    fn test_buffer_transform() {
+        let xyz_in = [0.3f32, 0.4, 0.2, 0.05, 0.02, 0.4];
+        let mut luv = [0.0f32; 6];
+        let mut xyz_back = [0.0f32; 6];
+
+        xyz_to_luv_buffer(&xyz_in, &mut luv);
+        luv_to_xyz_buffer(&luv, &mut xyz_back);
+
+        for i in 0..6 {
+            assert!((xyz_in[i] - xyz_back[i]).abs() < 1e-5);
+        }
+    }
+}
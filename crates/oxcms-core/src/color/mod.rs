@@ -2,19 +2,50 @@
 //!
 //! This module provides:
 //! - CIE XYZ color space
-//! - CIELAB (L*a*b*) color space
+//! - CIELAB (L*a*b*) and LCh(ab) color spaces
+//! - CIELUV (L*u*v*) and LCh(uv) color spaces
+//! - Oklab / Oklch
+//! - Jzazbz / JzCzhz and ICtCp (HDR)
+//! - Color difference metrics (ΔE76, ΔE94, CMC, ΔE2000, ΔE_ITP)
 //! - XYB color space (JPEG XL perceptual)
+//! - CIECAM02 / CAM16 color appearance models
+//! - Spectral colorimetry (CIE observers, illuminants, ASTM E308)
 //! - RGB primitives
-//! - White point definitions
+//! - White point definitions and correlated color temperature
 
+pub mod cam;
+pub mod delta_e;
+pub mod ictcp;
+pub mod jzazbz;
 pub mod lab;
+pub mod lch;
+pub mod luv;
+pub mod oklab;
 pub mod rgb;
+pub mod spectral;
+pub mod temperature;
 pub mod white_point;
 pub mod xyb;
 pub mod xyz;
 
+pub use cam::{
+    Cam16Ucs, CamAppearance, CamModel, ColorAppearanceModel, Surround, ViewingConditions,
+};
+pub use delta_e::{
+    Cie94Application, DeltaEMetric, delta_e_76, delta_e_94, delta_e_2000, delta_e_2000_weighted,
+    delta_e_cam16_ucs, delta_e_cmc, delta_e_itp,
+};
+pub use ictcp::{Ictcp, ictcp_to_xyz_buffer, xyz_to_ictcp_buffer};
+pub use jzazbz::{Jzazbz, Jzczhz, jzazbz_to_xyz_buffer, xyz_to_jzazbz_buffer};
 pub use lab::Lab;
+pub use lch::{Lch, lch_to_xyz_buffer, xyz_to_lch_buffer};
+pub use luv::{Lchuv, Luv, luv_to_xyz_buffer, xyz_to_luv_buffer};
+pub use oklab::{Oklab, Oklch, oklab_to_xyz_buffer, xyz_to_oklab_buffer};
 pub use rgb::Rgb;
+pub use spectral::{Observer, SpectralIntegrator, Spectrum, StandardIlluminant};
+pub use temperature::{cct_duv_to_xy, daylight_chromaticity, xy_to_cct_duv};
 pub use white_point::{D50, D55, D60, D65, D75, DCI_P3, WhitePoint};
-pub use xyb::{LinearRgb, Xyb, linear_rgb_to_xyb, srgb_to_xyb, xyb_to_linear_rgb, xyb_to_srgb};
+pub use xyb::{
+    LinearRgb, Xyb, XybParams, linear_rgb_to_xyb, srgb_to_xyb, xyb_to_linear_rgb, xyb_to_srgb,
+};
 pub use xyz::Xyz;
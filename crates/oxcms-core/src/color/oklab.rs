@@ -0,0 +1,269 @@
+//! Oklab Color Space
+//!
+//! Oklab (Björn Ottosson, 2020) is a perceptual color space built from an
+//! LMS cone response with a cube root nonlinearity. It predicts lightness,
+//! chroma and hue well with a very cheap transform.
+//!
+//! - L: Perceived lightness (0 = black, 1 = white)
+//! - a: Green-red axis
+//! - b: Blue-yellow axis
+//!
+//! Oklab is defined relative to D65: XYZ inputs are expected to be D65
+//! adapted with Y = 1 for the reference white. [`Oklch`] is its
+//! cylindrical form.
+
+use crate::color::Xyz;
+use crate::color::lch::{from_polar, hue_difference, to_polar};
+use crate::color::xyz::convert_buffer;
+use crate::math::Matrix3x3;
+
+/// XYZ (D65) to LMS cone response
+const XYZ_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [0.8189330101, 0.3618667424, -0.1288597137],
+    [0.0329845436, 0.9293118715, 0.0361456387],
+    [0.0482003018, 0.2643662691, 0.6338517070],
+]);
+
+/// Inverse of XYZ_TO_LMS
+const LMS_TO_XYZ: Matrix3x3 = Matrix3x3::new([
+    [1.2270138511, -0.5577999807, 0.2812561490],
+    [-0.0405801784, 1.1122568696, -0.0716766787],
+    [-0.0763812845, -0.4214819784, 1.5861632204],
+]);
+
+/// Nonlinear LMS to Lab
+const LMS_TO_LAB: Matrix3x3 = Matrix3x3::new([
+    [0.2104542553, 0.7936177850, -0.0040720468],
+    [1.9779984951, -2.4285922050, 0.4505937099],
+    [0.0259040371, 0.7827717662, -0.8086757660],
+]);
+
+/// Inverse of LMS_TO_LAB
+const LAB_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [0.9999999985, 0.3963377922, 0.2158037581],
+    [1.0000000089, -0.1055613423, -0.0638541748],
+    [1.0000000547, -0.0894841821, -1.2914855379],
+]);
+
+/// Oklab color coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklab {
+    /// Lightness (0 to 1)
+    pub l: f64,
+    /// Green-red axis (roughly -0.4 to 0.4)
+    pub a: f64,
+    /// Blue-yellow axis (roughly -0.4 to 0.4)
+    pub b: f64,
+}
+
+impl Oklab {
+    /// Create a new Oklab color
+    #[inline]
+    pub const fn new(l: f64, a: f64, b: f64) -> Self {
+        Self { l, a, b }
+    }
+
+    /// Create Oklab from an array
+    #[inline]
+    pub const fn from_array(arr: [f64; 3]) -> Self {
+        Self {
+            l: arr[0],
+            a: arr[1],
+            b: arr[2],
+        }
+    }
+
+    /// Convert to array
+    #[inline]
+    pub const fn to_array(&self) -> [f64; 3] {
+        [self.l, self.a, self.b]
+    }
+
+    /// Convert from D65 XYZ
+    pub fn from_xyz(xyz: Xyz) -> Self {
+        let lms = XYZ_TO_LMS.multiply_vec(xyz.to_array());
+        Self::from_array(LMS_TO_LAB.multiply_vec(lms.map(f64::cbrt)))
+    }
+
+    /// Convert to D65 XYZ
+    pub fn to_xyz(&self) -> Xyz {
+        let lms = LAB_TO_LMS.multiply_vec(self.to_array());
+        Xyz::from_array(LMS_TO_XYZ.multiply_vec(lms.map(|v| v * v * v)))
+    }
+
+    /// Convert to the cylindrical Oklch form
+    pub fn to_oklch(&self) -> Oklch {
+        Oklch::from_oklab(*self)
+    }
+
+    /// Euclidean distance to another Oklab color (ΔEOK)
+    #[inline]
+    pub fn distance(&self, other: &Self) -> f64 {
+        let dl = self.l - other.l;
+        let da = self.a - other.a;
+        let db = self.b - other.b;
+        (dl * dl + da * da + db * db).sqrt()
+    }
+
+    /// Check if approximately equal to another Oklab color
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.l - other.l).abs() < epsilon
+            && (self.a - other.a).abs() < epsilon
+            && (self.b - other.b).abs() < epsilon
+    }
+}
+
+/// Oklch color coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklch {
+    /// Lightness (0 to 1)
+    pub l: f64,
+    /// Chroma (0 and up, roughly 0.4 at most for real colors)
+    pub c: f64,
+    /// Hue angle in degrees (0 to 360)
+    pub h: f64,
+}
+
+impl Oklch {
+    /// Create a new Oklch color
+    #[inline]
+    pub const fn new(l: f64, c: f64, h: f64) -> Self {
+        Self { l, c, h }
+    }
+
+    /// Create Oklch from an array
+    #[inline]
+    pub const fn from_array(arr: [f64; 3]) -> Self {
+        Self {
+            l: arr[0],
+            c: arr[1],
+            h: arr[2],
+        }
+    }
+
+    /// Convert to array
+    #[inline]
+    pub const fn to_array(&self) -> [f64; 3] {
+        [self.l, self.c, self.h]
+    }
+
+    /// Convert from Oklab
+    pub fn from_oklab(lab: Oklab) -> Self {
+        let (c, h) = to_polar(lab.a, lab.b);
+        Self { l: lab.l, c, h }
+    }
+
+    /// Convert to Oklab
+    pub fn to_oklab(&self) -> Oklab {
+        let (a, b) = from_polar(self.c, self.h);
+        Oklab::new(self.l, a, b)
+    }
+
+    /// Convert from D65 XYZ
+    pub fn from_xyz(xyz: Xyz) -> Self {
+        Self::from_oklab(Oklab::from_xyz(xyz))
+    }
+
+    /// Convert to D65 XYZ
+    pub fn to_xyz(&self) -> Xyz {
+        self.to_oklab().to_xyz()
+    }
+
+    /// Check if approximately equal to another Oklch color
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.l - other.l).abs() < epsilon
+            && (self.c - other.c).abs() < epsilon
+            && hue_difference(self.h, other.h).abs() < epsilon
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(lab: Oklab) -> Self {
+        Self::from_oklab(lab)
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(lch: Oklch) -> Self {
+        lch.to_oklab()
+    }
+}
+
+/// Transform a buffer of D65 XYZ f32 values to Oklab f32 values
+///
+/// Both buffers should be triplets (length must be divisible by 3)
+pub fn xyz_to_oklab_buffer(xyz_data: &[f32], oklab_out: &mut [f32]) {
+    convert_buffer(xyz_data, oklab_out, |xyz| {
+        Oklab::from_xyz(Xyz::from_array(xyz)).to_array()
+    });
+}
+
+/// Transform a buffer of Oklab f32 values to D65 XYZ f32 values
+pub fn oklab_to_xyz_buffer(oklab_data: &[f32], xyz_out: &mut [f32]) {
+    convert_buffer(oklab_data, xyz_out, |lab| {
+        Oklab::from_array(lab).to_xyz().to_array()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_values() {
+        // Table from the Oklab specification
+        let cases = [
+            ([0.950, 1.000, 1.089], [1.000, 0.000, 0.000]),
+            ([1.000, 0.000, 0.000], [0.450, 1.236, -0.019]),
+            ([0.000, 1.000, 0.000], [0.922, -0.671, 0.263]),
+            ([0.000, 0.000, 1.000], [0.153, -1.415, -0.449]),
+        ];
+        for (xyz, expected) in cases {
+            let lab = Oklab::from_xyz(Xyz::from_array(xyz));
+            assert!(
+                lab.approx_eq(&Oklab::from_array(expected), 1e-3),
+                "{:?} -> {:?}",
+                xyz,
+                lab
+            );
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for xyz in [
+            Xyz::new(0.9505, 1.0, 1.089),
+            Xyz::new(0.3, 0.4, 0.2),
+            Xyz::new(0.05, 0.02, 0.4),
+        ] {
+            let back = Oklab::from_xyz(xyz).to_xyz();
+            assert!(xyz.approx_eq(&back, 1e-9), "{:?} vs {:?}", xyz, back);
+
+            let back = Oklch::from_xyz(xyz).to_xyz();
+            assert!(xyz.approx_eq(&back, 1e-9), "{:?} vs {:?}", xyz, back);
+        }
+    }
+
+    #[test]
+    fn test_oklch() {
+        let lch = Oklab::new(0.7, 0.0, 0.1).to_oklch();
+        assert!(lch.approx_eq(&Oklch::new(0.7, 0.1, 90.0), 1e-12));
+        assert!(lch.to_oklab().approx_eq(&Oklab::new(0.7, 0.0, 0.1), 1e-12));
+    }
+
+    #[test]
+    fn test_buffer_transform() {
+        let xyz_in = [0.3f32, 0.4, 0.2, 0.05, 0.02, 0.4];
+        let mut lab = [0.0f32; 6];
+        let mut xyz_back = [0.0f32; 6];
+
+        xyz_to_oklab_buffer(&xyz_in, &mut lab);
+        oklab_to_xyz_buffer(&lab, &mut xyz_back);
+
+        for i in 0..6 {
+            assert!((xyz_in[i] - xyz_back[i]).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,526 @@
+//! CIE Illuminant Spectral Power Distributions
+//!
+//! - A: Planckian radiator at 2856 K, from its defining formula
+//! - D series: daylight generated from a correlated color temperature with
+//!   the CIE S0, S1, S2 basis functions
+//! - E: equal energy
+//! - F1 to F12: tabulated fluorescent lamps
+//! - LED-B1 to LED-B5: tabulated phosphor-converted blue LEDs (CIE 015:2018)
+//!
+//! All distributions cover 380 to 780 nm in 5 nm steps. A, D, E and the LED
+//! sources are normalized to 100 at 560 nm; the fluorescent tables are kept
+//! as published. Other sources can be loaded from measured data as a
+//! [`Spectrum`].
+
+use super::observer::{CMF_END, CMF_INTERVAL, CMF_START};
+use super::{Observer, SpectralIntegrator, Spectrum};
+use crate::color::WhitePoint;
+use crate::color::temperature::daylight_chromaticity;
+
+/// Ratio of the current to the historical second radiation constant
+///
+/// The nominal D50, D55, D65 and D75 temperatures are scaled by this factor
+/// (e.g. 6500 K × 1.4388 / 1.4380 ≈ 6504 K).
+const C2_RATIO: f64 = 1.4388 / 1.4380;
+
+/// Standard CIE illuminant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StandardIlluminant {
+    /// Incandescent tungsten (2856 K)
+    A,
+    /// Horizon daylight (5003 K)
+    D50,
+    /// Mid-morning daylight (5503 K)
+    D55,
+    /// Noon daylight (6504 K)
+    D65,
+    /// North sky daylight (7504 K)
+    D75,
+    /// Equal energy
+    E,
+    /// Daylight fluorescent (6430 K)
+    F1,
+    /// Cool white fluorescent (4230 K)
+    F2,
+    /// White fluorescent (3450 K)
+    F3,
+    /// Warm white fluorescent (2940 K)
+    F4,
+    /// Daylight fluorescent (6350 K)
+    F5,
+    /// Lite white fluorescent (4150 K)
+    F6,
+    /// Broadband daylight fluorescent (6500 K)
+    F7,
+    /// Broadband fluorescent simulating D50 (5000 K)
+    F8,
+    /// Broadband cool white deluxe fluorescent (4150 K)
+    F9,
+    /// Narrow band fluorescent (5000 K)
+    F10,
+    /// Narrow band white fluorescent (4000 K)
+    F11,
+    /// Narrow band warm white fluorescent (3000 K)
+    F12,
+    /// Phosphor-converted blue LED (2733 K)
+    LedB1,
+    /// Phosphor-converted blue LED (2998 K)
+    LedB2,
+    /// Phosphor-converted blue LED (4103 K)
+    LedB3,
+    /// Phosphor-converted blue LED (5109 K)
+    LedB4,
+    /// Phosphor-converted blue LED (6598 K)
+    LedB5,
+}
+
+impl StandardIlluminant {
+    /// Relative spectral power distribution
+    pub fn spd(&self) -> Spectrum {
+        match self {
+            StandardIlluminant::A => illuminant_a(),
+            StandardIlluminant::D50 => daylight(5000.0 * C2_RATIO),
+            StandardIlluminant::D55 => daylight(5500.0 * C2_RATIO),
+            StandardIlluminant::D65 => daylight(6500.0 * C2_RATIO),
+            StandardIlluminant::D75 => daylight(7500.0 * C2_RATIO),
+            StandardIlluminant::E => Spectrum::from_fn(CMF_START, CMF_END, CMF_INTERVAL, |_| 100.0),
+            StandardIlluminant::F1 => tabulated(&F1_SPD),
+            StandardIlluminant::F2 => tabulated(&F2_SPD),
+            StandardIlluminant::F3 => tabulated(&F3_SPD),
+            StandardIlluminant::F4 => tabulated(&F4_SPD),
+            StandardIlluminant::F5 => tabulated(&F5_SPD),
+            StandardIlluminant::F6 => tabulated(&F6_SPD),
+            StandardIlluminant::F7 => tabulated(&F7_SPD),
+            StandardIlluminant::F8 => tabulated(&F8_SPD),
+            StandardIlluminant::F9 => tabulated(&F9_SPD),
+            StandardIlluminant::F10 => tabulated(&F10_SPD),
+            StandardIlluminant::F11 => tabulated(&F11_SPD),
+            StandardIlluminant::F12 => tabulated(&F12_SPD),
+            StandardIlluminant::LedB1 => tabulated(&LED_B1_SPD),
+            StandardIlluminant::LedB2 => tabulated(&LED_B2_SPD),
+            StandardIlluminant::LedB3 => tabulated(&LED_B3_SPD),
+            StandardIlluminant::LedB4 => tabulated(&LED_B4_SPD),
+            StandardIlluminant::LedB5 => tabulated(&LED_B5_SPD),
+        }
+    }
+
+    /// Short name, e.g. "D65"
+    pub fn name(&self) -> &'static str {
+        match self {
+            StandardIlluminant::A => "A",
+            StandardIlluminant::D50 => "D50",
+            StandardIlluminant::D55 => "D55",
+            StandardIlluminant::D65 => "D65",
+            StandardIlluminant::D75 => "D75",
+            StandardIlluminant::E => "E",
+            StandardIlluminant::F1 => "F1",
+            StandardIlluminant::F2 => "F2",
+            StandardIlluminant::F3 => "F3",
+            StandardIlluminant::F4 => "F4",
+            StandardIlluminant::F5 => "F5",
+            StandardIlluminant::F6 => "F6",
+            StandardIlluminant::F7 => "F7",
+            StandardIlluminant::F8 => "F8",
+            StandardIlluminant::F9 => "F9",
+            StandardIlluminant::F10 => "F10",
+            StandardIlluminant::F11 => "F11",
+            StandardIlluminant::F12 => "F12",
+            StandardIlluminant::LedB1 => "LED-B1",
+            StandardIlluminant::LedB2 => "LED-B2",
+            StandardIlluminant::LedB3 => "LED-B3",
+            StandardIlluminant::LedB4 => "LED-B4",
+            StandardIlluminant::LedB5 => "LED-B5",
+        }
+    }
+
+    /// White point of this illuminant for an observer (Y = 1)
+    pub fn white_point(&self, observer: Observer) -> WhitePoint {
+        let xyz = SpectralIntegrator::new(self.spd(), observer)
+            .white_point()
+            .xyz;
+        WhitePoint::new(self.name(), xyz.x, xyz.y, xyz.z)
+    }
+}
+
+/// Distribution tabulated from 380 to 780 nm in 5 nm steps
+fn tabulated(values: &[f64; 81]) -> Spectrum {
+    Spectrum::new(CMF_START, CMF_INTERVAL, values.to_vec())
+}
+
+/// CIE illuminant A from its defining formula
+fn illuminant_a() -> Spectrum {
+    const C2: f64 = 1.435e7;
+    const T: f64 = 2848.0;
+    let norm = (C2 / (T * 560.0)).exp() - 1.0;
+    Spectrum::from_fn(CMF_START, CMF_END, CMF_INTERVAL, |wl| {
+        100.0 * (560.0 / wl).powi(5) * norm / ((C2 / (T * wl)).exp() - 1.0)
+    })
+}
+
+/// CIE daylight illuminant for a correlated color temperature in kelvin
+///
+/// Valid from 4000 K to 25000 K. The S0, S1, S2 basis is tabulated at
+/// 10 nm and linearly interpolated to 5 nm, as CIE 015 prescribes; M1 and
+/// M2 are rounded to three decimals like the canonical D illuminants.
+pub fn daylight(cct: f64) -> Spectrum {
+    let (xd, yd) = daylight_chromaticity(cct);
+
+    let m = 0.0241 + 0.2562 * xd - 0.7341 * yd;
+    let m1 = round3((-1.3515 - 1.7703 * xd + 5.9114 * yd) / m);
+    let m2 = round3((0.0300 - 31.4424 * xd + 30.0717 * yd) / m);
+
+    let coarse: Vec<f64> = DAYLIGHT_BASIS
+        .iter()
+        .map(|[s0, s1, s2]| s0 + m1 * s1 + m2 * s2)
+        .collect();
+    let basis = Spectrum::new(CMF_START, 2.0 * CMF_INTERVAL, coarse);
+    Spectrum::from_fn(CMF_START, CMF_END, CMF_INTERVAL, |wl| basis.value_at(wl))
+}
+
+/// Planckian radiator for a temperature in kelvin, normalized to 100 at 560 nm
+pub fn blackbody(cct: f64) -> Spectrum {
+    const C2: f64 = 1.4388e-2;
+    let planck = |wl: f64| {
+        let m = wl * 1e-9;
+        1.0 / (m.powi(5) * ((C2 / (m * cct)).exp() - 1.0))
+    };
+    let norm = 100.0 / planck(560.0);
+    Spectrum::from_fn(CMF_START, CMF_END, CMF_INTERVAL, |wl| planck(wl) * norm)
+}
+
+fn round3(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
+
+/// CIE daylight basis functions S0, S1, S2, 380 to 780 nm in 10 nm steps
+const DAYLIGHT_BASIS: [[f64; 3]; 41] = [
+    [63.4, 38.5, 3.0],   // 380
+    [65.8, 35.0, 1.2],   // 390
+    [94.8, 43.4, -1.1],  // 400
+    [104.8, 46.3, -0.5], // 410
+    [105.9, 43.9, -0.7], // 420
+    [96.8, 37.1, -1.2],  // 430
+    [113.9, 36.7, -2.6], // 440
+    [125.6, 35.9, -2.9], // 450
+    [125.5, 32.6, -2.8], // 460
+    [121.3, 27.9, -2.6], // 470
+    [121.3, 24.3, -2.6], // 480
+    [113.5, 20.1, -1.8], // 490
+    [113.1, 16.2, -1.5], // 500
+    [110.8, 13.2, -1.3], // 510
+    [106.5, 8.6, -1.2],  // 520
+    [108.8, 6.1, -1.0],  // 530
+    [105.3, 4.2, -0.5],  // 540
+    [104.4, 1.9, -0.3],  // 550
+    [100.0, 0.0, 0.0],   // 560
+    [96.0, -1.6, 0.2],   // 570
+    [95.1, -3.5, 0.5],   // 580
+    [89.1, -3.5, 2.1],   // 590
+    [90.5, -5.8, 3.2],   // 600
+    [90.3, -7.2, 4.1],   // 610
+    [88.4, -8.6, 4.7],   // 620
+    [84.0, -9.5, 5.1],   // 630
+    [85.1, -10.9, 6.7],  // 640
+    [81.9, -10.7, 7.3],  // 650
+    [82.6, -12.0, 8.6],  // 660
+    [84.9, -14.0, 9.8],  // 670
+    [81.3, -13.6, 10.2], // 680
+    [71.9, -12.0, 8.3],  // 690
+    [74.3, -13.3, 9.6],  // 700
+    [76.4, -12.9, 8.5],  // 710
+    [63.3, -10.6, 7.0],  // 720
+    [71.7, -11.6, 7.6],  // 730
+    [77.0, -12.2, 8.0],  // 740
+    [65.2, -10.2, 6.7],  // 750
+    [47.7, -7.8, 5.2],   // 760
+    [68.6, -11.2, 7.4],  // 770
+    [65.0, -10.4, 6.8],  // 780
+];
+
+/// CIE F1 relative spectral power, 380 to 780 nm in 5 nm steps
+const F1_SPD: [f64; 81] = [
+    1.87, 2.36, 2.94, 3.47, 5.17, 19.49, 6.13, 6.24, 7.01, 7.79, 8.56, 43.67, 16.94, 10.72, 11.35,
+    11.89, 12.37, 12.75, 13.00, 13.15, 13.23, 13.17, 13.13, 12.85, 12.52, 12.20, 11.83, 11.50,
+    11.22, 11.05, 11.03, 11.18, 11.53, 27.74, 17.05, 13.55, 14.33, 15.01, 15.52, 18.29, 19.55,
+    15.48, 14.91, 14.15, 13.22, 12.19, 11.12, 10.03, 8.95, 7.96, 7.02, 6.20, 5.42, 4.73, 4.15,
+    3.64, 3.20, 2.81, 2.47, 2.18, 1.93, 1.72, 1.67, 1.43, 1.29, 1.19, 1.08, 0.96, 0.88, 0.81, 0.77,
+    0.75, 0.73, 0.68, 0.69, 0.64, 0.68, 0.69, 0.61, 0.52, 0.43,
+];
+
+/// CIE F2 relative spectral power, 380 to 780 nm in 5 nm steps
+const F2_SPD: [f64; 81] = [
+    1.18, 1.48, 1.84, 2.15, 3.44, 15.69, 3.85, 3.74, 4.19, 4.62, 5.06, 34.98, 11.81, 6.27, 6.63,
+    6.93, 7.19, 7.40, 7.54, 7.62, 7.65, 7.62, 7.62, 7.45, 7.28, 7.15, 7.05, 7.04, 7.16, 7.47, 8.04,
+    8.88, 10.01, 24.88, 16.64, 14.59, 16.16, 17.56, 18.62, 21.47, 22.79, 19.29, 18.66, 17.73,
+    16.54, 15.21, 13.80, 12.36, 10.95, 9.65, 8.40, 7.32, 6.31, 5.43, 4.68, 4.02, 3.45, 2.96, 2.55,
+    2.19, 1.89, 1.64, 1.53, 1.27, 1.10, 0.99, 0.88, 0.76, 0.68, 0.61, 0.56, 0.54, 0.51, 0.47, 0.47,
+    0.43, 0.46, 0.47, 0.40, 0.33, 0.27,
+];
+
+/// CIE F3 relative spectral power, 380 to 780 nm in 5 nm steps
+const F3_SPD: [f64; 81] = [
+    0.82, 1.02, 1.26, 1.44, 2.57, 14.36, 2.70, 2.45, 2.73, 3.00, 3.28, 31.85, 9.47, 4.02, 4.25,
+    4.44, 4.59, 4.72, 4.80, 4.86, 4.87, 4.85, 4.88, 4.77, 4.67, 4.62, 4.62, 4.73, 4.99, 5.48, 6.25,
+    7.34, 8.78, 23.82, 16.14, 14.59, 16.63, 18.49, 19.95, 23.11, 24.69, 21.41, 20.85, 19.93, 18.67,
+    17.22, 15.65, 14.04, 12.45, 10.95, 9.51, 8.27, 7.11, 6.09, 5.22, 4.45, 3.80, 3.23, 2.75, 2.33,
+    1.99, 1.70, 1.55, 1.27, 1.09, 0.96, 0.83, 0.71, 0.62, 0.54, 0.49, 0.46, 0.43, 0.39, 0.39, 0.35,
+    0.38, 0.39, 0.33, 0.28, 0.21,
+];
+
+/// CIE F4 relative spectral power, 380 to 780 nm in 5 nm steps
+#[allow(clippy::approx_constant)]
+const F4_SPD: [f64; 81] = [
+    0.57, 0.70, 0.87, 0.98, 2.01, 13.75, 1.95, 1.59, 1.76, 1.93, 2.10, 30.28, 8.03, 2.55, 2.70,
+    2.82, 2.91, 2.99, 3.04, 3.08, 3.09, 3.09, 3.14, 3.06, 3.00, 2.98, 3.01, 3.14, 3.41, 3.90, 4.69,
+    5.81, 7.32, 22.59, 15.11, 13.88, 16.33, 18.68, 20.64, 24.28, 26.26, 23.28, 22.94, 22.14, 20.91,
+    19.43, 17.74, 16.00, 14.42, 12.56, 10.93, 9.52, 8.18, 7.01, 6.00, 5.11, 4.36, 3.69, 3.13, 2.64,
+    2.24, 1.91, 1.70, 1.39, 1.18, 1.03, 0.88, 0.74, 0.64, 0.54, 0.49, 0.46, 0.42, 0.37, 0.37, 0.33,
+    0.35, 0.36, 0.31, 0.26, 0.19,
+];
+
+/// CIE F5 relative spectral power, 380 to 780 nm in 5 nm steps
+const F5_SPD: [f64; 81] = [
+    1.87, 2.35, 2.92, 3.45, 5.10, 18.91, 6.00, 6.11, 6.85, 7.58, 8.31, 40.76, 16.06, 10.32, 10.91,
+    11.40, 11.83, 12.17, 12.40, 12.54, 12.58, 12.52, 12.47, 12.20, 11.89, 11.61, 11.33, 11.10,
+    10.96, 10.97, 11.16, 11.54, 12.12, 27.78, 17.73, 14.47, 15.20, 15.77, 16.10, 18.54, 19.50,
+    15.39, 14.64, 13.72, 12.69, 11.57, 10.45, 9.35, 8.29, 7.32, 6.41, 5.63, 4.90, 4.26, 3.72, 3.25,
+    2.83, 2.49, 2.19, 1.93, 1.71, 1.52, 1.48, 1.26, 1.13, 1.05, 0.96, 0.85, 0.78, 0.72, 0.68, 0.67,
+    0.65, 0.61, 0.62, 0.59, 0.62, 0.64, 0.55, 0.47, 0.40,
+];
+
+/// CIE F6 relative spectral power, 380 to 780 nm in 5 nm steps
+#[allow(clippy::approx_constant)]
+const F6_SPD: [f64; 81] = [
+    1.05, 1.31, 1.63, 1.90, 3.11, 14.80, 3.43, 3.30, 3.68, 4.07, 4.45, 32.61, 10.74, 5.48, 5.78,
+    6.03, 6.25, 6.41, 6.52, 6.58, 6.59, 6.56, 6.56, 6.42, 6.28, 6.20, 6.19, 6.30, 6.60, 7.12, 7.94,
+    9.07, 10.49, 25.22, 17.46, 15.63, 17.22, 18.53, 19.43, 21.97, 23.01, 19.41, 18.56, 17.42,
+    16.09, 14.64, 13.15, 11.68, 10.25, 8.95, 7.74, 6.69, 5.71, 4.87, 4.16, 3.55, 3.02, 2.57, 2.20,
+    1.87, 1.60, 1.37, 1.29, 1.05, 0.91, 0.81, 0.71, 0.61, 0.54, 0.48, 0.44, 0.43, 0.40, 0.37, 0.38,
+    0.35, 0.39, 0.41, 0.33, 0.26, 0.21,
+];
+
+/// CIE F7 relative spectral power, 380 to 780 nm in 5 nm steps
+const F7_SPD: [f64; 81] = [
+    2.56, 3.18, 3.84, 4.53, 6.15, 19.37, 7.37, 7.05, 7.71, 8.41, 9.15, 44.14, 17.52, 11.35, 12.00,
+    12.58, 13.08, 13.45, 13.71, 13.88, 13.95, 13.93, 13.82, 13.64, 13.43, 13.25, 13.08, 12.93,
+    12.78, 12.60, 12.44, 12.33, 12.26, 29.52, 17.05, 12.44, 12.58, 12.72, 12.83, 15.46, 16.75,
+    12.83, 12.67, 12.45, 12.19, 11.89, 11.60, 11.35, 11.12, 10.95, 10.76, 10.42, 10.11, 10.04,
+    10.02, 10.11, 9.87, 8.65, 7.27, 6.44, 5.83, 5.41, 5.04, 4.57, 4.12, 3.77, 3.46, 3.08, 2.73,
+    2.47, 2.25, 2.06, 1.90, 1.75, 1.62, 1.54, 1.45, 1.32, 1.17, 0.99, 0.81,
+];
+
+/// CIE F8 relative spectral power, 380 to 780 nm in 5 nm steps
+const F8_SPD: [f64; 81] = [
+    1.21, 1.50, 1.81, 2.13, 3.17, 13.08, 3.83, 3.45, 3.86, 4.42, 5.09, 34.10, 12.42, 7.68, 8.60,
+    9.46, 10.24, 10.84, 11.33, 11.71, 11.98, 12.17, 12.28, 12.32, 12.35, 12.44, 12.55, 12.68,
+    12.77, 12.72, 12.60, 12.43, 12.22, 28.96, 16.51, 11.79, 11.76, 11.77, 11.84, 14.61, 16.11,
+    12.34, 12.53, 12.72, 12.92, 13.12, 13.34, 13.61, 13.87, 14.07, 14.20, 14.16, 14.13, 14.34,
+    14.50, 14.46, 14.00, 12.58, 10.99, 9.98, 9.22, 8.62, 8.07, 7.39, 6.71, 6.16, 5.63, 5.03, 4.46,
+    4.02, 3.66, 3.36, 3.09, 2.85, 2.65, 2.51, 2.37, 2.15, 1.89, 1.61, 1.32,
+];
+
+/// CIE F9 relative spectral power, 380 to 780 nm in 5 nm steps
+const F9_SPD: [f64; 81] = [
+    0.90, 1.12, 1.36, 1.60, 2.59, 12.80, 3.05, 2.56, 2.86, 3.30, 3.82, 32.62, 10.77, 5.84, 6.57,
+    7.25, 7.86, 8.35, 8.75, 9.06, 9.31, 9.48, 9.61, 9.68, 9.74, 9.88, 10.04, 10.26, 10.48, 10.63,
+    10.78, 10.96, 11.18, 27.71, 16.29, 12.28, 12.74, 13.21, 13.65, 16.57, 18.14, 14.55, 14.65,
+    14.66, 14.61, 14.50, 14.39, 14.40, 14.47, 14.62, 14.72, 14.55, 14.40, 14.58, 14.88, 15.51,
+    15.47, 13.20, 10.57, 9.18, 8.25, 7.57, 7.03, 6.35, 5.72, 5.25, 4.80, 4.29, 3.80, 3.43, 3.12,
+    2.86, 2.64, 2.43, 2.26, 2.14, 2.02, 1.83, 1.61, 1.38, 1.12,
+];
+
+/// CIE F10 relative spectral power, 380 to 780 nm in 5 nm steps
+const F10_SPD: [f64; 81] = [
+    1.11, 0.80, 0.62, 0.57, 1.48, 12.16, 2.12, 2.70, 3.74, 5.14, 6.75, 34.39, 14.86, 10.40, 10.76,
+    10.67, 10.11, 9.27, 8.29, 7.29, 7.91, 16.64, 16.73, 10.44, 5.94, 3.34, 2.35, 1.88, 1.59, 1.47,
+    1.80, 5.71, 40.98, 73.69, 33.61, 8.24, 3.38, 2.47, 2.14, 4.86, 11.45, 14.79, 12.16, 8.97, 6.52,
+    8.31, 44.12, 34.55, 12.09, 12.15, 10.52, 4.43, 1.95, 2.19, 3.19, 2.77, 2.29, 2.00, 1.52, 1.35,
+    1.47, 1.79, 1.74, 1.02, 1.14, 3.32, 4.49, 2.05, 0.49, 0.24, 0.21, 0.21, 0.24, 0.24, 0.21, 0.17,
+    0.21, 0.22, 0.17, 0.12, 0.09,
+];
+
+/// CIE F11 relative spectral power, 380 to 780 nm in 5 nm steps
+const F11_SPD: [f64; 81] = [
+    0.91, 0.63, 0.46, 0.37, 1.29, 12.68, 1.59, 1.79, 2.46, 3.33, 4.49, 33.94, 12.13, 6.95, 7.19,
+    7.12, 6.72, 6.13, 5.46, 4.79, 5.66, 14.29, 14.96, 8.97, 4.72, 2.33, 1.47, 1.10, 0.89, 0.83,
+    1.18, 4.90, 39.59, 72.84, 32.61, 7.52, 2.83, 1.96, 1.67, 4.43, 11.28, 14.76, 12.73, 9.74, 7.33,
+    9.72, 55.27, 42.58, 13.18, 13.16, 12.26, 5.11, 2.07, 2.34, 3.58, 3.01, 2.48, 2.14, 1.54, 1.33,
+    1.46, 1.94, 2.00, 1.20, 1.35, 4.10, 5.58, 2.51, 0.57, 0.27, 0.23, 0.21, 0.24, 0.24, 0.20, 0.24,
+    0.32, 0.26, 0.16, 0.12, 0.09,
+];
+
+/// CIE F12 relative spectral power, 380 to 780 nm in 5 nm steps
+const F12_SPD: [f64; 81] = [
+    0.96, 0.64, 0.40, 0.33, 1.19, 12.48, 1.12, 0.94, 1.08, 1.37, 1.78, 29.05, 7.90, 2.65, 2.71,
+    2.65, 2.49, 2.33, 2.10, 1.91, 3.01, 10.83, 11.88, 6.88, 3.43, 1.49, 0.92, 0.71, 0.60, 0.63,
+    1.10, 4.56, 34.40, 65.40, 29.48, 7.16, 3.08, 2.47, 2.27, 5.09, 11.96, 15.32, 14.27, 11.86,
+    9.28, 12.31, 68.53, 53.02, 14.67, 14.38, 14.71, 6.46, 2.57, 2.75, 4.18, 3.44, 2.81, 2.42, 1.64,
+    1.36, 1.49, 2.14, 2.34, 1.42, 1.61, 5.04, 6.98, 3.19, 0.71, 0.30, 0.26, 0.23, 0.28, 0.28, 0.21,
+    0.17, 0.21, 0.19, 0.15, 0.10, 0.05,
+];
+
+/// CIE LED-B1 relative spectral power, 380 to 780 nm in 5 nm steps
+const LED_B1_SPD: [f64; 81] = [
+    0.0193, 0.0374, 0.0725, 0.1404, 0.2721, 0.5270, 1.0208, 1.9891, 3.6965, 6.4648, 10.8160,
+    17.7113, 29.2377, 44.9200, 55.4731, 53.1851, 43.9396, 36.2553, 30.7049, 26.9599, 26.0179,
+    27.2822, 29.9296, 33.6523, 38.0031, 42.5369, 47.0695, 51.5862, 56.1174, 60.7140, 65.4770,
+    70.4283, 75.6176, 81.0696, 86.9956, 93.3241, 100.0000, 106.8367, 113.8730, 120.8314, 127.5190,
+    133.6889, 139.0227, 143.2695, 146.1893, 147.5956, 147.4150, 145.5598, 142.1711, 137.5235,
+    131.8610, 125.2810, 117.9791, 110.1050, 101.8452, 93.5364, 85.3806, 77.4633, 69.9256, 62.8022,
+    56.1261, 49.8988, 44.2596, 39.1453, 34.3787, 29.9754, 26.1677, 22.8306, 19.9075, 17.3617,
+    15.1291, 13.1735, 11.4734, 10.0160, 8.7787, 7.7181, 6.8009, 6.0123, 5.3309, 4.7580, 4.2871,
+];
+
+/// CIE LED-B2 relative spectral power, 380 to 780 nm in 5 nm steps
+const LED_B2_SPD: [f64; 81] = [
+    0.0069, 0.0152, 0.0331, 0.0723, 0.1577, 0.3442, 0.7513, 1.6429, 3.4148, 6.5530, 11.6385,
+    19.1767, 32.0365, 53.5494, 71.8753, 69.6778, 54.0066, 42.5435, 35.5833, 29.6469, 27.2402,
+    28.3988, 31.2520, 35.4635, 40.5252, 45.7298, 50.6497, 55.1578, 59.4859, 63.8927, 68.4400,
+    73.1706, 78.1526, 83.3607, 88.7801, 94.3124, 100.0000, 105.5018, 110.7522, 115.7140, 120.4081,
+    124.5628, 128.1518, 130.9183, 132.7849, 133.3224, 132.5845, 130.5536, 127.3659, 123.1567,
+    118.0077, 112.0924, 105.5337, 98.5944, 91.3310, 83.8899, 76.6372, 69.6582, 62.9595, 56.4950,
+    50.4717, 44.8813, 39.8288, 35.1762, 30.9488, 27.1248, 23.7191, 20.6805, 18.0200, 15.6902,
+    13.6799, 11.8903, 10.3406, 9.0051, 7.8221, 6.7766, 5.9044, 5.1685, 4.5259, 3.9860, 3.5266,
+];
+
+/// CIE LED-B3 relative spectral power, 380 to 780 nm in 5 nm steps
+const LED_B3_SPD: [f64; 81] = [
+    0.0127, 0.0286, 0.0644, 0.1448, 0.3258, 0.7328, 1.6482, 3.7033, 7.9793, 15.7357, 28.1270,
+    47.5688, 80.3264, 117.4025, 125.1182, 96.7028, 65.6839, 49.9165, 39.9228, 33.9098, 34.2645,
+    38.6286, 44.8800, 52.2510, 59.3128, 65.1540, 69.7480, 73.3592, 76.3274, 79.1377, 82.0433,
+    85.1070, 88.3007, 91.4912, 94.5459, 97.3870, 100.0000, 102.2793, 104.2339, 105.8965, 107.3406,
+    108.5571, 109.4257, 109.7644, 109.4070, 108.2235, 106.0997, 103.0811, 99.2030, 94.6135,
+    89.4267, 83.7165, 77.6783, 71.4918, 65.3168, 59.2163, 53.3363, 47.7582, 42.5527, 37.7459,
+    33.3107, 29.2956, 25.7075, 22.4613, 19.5415, 16.9692, 14.7020, 12.7042, 10.9565, 9.4601,
+    8.1567, 7.0273, 6.0405, 5.2000, 4.4801, 3.8673, 3.3355, 2.8777, 2.4836, 2.1463, 1.8560,
+];
+
+/// CIE LED-B4 relative spectral power, 380 to 780 nm in 5 nm steps
+const LED_B4_SPD: [f64; 81] = [
+    0.0040, 0.0105, 0.0275, 0.0716, 0.1867, 0.4864, 1.2673, 3.3033, 7.9820, 16.7574, 30.7071,
+    50.6249, 81.4074, 125.5199, 157.7934, 146.9213, 106.3829, 72.4909, 52.4935, 38.6352, 29.8121,
+    25.8803, 25.5475, 28.6155, 35.1391, 44.3093, 54.4592, 64.5524, 73.7839, 81.3641, 87.1236,
+    91.4903, 94.6563, 96.8708, 98.3752, 99.4772, 100.0000, 100.1120, 99.6366, 98.6836, 97.3390,
+    95.6219, 93.5357, 90.9350, 87.9350, 84.4027, 80.6626, 76.5764, 72.1350, 67.7039, 63.1939,
+    58.7852, 54.2686, 49.8929, 45.7520, 41.7700, 37.8143, 34.1334, 30.7698, 27.6371, 24.6731,
+    22.0323, 19.6441, 17.4595, 15.4218, 13.6577, 12.0986, 10.7031, 9.4284, 8.3205, 7.3486, 6.5361,
+    5.7937, 5.1523, 4.5598, 4.0986, 3.6956, 3.3453, 3.0226, 2.7624, 2.5292,
+];
+
+/// CIE LED-B5 relative spectral power, 380 to 780 nm in 5 nm steps
+const LED_B5_SPD: [f64; 81] = [
+    0.0176, 0.0392, 0.0875, 0.1955, 0.4366, 0.9748, 2.1767, 4.8497, 10.4044, 20.4763, 36.9060,
+    61.6386, 100.3259, 156.2789, 200.9337, 193.7064, 145.7466, 101.8355, 75.0539, 56.4135, 44.8043,
+    40.2459, 40.3138, 43.7992, 50.3207, 58.7140, 67.6064, 75.9069, 83.1043, 88.8103, 93.0898,
+    96.1594, 98.2301, 99.4413, 100.0516, 100.2415, 100.0000, 99.2868, 98.0020, 96.2980, 94.1481,
+    91.7046, 88.8802, 85.6902, 82.1157, 78.1962, 73.9934, 69.6062, 65.0332, 60.4490, 55.8894,
+    51.3952, 47.0193, 42.8423, 38.8601, 35.0841, 31.5547, 28.2598, 25.1989, 22.4370, 19.9768,
+    17.7498, 15.7698, 14.0221, 12.3972, 10.8836, 9.5923, 8.4539, 7.4403, 6.5436, 5.7723, 5.0840,
+    4.4760, 3.9462, 3.4844, 3.0690, 2.7265, 2.4202, 2.1476, 1.9043, 1.7066,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::white_point::{A, D50, D65, F2, F11, white_points_equal};
+
+    #[test]
+    fn test_white_points_match_constants() {
+        let cases = [
+            (StandardIlluminant::A, A),
+            (StandardIlluminant::D50, D50),
+            (StandardIlluminant::D65, D65),
+            (StandardIlluminant::F2, F2),
+            (StandardIlluminant::F11, F11),
+        ];
+        for (illuminant, expected) in cases {
+            let wp = illuminant.white_point(Observer::Cie1931);
+            assert!(
+                white_points_equal(&wp, &expected, 1e-3),
+                "{}: {:?}",
+                illuminant.name(),
+                wp
+            );
+        }
+    }
+
+    #[test]
+    fn test_ten_degree_white_points() {
+        // CIE 015 Table T.3
+        let cases = [
+            (StandardIlluminant::A, [1.11144, 0.35200]),
+            (StandardIlluminant::D50, [0.96720, 0.81427]),
+            (StandardIlluminant::D65, [0.94811, 1.07304]),
+            (StandardIlluminant::F2, [1.03280, 0.69026]),
+        ];
+        for (illuminant, [x, z]) in cases {
+            let wp = illuminant.white_point(Observer::Cie1964).xyz;
+            assert!((wp.x - x).abs() < 3e-4, "{}: {:?}", illuminant.name(), wp);
+            assert!((wp.z - z).abs() < 3e-4, "{}: {:?}", illuminant.name(), wp);
+        }
+    }
+
+    #[test]
+    fn test_tabulated_chromaticities() {
+        // CIE 015:2018 Tables 10.5 and 10.6, 2° observer
+        let cases = [
+            (StandardIlluminant::F1, [0.31310, 0.33727], 6430.0),
+            (StandardIlluminant::F2, [0.37208, 0.37529], 4230.0),
+            (StandardIlluminant::F3, [0.40910, 0.39430], 3450.0),
+            (StandardIlluminant::F4, [0.44018, 0.40329], 2940.0),
+            (StandardIlluminant::F5, [0.31379, 0.34531], 6350.0),
+            (StandardIlluminant::F6, [0.37790, 0.38835], 4150.0),
+            (StandardIlluminant::F7, [0.31292, 0.32933], 6500.0),
+            (StandardIlluminant::F8, [0.34588, 0.35875], 5000.0),
+            (StandardIlluminant::F9, [0.37417, 0.37281], 4150.0),
+            (StandardIlluminant::F11, [0.38052, 0.37713], 4000.0),
+            (StandardIlluminant::F12, [0.43695, 0.40441], 3000.0),
+            (StandardIlluminant::LedB1, [0.4560, 0.4078], 2733.0),
+            (StandardIlluminant::LedB2, [0.4357, 0.4012], 2998.0),
+            (StandardIlluminant::LedB3, [0.3756, 0.3723], 4103.0),
+            (StandardIlluminant::LedB4, [0.3422, 0.3502], 5109.0),
+            (StandardIlluminant::LedB5, [0.3118, 0.3236], 6598.0),
+        ];
+        for (illuminant, [x, y], cct) in cases {
+            let wp = illuminant.white_point(Observer::Cie1931);
+            let (wx, wy) = wp.chromaticity();
+            // The F chromaticities were not summed at 5 nm; y differs by up to 2e-4
+            assert!((wx - x).abs() < 3e-4, "{}: x = {}", illuminant.name(), wx);
+            assert!((wy - y).abs() < 3e-4, "{}: y = {}", illuminant.name(), wy);
+            // F temperatures are published rounded to 10 K
+            assert!(
+                (wp.cct() - cct).abs() < 10.0,
+                "{}: {} K",
+                illuminant.name(),
+                wp.cct()
+            );
+        }
+
+        // The published F10 chromaticity (0.34609, 0.35986) is about 1e-3 off
+        // its own table, so only its temperature is checked
+        let f10 = StandardIlluminant::F10.white_point(Observer::Cie1931);
+        assert!((f10.cct() - 5000.0).abs() < 10.0, "F10: {} K", f10.cct());
+    }
+
+    #[test]
+    fn test_daylight_normalized_at_560() {
+        for cct in [4000.0, 5003.0, 6504.0, 10000.0, 25000.0] {
+            assert!((daylight(cct).value_at(560.0) - 100.0).abs() < 1e-9);
+        }
+        // Canonical D65 values at 10 nm steps
+        let d65 = StandardIlluminant::D65.spd();
+        assert!((d65.value_at(380.0) - 49.9755).abs() < 0.01);
+        assert!((d65.value_at(460.0) - 117.812).abs() < 0.01);
+        assert!((d65.value_at(700.0) - 71.6091).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_blackbody_matches_a() {
+        // A is a 2856 K Planckian radiator on the current radiation constant
+        let a = StandardIlluminant::A.spd();
+        let planck = blackbody(2856.0);
+        for (p, q) in a.values().iter().zip(planck.values()) {
+            assert!((p - q).abs() / q < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! Spectral Colorimetry
+//!
+//! Converts measured spectra to tristimulus values:
+//! - [`Observer`]: CIE 1931 2° and CIE 1964 10° color matching functions
+//! - [`StandardIlluminant`], [`daylight`], [`blackbody`]: illuminant SPDs
+//! - [`SpectralIntegrator`]: reflectance/transmittance → XYZ under any
+//!   illuminant and observer, following ASTM E308
+//! - [`emission_to_xyz`]: emission spectra → absolute XYZ
+//!
+//! # Usage
+//!
+//! ```
+//! use oxcms_core::color::spectral::{Observer, SpectralIntegrator, Spectrum, StandardIlluminant};
+//!
+//! // A flat 50% reflector measured from 400 to 700 nm every 10 nm
+//! let sample = Spectrum::new(400.0, 10.0, vec![0.5; 31]);
+//!
+//! let d50 = SpectralIntegrator::new(StandardIlluminant::D50.spd(), Observer::Cie1931);
+//! let xyz = d50.reflectance_to_xyz(&sample);
+//! assert!((xyz.y - 0.5).abs() < 1e-9);
+//! ```
+
+mod illuminant;
+mod observer;
+mod tristimulus;
+
+pub use illuminant::{StandardIlluminant, blackbody, daylight};
+pub use observer::{CMF_END, CMF_INTERVAL, CMF_START, Observer};
+pub use tristimulus::{SpectralIntegrator, emission_to_xyz};
+
+use crate::{Error, Result};
+
+/// Sprague (1880) end extrapolation coefficients, divided by 209 (CIE 167)
+const SPRAGUE_C: [[f64; 6]; 4] = [
+    [884.0, -1960.0, 3033.0, -2648.0, 1080.0, -180.0],
+    [508.0, -540.0, 488.0, -367.0, 144.0, -24.0],
+    [-24.0, 144.0, -367.0, 488.0, -540.0, 508.0],
+    [-180.0, 1080.0, -2648.0, 3033.0, -1960.0, 884.0],
+];
+
+/// Bandwidth constant of the Stearns & Stearns correction
+const STEARNS_A: f64 = 0.083;
+
+/// Spectral data sampled at uniform wavelength steps
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    start: f64,
+    interval: f64,
+    values: Vec<f64>,
+}
+
+impl Spectrum {
+    /// Create a spectrum from samples starting at `start` nm, `interval` nm apart
+    ///
+    /// # Panics
+    ///
+    /// Panics where [`Spectrum::try_new`] fails.
+    pub fn new(start: f64, interval: f64, values: Vec<f64>) -> Self {
+        Self::try_new(start, interval, values).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a spectrum, checking the wavelength grid
+    ///
+    /// Fails unless `interval` is positive, the wavelengths are all finite
+    /// and `values` is not empty.
+    pub fn try_new(start: f64, interval: f64, values: Vec<f64>) -> Result<Self> {
+        if values.is_empty() {
+            return Err(Error::InvalidArgument(
+                "spectrum needs at least one value".into(),
+            ));
+        }
+        let end = start + (values.len() - 1) as f64 * interval;
+        if interval.is_nan() || interval <= 0.0 || !start.is_finite() || !end.is_finite() {
+            return Err(Error::InvalidArgument(format!(
+                "spectrum from {} nm every {} nm",
+                start, interval
+            )));
+        }
+        Ok(Self {
+            start,
+            interval,
+            values,
+        })
+    }
+
+    /// Sample a function from `start` to `end` nm (inclusive)
+    ///
+    /// # Panics
+    ///
+    /// Panics where [`Spectrum::new`] does.
+    pub fn from_fn(start: f64, end: f64, interval: f64, f: impl Fn(f64) -> f64) -> Self {
+        let count = ((end - start) / interval).round().max(0.0) as usize + 1;
+        let values = (0..count).map(|i| f(start + i as f64 * interval)).collect();
+        Self::new(start, interval, values)
+    }
+
+    /// First wavelength in nm
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    /// Last wavelength in nm
+    pub fn end(&self) -> f64 {
+        self.start + (self.values.len() - 1) as f64 * self.interval
+    }
+
+    /// Wavelength step in nm
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    /// Sample values
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Number of samples
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Always false; a spectrum has at least one sample
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Sample wavelengths in nm
+    pub fn wavelengths(&self) -> impl Iterator<Item = f64> + '_ {
+        (0..self.values.len()).map(|i| self.start + i as f64 * self.interval)
+    }
+
+    /// Value at a wavelength
+    ///
+    /// Linear between samples; outside the range the end values are
+    /// repeated, as ASTM E308 prescribes for truncated measurements.
+    pub fn value_at(&self, wavelength: f64) -> f64 {
+        let last = self.values.len() - 1;
+        let pos = (wavelength - self.start) / self.interval;
+        if pos <= 0.0 {
+            return self.values[0];
+        }
+        if pos >= last as f64 {
+            return self.values[last];
+        }
+        let i = pos.floor() as usize;
+        let t = pos - i as f64;
+        self.values[i] + (self.values[i + 1] - self.values[i]) * t
+    }
+
+    /// Resample over the same range at a finer `interval`
+    ///
+    /// Uses Sprague interpolation (the CIE 167 recommendation) when the
+    /// current interval is a whole multiple of the new one and there are at
+    /// least six samples, and linear interpolation otherwise.
+    pub fn interpolated(&self, interval: f64) -> Self {
+        let ratio = self.interval / interval;
+        let factor = ratio.round() as usize;
+        if self.values.len() < 6 || factor < 1 || (ratio - factor as f64).abs() > 1e-9 {
+            return Self::from_fn(self.start, self.end(), interval, |wl| self.value_at(wl));
+        }
+
+        let n = self.values.len();
+        let y = &self.values;
+        let extrapolate = |coeffs: &[f64; 6], window: &[f64]| {
+            coeffs.iter().zip(window).map(|(c, v)| c * v).sum::<f64>() / 209.0
+        };
+        let mut padded = Vec::with_capacity(n + 4);
+        padded.push(extrapolate(&SPRAGUE_C[0], &y[..6]));
+        padded.push(extrapolate(&SPRAGUE_C[1], &y[..6]));
+        padded.extend_from_slice(y);
+        padded.push(extrapolate(&SPRAGUE_C[2], &y[n - 6..]));
+        padded.push(extrapolate(&SPRAGUE_C[3], &y[n - 6..]));
+
+        let mut values = Vec::with_capacity((n - 1) * factor + 1);
+        for p in padded.windows(6).take(n - 1) {
+            let a0 = p[2];
+            let a1 = (2.0 * p[0] - 16.0 * p[1] + 16.0 * p[3] - 2.0 * p[4]) / 24.0;
+            let a2 = (-p[0] + 16.0 * p[1] - 30.0 * p[2] + 16.0 * p[3] - p[4]) / 24.0;
+            let a3 = (-9.0 * p[0] + 39.0 * p[1] - 70.0 * p[2] + 66.0 * p[3] - 33.0 * p[4]
+                + 7.0 * p[5])
+                / 24.0;
+            let a4 = (13.0 * p[0] - 64.0 * p[1] + 126.0 * p[2] - 124.0 * p[3] + 61.0 * p[4]
+                - 12.0 * p[5])
+                / 24.0;
+            let a5 = (-5.0 * p[0] + 25.0 * p[1] - 50.0 * p[2] + 50.0 * p[3] - 25.0 * p[4]
+                + 5.0 * p[5])
+                / 24.0;
+            for j in 0..factor {
+                let x = j as f64 / factor as f64;
+                values.push(a0 + x * (a1 + x * (a2 + x * (a3 + x * (a4 + x * a5)))));
+            }
+        }
+        values.push(y[n - 1]);
+
+        Self::new(self.start, interval, values)
+    }
+
+    /// Correct for a triangular instrument bandpass equal to the interval
+    ///
+    /// Stearns & Stearns (1988), as referenced by ASTM E308.
+    pub fn bandpass_corrected(&self) -> Self {
+        let v = &self.values;
+        let n = v.len();
+        if n < 3 {
+            return self.clone();
+        }
+        let mut values = Vec::with_capacity(n);
+        values.push((1.0 + STEARNS_A) * v[0] - STEARNS_A * v[1]);
+        for i in 1..n - 1 {
+            values.push(
+                -STEARNS_A * v[i - 1] + (1.0 + 2.0 * STEARNS_A) * v[i] - STEARNS_A * v[i + 1],
+            );
+        }
+        values.push((1.0 + STEARNS_A) * v[n - 1] - STEARNS_A * v[n - 2]);
+        Self::new(self.start, self.interval, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_new() {
+        assert!(Spectrum::try_new(400.0, 10.0, vec![1.0]).is_ok());
+        for (start, interval, len) in [
+            (400.0, 0.0, 3),
+            (400.0, -5.0, 3),
+            (400.0, f64::NAN, 3),
+            (f64::NAN, 10.0, 3),
+            (f64::NEG_INFINITY, 10.0, 3),
+            (400.0, f64::MAX, 3),
+            (400.0, 10.0, 0),
+        ] {
+            assert!(
+                matches!(
+                    Spectrum::try_new(start, interval, vec![0.5; len]),
+                    Err(Error::InvalidArgument(_))
+                ),
+                "{} {} {}",
+                start,
+                interval,
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_value_at() {
+        let s = Spectrum::new(400.0, 10.0, vec![1.0, 3.0, 2.0]);
+        assert_eq!(s.end(), 420.0);
+        assert_eq!(s.value_at(405.0), 2.0);
+        assert_eq!(s.value_at(420.0), 2.0);
+        // Ends are held outside the range
+        assert_eq!(s.value_at(380.0), 1.0);
+        assert_eq!(s.value_at(700.0), 2.0);
+    }
+
+    #[test]
+    fn test_sprague_reproduces_polynomials() {
+        let poly = |x: f64| 1.0 + 0.3 * x - 0.01 * x * x + 1e-4 * x.powi(3);
+        let coarse = Spectrum::from_fn(380.0, 480.0, 5.0, poly);
+        let fine = coarse.interpolated(1.0);
+        assert_eq!(fine.len(), 101);
+        assert_eq!(fine.values()[100], coarse.values()[20]);
+
+        // Exact away from the extrapolated ends
+        for (wl, v) in fine.wavelengths().zip(fine.values()).skip(10).take(80) {
+            assert!((v - poly(wl)).abs() < 1e-9, "{}: {} vs {}", wl, v, poly(wl));
+        }
+    }
+
+    #[test]
+    fn test_linear_fallback() {
+        let s = Spectrum::new(400.0, 10.0, vec![0.0, 1.0, 0.0]);
+        let fine = s.interpolated(5.0);
+        assert_eq!(fine.values(), &[0.0, 0.5, 1.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_bandpass_correction() {
+        // Linear data is unchanged in the interior
+        let s = Spectrum::from_fn(400.0, 500.0, 10.0, |wl| wl / 100.0);
+        let corrected = s.bandpass_corrected();
+        for (a, b) in s.values()[1..10].iter().zip(&corrected.values()[1..10]) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // A peak is sharpened
+        let peak = Spectrum::new(400.0, 10.0, vec![0.2, 0.2, 1.0, 0.2, 0.2]);
+        assert!(peak.bandpass_corrected().values()[2] > 1.0);
+    }
+}
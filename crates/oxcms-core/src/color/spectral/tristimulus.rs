@@ -0,0 +1,311 @@
+//! Tristimulus Integration (ASTM E308)
+//!
+//! Measured spectra are weighted by the illuminant and color matching
+//! functions. Data at 5 nm or finer is summed directly; coarser data (10 or
+//! 20 nm) uses weighting factors derived from the 1 nm illuminant × CMF
+//! products with the Lagrange interpolation of ASTM E2022, which is how the
+//! E308 tables are built. Measurements that do not cover the full 380 to
+//! 780 nm range are extended by repeating their end values.
+
+use super::observer::{CMF_END, CMF_START};
+use super::{Observer, Spectrum};
+use crate::color::{Lab, WhitePoint, Xyz};
+
+/// Maximum luminous efficacy for photopic vision, in lm/W
+const KM: f64 = 683.0;
+
+/// Intervals up to this many nm are summed directly
+const DIRECT_SUM_INTERVAL: usize = 5;
+
+/// Converts spectra to XYZ under one illuminant and observer
+#[derive(Debug, Clone)]
+pub struct SpectralIntegrator {
+    observer: Observer,
+    /// Illuminant × CMF products from CMF_START to CMF_END in 1 nm steps
+    products: Vec<[f64; 3]>,
+    bandpass_correction: bool,
+}
+
+impl SpectralIntegrator {
+    /// Create an integrator for an illuminant SPD and observer
+    pub fn new(illuminant: Spectrum, observer: Observer) -> Self {
+        let illuminant = illuminant.interpolated(1.0);
+        let cmf = cmf_1nm(observer);
+        let products = (0..cmf[0].len())
+            .map(|i| {
+                let s = illuminant.value_at(CMF_START + i as f64);
+                [0, 1, 2].map(|c| s * cmf[c].values()[i])
+            })
+            .collect();
+        Self {
+            observer,
+            products,
+            bandpass_correction: false,
+        }
+    }
+
+    /// Apply the Stearns & Stearns bandpass correction to measurements
+    ///
+    /// Use this for instruments whose bandpass equals the sampling interval
+    /// and that do not correct it themselves.
+    pub fn with_bandpass_correction(mut self, enabled: bool) -> Self {
+        self.bandpass_correction = enabled;
+        self
+    }
+
+    /// The observer in use
+    pub fn observer(&self) -> Observer {
+        self.observer
+    }
+
+    /// White point of the illuminant for this observer (Y = 1)
+    pub fn white_point(&self) -> WhitePoint {
+        let weights = self.weighting_factors(CMF_START, CMF_END, super::CMF_INTERVAL);
+        let sum = weights.iter().fold([0.0; 3], |acc, w| {
+            [acc[0] + w[0], acc[1] + w[1], acc[2] + w[2]]
+        });
+        WhitePoint::from_xyz(Xyz::from_array(sum))
+    }
+
+    /// ASTM E308 weighting factors for a sampling grid
+    ///
+    /// Returns one `[Wx, Wy, Wz]` per wavelength from `start` to `end`,
+    /// normalized so that the Wy sum to 1.
+    pub fn weighting_factors(&self, start: f64, end: f64, interval: f64) -> Vec<[f64; 3]> {
+        let r = interval.round().max(1.0) as usize;
+        let n = ((end - start) / r as f64).round().max(0.0) as usize;
+        let mut weights = vec![[0.0; 3]; n + 1];
+
+        if r <= DIRECT_SUM_INTERVAL || n < 3 {
+            for (k, w) in weights.iter_mut().enumerate() {
+                *w = self.product_at(start + (k * r) as f64);
+            }
+        } else {
+            let mut add = |k: usize, coeff: f64, p: [f64; 3]| {
+                for c in 0..3 {
+                    weights[k][c] += coeff * p[c];
+                }
+            };
+            for j in 0..=n * r {
+                let p = self.product_at(start + j as f64);
+                let (k, rem) = (j / r, j % r);
+                if rem == 0 {
+                    add(k, 1.0, p);
+                    continue;
+                }
+                let x = rem as f64 / r as f64;
+                if k == 0 {
+                    for (i, c) in lagrange_boundary(x).into_iter().enumerate() {
+                        add(i, c, p);
+                    }
+                } else if k == n - 1 {
+                    for (i, c) in lagrange_boundary(1.0 - x).into_iter().enumerate() {
+                        add(n - i, c, p);
+                    }
+                } else {
+                    for (i, c) in lagrange_inner(x).into_iter().enumerate() {
+                        add(k + i - 1, c, p);
+                    }
+                }
+            }
+        }
+
+        let sum_y: f64 = weights.iter().map(|w| w[1]).sum();
+        if sum_y > 0.0 {
+            for w in &mut weights {
+                for v in w.iter_mut() {
+                    *v /= sum_y;
+                }
+            }
+        }
+        weights
+    }
+
+    /// Convert a reflectance or transmittance spectrum (0 to 1) to XYZ
+    ///
+    /// A perfect reflector gives the illuminant white point with Y = 1.
+    pub fn reflectance_to_xyz(&self, sample: &Spectrum) -> Xyz {
+        let mut r = sample.interval().round().max(1.0);
+        let resampled;
+        let mut sample = if (sample.interval() - r).abs() > 1e-9 {
+            r = 1.0;
+            resampled = sample.interpolated(1.0);
+            &resampled
+        } else {
+            sample
+        };
+        let corrected;
+        if self.bandpass_correction {
+            corrected = sample.bandpass_corrected();
+            sample = &corrected;
+        }
+
+        // Extend on the sample's grid to cover the CMF range; the spectrum
+        // constructor guarantees finite ends, so the step counts are finite
+        let below = ((sample.start() - CMF_START) / r).ceil().max(0.0);
+        let above = ((CMF_END - sample.end()) / r).ceil().max(0.0);
+        let start = sample.start() - below * r;
+        let end = sample.end() + above * r;
+
+        let weights = self.weighting_factors(start, end, r);
+        let mut xyz = [0.0; 3];
+        for (k, w) in weights.iter().enumerate() {
+            let v = sample.value_at(start + k as f64 * r);
+            for c in 0..3 {
+                xyz[c] += w[c] * v;
+            }
+        }
+        Xyz::from_array(xyz)
+    }
+
+    /// Convert a reflectance spectrum to CIELAB relative to this illuminant
+    pub fn reflectance_to_lab(&self, sample: &Spectrum) -> Lab {
+        Lab::from_xyz_with_white(self.reflectance_to_xyz(sample), &self.white_point())
+    }
+
+    /// Illuminant × CMF product at a wavelength
+    fn product_at(&self, wavelength: f64) -> [f64; 3] {
+        let pos = wavelength - CMF_START;
+        let last = self.products.len() - 1;
+        if pos < 0.0 || pos > last as f64 {
+            return [0.0; 3];
+        }
+        let i = (pos.floor() as usize).min(last.saturating_sub(1));
+        let t = pos - i as f64;
+        let (a, b) = (self.products[i], self.products[(i + 1).min(last)]);
+        [0, 1, 2].map(|c| a[c] + (b[c] - a[c]) * t)
+    }
+}
+
+/// Convert an emission spectrum to absolute XYZ
+///
+/// With spectral radiance in W·sr⁻¹·m⁻²·nm⁻¹ the result is in cd/m²; with
+/// spectral irradiance in W·m⁻²·nm⁻¹ it is in lux.
+pub fn emission_to_xyz(spd: &Spectrum, observer: Observer) -> Xyz {
+    let cmf = cmf_1nm(observer);
+    let mut xyz = [0.0; 3];
+    // value_at would hold the end values, so skip anything outside the table
+    for (wl, v) in spd
+        .wavelengths()
+        .zip(spd.values())
+        .filter(|(wl, _)| (CMF_START..=CMF_END).contains(wl))
+    {
+        for c in 0..3 {
+            xyz[c] += v * cmf[c].value_at(wl) * spd.interval();
+        }
+    }
+    Xyz::from_array(xyz.map(|v| v * KM))
+}
+
+/// Color matching functions at 1 nm by Sprague interpolation
+fn cmf_1nm(observer: Observer) -> [Spectrum; 3] {
+    observer.cmf().map(|s| s.interpolated(1.0))
+}
+
+/// Quadratic Lagrange coefficients for samples 0, 1, 2 at `x` in (0, 1)
+fn lagrange_boundary(x: f64) -> [f64; 3] {
+    [
+        (x - 1.0) * (x - 2.0) / 2.0,
+        -x * (x - 2.0),
+        x * (x - 1.0) / 2.0,
+    ]
+}
+
+/// Cubic Lagrange coefficients for samples -1, 0, 1, 2 at `x` in (0, 1)
+fn lagrange_inner(x: f64) -> [f64; 4] {
+    [
+        -x * (x - 1.0) * (x - 2.0) / 6.0,
+        (x + 1.0) * (x - 1.0) * (x - 2.0) / 2.0,
+        -(x + 1.0) * x * (x - 2.0) / 2.0,
+        (x + 1.0) * x * (x - 1.0) / 6.0,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::spectral::StandardIlluminant;
+
+    /// A smooth orange-ish reflectance
+    fn sample(wl: f64) -> f64 {
+        0.1 + 0.7 / (1.0 + (-(wl - 580.0) / 25.0).exp())
+    }
+
+    #[test]
+    fn test_perfect_reflector_is_white() {
+        let d65 = SpectralIntegrator::new(StandardIlluminant::D65.spd(), Observer::Cie1931);
+        let white = Spectrum::new(CMF_START, 5.0, vec![1.0; 81]);
+        let xyz = d65.reflectance_to_xyz(&white);
+        assert!(xyz.approx_eq(&d65.white_point().xyz, 1e-12));
+        assert!((xyz.y - 1.0).abs() < 1e-12);
+        assert!((xyz.x - 0.95047).abs() < 1e-4 && (xyz.z - 1.08883).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_coarse_intervals_agree() {
+        for illuminant in [StandardIlluminant::D65, StandardIlluminant::A] {
+            let integrator = SpectralIntegrator::new(illuminant.spd(), Observer::Cie1964);
+            let fine = integrator.reflectance_to_xyz(&Spectrum::from_fn(380.0, 780.0, 1.0, sample));
+            for interval in [5.0, 10.0, 20.0] {
+                let coarse = Spectrum::from_fn(380.0, 780.0, interval, sample);
+                let xyz = integrator.reflectance_to_xyz(&coarse);
+                assert!(
+                    xyz.approx_eq(&fine, 1e-3),
+                    "{}: {:?} vs {:?}",
+                    interval,
+                    xyz,
+                    fine
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighting_factors_normalized() {
+        let d50 = SpectralIntegrator::new(StandardIlluminant::D50.spd(), Observer::Cie1931);
+        for interval in [1.0, 5.0, 10.0, 20.0] {
+            let weights = d50.weighting_factors(380.0, 780.0, interval);
+            assert_eq!(weights.len(), (400.0 / interval) as usize + 1);
+            let sum: f64 = weights.iter().map(|w| w[1]).sum();
+            assert!((sum - 1.0).abs() < 1e-12);
+        }
+        // The same white results from any grid
+        let white = d50.white_point().xyz;
+        let weights = d50.weighting_factors(380.0, 780.0, 10.0);
+        let x: f64 = weights.iter().map(|w| w[0]).sum();
+        assert!((x - white.x).abs() < 2e-4, "{} vs {}", x, white.x);
+    }
+
+    #[test]
+    fn test_truncated_range_extends_ends() {
+        let d50 = SpectralIntegrator::new(StandardIlluminant::D50.spd(), Observer::Cie1931);
+        let grey = Spectrum::new(400.0, 10.0, vec![0.18; 31]);
+        let xyz = d50.reflectance_to_xyz(&grey);
+        let white = d50.white_point().xyz;
+        assert!(xyz.approx_eq(&white.scale(0.18), 2e-4), "{:?}", xyz);
+
+        let lab = d50.reflectance_to_lab(&grey);
+        assert!(lab.a.abs() < 0.05 && lab.b.abs() < 0.05, "{:?}", lab);
+    }
+
+    #[test]
+    fn test_bandpass_correction_keeps_flat_spectra() {
+        let d65 = SpectralIntegrator::new(StandardIlluminant::D65.spd(), Observer::Cie1931)
+            .with_bandpass_correction(true);
+        let grey = Spectrum::new(380.0, 10.0, vec![0.5; 41]);
+        assert!((d65.reflectance_to_xyz(&grey).y - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_emission_is_absolute() {
+        // 1 W·sr⁻¹·m⁻²·nm⁻¹ across the visible range: Y = 683 × ∫ȳ
+        let flat = Spectrum::new(380.0, 5.0, vec![1.0; 81]);
+        let xyz = emission_to_xyz(&flat, Observer::Cie1931);
+        assert!((xyz.y / (683.0 * 106.857) - 1.0).abs() < 1e-5, "{:?}", xyz);
+
+        // Energy outside the table does not count
+        let wide = Spectrum::new(300.0, 5.0, vec![1.0; 121]);
+        let wide_xyz = emission_to_xyz(&wide, Observer::Cie1931);
+        assert!(wide_xyz.approx_eq(&xyz, 1e-6), "{:?}", wide_xyz);
+    }
+}
@@ -0,0 +1,199 @@
+//! Correlated Color Temperature
+//!
+//! Relates chromaticities to the Planckian and CIE daylight loci:
+//! - [`xy_to_cct_duv`]: CCT and Duv with Ohno's (2013) combined triangular
+//!   and parabolic solution
+//! - [`cct_duv_to_xy`]: chromaticity at a CCT and distance from the
+//!   Planckian locus
+//! - [`daylight_chromaticity`]: the CIE daylight locus
+//!
+//! Duv is the signed distance from the Planckian locus in the CIE 1960 UCS;
+//! positive values lie above the locus (greenish), negative below (pinkish).
+//! The locus is computed from Planck's law with the CIE 1931 2° observer.
+
+use crate::color::spectral::{Observer, blackbody};
+
+/// Search range of the Planckian locus, in kelvin
+const CCT_MIN: f64 = 1000.0;
+const CCT_MAX: f64 = 100000.0;
+
+/// Samples per cascade step and number of steps
+const CASCADE_SAMPLES: usize = 16;
+const CASCADE_STEPS: usize = 6;
+
+/// Below this |Duv| the triangular solution is used, above it the parabolic
+const TRIANGULAR_DUV_LIMIT: f64 = 0.002;
+
+/// Ohno's correction of the parabolic CCT
+const PARABOLIC_CCT_CORRECTION: f64 = 0.99991;
+
+/// CCT in kelvin and Duv of a chromaticity
+///
+/// The CCT is clamped to 1000 K to 100000 K. Within |Duv| < 0.002 it is
+/// accurate to a fraction of a kelvin; further from the locus the parabolic
+/// solution applies Ohno's 0.99991 factor, as published, so CCTs read
+/// 0.009% low against the exact locus distance.
+pub fn xy_to_cct_duv(x: f64, y: f64) -> (f64, f64) {
+    let (u, v) = xy_to_uv1960(x, y);
+    let distance = |t: f64| {
+        let (ut, vt) = planckian_uv(t);
+        (u - ut).hypot(v - vt)
+    };
+
+    // Narrow a geometric table around the nearest point on the locus
+    let (mut lo, mut hi) = (CCT_MIN, CCT_MAX);
+    let mut nearest = [(lo, 0.0); 3];
+    for _ in 0..CASCADE_STEPS {
+        let ratio = (hi / lo).powf(1.0 / (CASCADE_SAMPLES - 1) as f64);
+        let table: Vec<(f64, f64)> = (0..CASCADE_SAMPLES)
+            .map(|i| {
+                let t = lo * ratio.powi(i as i32);
+                (t, distance(t))
+            })
+            .collect();
+        let i = (0..CASCADE_SAMPLES)
+            .min_by(|&a, &b| table[a].1.total_cmp(&table[b].1))
+            .unwrap_or(0)
+            .clamp(1, CASCADE_SAMPLES - 2);
+        nearest = [table[i - 1], table[i], table[i + 1]];
+        lo = nearest[0].0;
+        hi = nearest[2].0;
+    }
+    let [(t0, d0), (t1, d1), (t2, d2)] = nearest;
+
+    // Triangular solution
+    let (u0, v0) = planckian_uv(t0);
+    let (u2, v2) = planckian_uv(t2);
+    let l = (u2 - u0).hypot(v2 - v0);
+    let along = (d0 * d0 - d2 * d2 + l * l) / (2.0 * l);
+    let cct = t0 + (t2 - t0) * along / l;
+    let v_locus = v0 + (v2 - v0) * along / l;
+    let sign = if v >= v_locus { 1.0 } else { -1.0 };
+    let duv = sign * (d0 * d0 - along * along).max(0.0).sqrt();
+    if duv.abs() < TRIANGULAR_DUV_LIMIT {
+        return (cct, duv);
+    }
+
+    // Parabolic solution
+    let den = (t2 - t1) * (t0 - t2) * (t1 - t0);
+    let a = (t0 * (d2 - d1) + t1 * (d0 - d2) + t2 * (d1 - d0)) / den;
+    let b = -(t0 * t0 * (d2 - d1) + t1 * t1 * (d0 - d2) + t2 * t2 * (d1 - d0)) / den;
+    let c = -(d0 * (t2 - t1) * t1 * t2 + d1 * (t0 - t2) * t0 * t2 + d2 * (t1 - t0) * t0 * t1) / den;
+    let cct = -b / (2.0 * a);
+    (
+        cct * PARABOLIC_CCT_CORRECTION,
+        sign * (a * cct * cct + b * cct + c),
+    )
+}
+
+/// Chromaticity at a CCT in kelvin and Duv from the Planckian locus
+pub fn cct_duv_to_xy(cct: f64, duv: f64) -> (f64, f64) {
+    let (u0, v0) = planckian_uv(cct);
+    let (u1, v1) = planckian_uv(cct + 0.01);
+    let l = (u1 - u0).hypot(v1 - v0);
+    let u = u0 + duv * (v1 - v0) / l;
+    let v = v0 - duv * (u1 - u0) / l;
+    uv1960_to_xy(u, v)
+}
+
+/// Chromaticity of CIE daylight at a CCT in kelvin (CIE 015)
+///
+/// The daylight locus is defined from 4000 K to 25000 K; temperatures
+/// outside are clamped.
+pub fn daylight_chromaticity(cct: f64) -> (f64, f64) {
+    let t = cct.clamp(4000.0, 25000.0);
+    let x = if t <= 7000.0 {
+        -4.6070e9 / t.powi(3) + 2.9678e6 / t.powi(2) + 0.09911e3 / t + 0.244063
+    } else {
+        -2.0064e9 / t.powi(3) + 1.9018e6 / t.powi(2) + 0.24748e3 / t + 0.237040
+    };
+    (x, -3.0 * x * x + 2.870 * x - 0.275)
+}
+
+/// CIE 1960 UCS coordinates of a Planckian radiator
+fn planckian_uv(cct: f64) -> (f64, f64) {
+    let spd = blackbody(cct);
+    let mut xyz = [0.0; 3];
+    for (s, cmf) in spd.values().iter().zip(Observer::Cie1931.table()) {
+        for c in 0..3 {
+            xyz[c] += s * cmf[c];
+        }
+    }
+    let den = xyz[0] + 15.0 * xyz[1] + 3.0 * xyz[2];
+    (4.0 * xyz[0] / den, 6.0 * xyz[1] / den)
+}
+
+fn xy_to_uv1960(x: f64, y: f64) -> (f64, f64) {
+    let den = -2.0 * x + 12.0 * y + 3.0;
+    (4.0 * x / den, 6.0 * y / den)
+}
+
+fn uv1960_to_xy(u: f64, v: f64) -> (f64, f64) {
+    let den = 2.0 * u - 8.0 * v + 4.0;
+    (3.0 * u / den, 2.0 * v / den)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_illuminants() {
+        // CIE illuminant A is a 2856 K Planckian radiator
+        let (cct, duv) = xy_to_cct_duv(0.44757, 0.40745);
+        assert!((cct - 2856.0).abs() < 2.0, "{}", cct);
+        assert!(duv.abs() < 1e-4, "{}", duv);
+
+        // D65 sits above the Planckian locus
+        let (cct, duv) = xy_to_cct_duv(0.31271, 0.32902);
+        assert!((cct - 6504.0).abs() < 3.0, "{}", cct);
+        assert!((duv - 0.0032).abs() < 1e-4, "{}", duv);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for cct in [1500.0, 2700.0, 5000.0, 9300.0, 30000.0] {
+            for duv in [-0.02, -0.001, 0.0, 0.001, 0.02] {
+                let (x, y) = cct_duv_to_xy(cct, duv);
+                let (cct_back, duv_back) = xy_to_cct_duv(x, y);
+                assert!(
+                    (cct_back / cct - 1.0).abs() < 1e-4,
+                    "{} {}: {}",
+                    cct,
+                    duv,
+                    cct_back
+                );
+                assert!(
+                    (duv_back - duv).abs() < 1e-5,
+                    "{} {}: {}",
+                    cct,
+                    duv,
+                    duv_back
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parabolic_correction() {
+        for cct in [2700.0, 9300.0] {
+            let (x, y) = cct_duv_to_xy(cct, 0.02);
+            let (cct_back, _) = xy_to_cct_duv(x, y);
+            assert!(
+                (cct_back / cct - PARABOLIC_CCT_CORRECTION).abs() < 1e-6,
+                "{}: {}",
+                cct,
+                cct_back
+            );
+        }
+    }
+
+    #[test]
+    fn test_daylight_chromaticity() {
+        // D65 is daylight at 6500 × 1.4388 / 1.4380 K
+        let (x, y) = daylight_chromaticity(6504.0);
+        assert!((x - 0.31271).abs() < 1e-4 && (y - 0.32902).abs() < 1e-4);
+        let (x, y) = daylight_chromaticity(5003.0);
+        assert!((x - 0.34567).abs() < 1e-4 && (y - 0.35851).abs() < 1e-4);
+    }
+}
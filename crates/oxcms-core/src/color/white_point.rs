@@ -6,6 +6,7 @@
 //! Values are from CIE standards and ICC.1:2022.
 
 use crate::color::Xyz;
+use crate::color::temperature::{cct_duv_to_xy, daylight_chromaticity, xy_to_cct_duv};
 
 /// A white point definition
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -33,6 +34,42 @@ impl WhitePoint {
         }
     }
 
+    /// Create a white point from chromaticity coordinates (unnamed, Y = 1)
+    ///
+    /// Like [`Xyz::from_xyy`], a `y` of zero or less gives all-zero XYZ
+    /// rather than infinities.
+    pub fn from_chromaticity(x: f64, y: f64) -> Self {
+        Self::from_xyz(Xyz::from_xyy(x, y, 1.0))
+    }
+
+    /// White point on the CIE daylight locus at a CCT in kelvin
+    ///
+    /// The locus is defined from 4000 K to 25000 K; temperatures outside
+    /// are clamped. D65 is `from_daylight_cct(6504.0)`.
+    pub fn from_daylight_cct(cct: f64) -> Self {
+        let (x, y) = daylight_chromaticity(cct);
+        Self::from_chromaticity(x, y)
+    }
+
+    /// White point at a CCT in kelvin and Duv from the Planckian locus
+    ///
+    /// A Duv of 0 gives a blackbody radiator.
+    pub fn from_planckian_cct(cct: f64, duv: f64) -> Self {
+        let (x, y) = cct_duv_to_xy(cct, duv);
+        Self::from_chromaticity(x, y)
+    }
+
+    /// Correlated color temperature in kelvin and Duv (Ohno 2013)
+    pub fn cct_duv(&self) -> (f64, f64) {
+        let (x, y) = self.chromaticity();
+        xy_to_cct_duv(x, y)
+    }
+
+    /// Correlated color temperature in kelvin
+    pub fn cct(&self) -> f64 {
+        self.cct_duv().0
+    }
+
     /// Get the chromaticity coordinates (x, y)
     pub fn chromaticity(&self) -> (f64, f64) {
         let sum = self.xyz.x + self.xyz.y + self.xyz.z;
@@ -158,6 +195,31 @@ mod tests {
         assert!((y - 0.3290).abs() < 0.001);
     }
 
+    #[test]
+    fn test_from_chromaticity() {
+        let d65 = WhitePoint::from_chromaticity(0.3127, 0.3290);
+        assert!(white_points_equal(&d65, &D65, 0.001));
+        assert_eq!(d65.xyz.y, 1.0);
+
+        let degenerate = WhitePoint::from_chromaticity(0.3, 0.0);
+        assert_eq!(degenerate.xyz, Xyz::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_cct() {
+        let (cct, duv) = D65.cct_duv();
+        assert!((cct - 6504.0).abs() < 5.0, "{}", cct);
+        assert!((duv - 0.0032).abs() < 2e-4, "{}", duv);
+        assert!((D50.cct() - 5003.0).abs() < 5.0);
+
+        let d65 = WhitePoint::from_daylight_cct(6504.0);
+        assert!(white_points_equal(&d65, &D65, 0.001));
+
+        let warm = WhitePoint::from_planckian_cct(3200.0, 0.0);
+        let (cct, duv) = warm.cct_duv();
+        assert!((cct - 3200.0).abs() < 0.5 && duv.abs() < 1e-6);
+    }
+
     #[test]
     fn test_from_name() {
         assert!(from_name("D50").is_some());
@@ -26,6 +26,16 @@
 //! XYB is **not** an ICC profile color space - it must be handled outside
 //! the ICC pipeline. For JPEG XL decoding, convert XYB to linear RGB first,
 //! then apply ICC transforms.
+//!
+//! The free functions use the default JPEG XL constants and linear sRGB.
+//! [`XybParams`] carries custom opsin matrices and biases from a JPEG XL
+//! header and can retarget XYB to the primaries of any RGB matrix-shaper
+//! profile; `Transform::new_from_xyb` and `Transform::new_to_xyb` link XYB
+//! directly with an ICC profile.
+
+use crate::color::xyz::convert_buffer;
+use crate::math::Matrix3x3;
+use crate::{ColorProfile, Error, Result};
 
 /// Bias added before cube root in forward transform
 pub const BIAS: f64 = 0.003_793_073_255_275_449_3;
@@ -33,6 +43,37 @@ pub const BIAS: f64 = 0.003_793_073_255_275_449_3;
 /// Cube root of BIAS, subtracted after cube root
 pub const BIAS_CBRT: f64 = 0.155_954_200_549_248_63;
 
+/// Default JPEG XL opsin inverse matrix (mixed LMS → linear sRGB)
+pub const DEFAULT_OPSIN_INVERSE: Matrix3x3 = Matrix3x3::new([
+    [11.031566901960783, -9.866943921568629, -0.16462299647058826],
+    [-3.254147380392157, 4.418770392156863, -0.16462299647058826],
+    [-3.6588512862745097, 2.7129230470588235, 1.9459282392156863],
+]);
+
+/// Default JPEG XL opsin absorbance matrix (linear sRGB → mixed LMS)
+const DEFAULT_OPSIN: Matrix3x3 = Matrix3x3::new([
+    [0.3, 0.622, 0.078],
+    [0.23, 0.692, 0.078],
+    [
+        0.243_422_689_245_478_2,
+        0.204_767_444_244_968_2,
+        0.551_809_866_509_553_5,
+    ],
+]);
+
+/// Default JPEG XL opsin biases (negated [`BIAS`], as stored in the header)
+pub const DEFAULT_OPSIN_BIASES: [f64; 3] = [-BIAS, -BIAS, -BIAS];
+
+/// Default JPEG XL dequantization biases
+pub const DEFAULT_QUANT_BIASES: [f64; 3] = [
+    1.0 - 0.054_650_073_307_154_01,
+    1.0 - 0.070_054_498_917_485_93,
+    1.0 - 0.049_935_103_337_343_655,
+];
+
+/// Default JPEG XL dequantization bias numerator
+pub const DEFAULT_QUANT_BIAS_NUMERATOR: f64 = 0.145;
+
 /// XYB color value
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Xyb {
@@ -239,6 +280,147 @@ pub fn linear_rgb_to_xyb_buffer(rgb_data: &[f32], xyb_out: &mut [f32]) {
     }
 }
 
+/// Parameters of a JPEG XL XYB encoding
+///
+/// The opsin inverse matrix maps the mixed LMS response to linear RGB;
+/// the JPEG XL defaults target linear sRGB. The quantization biases are
+/// carried for decoders and do not take part in the color conversion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XybParams {
+    opsin_inverse: Matrix3x3,
+    opsin: Matrix3x3,
+    opsin_biases: [f64; 3],
+    quant_biases: [f64; 3],
+    quant_bias_numerator: f64,
+}
+
+impl Default for XybParams {
+    fn default() -> Self {
+        Self {
+            opsin_inverse: DEFAULT_OPSIN_INVERSE,
+            opsin: DEFAULT_OPSIN,
+            opsin_biases: DEFAULT_OPSIN_BIASES,
+            quant_biases: DEFAULT_QUANT_BIASES,
+            quant_bias_numerator: DEFAULT_QUANT_BIAS_NUMERATOR,
+        }
+    }
+}
+
+impl XybParams {
+    /// Create parameters from a custom opsin inverse matrix and biases
+    ///
+    /// Biases follow the JPEG XL header convention (negative values).
+    /// Fails if the matrix is singular.
+    pub fn new(opsin_inverse: Matrix3x3, opsin_biases: [f64; 3]) -> Result<Self> {
+        let opsin = opsin_inverse
+            .inverse()
+            .ok_or_else(|| Error::Transform("XYB opsin inverse matrix is singular".into()))?;
+        Ok(Self {
+            opsin_inverse,
+            opsin,
+            opsin_biases,
+            ..Self::default()
+        })
+    }
+
+    /// Set the dequantization biases
+    pub fn with_quant_biases(mut self, biases: [f64; 3], numerator: f64) -> Self {
+        self.quant_biases = biases;
+        self.quant_bias_numerator = numerator;
+        self
+    }
+
+    /// Opsin inverse matrix (mixed LMS → linear RGB)
+    pub fn opsin_inverse(&self) -> Matrix3x3 {
+        self.opsin_inverse
+    }
+
+    /// Opsin biases, JPEG XL header convention
+    pub fn opsin_biases(&self) -> [f64; 3] {
+        self.opsin_biases
+    }
+
+    /// Dequantization biases
+    pub fn quant_biases(&self) -> [f64; 3] {
+        self.quant_biases
+    }
+
+    /// Dequantization bias numerator
+    pub fn quant_bias_numerator(&self) -> f64 {
+        self.quant_bias_numerator
+    }
+
+    /// Retarget these parameters from linear sRGB to other primaries
+    ///
+    /// `rgb_to_xyz` is the D50-adapted colorant matrix of the target
+    /// primaries, as stored in an ICC matrix-shaper profile.
+    pub fn for_primaries(&self, rgb_to_xyz: Matrix3x3) -> Result<Self> {
+        let srgb_to_xyz = Matrix3x3::new(ColorProfile::new_srgb().colorant_matrix().m);
+        let (Some(xyz_to_rgb), Some(xyz_to_srgb)) = (rgb_to_xyz.inverse(), srgb_to_xyz.inverse())
+        else {
+            return Err(Error::Transform("Singular colorant matrix".into()));
+        };
+        Ok(Self {
+            opsin_inverse: xyz_to_rgb
+                .multiply(&srgb_to_xyz)
+                .multiply(&self.opsin_inverse),
+            opsin: self.opsin.multiply(&xyz_to_srgb).multiply(&rgb_to_xyz),
+            ..*self
+        })
+    }
+
+    /// Retarget these parameters to the primaries of an RGB matrix-shaper profile
+    pub fn for_profile(&self, profile: &ColorProfile) -> Result<Self> {
+        if profile.color_space() != crate::ColorSpace::Rgb || !profile.is_matrix_shaper() {
+            return Err(Error::UnsupportedColorSpace(
+                "XYB primaries need an RGB matrix-shaper profile".into(),
+            ));
+        }
+        self.for_primaries(Matrix3x3::new(profile.colorant_matrix().m))
+    }
+
+    /// Convert linear RGB to XYB
+    pub fn linear_rgb_to_xyb(&self, rgb: [f64; 3]) -> Xyb {
+        let mixed = self.opsin.multiply_vec(rgb);
+        let mut gamma = [0.0; 3];
+        for c in 0..3 {
+            let bias = self.opsin_biases[c];
+            gamma[c] = cbrt(mixed[c] - bias) + cbrt(bias);
+        }
+        Xyb {
+            x: (gamma[0] - gamma[1]) * 0.5,
+            y: (gamma[0] + gamma[1]) * 0.5,
+            b: gamma[2] - gamma[1],
+        }
+    }
+
+    /// Convert XYB to linear RGB
+    pub fn xyb_to_linear_rgb(&self, xyb: &Xyb) -> [f64; 3] {
+        let gamma = [xyb.y + xyb.x, xyb.y - xyb.x, xyb.y - xyb.x + xyb.b];
+        let mut mixed = [0.0; 3];
+        for c in 0..3 {
+            let bias = self.opsin_biases[c];
+            let g = gamma[c] - cbrt(bias);
+            mixed[c] = g * g * g + bias;
+        }
+        self.opsin_inverse.multiply_vec(mixed)
+    }
+
+    /// Transform a buffer of linear RGB f32 triplets to XYB
+    pub fn linear_rgb_to_xyb_buffer(&self, rgb_data: &[f32], xyb_out: &mut [f32]) {
+        convert_buffer(rgb_data, xyb_out, |rgb| {
+            self.linear_rgb_to_xyb(rgb).to_array()
+        });
+    }
+
+    /// Transform a buffer of XYB f32 triplets to linear RGB
+    pub fn xyb_to_linear_rgb_buffer(&self, xyb_data: &[f32], rgb_out: &mut [f32]) {
+        convert_buffer(xyb_data, rgb_out, |xyb| {
+            self.xyb_to_linear_rgb(&Xyb::from_array(xyb))
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,4 +514,79 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_params_default_matches_free_functions() {
+        let params = XybParams::default();
+        for rgb in [
+            [0.5, 0.3, 0.7],
+            [1.0, 1.0, 1.0],
+            [0.0, 0.0, 0.0],
+            [0.9, 0.1, 0.0],
+        ] {
+            let expected = linear_rgb_to_xyb(rgb[0], rgb[1], rgb[2]);
+            let xyb = params.linear_rgb_to_xyb(rgb);
+            for (a, b) in xyb.to_array().iter().zip(expected.to_array()) {
+                assert!((a - b).abs() < 1e-12, "{:?} vs {:?}", xyb, expected);
+            }
+
+            let (r, g, b) = xyb_to_linear_rgb(&xyb);
+            let back = params.xyb_to_linear_rgb(&xyb);
+            assert!((back[0] - r).abs() < 1e-12);
+            assert!((back[1] - g).abs() < 1e-12);
+            assert!((back[2] - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_params_for_primaries() {
+        let params = XybParams::default();
+        let srgb = params.for_profile(&ColorProfile::new_srgb()).unwrap();
+        let p3 = params.for_profile(&ColorProfile::new_display_p3()).unwrap();
+        let srgb_to_xyz = Matrix3x3::new(ColorProfile::new_srgb().colorant_matrix().m);
+        let p3_to_xyz = Matrix3x3::new(ColorProfile::new_display_p3().colorant_matrix().m);
+
+        let xyb = srgb_to_xyb(200, 40, 90);
+        let via_srgb = srgb.xyb_to_linear_rgb(&xyb);
+        let via_p3 = p3.xyb_to_linear_rgb(&xyb);
+        let xyz_a = srgb_to_xyz.multiply_vec(via_srgb);
+        let xyz_b = p3_to_xyz.multiply_vec(via_p3);
+        for c in 0..3 {
+            assert!(
+                (xyz_a[c] - xyz_b[c]).abs() < 1e-9,
+                "{:?} vs {:?}",
+                xyz_a,
+                xyz_b
+            );
+        }
+
+        // Encoding from P3 primaries lands on the same XYB value
+        let back = p3.linear_rgb_to_xyb(via_p3);
+        for (a, b) in back.to_array().iter().zip(xyb.to_array()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+
+        assert!(matches!(
+            params.for_profile(&ColorProfile::new_lab()),
+            Err(Error::UnsupportedColorSpace(_))
+        ));
+    }
+
+    #[test]
+    fn test_custom_params() {
+        let singular = Matrix3x3::new([[1.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        assert!(XybParams::new(singular, DEFAULT_OPSIN_BIASES).is_err());
+
+        let params = XybParams::new(DEFAULT_OPSIN_INVERSE, [-0.01, -0.004, -0.002])
+            .unwrap()
+            .with_quant_biases([0.9; 3], 0.2);
+        assert_eq!(params.quant_biases(), [0.9; 3]);
+        assert_eq!(params.quant_bias_numerator(), 0.2);
+
+        let rgb = [0.25, 0.5, 0.75];
+        let back = params.xyb_to_linear_rgb(&params.linear_rgb_to_xyb(rgb));
+        for c in 0..3 {
+            assert!((back[c] - rgb[c]).abs() < 1e-9);
+        }
+    }
 }
@@ -164,6 +164,21 @@ impl Mul<f64> for Xyz {
     }
 }
 
+/// Apply a per-color conversion to a buffer of f32 triplets
+///
+/// Shared by the `*_buffer` functions of the color space modules.
+pub(crate) fn convert_buffer(input: &[f32], output: &mut [f32], f: impl Fn([f64; 3]) -> [f64; 3]) {
+    assert_eq!(input.len(), output.len());
+    assert_eq!(input.len() % 3, 0);
+
+    for (src, dst) in input.chunks_exact(3).zip(output.chunks_exact_mut(3)) {
+        let out = f([src[0] as f64, src[1] as f64, src[2] as f64]);
+        dst[0] = out[0] as f32;
+        dst[1] = out[1] as f32;
+        dst[2] = out[2] as f32;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -13,6 +13,10 @@ pub enum Error {
     #[error("Profile parse error: {0}")]
     ProfileParse(String),
 
+    /// Native ICC parser error
+    #[error("ICC error: {0}")]
+    Icc(#[from] crate::icc::IccError),
+
     /// Invalid ICC profile structure
     #[error("Invalid profile: {0}")]
     InvalidProfile(String),
@@ -33,6 +37,10 @@ pub enum Error {
     #[error("Buffer size mismatch: expected {expected}, got {actual}")]
     BufferSize { expected: usize, actual: usize },
 
+    /// Invalid argument to a constructor or conversion
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     /// Invalid pixel layout
     #[error("Invalid layout: {0}")]
     InvalidLayout(String),
@@ -0,0 +1,768 @@
+//! Gamut Boundary Descriptors
+//!
+//! Describes the surface of a color gamut with the segment-maxima method
+//! (Morovič & Luo, 2000): color space is split into segments by hue and
+//! elevation around a mid-grey center, and each segment keeps the sample
+//! farthest from that center. The boundary is closed into a triangle mesh,
+//! which gives:
+//! - [`GamutBoundary::volume`]: gamut volume in cubic ΔE units
+//! - [`GamutBoundary::coverage_of`]: how much of another gamut is covered
+//! - [`GamutBoundary::write_mesh`]: OBJ, PLY or VRML export for viewing
+//!
+//! Profiles are evaluated natively into PCS XYZ: matrix-shaper profiles
+//! through their TRCs and colorants, LUT profiles through their A2B1 (or
+//! A2B0) table, with the table's Lab or XYZ PCS encoding decoded.
+//!
+//! # Usage
+//!
+//! ```
+//! use oxcms_core::ColorProfile;
+//! use oxcms_core::gamut_boundary::{BoundarySpace, GamutBoundary};
+//!
+//! let srgb = GamutBoundary::from_profile(&ColorProfile::new_srgb(), BoundarySpace::Lab).unwrap();
+//! let p3 = GamutBoundary::from_profile(&ColorProfile::new_display_p3(), BoundarySpace::Lab).unwrap();
+//!
+//! // sRGB covers about two thirds of the Display P3 volume
+//! let coverage = srgb.coverage_of(&p3).unwrap();
+//! assert!(coverage > 65.0 && coverage < 70.0);
+//! ```
+
+use std::io::Write;
+
+use crate::color::white_point::{D50, D65};
+use crate::color::{Jzazbz, Lab, Xyz};
+use crate::icc::{ColorSpace, IccError, IccProfile, IccRenderingIntent, TagData, TagSignature};
+use crate::math::gamma::srgb_gamma_encode;
+use crate::math::matrix::XYZ_TO_SRGB;
+use crate::math::{Matrix3x3, bradford_matrix};
+use crate::pipeline::LutPipeline;
+use crate::{ColorProfile, Error, Result};
+
+/// Default number of hue segments (5° each)
+pub const DEFAULT_HUE_SEGMENTS: usize = 72;
+
+/// Default number of elevation segments (5° each)
+pub const DEFAULT_ELEVATION_SEGMENTS: usize = 36;
+
+/// Luminance of the reference white for [`BoundarySpace::Jzazbz`], in cd/m²
+const JZAZBZ_WHITE_LUMINANCE: f64 = 100.0;
+
+/// Relative luminance of L* = 50, the center of the segment grid
+const MID_GREY_Y: f64 = 0.184_186;
+
+/// Device values sampled per axis on the faces of the RGB cube
+const RGB_SAMPLE_STEPS: usize = 65;
+
+/// Device values sampled per axis on the faces of the CMYK hypercube
+const CMYK_SAMPLE_STEPS: usize = 17;
+
+/// PCS XYZ of 1.0 in an A2B table's normalized output (u1Fixed15)
+const PCS_XYZ_SCALE: f64 = 65535.0 / 32768.0;
+
+/// lut16 Lab of 1.0 in the v4 Lab encoding (legacy 0xFF00 = L* 100)
+const LEGACY_LAB_SCALE: f64 = 65280.0 / 65535.0;
+
+/// Color space the boundary is described in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundarySpace {
+    /// CIELAB relative to the D50 PCS white; volumes are in ΔE*ab³
+    #[default]
+    Lab,
+    /// Jzazbz with the white adapted to D65 at 100 cd/m²; volumes are in ΔEz³
+    Jzazbz,
+}
+
+impl BoundarySpace {
+    /// Coordinates of a PCS (D50) color as [lightness, a, b]
+    fn coordinates(self, xyz: Xyz) -> [f64; 3] {
+        match self {
+            Self::Lab => Lab::from_xyz(xyz).to_array(),
+            Self::Jzazbz => {
+                let d65 = bradford_matrix(&D50, &D65).multiply_vec(xyz.to_array());
+                Jzazbz::from_xyz(Xyz::from_array(d65), JZAZBZ_WHITE_LUMINANCE).to_array()
+            }
+        }
+    }
+
+    /// PCS (D50) color of [lightness, a, b] coordinates
+    fn pcs(self, coords: [f64; 3]) -> Xyz {
+        match self {
+            Self::Lab => Lab::from_array(coords).to_xyz(),
+            Self::Jzazbz => {
+                let d65 = Jzazbz::from_array(coords).to_xyz(JZAZBZ_WHITE_LUMINANCE);
+                Xyz::from_array(bradford_matrix(&D65, &D50).multiply_vec(d65.to_array()))
+            }
+        }
+    }
+
+    /// Mid-grey center of the segment grid
+    fn center(self) -> [f64; 3] {
+        self.coordinates(D50.xyz.scale(MID_GREY_Y))
+    }
+}
+
+/// Mesh file format for [`GamutBoundary::write_mesh`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshFormat {
+    /// Wavefront OBJ, geometry only
+    Obj,
+    /// ASCII PLY with per-vertex sRGB colors
+    Ply,
+    /// VRML 2.0 indexed face set with per-vertex sRGB colors
+    Vrml,
+}
+
+/// Segment-maxima gamut boundary descriptor
+///
+/// Segments are indexed by hue angle in the chroma plane and elevation
+/// angle above it, both measured from a mid-grey center. Samples are added
+/// as PCS XYZ (D50, Y = 1 for white); segments no sample fell into are
+/// filled from their neighbours when the mesh is built.
+#[derive(Debug, Clone)]
+pub struct GamutBoundary {
+    space: BoundarySpace,
+    center: [f64; 3],
+    hue_segments: usize,
+    elevation_segments: usize,
+    /// Farthest sample per segment, rows ordered from the bottom elevation
+    maxima: Vec<Option<[f64; 3]>>,
+    /// Darkest and lightest samples, closing the mesh at the poles
+    black: Option<[f64; 3]>,
+    white: Option<[f64; 3]>,
+}
+
+impl GamutBoundary {
+    /// Create an empty descriptor with the default segment grid
+    pub fn new(space: BoundarySpace) -> Self {
+        Self {
+            space,
+            center: space.center(),
+            hue_segments: DEFAULT_HUE_SEGMENTS,
+            elevation_segments: DEFAULT_ELEVATION_SEGMENTS,
+            maxima: vec![None; DEFAULT_HUE_SEGMENTS * DEFAULT_ELEVATION_SEGMENTS],
+            black: None,
+            white: None,
+        }
+    }
+
+    /// Use a segment grid of `hue` × `elevation` segments, discarding samples
+    ///
+    /// Fails if `hue` is less than 3 or `elevation` is zero.
+    pub fn with_segments(mut self, hue: usize, elevation: usize) -> Result<Self> {
+        if hue < 3 || elevation == 0 {
+            return Err(Error::InvalidArgument(format!(
+                "segment grid of {} hues by {} elevations",
+                hue, elevation
+            )));
+        }
+        self.hue_segments = hue;
+        self.elevation_segments = elevation;
+        self.maxima = vec![None; hue * elevation];
+        self.black = None;
+        self.white = None;
+        Ok(self)
+    }
+
+    /// Describe the gamut of an RGB or CMYK profile
+    ///
+    /// The faces of the device cube (or hypercube) are sampled relative
+    /// colorimetrically, into the default segment grid.
+    pub fn from_profile(profile: &ColorProfile, space: BoundarySpace) -> Result<Self> {
+        let mut boundary = Self::new(space);
+        boundary.add_profile(profile)?;
+        Ok(boundary)
+    }
+
+    /// Color space of the descriptor
+    pub fn space(&self) -> BoundarySpace {
+        self.space
+    }
+
+    /// Number of hue and elevation segments
+    pub fn segments(&self) -> (usize, usize) {
+        (self.hue_segments, self.elevation_segments)
+    }
+
+    /// Add a PCS XYZ sample
+    pub fn add_xyz(&mut self, xyz: Xyz) {
+        let point = self.space.coordinates(xyz);
+        if self.black.is_none_or(|black| point[0] < black[0]) {
+            self.black = Some(point);
+        }
+        if self.white.is_none_or(|white| point[0] > white[0]) {
+            self.white = Some(point);
+        }
+
+        let radius = distance(point, self.center);
+        if radius <= f64::EPSILON {
+            return;
+        }
+        let index = self.segment_of(point);
+        if self.maxima[index].is_none_or(|max| distance(max, self.center) < radius) {
+            self.maxima[index] = Some(point);
+        }
+    }
+
+    /// Add the gamut of an RGB or CMYK profile
+    pub fn add_profile(&mut self, profile: &ColorProfile) -> Result<()> {
+        self.add_icc_profile(&IccProfile::parse(&profile.inner().encode()?)?)
+    }
+
+    /// Add the gamut of a native RGB or CMYK profile
+    pub fn add_icc_profile(&mut self, profile: &IccProfile) -> Result<()> {
+        let steps = match profile.header.color_space {
+            ColorSpace::Rgb => RGB_SAMPLE_STEPS,
+            ColorSpace::Cmyk => CMYK_SAMPLE_STEPS,
+            other => {
+                return Err(Error::UnsupportedColorSpace(format!(
+                    "gamut boundary of {:?} profile",
+                    other
+                )));
+            }
+        };
+        let to_pcs = DeviceToPcs::new(profile)?;
+        let channels = profile.input_channels();
+        for device in device_surface(channels, steps).chunks_exact(channels) {
+            self.add_xyz(to_pcs.eval(device));
+        }
+        Ok(())
+    }
+
+    /// Gamut volume in cubic ΔE units of the boundary space
+    pub fn volume(&self) -> f64 {
+        let vertices = self.vertices();
+        self.triangles()
+            .map(|[a, b, c]| tetrahedron_volume(self.center, vertices[a], vertices[b], vertices[c]))
+            .sum()
+    }
+
+    /// Percentage of `other`'s volume that lies inside this gamut
+    ///
+    /// The intersection keeps, per segment, whichever boundary is closer to
+    /// the center; `srgb.coverage_of(&p3)` reads as "% of P3".
+    ///
+    /// Fails if the descriptors use different spaces or segment grids.
+    pub fn coverage_of(&self, other: &GamutBoundary) -> Result<f64> {
+        if self.space != other.space {
+            return Err(Error::InvalidArgument(format!(
+                "coverage of a {:?} boundary by a {:?} one",
+                other.space, self.space
+            )));
+        }
+        if self.segments() != other.segments() {
+            return Err(Error::InvalidArgument(format!(
+                "coverage of a {:?} segment grid by a {:?} one",
+                other.segments(),
+                self.segments()
+            )));
+        }
+        let total = other.volume();
+        if total <= 0.0 {
+            return Ok(0.0);
+        }
+
+        let ours = self.vertices();
+        let theirs = other.vertices();
+        let inner: Vec<[f64; 3]> = ours
+            .iter()
+            .zip(&theirs)
+            .map(|(&a, &b)| {
+                if distance(a, self.center) <= distance(b, self.center) {
+                    a
+                } else {
+                    b
+                }
+            })
+            .collect();
+        let intersection: f64 = self
+            .triangles()
+            .map(|[a, b, c]| tetrahedron_volume(self.center, inner[a], inner[b], inner[c]))
+            .sum();
+        Ok(100.0 * intersection / total)
+    }
+
+    /// Write the boundary mesh
+    ///
+    /// Coordinates are written as (a, b, lightness), so lightness runs
+    /// along z; faces wind counter-clockwise seen from outside.
+    pub fn write_mesh<W: Write>(&self, format: MeshFormat, mut writer: W) -> Result<()> {
+        let vertices = self.vertices();
+        let faces: Vec<[usize; 3]> = self.triangles().collect();
+        let colors: Vec<[u8; 3]> = vertices.iter().map(|&v| self.display_color(v)).collect();
+
+        match format {
+            MeshFormat::Obj => {
+                writeln!(writer, "# gamut boundary ({:?})", self.space)?;
+                for v in &vertices {
+                    writeln!(writer, "v {} {} {}", v[1], v[2], v[0])?;
+                }
+                for f in &faces {
+                    writeln!(writer, "f {} {} {}", f[0] + 1, f[1] + 1, f[2] + 1)?;
+                }
+            }
+            MeshFormat::Ply => {
+                writeln!(writer, "ply")?;
+                writeln!(writer, "format ascii 1.0")?;
+                writeln!(writer, "comment gamut boundary ({:?})", self.space)?;
+                writeln!(writer, "element vertex {}", vertices.len())?;
+                writeln!(writer, "property float x")?;
+                writeln!(writer, "property float y")?;
+                writeln!(writer, "property float z")?;
+                writeln!(writer, "property uchar red")?;
+                writeln!(writer, "property uchar green")?;
+                writeln!(writer, "property uchar blue")?;
+                writeln!(writer, "element face {}", faces.len())?;
+                writeln!(writer, "property list uchar int vertex_indices")?;
+                writeln!(writer, "end_header")?;
+                for (v, c) in vertices.iter().zip(&colors) {
+                    writeln!(
+                        writer,
+                        "{} {} {} {} {} {}",
+                        v[1], v[2], v[0], c[0], c[1], c[2]
+                    )?;
+                }
+                for f in &faces {
+                    writeln!(writer, "3 {} {} {}", f[0], f[1], f[2])?;
+                }
+            }
+            MeshFormat::Vrml => {
+                writeln!(writer, "#VRML V2.0 utf8")?;
+                writeln!(writer, "# gamut boundary ({:?})", self.space)?;
+                writeln!(writer, "Shape {{")?;
+                writeln!(writer, "  geometry IndexedFaceSet {{")?;
+                writeln!(writer, "    coord Coordinate {{ point [")?;
+                for v in &vertices {
+                    writeln!(writer, "      {} {} {},", v[1], v[2], v[0])?;
+                }
+                writeln!(writer, "    ] }}")?;
+                writeln!(writer, "    color Color {{ color [")?;
+                for c in &colors {
+                    writeln!(
+                        writer,
+                        "      {} {} {},",
+                        c[0] as f64 / 255.0,
+                        c[1] as f64 / 255.0,
+                        c[2] as f64 / 255.0
+                    )?;
+                }
+                writeln!(writer, "    ] }}")?;
+                writeln!(writer, "    coordIndex [")?;
+                for f in &faces {
+                    writeln!(writer, "      {}, {}, {}, -1,", f[0], f[1], f[2])?;
+                }
+                writeln!(writer, "    ]")?;
+                writeln!(writer, "  }}")?;
+                writeln!(writer, "}}")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Segment a point falls into
+    fn segment_of(&self, point: [f64; 3]) -> usize {
+        let (hue, elevation) = self.angles(point);
+        let h = (hue / std::f64::consts::TAU * self.hue_segments as f64) as usize;
+        let e =
+            ((elevation / std::f64::consts::PI + 0.5) * self.elevation_segments as f64) as usize;
+        e.min(self.elevation_segments - 1) * self.hue_segments + h.min(self.hue_segments - 1)
+    }
+
+    /// Hue in [0, 2π) and elevation in [-π/2, π/2] of a point about the center
+    fn angles(&self, point: [f64; 3]) -> (f64, f64) {
+        let dl = point[0] - self.center[0];
+        let da = point[1] - self.center[1];
+        let db = point[2] - self.center[2];
+        let hue = db.atan2(da).rem_euclid(std::f64::consts::TAU);
+        (hue, dl.atan2(da.hypot(db)))
+    }
+
+    /// Mesh vertices: the segment maxima, then the black and white poles
+    ///
+    /// Empty segments get a point in their central direction at the mean
+    /// radius of their filled neighbours.
+    fn vertices(&self) -> Vec<[f64; 3]> {
+        let (hues, rows) = (self.hue_segments, self.elevation_segments);
+        let mut radii: Vec<Option<f64>> = self
+            .maxima
+            .iter()
+            .map(|m| m.map(|p| distance(p, self.center)))
+            .collect();
+        if radii.iter().all(Option::is_none) {
+            radii.fill(Some(0.0));
+        }
+        let mut vertices = self.maxima.clone();
+        while radii.iter().any(Option::is_none) {
+            let previous = radii.clone();
+            for e in 0..rows {
+                for h in 0..hues {
+                    let index = e * hues + h;
+                    if previous[index].is_some() {
+                        continue;
+                    }
+                    let mut neighbours = vec![
+                        previous[e * hues + (h + 1) % hues],
+                        previous[e * hues + (h + hues - 1) % hues],
+                    ];
+                    if e > 0 {
+                        neighbours.push(previous[index - hues]);
+                    }
+                    if e + 1 < rows {
+                        neighbours.push(previous[index + hues]);
+                    }
+                    let filled: Vec<f64> = neighbours.into_iter().flatten().collect();
+                    if filled.is_empty() {
+                        continue;
+                    }
+                    let radius = filled.iter().sum::<f64>() / filled.len() as f64;
+                    let hue = (h as f64 + 0.5) / hues as f64 * std::f64::consts::TAU;
+                    let elevation = ((e as f64 + 0.5) / rows as f64 - 0.5) * std::f64::consts::PI;
+                    radii[index] = Some(radius);
+                    vertices[index] = Some([
+                        self.center[0] + radius * elevation.sin(),
+                        self.center[1] + radius * elevation.cos() * hue.cos(),
+                        self.center[2] + radius * elevation.cos() * hue.sin(),
+                    ]);
+                }
+            }
+        }
+
+        let mut vertices: Vec<[f64; 3]> = vertices
+            .into_iter()
+            .map(|v| v.unwrap_or(self.center))
+            .collect();
+        vertices.push(self.black.unwrap_or(self.center));
+        vertices.push(self.white.unwrap_or(self.center));
+        vertices
+    }
+
+    /// Outward-facing triangles over [`Self::vertices`]
+    fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        let (hues, rows) = (self.hue_segments, self.elevation_segments);
+        let vertex = move |h: usize, e: usize| e * hues + h % hues;
+        let black = hues * rows;
+        let white = black + 1;
+
+        let bottom = (0..hues).map(move |h| [black, vertex(h + 1, 0), vertex(h, 0)]);
+        let sides = (0..rows - 1).flat_map(move |e| {
+            (0..hues).flat_map(move |h| {
+                [
+                    [vertex(h, e), vertex(h + 1, e), vertex(h + 1, e + 1)],
+                    [vertex(h, e), vertex(h + 1, e + 1), vertex(h, e + 1)],
+                ]
+            })
+        });
+        let top = (0..hues).map(move |h| [white, vertex(h, rows - 1), vertex(h + 1, rows - 1)]);
+        bottom.chain(sides).chain(top)
+    }
+
+    /// 8-bit sRGB color of a boundary point, clipped
+    fn display_color(&self, point: [f64; 3]) -> [u8; 3] {
+        let xyz = self.space.pcs(point).to_array();
+        let linear = XYZ_TO_SRGB.multiply_vec(bradford_matrix(&D50, &D65).multiply_vec(xyz));
+        linear.map(|c| (srgb_gamma_encode(c.clamp(0.0, 1.0)) * 255.0).round() as u8)
+    }
+}
+
+/// How an A2B table encodes the PCS in its normalized output
+#[derive(Debug, Clone, Copy)]
+enum PcsEncoding {
+    /// L* / 100, (a* + 128) / 255, (b* + 128) / 255
+    Lab,
+    /// The lut16 Lab encoding, [`LEGACY_LAB_SCALE`] times the one above
+    LegacyLab,
+    /// u1Fixed15 XYZ
+    Xyz,
+}
+
+/// A profile's device to PCS XYZ conversion, evaluated natively
+#[derive(Debug)]
+enum DeviceToPcs {
+    /// Red, green and blue TRCs, then the colorant matrix
+    MatrixShaper {
+        trcs: Box<[TagData; 3]>,
+        matrix: Matrix3x3,
+    },
+    /// A2B table and the PCS encoding of its output
+    Lut { lut: LutPipeline, pcs: PcsEncoding },
+}
+
+impl DeviceToPcs {
+    /// Relative colorimetric conversion, from the A2B1 (or A2B0) table if
+    /// there is one and from the matrix and TRCs otherwise
+    fn new(profile: &IccProfile) -> Result<Self> {
+        if let Some(tag) = profile.a2b_for_intent(IccRenderingIntent::RelativeColorimetric) {
+            let pcs = match (profile.header.pcs, tag) {
+                (ColorSpace::Lab, TagData::Lut16(_)) => PcsEncoding::LegacyLab,
+                (ColorSpace::Lab, _) => PcsEncoding::Lab,
+                (ColorSpace::Xyz, _) => PcsEncoding::Xyz,
+                (other, _) => {
+                    return Err(Error::UnsupportedColorSpace(format!("{:?} PCS", other)));
+                }
+            };
+            let lut = LutPipeline::from_tag_data(tag)?;
+            if lut.input_channels != profile.input_channels() || lut.output_channels != 3 {
+                return Err(Error::InvalidProfile(format!(
+                    "A2B table with {} inputs and {} outputs",
+                    lut.input_channels, lut.output_channels
+                )));
+            }
+            return Ok(Self::Lut { lut, pcs });
+        }
+
+        let trc = |sig: TagSignature| match profile.get_tag(sig) {
+            Some(tag @ (TagData::Curve(_) | TagData::ParametricCurve(_))) => Ok(tag.clone()),
+            _ => Err(IccError::MissingTag(sig.0)),
+        };
+        let trcs = Box::new([
+            trc(TagSignature::RED_TRC)?,
+            trc(TagSignature::GREEN_TRC)?,
+            trc(TagSignature::BLUE_TRC)?,
+        ]);
+        let colorant = |xyz: Option<Xyz>, sig: TagSignature| xyz.ok_or(IccError::MissingTag(sig.0));
+        let columns = [
+            colorant(profile.red_colorant(), TagSignature::RED_COLORANT)?,
+            colorant(profile.green_colorant(), TagSignature::GREEN_COLORANT)?,
+            colorant(profile.blue_colorant(), TagSignature::BLUE_COLORANT)?,
+        ];
+        let matrix = Matrix3x3::new([
+            columns.map(|c| c.x),
+            columns.map(|c| c.y),
+            columns.map(|c| c.z),
+        ]);
+        Ok(Self::MatrixShaper { trcs, matrix })
+    }
+
+    /// PCS XYZ (D50, Y = 1 for white) of normalized device values
+    fn eval(&self, device: &[f64]) -> Xyz {
+        match self {
+            Self::MatrixShaper { trcs, matrix } => {
+                let linear = [0, 1, 2].map(|c| match &trcs[c] {
+                    TagData::Curve(curve) => curve.eval(device[c]),
+                    TagData::ParametricCurve(curve) => curve.eval(device[c]),
+                    _ => device[c],
+                });
+                Xyz::from_array(matrix.multiply_vec(linear))
+            }
+            Self::Lut { lut, pcs } => {
+                let out = lut.transform(device);
+                let v = [out[0], out[1], out[2]];
+                let v = match pcs {
+                    PcsEncoding::Xyz => return Xyz::from_array(v.map(|x| x * PCS_XYZ_SCALE)),
+                    PcsEncoding::Lab => v,
+                    PcsEncoding::LegacyLab => v.map(|x| x / LEGACY_LAB_SCALE),
+                };
+                Lab::new(v[0] * 100.0, v[1] * 255.0 - 128.0, v[2] * 255.0 - 128.0).to_xyz()
+            }
+        }
+    }
+}
+
+/// Device values on the faces of the unit hypercube, `steps` per axis
+fn device_surface(channels: usize, steps: usize) -> Vec<f64> {
+    let scale = (steps - 1) as f64;
+    let mut values = Vec::new();
+    let mut index = vec![0; channels];
+    loop {
+        if index.iter().any(|&i| i == 0 || i == steps - 1) {
+            values.extend(index.iter().map(|&i| i as f64 / scale));
+        }
+        let Some(axis) = index.iter().position(|&i| i + 1 < steps) else {
+            break;
+        };
+        index[axis] += 1;
+        index[..axis].fill(0);
+    }
+    values
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// Signed volume of the tetrahedron spanned from `apex` to a triangle
+///
+/// Coordinates are taken as (a, b, lightness) so that outward-facing
+/// triangles give positive volumes.
+fn tetrahedron_volume(apex: [f64; 3], p: [f64; 3], q: [f64; 3], r: [f64; 3]) -> f64 {
+    let d = |v: [f64; 3]| [v[1] - apex[1], v[2] - apex[2], v[0] - apex[0]];
+    let (u, v, w) = (d(p), d(q), d(r));
+    let cross = [
+        v[1] * w[2] - v[2] * w[1],
+        v[2] * w[0] - v[0] * w[2],
+        v[0] * w[1] - v[1] * w[0],
+    ];
+    (u[0] * cross[0] + u[1] * cross[1] + u[2] * cross[2]) / 6.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_surface() {
+        // 3³ grid minus the single interior point
+        assert_eq!(device_surface(3, 3).len(), 26 * 3);
+        assert_eq!(device_surface(4, 3).len(), 80 * 4);
+    }
+
+    #[test]
+    fn test_srgb_volume() {
+        let srgb =
+            GamutBoundary::from_profile(&ColorProfile::new_srgb(), BoundarySpace::Lab).unwrap();
+        let volume = srgb.volume();
+        // Counting 1 ΔE voxels inside the sRGB cube gives 833,000 ΔE*ab³
+        assert!((volume / 833_000.0 - 1.0).abs() < 0.01, "{}", volume);
+
+        let jz = GamutBoundary::from_profile(&ColorProfile::new_srgb(), BoundarySpace::Jzazbz)
+            .unwrap()
+            .volume();
+        assert!(jz > 0.0 && jz < volume * 1e-3, "{}", jz);
+    }
+
+    #[test]
+    fn test_coverage() {
+        let srgb =
+            GamutBoundary::from_profile(&ColorProfile::new_srgb(), BoundarySpace::Lab).unwrap();
+        let p3 = GamutBoundary::from_profile(&ColorProfile::new_display_p3(), BoundarySpace::Lab)
+            .unwrap();
+
+        assert!((srgb.coverage_of(&srgb).unwrap() - 100.0).abs() < 1e-9);
+        let of_srgb = p3.coverage_of(&srgb).unwrap();
+        assert!(of_srgb > 99.0, "{}", of_srgb);
+        let of_p3 = srgb.coverage_of(&p3).unwrap();
+        assert!((of_p3 - 67.5).abs() < 0.5, "{}", of_p3);
+        assert!(p3.volume() > srgb.volume());
+    }
+
+    #[test]
+    fn test_mismatched_boundaries() {
+        let lab = GamutBoundary::new(BoundarySpace::Lab);
+        let jz = GamutBoundary::new(BoundarySpace::Jzazbz);
+        assert!(matches!(
+            lab.coverage_of(&jz),
+            Err(Error::InvalidArgument(_))
+        ));
+        let coarse = GamutBoundary::new(BoundarySpace::Lab)
+            .with_segments(8, 4)
+            .unwrap();
+        assert!(matches!(
+            lab.coverage_of(&coarse),
+            Err(Error::InvalidArgument(_))
+        ));
+        for (hue, elevation) in [(2, 4), (8, 0)] {
+            assert!(matches!(
+                GamutBoundary::new(BoundarySpace::Lab).with_segments(hue, elevation),
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+    }
+
+    /// sRGB as an RGB profile with only an A2B0 table, into a `pcs` PCS
+    fn srgb_lut_profile(pcs: ColorSpace) -> IccProfile {
+        use crate::icc::tags::{CurveSegment, LutAToBData};
+        use crate::icc::{IccHeader, ProfileClass};
+        use crate::pipeline::ClutData;
+
+        let srgb = IccProfile::parse(&ColorProfile::new_srgb().inner().encode().unwrap()).unwrap();
+        let to_pcs = DeviceToPcs::new(&srgb).unwrap();
+        let clut = ClutData::sample(3, 33, 3, |p| {
+            let xyz = to_pcs.eval(p);
+            match pcs {
+                ColorSpace::Xyz => xyz.to_array().map(|v| v / PCS_XYZ_SCALE).to_vec(),
+                _ => {
+                    let lab = Lab::from_xyz(xyz);
+                    vec![
+                        lab.l / 100.0,
+                        (lab.a + 128.0) / 255.0,
+                        (lab.b + 128.0) / 255.0,
+                    ]
+                }
+            }
+        });
+
+        let mut profile =
+            IccProfile::new(IccHeader::new(ProfileClass::Display, ColorSpace::Rgb, pcs));
+        profile.set_tag(
+            TagSignature::A2B0,
+            TagData::LutAToB(LutAToBData {
+                input_channels: 3,
+                output_channels: 3,
+                b_curves: Some(vec![CurveSegment::Identity; 3]),
+                matrix: None,
+                m_curves: None,
+                clut: Some(clut.to_lut_clut()),
+                a_curves: Some(vec![CurveSegment::Identity; 3]),
+            }),
+        );
+        profile
+    }
+
+    #[test]
+    fn test_lut_profiles() {
+        let expected = GamutBoundary::from_profile(&ColorProfile::new_srgb(), BoundarySpace::Lab)
+            .unwrap()
+            .volume();
+
+        let lab = srgb_lut_profile(ColorSpace::Lab);
+        // Converting to v2 turns the table into a lut16 with legacy Lab encoding
+        let (lut16, _) = lab.to_v2().unwrap();
+        assert!(matches!(lut16.a2b0(), Some(TagData::Lut16(_))));
+        for profile in [lab, lut16, srgb_lut_profile(ColorSpace::Xyz)] {
+            let mut boundary = GamutBoundary::new(BoundarySpace::Lab);
+            boundary.add_icc_profile(&profile).unwrap();
+            let volume = boundary.volume();
+            assert!(
+                (volume / expected - 1.0).abs() < 0.01,
+                "{:?}: {} vs {}",
+                profile.header.pcs,
+                volume,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_unsupported_profile() {
+        let gray = ColorProfile::new_linear_gray();
+        assert!(matches!(
+            GamutBoundary::from_profile(&gray, BoundarySpace::Lab),
+            Err(Error::UnsupportedColorSpace(_))
+        ));
+    }
+
+    #[test]
+    fn test_mesh_export() {
+        let mut boundary = GamutBoundary::new(BoundarySpace::Lab)
+            .with_segments(8, 4)
+            .unwrap();
+        for xyz in [D50.xyz, Xyz::new(0.0, 0.0, 0.0)] {
+            boundary.add_xyz(xyz);
+        }
+        let vertices = 8 * 4 + 2;
+        let faces = 8 * 2 * 4;
+
+        let mut obj = Vec::new();
+        boundary.write_mesh(MeshFormat::Obj, &mut obj).unwrap();
+        let obj = String::from_utf8(obj).unwrap();
+        assert_eq!(
+            obj.lines().filter(|l| l.starts_with("v ")).count(),
+            vertices
+        );
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), faces);
+
+        let mut ply = Vec::new();
+        boundary.write_mesh(MeshFormat::Ply, &mut ply).unwrap();
+        let ply = String::from_utf8(ply).unwrap();
+        assert!(ply.contains(&format!("element vertex {}", vertices)));
+        // The white pole is written last before the faces, in white
+        let body: Vec<&str> = ply.split("end_header\n").nth(1).unwrap().lines().collect();
+        assert_eq!(body.len(), vertices + faces);
+        assert!(body[vertices - 1].ends_with("255 255 255"));
+
+        let mut vrml = Vec::new();
+        boundary.write_mesh(MeshFormat::Vrml, &mut vrml).unwrap();
+        let vrml = String::from_utf8(vrml).unwrap();
+        assert!(vrml.starts_with("#VRML V2.0 utf8"));
+        assert_eq!(vrml.matches("-1,").count(), faces);
+    }
+}
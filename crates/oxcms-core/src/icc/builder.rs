@@ -0,0 +1,802 @@
+//! Profile Builders
+//!
+//! Construct ICC profiles from colorimetric parameters rather than bytes:
+//! matrix-shaper RGB, gray, Lab identity, abstract (Lab to Lab) and
+//! device-link linearization profiles.
+//! Built profiles are native [`IccProfile`]s; serialize them with
+//! [`IccProfile::to_bytes`] or wrap them with
+//! [`ColorProfile::from_icc`](crate::ColorProfile::from_icc).
+//!
+//! # Example
+//!
+//! ```
+//! use oxcms_core::icc::{RgbProfileBuilder, Trc};
+//!
+//! // Adobe RGB (1998)
+//! let profile = RgbProfileBuilder::new()
+//!     .with_primaries((0.64, 0.33), (0.21, 0.71), (0.15, 0.06))
+//!     .with_trc(Trc::Gamma(563.0 / 256.0))
+//!     .with_description("Custom Adobe RGB")
+//!     .build()
+//!     .unwrap();
+//! let bytes = profile.to_bytes().unwrap();
+//! ```
+
+use super::convert::{lut16, xyz_tag};
+use super::error::IccError;
+use super::header::{ColorSpace, IccHeader, ProfileClass, ProfileVersion};
+use super::parser::IccProfile;
+use super::tags::{
+    CurveData, CurveSegment, LutAToBData, LutBToAData, ParametricCurveData, TagData, TextData,
+};
+use super::types::{TagSignature, TypeSignature, XyzNumber};
+use crate::color::{Lab, WhitePoint, Xyz};
+use crate::math::{Matrix3x3, ParametricCurve, bradford_matrix};
+use crate::pipeline::ClutData;
+
+/// profileSequenceDescType
+const PROFILE_SEQUENCE_DESC: TypeSignature = TypeSignature::from_bytes(*b"pseq");
+
+/// Tone reproduction curve for a built profile
+#[derive(Debug, Clone)]
+pub enum Trc {
+    /// Pure power law, stored as `curv` (u8Fixed8 precision)
+    Gamma(f64),
+    /// ICC parametric curve, stored as `para`
+    Parametric(ParametricCurve),
+    /// Sampled curve over [0, 1], stored as `curv`
+    Table(Vec<u16>),
+}
+
+impl Trc {
+    /// The sRGB (IEC 61966-2-1) transfer function
+    pub fn srgb() -> Self {
+        Self::Parametric(ParametricCurve::srgb())
+    }
+
+    /// Linear (gamma 1.0)
+    pub fn linear() -> Self {
+        Self::Gamma(1.0)
+    }
+
+    fn check(&self) -> Result<(), IccError> {
+        let ok = match self {
+            Trc::Gamma(g) => g.is_finite() && *g > 0.0,
+            Trc::Parametric(c) => {
+                [c.g, c.a, c.b, c.c, c.d, c.e, c.f]
+                    .iter()
+                    .all(|v| v.is_finite())
+                    && c.g > 0.0
+            }
+            Trc::Table(table) => table.len() >= 2,
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(IccError::InvalidArgument(format!(
+                "invalid TRC: {:?}",
+                self
+            )))
+        }
+    }
+
+    fn to_tag(&self) -> TagData {
+        match self {
+            Trc::Gamma(g) if *g == 1.0 => TagData::Curve(CurveData::Identity),
+            Trc::Gamma(g) => TagData::Curve(CurveData::Gamma(*g)),
+            Trc::Parametric(curve) => {
+                TagData::ParametricCurve(ParametricCurveData { curve: *curve })
+            }
+            Trc::Table(table) => TagData::Curve(CurveData::Table(table.clone())),
+        }
+    }
+
+    fn to_segment(&self) -> CurveSegment {
+        match self {
+            Trc::Gamma(g) => CurveSegment::Parametric {
+                curve_type: 0,
+                params: vec![*g],
+            },
+            Trc::Parametric(curve) => {
+                let (curve_type, params) = ParametricCurveData { curve: *curve }.icc_params();
+                CurveSegment::Parametric { curve_type, params }
+            }
+            Trc::Table(table) => {
+                CurveSegment::Table(table.iter().map(|&v| v as f64 / 65535.0).collect())
+            }
+        }
+    }
+}
+
+/// Builder for v4 matrix-shaper RGB display profiles
+///
+/// Defaults to sRGB: BT.709 primaries, D65 white and the sRGB TRC. The
+/// colorants are computed from the xy primaries and adapted to the D50 PCS
+/// with Bradford; the adaptation is stored in `chad` and `wtpt` is D50.
+#[derive(Debug, Clone)]
+pub struct RgbProfileBuilder {
+    primaries: [(f64, f64); 3],
+    white: (f64, f64),
+    trcs: [Trc; 3],
+    description: String,
+    copyright: String,
+}
+
+impl Default for RgbProfileBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RgbProfileBuilder {
+    /// Start from sRGB
+    pub fn new() -> Self {
+        Self {
+            primaries: [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)],
+            white: (0.3127, 0.3290),
+            trcs: [Trc::srgb(), Trc::srgb(), Trc::srgb()],
+            description: "Custom RGB".to_string(),
+            copyright: "No copyright, use freely".to_string(),
+        }
+    }
+
+    /// Set the red, green and blue primaries as xy chromaticities
+    pub fn with_primaries(mut self, red: (f64, f64), green: (f64, f64), blue: (f64, f64)) -> Self {
+        self.primaries = [red, green, blue];
+        self
+    }
+
+    /// Set the white point as an xy chromaticity
+    pub fn with_white_xy(mut self, x: f64, y: f64) -> Self {
+        self.white = (x, y);
+        self
+    }
+
+    /// Set the white point from a standard illuminant
+    pub fn with_white_point(mut self, white: &WhitePoint) -> Self {
+        self.white = white.chromaticity();
+        self
+    }
+
+    /// Use the same TRC for all three channels
+    pub fn with_trc(mut self, trc: Trc) -> Self {
+        self.trcs = [trc.clone(), trc.clone(), trc];
+        self
+    }
+
+    /// Set separate red, green and blue TRCs
+    pub fn with_channel_trcs(mut self, red: Trc, green: Trc, blue: Trc) -> Self {
+        self.trcs = [red, green, blue];
+        self
+    }
+
+    /// Set the profile description (`desc`)
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Set the copyright string (`cprt`)
+    pub fn with_copyright(mut self, copyright: &str) -> Self {
+        self.copyright = copyright.to_string();
+        self
+    }
+
+    /// Build the profile
+    ///
+    /// Fails if a chromaticity is degenerate, the primaries are collinear or
+    /// a TRC is invalid.
+    pub fn build(&self) -> Result<IccProfile, IccError> {
+        for trc in &self.trcs {
+            trc.check()?;
+        }
+        let white = xy_to_xyz(self.white)?;
+        let colorants = rgb_to_xyz_matrix(self.primaries, white)?;
+
+        let d50 = XyzNumber::D50.to_xyz();
+        let chad = bradford_matrix(&WhitePoint::from_xyz(white), &WhitePoint::from_xyz(d50));
+        let adapted = chad.multiply(&colorants);
+
+        let header = IccHeader::new(ProfileClass::Display, ColorSpace::Rgb, ColorSpace::Xyz);
+        let mut profile = IccProfile::new(header);
+        set_text_tags(&mut profile, &self.description, &self.copyright);
+        profile.set_tag(TagSignature::MEDIA_WHITE, xyz_tag(d50));
+        profile.set_tag(TagSignature::CHAD, TagData::ChromaticAdaptation(chad.m));
+
+        let colorant_sigs = [
+            TagSignature::RED_COLORANT,
+            TagSignature::GREEN_COLORANT,
+            TagSignature::BLUE_COLORANT,
+        ];
+        let trc_sigs = [
+            TagSignature::RED_TRC,
+            TagSignature::GREEN_TRC,
+            TagSignature::BLUE_TRC,
+        ];
+        for (i, trc) in self.trcs.iter().enumerate() {
+            let column = Xyz::new(adapted.m[0][i], adapted.m[1][i], adapted.m[2][i]);
+            profile.set_tag(colorant_sigs[i], xyz_tag(column));
+            profile.set_tag(trc_sigs[i], trc.to_tag());
+        }
+        Ok(profile)
+    }
+
+    /// Build the profile and serialize it to ICC bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IccError> {
+        self.build()?.to_bytes()
+    }
+}
+
+/// Builder for v4 gray display profiles
+///
+/// Defaults to a D65 white and gamma 2.2. As for RGB, `wtpt` is D50 and the
+/// adaptation from the given white is stored in `chad`.
+#[derive(Debug, Clone)]
+pub struct GrayProfileBuilder {
+    white: (f64, f64),
+    trc: Trc,
+    description: String,
+    copyright: String,
+}
+
+impl Default for GrayProfileBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GrayProfileBuilder {
+    /// Start from gamma 2.2 with a D65 white
+    pub fn new() -> Self {
+        Self {
+            white: (0.3127, 0.3290),
+            trc: Trc::Gamma(2.2),
+            description: "Custom Gray".to_string(),
+            copyright: "No copyright, use freely".to_string(),
+        }
+    }
+
+    /// Set the white point as an xy chromaticity
+    pub fn with_white_xy(mut self, x: f64, y: f64) -> Self {
+        self.white = (x, y);
+        self
+    }
+
+    /// Set the white point from a standard illuminant
+    pub fn with_white_point(mut self, white: &WhitePoint) -> Self {
+        self.white = white.chromaticity();
+        self
+    }
+
+    /// Set the gray TRC (`kTRC`)
+    pub fn with_trc(mut self, trc: Trc) -> Self {
+        self.trc = trc;
+        self
+    }
+
+    /// Set the profile description (`desc`)
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Set the copyright string (`cprt`)
+    pub fn with_copyright(mut self, copyright: &str) -> Self {
+        self.copyright = copyright.to_string();
+        self
+    }
+
+    /// Build the profile
+    pub fn build(&self) -> Result<IccProfile, IccError> {
+        self.trc.check()?;
+        let white = xy_to_xyz(self.white)?;
+        let d50 = XyzNumber::D50.to_xyz();
+        let chad = bradford_matrix(&WhitePoint::from_xyz(white), &WhitePoint::from_xyz(d50));
+
+        let header = IccHeader::new(ProfileClass::Display, ColorSpace::Gray, ColorSpace::Xyz);
+        let mut profile = IccProfile::new(header);
+        set_text_tags(&mut profile, &self.description, &self.copyright);
+        profile.set_tag(TagSignature::MEDIA_WHITE, xyz_tag(d50));
+        profile.set_tag(TagSignature::CHAD, TagData::ChromaticAdaptation(chad.m));
+        profile.set_tag(TagSignature::GRAY_TRC, self.trc.to_tag());
+        Ok(profile)
+    }
+
+    /// Build the profile and serialize it to ICC bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IccError> {
+        self.build()?.to_bytes()
+    }
+}
+
+/// Builder for Lab identity profiles (abstract class, Lab to Lab)
+///
+/// v4 profiles hold identity `mAB`/`mBA` curves; v2 profiles hold identity
+/// `lut16` tables, so Lab values stay in the legacy 16-bit encoding.
+#[derive(Debug, Clone)]
+pub struct LabIdentityBuilder {
+    version: u8,
+    description: String,
+    copyright: String,
+}
+
+impl Default for LabIdentityBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LabIdentityBuilder {
+    /// Start with a v4 profile
+    pub fn new() -> Self {
+        Self {
+            version: 4,
+            description: "Lab identity".to_string(),
+            copyright: "No copyright, use freely".to_string(),
+        }
+    }
+
+    /// Set the major version (2 or 4)
+    pub fn with_version(mut self, major: u8) -> Self {
+        self.version = major;
+        self
+    }
+
+    /// Set the profile description (`desc`)
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Set the copyright string (`cprt`)
+    pub fn with_copyright(mut self, copyright: &str) -> Self {
+        self.copyright = copyright.to_string();
+        self
+    }
+
+    /// Build the profile
+    pub fn build(&self) -> Result<IccProfile, IccError> {
+        let mut header = IccHeader::new(ProfileClass::Abstract, ColorSpace::Lab, ColorSpace::Lab);
+        let (a2b, b2a) = match self.version {
+            4 => (
+                TagData::LutAToB(LutAToBData {
+                    input_channels: 3,
+                    output_channels: 3,
+                    b_curves: Some(vec![CurveSegment::Identity; 3]),
+                    matrix: None,
+                    m_curves: None,
+                    clut: None,
+                    a_curves: None,
+                }),
+                TagData::LutBToA(LutBToAData {
+                    input_channels: 3,
+                    output_channels: 3,
+                    b_curves: Some(vec![CurveSegment::Identity; 3]),
+                    matrix: None,
+                    m_curves: None,
+                    clut: None,
+                    a_curves: None,
+                }),
+            ),
+            2 => {
+                header.version = ProfileVersion {
+                    major: 2,
+                    minor: 1,
+                    patch: 0,
+                };
+                let identity = || {
+                    let clut = ClutData::sample(3, 2, 3, |p| p.to_vec());
+                    let clut = clut.data.iter().map(|&v| (v * 65535.0) as u16).collect();
+                    let curves = vec![vec![0, 65535]; 3];
+                    TagData::Lut16(lut16(3, 3, 2, curves.clone(), clut, curves))
+                };
+                (identity(), identity())
+            }
+            major => return Err(IccError::UnsupportedVersion { major, minor: 0 }),
+        };
+
+        let mut profile = IccProfile::new(header);
+        set_text_tags(&mut profile, &self.description, &self.copyright);
+        profile.set_tag(TagSignature::MEDIA_WHITE, xyz_tag(XyzNumber::D50.to_xyz()));
+        profile.set_tag(TagSignature::A2B0, a2b);
+        profile.set_tag(TagSignature::B2A0, b2a);
+        Ok(profile)
+    }
+
+    /// Build the profile and serialize it to ICC bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IccError> {
+        self.build()?.to_bytes()
+    }
+}
+
+/// Builder for abstract (Lab to Lab) profiles from a color function
+///
+/// The function is sampled on a uniform Lab grid into the CLUT of a v4
+/// `mAB` tag, so it should be smooth; corrections with sharp edges need a
+/// larger grid.
+///
+/// ```
+/// use oxcms_core::color::Lab;
+/// use oxcms_core::icc::AbstractProfileBuilder;
+///
+/// // Desaturate by half
+/// let profile = AbstractProfileBuilder::new(|lab: Lab| Lab::new(lab.l, lab.a * 0.5, lab.b * 0.5))
+///     .with_grid_points(17)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct AbstractProfileBuilder<F> {
+    transform: F,
+    grid_points: usize,
+    description: String,
+    copyright: String,
+}
+
+impl<F: Fn(Lab) -> Lab> AbstractProfileBuilder<F> {
+    /// Start with a 33-point grid
+    pub fn new(transform: F) -> Self {
+        Self {
+            transform,
+            grid_points: 33,
+            description: "Abstract".to_string(),
+            copyright: "No copyright, use freely".to_string(),
+        }
+    }
+
+    /// Set the CLUT grid size per dimension (2 to 255)
+    pub fn with_grid_points(mut self, grid_points: usize) -> Self {
+        self.grid_points = grid_points;
+        self
+    }
+
+    /// Set the profile description (`desc`)
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Set the copyright string (`cprt`)
+    pub fn with_copyright(mut self, copyright: &str) -> Self {
+        self.copyright = copyright.to_string();
+        self
+    }
+
+    /// Build the profile
+    pub fn build(&self) -> Result<IccProfile, IccError> {
+        if !(2..=255).contains(&self.grid_points) {
+            return Err(IccError::InvalidArgument(format!(
+                "invalid CLUT grid size {}",
+                self.grid_points
+            )));
+        }
+        let clut = ClutData::sample(3, self.grid_points, 3, |p| {
+            encode_lab((self.transform)(decode_lab(p))).to_vec()
+        });
+
+        let header = IccHeader::new(ProfileClass::Abstract, ColorSpace::Lab, ColorSpace::Lab);
+        let mut profile = IccProfile::new(header);
+        set_text_tags(&mut profile, &self.description, &self.copyright);
+        profile.set_tag(TagSignature::MEDIA_WHITE, xyz_tag(XyzNumber::D50.to_xyz()));
+        profile.set_tag(
+            TagSignature::A2B0,
+            TagData::LutAToB(LutAToBData {
+                input_channels: 3,
+                output_channels: 3,
+                b_curves: Some(vec![CurveSegment::Identity; 3]),
+                matrix: None,
+                m_curves: None,
+                clut: Some(clut.to_lut_clut()),
+                a_curves: Some(vec![CurveSegment::Identity; 3]),
+            }),
+        );
+        Ok(profile)
+    }
+
+    /// Build the profile and serialize it to ICC bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IccError> {
+        self.build()?.to_bytes()
+    }
+}
+
+/// Builder for device-link profiles that apply one curve per channel
+///
+/// Input and output share the same color space. The curves are stored as
+/// the B curves of a v4 `mAB` tag.
+#[derive(Debug, Clone)]
+pub struct LinearizationBuilder {
+    color_space: ColorSpace,
+    curves: Vec<Trc>,
+    description: String,
+    copyright: String,
+}
+
+impl LinearizationBuilder {
+    /// Start with one curve per channel of `color_space`
+    pub fn new(color_space: ColorSpace, curves: Vec<Trc>) -> Self {
+        Self {
+            color_space,
+            curves,
+            description: "Linearization".to_string(),
+            copyright: "No copyright, use freely".to_string(),
+        }
+    }
+
+    /// Set the profile description (`desc`)
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Set the copyright string (`cprt`)
+    pub fn with_copyright(mut self, copyright: &str) -> Self {
+        self.copyright = copyright.to_string();
+        self
+    }
+
+    /// Build the profile
+    ///
+    /// Fails if the number of curves does not match the color space.
+    pub fn build(&self) -> Result<IccProfile, IccError> {
+        let channels = self.color_space.channels();
+        if self.curves.len() != channels || channels == 0 || channels > 15 {
+            return Err(IccError::InvalidArgument(format!(
+                "{} curves for {:?}",
+                self.curves.len(),
+                self.color_space
+            )));
+        }
+        for trc in &self.curves {
+            trc.check()?;
+        }
+
+        let header = IccHeader::new(ProfileClass::DeviceLink, self.color_space, self.color_space);
+        let mut profile = IccProfile::new(header);
+        set_text_tags(&mut profile, &self.description, &self.copyright);
+        profile.set_tag(TagSignature::PROFILE_SEQUENCE, empty_profile_sequence());
+        profile.set_tag(
+            TagSignature::A2B0,
+            TagData::LutAToB(LutAToBData {
+                input_channels: channels as u8,
+                output_channels: channels as u8,
+                b_curves: Some(self.curves.iter().map(Trc::to_segment).collect()),
+                matrix: None,
+                m_curves: None,
+                clut: None,
+                a_curves: None,
+            }),
+        );
+        Ok(profile)
+    }
+
+    /// Build the profile and serialize it to ICC bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IccError> {
+        self.build()?.to_bytes()
+    }
+}
+
+fn set_text_tags(profile: &mut IccProfile, description: &str, copyright: &str) {
+    profile.set_tag(
+        TagSignature::DESC,
+        TagData::Text(TextData::new(description.to_string())),
+    );
+    profile.set_tag(
+        TagSignature::COPYRIGHT,
+        TagData::Text(TextData::new(copyright.to_string())),
+    );
+}
+
+/// profileSequenceDescType with no entries
+fn empty_profile_sequence() -> TagData {
+    let mut data = PROFILE_SEQUENCE_DESC.0.to_be_bytes().to_vec();
+    data.extend_from_slice(&[0; 8]);
+    TagData::Unknown {
+        type_sig: PROFILE_SEQUENCE_DESC.0,
+        data,
+    }
+}
+
+/// v4 PCS Lab encoding: L* / 100, (a* + 128) / 255, (b* + 128) / 255
+pub(super) fn decode_lab(p: &[f64]) -> Lab {
+    Lab::new(p[0] * 100.0, p[1] * 255.0 - 128.0, p[2] * 255.0 - 128.0)
+}
+
+pub(super) fn encode_lab(lab: Lab) -> [f64; 3] {
+    [
+        lab.l / 100.0,
+        (lab.a + 128.0) / 255.0,
+        (lab.b + 128.0) / 255.0,
+    ]
+}
+
+/// XYZ with Y = 1 for an xy chromaticity
+fn xy_to_xyz((x, y): (f64, f64)) -> Result<Xyz, IccError> {
+    if !(x.is_finite() && y.is_finite()) || y <= 0.0 || x < 0.0 || x + y > 1.0 {
+        return Err(IccError::InvalidArgument(format!(
+            "invalid chromaticity ({}, {})",
+            x, y
+        )));
+    }
+    Ok(Xyz::from_xyy(x, y, 1.0))
+}
+
+/// RGB to XYZ matrix whose columns are the primaries scaled so that
+/// RGB (1, 1, 1) maps to `white`
+fn rgb_to_xyz_matrix(primaries: [(f64, f64); 3], white: Xyz) -> Result<Matrix3x3, IccError> {
+    let mut m = [[0.0; 3]; 3];
+    for (i, &xy) in primaries.iter().enumerate() {
+        let p = xy_to_xyz(xy)?;
+        m[0][i] = p.x;
+        m[1][i] = p.y;
+        m[2][i] = p.z;
+    }
+    let m = Matrix3x3::new(m);
+    let inv = m
+        .inverse()
+        .ok_or_else(|| IccError::InvalidArgument("primaries are collinear".to_string()))?;
+    let s = inv.multiply_vec(white.to_array());
+
+    let mut out = m.m;
+    for row in &mut out {
+        for (v, scale) in row.iter_mut().zip(s) {
+            *v *= scale;
+        }
+    }
+    Ok(Matrix3x3::new(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::LutPipeline;
+
+    #[test]
+    fn test_srgb_colorants() {
+        let profile = RgbProfileBuilder::new().build().unwrap();
+        assert!(profile.validate().is_valid());
+
+        // ICC sRGB colorants (D50-adapted)
+        let red = profile.red_colorant().unwrap();
+        let green = profile.green_colorant().unwrap();
+        let blue = profile.blue_colorant().unwrap();
+        assert!(red.approx_eq(&Xyz::new(0.4361, 0.2225, 0.0139), 1e-3));
+        assert!(green.approx_eq(&Xyz::new(0.3851, 0.7169, 0.0971), 1e-3));
+        assert!(blue.approx_eq(&Xyz::new(0.1431, 0.0606, 0.7141), 1e-3));
+    }
+
+    #[test]
+    fn test_roundtrip_bytes() {
+        let bytes = RgbProfileBuilder::new()
+            .with_white_point(&crate::color::D50)
+            .with_channel_trcs(
+                Trc::Gamma(2.2),
+                Trc::srgb(),
+                Trc::Table(vec![0, 16384, 65535]),
+            )
+            .with_description("Camera RGB")
+            .with_copyright("none")
+            .to_bytes()
+            .unwrap();
+
+        let profile = IccProfile::parse(&bytes).unwrap();
+        assert!(profile.is_matrix_shaper());
+        assert_eq!(profile.description().as_deref(), Some("Camera RGB"));
+        assert_eq!(profile.copyright().as_deref(), Some("none"));
+        assert!(matches!(profile.red_trc(), Some(CurveData::Gamma(_))));
+        assert!(matches!(profile.blue_trc(), Some(CurveData::Table(t)) if t.len() == 3));
+        assert!(matches!(
+            profile.get_tag(TagSignature::GREEN_TRC),
+            Some(TagData::ParametricCurve(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let collinear = RgbProfileBuilder::new().with_primaries((0.2, 0.2), (0.3, 0.3), (0.4, 0.4));
+        assert!(matches!(
+            collinear.build(),
+            Err(IccError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            RgbProfileBuilder::new().with_white_xy(0.3, 0.0).build(),
+            Err(IccError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            RgbProfileBuilder::new().with_trc(Trc::Gamma(0.0)).build(),
+            Err(IccError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            RgbProfileBuilder::new()
+                .with_trc(Trc::Table(vec![0]))
+                .build(),
+            Err(IccError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_gray_profile() {
+        let bytes = GrayProfileBuilder::new()
+            .with_white_point(&crate::color::D50)
+            .with_trc(Trc::Gamma(1.8))
+            .to_bytes()
+            .unwrap();
+        let profile = IccProfile::parse(&bytes).unwrap();
+        assert!(profile.validate().is_valid());
+        assert_eq!(profile.header.color_space, ColorSpace::Gray);
+        assert!(matches!(profile.gray_trc(), Some(CurveData::Gamma(g)) if (g - 1.8).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_lab_identity() {
+        for version in [2, 4] {
+            let bytes = LabIdentityBuilder::new()
+                .with_version(version)
+                .to_bytes()
+                .unwrap();
+            let profile = IccProfile::parse(&bytes).unwrap();
+            assert_eq!(profile.header.version.major, version);
+            assert!(profile.validate().is_valid());
+
+            for sig in [TagSignature::A2B0, TagSignature::B2A0] {
+                let lut = LutPipeline::from_tag_data(profile.get_tag(sig).unwrap()).unwrap();
+                let out = lut.transform(&[0.5, 0.25, 0.75]);
+                for (o, i) in out.iter().zip([0.5, 0.25, 0.75]) {
+                    assert!((o - i).abs() < 1e-4, "v{}: {:?}", version, out);
+                }
+            }
+        }
+        assert!(LabIdentityBuilder::new().with_version(3).build().is_err());
+    }
+
+    #[test]
+    fn test_abstract_profile() {
+        let shift = |lab: Lab| Lab::new(lab.l * 0.9, lab.a + 10.0, lab.b * 0.5);
+        let profile = AbstractProfileBuilder::new(shift)
+            .with_grid_points(9)
+            .build()
+            .unwrap();
+        assert!(profile.validate().is_valid());
+
+        let lut = LutPipeline::from_tag_data(profile.a2b0().unwrap()).unwrap();
+        let lab = Lab::new(60.0, -20.0, 30.0);
+        let out = decode_lab(&lut.transform(&encode_lab(lab)));
+        let expected = shift(lab);
+        assert!(
+            out.approx_eq(&expected, 0.05),
+            "{:?} vs {:?}",
+            out,
+            expected
+        );
+
+        assert!(
+            AbstractProfileBuilder::new(shift)
+                .with_grid_points(1)
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_linearization_link() {
+        let curves = vec![
+            Trc::Gamma(2.0),
+            Trc::linear(),
+            Trc::srgb(),
+            Trc::Table(vec![0, 65535, 65535]),
+        ];
+        let bytes = LinearizationBuilder::new(ColorSpace::Cmyk, curves)
+            .to_bytes()
+            .unwrap();
+        let profile = IccProfile::parse(&bytes).unwrap();
+        assert_eq!(profile.header.device_class, ProfileClass::DeviceLink);
+        assert!(profile.validate().is_valid());
+
+        let lut = LutPipeline::from_tag_data(profile.a2b0().unwrap()).unwrap();
+        let out = lut.transform(&[0.5, 0.5, 0.5, 0.25]);
+        assert!((out[0] - 0.25).abs() < 1e-3);
+        assert!((out[1] - 0.5).abs() < 1e-3);
+        assert!((out[2] - 0.2140).abs() < 2e-3);
+        assert!((out[3] - 0.5).abs() < 1e-3);
+
+        let mismatched = LinearizationBuilder::new(ColorSpace::Rgb, vec![Trc::linear()]);
+        assert!(mismatched.build().is_err());
+    }
+}
@@ -0,0 +1,662 @@
+//! CGATS.17 / IT8 Measurement Data
+//!
+//! CGATS is the line-oriented text format written by spectrophotometers and
+//! profiling tools (IT8.7/x, ECI2002, Argyll `.ti3`), and stored in the ICC
+//! `targ` (CharTarget) tag. A file holds one or more tables, each with:
+//!
+//! ```text
+//! CGATS.17                       sheet type (optional)
+//! ORIGINATOR "instrument"        keywords
+//! NUMBER_OF_FIELDS 4
+//! BEGIN_DATA_FORMAT
+//! SAMPLE_ID LAB_L LAB_A LAB_B    field names
+//! END_DATA_FORMAT
+//! NUMBER_OF_SETS 2
+//! BEGIN_DATA
+//! A1 95.0 0.0 -2.0               one row per sample
+//! A2 48.0 74.0 -3.0
+//! END_DATA
+//! ```
+//!
+//! `#` starts a comment and values may be quoted. Other `BEGIN_x`/`END_x`
+//! sections (e.g. Argyll's command line) are kept verbatim.
+
+use std::fmt;
+
+use super::characterization::Measurement;
+use super::error::IccError;
+use super::header::ColorSpace;
+use crate::color::{Lab, Xyz};
+
+/// Keywords defined by CGATS.17 that need no `KEYWORD` declaration
+const STANDARD_KEYWORDS: &[&str] = &[
+    "ORIGINATOR",
+    "DESCRIPTOR",
+    "CREATED",
+    "MANUFACTURER",
+    "MANUFACTURE",
+    "PROD_DATE",
+    "SERIAL",
+    "MATERIAL",
+    "INSTRUMENTATION",
+    "MEASUREMENT_SOURCE",
+    "PRINT_CONDITIONS",
+    "SAMPLE_BACKING",
+    "CHISQ_DOF",
+    "FILTER",
+    "POLARIZATION",
+    "WEIGHTING_FUNCTION",
+    "COMPUTATIONAL_PARAMETER",
+    "TARGET_TYPE",
+    "COLORANT",
+    "TABLE_DESCRIPTOR",
+];
+
+/// A CGATS file: one or more tables
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CgatsFile {
+    /// Tables in file order
+    pub tables: Vec<CgatsTable>,
+}
+
+/// A single CGATS table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CgatsTable {
+    /// Sheet type from the first line (e.g. `CGATS.17`, `IT8.7/2`, `CTI3`)
+    pub sheet_type: Option<String>,
+    /// Keyword/value pairs in file order
+    pub properties: Vec<(String, String)>,
+    /// Other `BEGIN_x`/`END_x` sections as (name, raw text)
+    pub blocks: Vec<(String, String)>,
+    /// Field names from the data format
+    pub fields: Vec<String>,
+    /// Data rows, one value per field
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Spectral columns of a table
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectralData {
+    /// Wavelengths in nm, ascending
+    pub wavelengths: Vec<f64>,
+    /// One reflectance/transmittance vector per row, in wavelength order
+    pub samples: Vec<Vec<f64>>,
+}
+
+impl CgatsFile {
+    /// Parse CGATS text
+    pub fn parse(text: &str) -> Result<Self, IccError> {
+        Parser::default().parse(text)
+    }
+
+    /// First table, which holds the measurements in most files
+    pub fn table(&self) -> Option<&CgatsTable> {
+        self.tables.first()
+    }
+}
+
+impl fmt::Display for CgatsFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, table) in self.tables.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", table)?;
+        }
+        Ok(())
+    }
+}
+
+impl CgatsTable {
+    /// Create an empty table with a sheet type
+    pub fn new(sheet_type: &str) -> Self {
+        Self {
+            sheet_type: Some(sheet_type.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Get a keyword value
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Insert or replace a keyword value
+    pub fn set_property(&mut self, name: &str, value: &str) {
+        match self
+            .properties
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        {
+            Some(entry) => entry.1 = value.to_string(),
+            None => self.properties.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Append a data row
+    ///
+    /// Fails if the number of values does not match the fields.
+    pub fn push_row(&mut self, row: Vec<String>) -> Result<(), IccError> {
+        if row.len() != self.fields.len() {
+            return Err(IccError::CorruptedData(format!(
+                "CGATS row has {} values, expected {}",
+                row.len(),
+                self.fields.len()
+            )));
+        }
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Index of a field by name
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|f| f.eq_ignore_ascii_case(name))
+    }
+
+    /// Values of a field as strings
+    pub fn column_str(&self, name: &str) -> Option<Vec<&str>> {
+        let index = self.field_index(name)?;
+        Some(self.rows.iter().map(|r| r[index].as_str()).collect())
+    }
+
+    /// Values of a numeric field; `None` if missing or not numeric
+    pub fn column(&self, name: &str) -> Option<Vec<f64>> {
+        let index = self.field_index(name)?;
+        self.rows.iter().map(|r| r[index].parse().ok()).collect()
+    }
+
+    /// Rows of several numeric fields
+    pub fn columns<const N: usize>(&self, names: [&str; N]) -> Option<Vec<[f64; N]>> {
+        let mut indices = [0; N];
+        for (index, name) in indices.iter_mut().zip(names) {
+            *index = self.field_index(name)?;
+        }
+        self.rows
+            .iter()
+            .map(|row| {
+                let mut values = [0.0; N];
+                for (v, &i) in values.iter_mut().zip(&indices) {
+                    *v = row[i].parse().ok()?;
+                }
+                Some(values)
+            })
+            .collect()
+    }
+
+    /// Sample identifiers (`SAMPLE_ID` or `SAMPLE_NAME`)
+    pub fn sample_ids(&self) -> Option<Vec<&str>> {
+        self.column_str("SAMPLE_ID")
+            .or_else(|| self.column_str("SAMPLE_NAME"))
+    }
+
+    /// `RGB_R`, `RGB_G`, `RGB_B` as stored (scale depends on the file)
+    pub fn rgb(&self) -> Option<Vec<[f64; 3]>> {
+        self.columns(["RGB_R", "RGB_G", "RGB_B"])
+    }
+
+    /// `CMYK_C`, `CMYK_M`, `CMYK_Y`, `CMYK_K` in percent
+    pub fn cmyk(&self) -> Option<Vec<[f64; 4]>> {
+        self.columns(["CMYK_C", "CMYK_M", "CMYK_Y", "CMYK_K"])
+    }
+
+    /// `CMY_C`, `CMY_M`, `CMY_Y` in percent
+    pub fn cmy(&self) -> Option<Vec<[f64; 3]>> {
+        self.columns(["CMY_C", "CMY_M", "CMY_Y"])
+    }
+
+    /// `LAB_L`, `LAB_A`, `LAB_B`
+    pub fn lab(&self) -> Option<Vec<Lab>> {
+        let values = self.columns(["LAB_L", "LAB_A", "LAB_B"])?;
+        Some(values.into_iter().map(Lab::from).collect())
+    }
+
+    /// `XYZ_X`, `XYZ_Y`, `XYZ_Z` as stored (usually Y = 100 for white)
+    pub fn xyz(&self) -> Option<Vec<Xyz>> {
+        let values = self.columns(["XYZ_X", "XYZ_Y", "XYZ_Z"])?;
+        Some(values.into_iter().map(Xyz::from).collect())
+    }
+
+    /// Spectral fields (`SPECTRAL_NM380`, `SPECTRAL_380`, `SPEC_380`, `nm380`)
+    ///
+    /// Values are returned as stored; most instruments write percent.
+    pub fn spectral(&self) -> Option<SpectralData> {
+        let mut bands: Vec<(f64, usize)> = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| spectral_wavelength(name).map(|nm| (nm, i)))
+            .collect();
+        if bands.is_empty() {
+            return None;
+        }
+        bands.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let samples = self
+            .rows
+            .iter()
+            .map(|row| bands.iter().map(|&(_, i)| row[i].parse().ok()).collect())
+            .collect::<Option<Vec<Vec<f64>>>>()?;
+        Some(SpectralData {
+            wavelengths: bands.iter().map(|&(nm, _)| nm).collect(),
+            samples,
+        })
+    }
+
+    /// Measurements for [`CharacterizationBuilder`](super::CharacterizationBuilder)
+    ///
+    /// Device values are divided by `device_max` (100 for percentages, 255
+    /// for 8-bit RGB). Lab is used if present, otherwise XYZ scaled so that
+    /// Y = 100 becomes 1.
+    pub fn to_measurements(
+        &self,
+        color_space: ColorSpace,
+        device_max: f64,
+    ) -> Option<Vec<Measurement>> {
+        let device: Vec<Vec<f64>> = match color_space {
+            ColorSpace::Rgb => self.rgb()?.iter().map(|v| v.to_vec()).collect(),
+            ColorSpace::Cmy => self.cmy()?.iter().map(|v| v.to_vec()).collect(),
+            ColorSpace::Cmyk => self.cmyk()?.iter().map(|v| v.to_vec()).collect(),
+            _ => return None,
+        };
+        let device = device
+            .into_iter()
+            .map(|d| d.into_iter().map(|v| v / device_max).collect::<Vec<_>>());
+
+        if let Some(lab) = self.lab() {
+            return Some(
+                device
+                    .zip(lab)
+                    .map(|(d, lab)| Measurement::lab(d, lab))
+                    .collect(),
+            );
+        }
+        let xyz = self.xyz()?;
+        Some(
+            device
+                .zip(xyz)
+                .map(|(d, xyz)| Measurement::xyz(d, xyz * 0.01))
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for CgatsTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.sheet_type.as_deref().unwrap_or("CGATS.17"))?;
+        for (name, value) in &self.properties {
+            if !STANDARD_KEYWORDS.contains(&name.as_str()) {
+                writeln!(f, "KEYWORD \"{}\"", name)?;
+            }
+            if value.parse::<f64>().is_ok() {
+                writeln!(f, "{} {}", name, value)?;
+            } else {
+                writeln!(f, "{} \"{}\"", name, value)?;
+            }
+        }
+        for (name, text) in &self.blocks {
+            writeln!(f, "BEGIN_{}", name)?;
+            writeln!(f, "{}", text)?;
+            writeln!(f, "END_{}", name)?;
+        }
+        // A header-only table (e.g. a reference-data pointer) has no data
+        if self.fields.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(f)?;
+        writeln!(f, "NUMBER_OF_FIELDS {}", self.fields.len())?;
+        writeln!(f, "BEGIN_DATA_FORMAT")?;
+        writeln!(f, "{}", self.fields.join(" "))?;
+        writeln!(f, "END_DATA_FORMAT")?;
+        writeln!(f)?;
+        writeln!(f, "NUMBER_OF_SETS {}", self.rows.len())?;
+        writeln!(f, "BEGIN_DATA")?;
+        for row in &self.rows {
+            let values: Vec<String> = row.iter().map(|v| quote_value(v)).collect();
+            writeln!(f, "{}", values.join(" "))?;
+        }
+        writeln!(f, "END_DATA")
+    }
+}
+
+fn quote_value(value: &str) -> String {
+    if value.is_empty() || value.contains(char::is_whitespace) || value.contains('#') {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn spectral_wavelength(field: &str) -> Option<f64> {
+    let upper = field.to_ascii_uppercase();
+    let digits = ["SPECTRAL_NM_", "SPECTRAL_NM", "SPECTRAL_", "SPEC_", "NM"]
+        .iter()
+        .find_map(|prefix| upper.strip_prefix(prefix))?;
+    digits.parse().ok()
+}
+
+/// Split a line into tokens, honoring quotes and `#` comments
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '#' {
+            break;
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let token: String = chars.by_ref().take_while(|&q| q != c).collect();
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '#' {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+enum Section {
+    Header,
+    DataFormat,
+    Data,
+    Block(String, Vec<String>),
+}
+
+#[derive(Default)]
+struct Parser {
+    tables: Vec<CgatsTable>,
+    table: CgatsTable,
+    /// Tokens of the current data section
+    values: Vec<String>,
+    /// Whether the current table has any content yet
+    started: bool,
+}
+
+impl Parser {
+    fn parse(mut self, text: &str) -> Result<CgatsFile, IccError> {
+        let mut section = Section::Header;
+        for (number, line) in text.lines().enumerate() {
+            let error =
+                |msg: &str| IccError::CorruptedData(format!("CGATS line {}: {}", number + 1, msg));
+            let tokens = tokenize(line);
+
+            section = match section {
+                Section::Block(name, mut lines) => {
+                    if tokens
+                        .first()
+                        .is_some_and(|t| *t == format!("END_{}", name))
+                    {
+                        self.table.blocks.push((name, lines.join("\n")));
+                        Section::Header
+                    } else {
+                        lines.push(line.trim_end().to_string());
+                        Section::Block(name, lines)
+                    }
+                }
+                Section::DataFormat => {
+                    let end = tokens.iter().position(|t| t == "END_DATA_FORMAT");
+                    let count = end.unwrap_or(tokens.len());
+                    self.table.fields.extend(tokens.into_iter().take(count));
+                    if end.is_some() {
+                        Section::Header
+                    } else {
+                        Section::DataFormat
+                    }
+                }
+                Section::Data => {
+                    let end = tokens.iter().position(|t| t == "END_DATA");
+                    let count = end.unwrap_or(tokens.len());
+                    self.values.extend(tokens.into_iter().take(count));
+                    if end.is_some() {
+                        self.finish_table().map_err(|e| error(&e))?;
+                        Section::Header
+                    } else {
+                        Section::Data
+                    }
+                }
+                Section::Header => self.header_line(tokens).map_err(|e| error(&e))?,
+            };
+        }
+
+        match section {
+            Section::Header => {}
+            Section::DataFormat => {
+                return Err(IccError::CorruptedData(
+                    "CGATS: missing END_DATA_FORMAT".to_string(),
+                ));
+            }
+            Section::Data => {
+                return Err(IccError::CorruptedData(
+                    "CGATS: missing END_DATA".to_string(),
+                ));
+            }
+            Section::Block(name, _) => {
+                return Err(IccError::CorruptedData(format!(
+                    "CGATS: missing END_{}",
+                    name
+                )));
+            }
+        }
+        if self.started {
+            self.tables.push(self.table);
+        }
+        if self.tables.is_empty() {
+            return Err(IccError::CorruptedData("CGATS: no tables".to_string()));
+        }
+        Ok(CgatsFile {
+            tables: self.tables,
+        })
+    }
+
+    fn header_line(&mut self, tokens: Vec<String>) -> Result<Section, String> {
+        let Some(first) = tokens.first() else {
+            return Ok(Section::Header);
+        };
+        let first_line = !self.started;
+        self.started = true;
+
+        match first.as_str() {
+            "BEGIN_DATA_FORMAT" => {
+                self.table.fields.clear();
+                Ok(Section::DataFormat)
+            }
+            "BEGIN_DATA" => {
+                if self.table.fields.is_empty() {
+                    return Err("BEGIN_DATA before BEGIN_DATA_FORMAT".to_string());
+                }
+                Ok(Section::Data)
+            }
+            // Declares a non-standard keyword; the value follows on its own line
+            "KEYWORD" => Ok(Section::Header),
+            // Derived from the data when writing
+            "NUMBER_OF_FIELDS" | "NUMBER_OF_SETS" => Ok(Section::Header),
+            block if block.starts_with("BEGIN_") => Ok(Section::Block(
+                block["BEGIN_".len()..].to_string(),
+                Vec::new(),
+            )),
+            _ if first_line && tokens.len() == 1 => {
+                self.table.sheet_type = Some(first.clone());
+                Ok(Section::Header)
+            }
+            _ => {
+                let value = tokens[1..].join(" ");
+                self.table.properties.push((first.clone(), value));
+                Ok(Section::Header)
+            }
+        }
+    }
+
+    fn finish_table(&mut self) -> Result<(), String> {
+        let fields = self.table.fields.len();
+        if self.values.len() % fields != 0 {
+            return Err(format!(
+                "{} data values do not fill rows of {} fields",
+                self.values.len(),
+                fields
+            ));
+        }
+        self.table.rows = self.values.chunks(fields).map(|r| r.to_vec()).collect();
+        self.values.clear();
+        self.tables.push(std::mem::take(&mut self.table));
+        self.started = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IT8: &str = r#"IT8.7/2   # target type
+ORIGINATOR "Spectro 1"
+KEYWORD "PAPER"
+PAPER "Coated #1"
+NUMBER_OF_FIELDS 8
+BEGIN_DATA_FORMAT
+SAMPLE_ID CMYK_C CMYK_M CMYK_Y
+CMYK_K LAB_L LAB_A LAB_B
+END_DATA_FORMAT
+NUMBER_OF_SETS 2
+BEGIN_DATA
+A1 0 0 0 0 95.0 0.0 -2.0
+"A 2" 0 100 0 0
+48.0 74.0 -3.0
+END_DATA
+
+CAL
+BEGIN_DATA_FORMAT
+RGB_I RGB_R
+END_DATA_FORMAT
+BEGIN_DATA
+0.0 0.0
+1.0 1.0
+END_DATA
+"#;
+
+    #[test]
+    fn test_parse_tables() {
+        let file = CgatsFile::parse(IT8).unwrap();
+        assert_eq!(file.tables.len(), 2);
+
+        let table = file.table().unwrap();
+        assert_eq!(table.sheet_type.as_deref(), Some("IT8.7/2"));
+        assert_eq!(table.property("originator"), Some("Spectro 1"));
+        assert_eq!(table.property("PAPER"), Some("Coated #1"));
+        assert_eq!(table.fields.len(), 8);
+        assert_eq!(table.sample_ids(), Some(vec!["A1", "A 2"]));
+        assert_eq!(table.cmyk().unwrap()[1], [0.0, 100.0, 0.0, 0.0]);
+        assert_eq!(table.lab().unwrap()[1], Lab::new(48.0, 74.0, -3.0));
+        assert!(table.xyz().is_none());
+        assert!(table.column("SAMPLE_ID").is_none());
+
+        assert_eq!(file.tables[1].sheet_type.as_deref(), Some("CAL"));
+        assert_eq!(file.tables[1].column("RGB_R"), Some(vec![0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_write_roundtrip() {
+        let mut table = CgatsTable::new("CGATS.17");
+        table.set_property("ORIGINATOR", "oxcms");
+        table.set_property("WEIGHTING_FUNCTION", "ILLUMINANT, D50");
+        table.set_property("DEVICE_CLASS", "OUTPUT");
+        table
+            .blocks
+            .push(("NOTES".to_string(), "free text".to_string()));
+        table.fields = [
+            "SAMPLE_ID",
+            "XYZ_X",
+            "XYZ_Y",
+            "XYZ_Z",
+            "SPECTRAL_NM400",
+            "SPECTRAL_NM380",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        table
+            .push_row(
+                ["1", "96.4", "100", "82.5", "90", "80"]
+                    .map(String::from)
+                    .to_vec(),
+            )
+            .unwrap();
+        assert!(table.push_row(vec!["1".to_string()]).is_err());
+
+        let file = CgatsFile {
+            tables: vec![table.clone()],
+        };
+        let text = file.to_string();
+        assert!(text.contains("KEYWORD \"DEVICE_CLASS\""));
+        assert!(!text.contains("KEYWORD \"ORIGINATOR\""));
+        assert_eq!(CgatsFile::parse(&text).unwrap(), file);
+
+        let spectral = table.spectral().unwrap();
+        assert_eq!(spectral.wavelengths, vec![380.0, 400.0]);
+        assert_eq!(spectral.samples, vec![vec![80.0, 90.0]]);
+        assert!(table.xyz().unwrap()[0].approx_eq(&Xyz::new(96.4, 100.0, 82.5), 1e-12));
+    }
+
+    #[test]
+    fn test_to_measurements() {
+        let table = CgatsFile::parse(IT8).unwrap().tables.remove(0);
+        let measurements = table.to_measurements(ColorSpace::Cmyk, 100.0).unwrap();
+        assert_eq!(measurements[1].device, vec![0.0, 1.0, 0.0, 0.0]);
+        assert!(table.to_measurements(ColorSpace::Rgb, 255.0).is_none());
+    }
+
+    #[test]
+    fn test_char_target() {
+        use crate::icc::tags::TextData;
+        use crate::icc::{IccHeader, IccProfile, ProfileClass, TagData, TagSignature};
+
+        let header = IccHeader::new(ProfileClass::Output, ColorSpace::Cmyk, ColorSpace::Lab);
+        let mut profile = IccProfile::new(header);
+        assert_eq!(profile.char_target().unwrap(), None);
+
+        profile.set_tag(
+            TagSignature::CHAR_TARGET,
+            TagData::Text(TextData::new(IT8.to_string())),
+        );
+        let parsed = IccProfile::parse(&profile.to_bytes().unwrap()).unwrap();
+        let target = parsed.char_target().unwrap().unwrap();
+        assert_eq!(target, CgatsFile::parse(IT8).unwrap());
+
+        profile.set_tag(
+            TagSignature::CHAR_TARGET,
+            TagData::Text(TextData::new("ICCHDAT FOGRA39".to_string())),
+        );
+        let reference = profile.char_target().unwrap().unwrap();
+        assert_eq!(
+            reference.table().unwrap().property("ICCHDAT"),
+            Some("FOGRA39")
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CgatsFile::parse("").is_err());
+        assert!(CgatsFile::parse("BEGIN_DATA\n1\nEND_DATA\n").is_err());
+        assert!(
+            CgatsFile::parse(
+                "BEGIN_DATA_FORMAT\nA B\nEND_DATA_FORMAT\nBEGIN_DATA\n1 2 3\nEND_DATA\n"
+            )
+            .is_err()
+        );
+        assert!(
+            CgatsFile::parse("BEGIN_DATA_FORMAT\nA B\nEND_DATA_FORMAT\nBEGIN_DATA\n1 2\n").is_err()
+        );
+    }
+}
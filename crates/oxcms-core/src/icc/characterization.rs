@@ -0,0 +1,883 @@
+//! Output Profile Characterization
+//!
+//! Builds LUT-based output profiles from measured charts (IT8.7/4,
+//! ECI2002, ...). Each [`Measurement`] pairs device values with the color
+//! measured for them; [`CharacterizationBuilder`] turns the set into a v4
+//! output profile with Lab PCS:
+//!
+//! - `A2B0..A2B2` sample a moving least-squares fit of the measurements on a
+//!   regular device grid. Each node is a locally weighted linear regression
+//!   over its nearest patches, so noise is smoothed and the fit extrapolates
+//!   sensibly outside the measured range.
+//! - `B2A0..B2A2` invert the forward table at every Lab grid node with a
+//!   damped Gauss-Newton (Levenberg-Marquardt) search. For CMYK, K is fixed
+//!   first by the [`BlackGeneration`] setting and CMY solved for; the total
+//!   ink limit is enforced throughout. Out-of-gamut colors are clipped to the
+//!   nearest reachable color (ΔE*ab).
+//! - `gamt` flags Lab nodes whose inversion error exceeds
+//!   [`GAMUT_THRESHOLD`].
+//!
+//! The PCS is media-relative: measurements are scaled by the measured paper
+//! white, which is stored in `wtpt`. All three intents carry the same
+//! (colorimetric) tables.
+
+use super::builder::{decode_lab, encode_lab};
+use super::convert::xyz_tag;
+use super::error::IccError;
+use super::header::{ColorSpace, IccHeader, ProfileClass};
+use super::parser::IccProfile;
+use super::tags::{CurveSegment, LutAToBData, LutBToAData, TagData, TextData};
+use super::types::TagSignature;
+use crate::color::{D50, Lab, Xyz};
+use crate::pipeline::ClutData;
+
+/// ΔE*ab above which a Lab node is marked out of gamut in `gamt`
+pub const GAMUT_THRESHOLD: f64 = 3.0;
+
+/// Color measured for a patch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeasuredColor {
+    /// CIELAB (D50)
+    Lab(Lab),
+    /// CIE XYZ (D50, Y = 1 for the perfect diffuser)
+    Xyz(Xyz),
+}
+
+impl MeasuredColor {
+    fn to_xyz(self) -> Xyz {
+        match self {
+            MeasuredColor::Lab(lab) => lab.to_xyz(),
+            MeasuredColor::Xyz(xyz) => xyz,
+        }
+    }
+}
+
+/// One measured patch
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    /// Device values in [0, 1], one per channel (percentages / 100)
+    pub device: Vec<f64>,
+    /// Measured color
+    pub color: MeasuredColor,
+}
+
+impl Measurement {
+    /// Create a measurement with a Lab value
+    pub fn lab(device: Vec<f64>, lab: Lab) -> Self {
+        Self {
+            device,
+            color: MeasuredColor::Lab(lab),
+        }
+    }
+
+    /// Create a measurement with an XYZ value
+    pub fn xyz(device: Vec<f64>, xyz: Xyz) -> Self {
+        Self {
+            device,
+            color: MeasuredColor::Xyz(xyz),
+        }
+    }
+}
+
+/// How much black (K) replaces CMY in CMYK separations
+///
+/// K is a function of darkness (1 - L*/100): zero up to `start`, then
+/// rising linearly to `strength` at black. If the target cannot be reached
+/// with that K, less is used.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BlackGeneration {
+    /// No black; CMY only
+    None,
+    /// Black in the shadows only
+    Light,
+    /// Moderate gray component replacement
+    #[default]
+    Medium,
+    /// Strong gray component replacement
+    Heavy,
+    /// Black replaces as much of CMY as possible
+    Maximum,
+    /// Custom curve
+    Custom {
+        /// Darkness (0 to 1) at which black starts
+        start: f64,
+        /// Fraction of full K used at maximum darkness (0 to 1)
+        strength: f64,
+    },
+}
+
+impl BlackGeneration {
+    /// Target K for a given darkness (0 = paper white, 1 = L* 0)
+    pub fn black(&self, darkness: f64) -> f64 {
+        let (start, strength) = match *self {
+            BlackGeneration::None => return 0.0,
+            BlackGeneration::Light => (0.5, 0.5),
+            BlackGeneration::Medium => (0.3, 0.7),
+            BlackGeneration::Heavy => (0.1, 0.9),
+            BlackGeneration::Maximum => (0.0, 1.0),
+            BlackGeneration::Custom { start, strength } => (start, strength),
+        };
+        if start >= 1.0 {
+            return 0.0;
+        }
+        let t = ((darkness - start) / (1.0 - start)).clamp(0.0, 1.0);
+        (strength * t).clamp(0.0, 1.0)
+    }
+}
+
+/// Builder for LUT-based output profiles from measurement data
+///
+/// Supports Gray, RGB, CMY and CMYK devices. Gray and RGB are treated as
+/// additive (white at 1.0); CMY and CMYK as subtractive (white at 0.0), and
+/// only the latter honor the ink limit.
+///
+/// ```no_run
+/// use oxcms_core::color::Lab;
+/// use oxcms_core::icc::{BlackGeneration, CharacterizationBuilder, ColorSpace, Measurement};
+///
+/// # let patches: Vec<([f64; 4], [f64; 3])> = Vec::new();
+/// let measurements = patches
+///     .iter()
+///     .map(|(cmyk, lab)| Measurement::lab(cmyk.to_vec(), Lab::new(lab[0], lab[1], lab[2])))
+///     .collect();
+/// let profile = CharacterizationBuilder::new(ColorSpace::Cmyk, measurements)
+///     .with_ink_limit(3.0)
+///     .with_black_generation(BlackGeneration::Medium)
+///     .build()
+///     .unwrap();
+/// let bytes = profile.to_bytes().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CharacterizationBuilder {
+    color_space: ColorSpace,
+    measurements: Vec<Measurement>,
+    a2b_grid_points: usize,
+    b2a_grid_points: usize,
+    ink_limit: Option<f64>,
+    black_generation: BlackGeneration,
+    description: String,
+    copyright: String,
+}
+
+impl CharacterizationBuilder {
+    /// Start with 17-point grids, no ink limit and medium black generation
+    pub fn new(color_space: ColorSpace, measurements: Vec<Measurement>) -> Self {
+        Self {
+            color_space,
+            measurements,
+            a2b_grid_points: 17,
+            b2a_grid_points: 17,
+            ink_limit: None,
+            black_generation: BlackGeneration::default(),
+            description: "Output profile".to_string(),
+            copyright: "No copyright, use freely".to_string(),
+        }
+    }
+
+    /// Set the CLUT grid size of the device to PCS tables (2 to 255)
+    pub fn with_a2b_grid_points(mut self, grid_points: usize) -> Self {
+        self.a2b_grid_points = grid_points;
+        self
+    }
+
+    /// Set the CLUT grid size of the PCS to device tables (2 to 255)
+    pub fn with_b2a_grid_points(mut self, grid_points: usize) -> Self {
+        self.b2a_grid_points = grid_points;
+        self
+    }
+
+    /// Limit total ink coverage as a sum of channel values (3.0 = 300%)
+    pub fn with_ink_limit(mut self, limit: f64) -> Self {
+        self.ink_limit = Some(limit);
+        self
+    }
+
+    /// Set the black generation strategy (CMYK only)
+    pub fn with_black_generation(mut self, black_generation: BlackGeneration) -> Self {
+        self.black_generation = black_generation;
+        self
+    }
+
+    /// Set the profile description (`desc`)
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Set the copyright string (`cprt`)
+    pub fn with_copyright(mut self, copyright: &str) -> Self {
+        self.copyright = copyright.to_string();
+        self
+    }
+
+    /// Fit the measurements and build the profile
+    pub fn build(&self) -> Result<IccProfile, IccError> {
+        let channels = self.check()?;
+        let subtractive = matches!(self.color_space, ColorSpace::Cmy | ColorSpace::Cmyk);
+
+        let points: Vec<Vec<f64>> = self.measurements.iter().map(|m| m.device.clone()).collect();
+        let fit = LocalLinearFit::new(points, channels);
+
+        // Media white: measured (or fitted) color of unprinted paper
+        let xyz: Vec<[f64; 3]> = self
+            .measurements
+            .iter()
+            .map(|m| m.color.to_xyz().to_array())
+            .collect();
+        let white_device = vec![if subtractive { 0.0 } else { 1.0 }; channels];
+        let white = media_white(&self.measurements, &white_device)
+            .unwrap_or_else(|| Xyz::from_array(fit.eval(&white_device, &xyz)));
+        if !(white.x > 0.0 && white.y > 0.0 && white.z > 0.0) {
+            return Err(IccError::CorruptedData(format!(
+                "media white ({:.4}, {:.4}, {:.4}) is not positive",
+                white.x, white.y, white.z
+            )));
+        }
+
+        // Media-relative Lab
+        let scale = [
+            D50.xyz.x / white.x,
+            D50.xyz.y / white.y,
+            D50.xyz.z / white.z,
+        ];
+        let lab: Vec<[f64; 3]> = xyz
+            .iter()
+            .map(|v| {
+                let rel = Xyz::new(v[0] * scale[0], v[1] * scale[1], v[2] * scale[2]);
+                Lab::from_xyz(rel).to_array()
+            })
+            .collect();
+
+        let forward = ClutData::sample(channels, self.a2b_grid_points, 3, |p| {
+            let [l, a, b] = fit.eval(p, &lab);
+            encode_lab(Lab::new(l, a, b)).to_vec()
+        });
+
+        let inverter = Inverter {
+            forward: &forward,
+            measurements: &self.measurements,
+            lab: &lab,
+            channels,
+            has_black: self.color_space == ColorSpace::Cmyk,
+            ink_limit: if subtractive { self.ink_limit } else { None },
+            black_generation: self.black_generation,
+        };
+        // Device values plus one gamut channel per node
+        let inverse = ClutData::sample(3, self.b2a_grid_points, channels + 1, |p| {
+            let (device, error) = inverter.invert(decode_lab(p));
+            let mut out = device;
+            out.push(if error > GAMUT_THRESHOLD { 1.0 } else { 0.0 });
+            out
+        });
+        let (reverse, gamut) = split_last_channel(&inverse);
+
+        let header = IccHeader::new(ProfileClass::Output, self.color_space, ColorSpace::Lab);
+        let mut profile = IccProfile::new(header);
+        profile.set_tag(
+            TagSignature::DESC,
+            TagData::Text(TextData::new(self.description.clone())),
+        );
+        profile.set_tag(
+            TagSignature::COPYRIGHT,
+            TagData::Text(TextData::new(self.copyright.clone())),
+        );
+        profile.set_tag(TagSignature::MEDIA_WHITE, xyz_tag(white));
+
+        let a2b = TagData::LutAToB(LutAToBData {
+            input_channels: channels as u8,
+            output_channels: 3,
+            b_curves: Some(vec![CurveSegment::Identity; 3]),
+            matrix: None,
+            m_curves: None,
+            clut: Some(forward.to_lut_clut()),
+            a_curves: Some(vec![CurveSegment::Identity; channels]),
+        });
+        let b2a = btoa(&reverse);
+        for sig in [TagSignature::A2B0, TagSignature::A2B1, TagSignature::A2B2] {
+            profile.set_tag(sig, a2b.clone());
+        }
+        for sig in [TagSignature::B2A0, TagSignature::B2A1, TagSignature::B2A2] {
+            profile.set_tag(sig, b2a.clone());
+        }
+        profile.set_tag(TagSignature::GAMUT, btoa(&gamut));
+        Ok(profile)
+    }
+
+    /// Validate the inputs, returning the device channel count
+    fn check(&self) -> Result<usize, IccError> {
+        if !matches!(
+            self.color_space,
+            ColorSpace::Gray | ColorSpace::Rgb | ColorSpace::Cmy | ColorSpace::Cmyk
+        ) {
+            return Err(IccError::Unsupported(format!(
+                "characterization of {:?} devices",
+                self.color_space
+            )));
+        }
+        let channels = self.color_space.channels();
+
+        for grid in [self.a2b_grid_points, self.b2a_grid_points] {
+            if !(2..=255).contains(&grid) {
+                return Err(IccError::CorruptedData(format!(
+                    "invalid CLUT grid size {}",
+                    grid
+                )));
+            }
+        }
+        if let Some(limit) = self.ink_limit {
+            if !(limit > 0.0 && limit.is_finite()) {
+                return Err(IccError::CorruptedData(format!(
+                    "invalid ink limit {}",
+                    limit
+                )));
+            }
+        }
+        if self.measurements.len() < channels + 2 {
+            return Err(IccError::CorruptedData(format!(
+                "{} measurements are too few for {} channels",
+                self.measurements.len(),
+                channels
+            )));
+        }
+        for (i, m) in self.measurements.iter().enumerate() {
+            if m.device.len() != channels {
+                return Err(IccError::CorruptedData(format!(
+                    "measurement {} has {} device values, expected {}",
+                    i,
+                    m.device.len(),
+                    channels
+                )));
+            }
+            if m.device.iter().any(|v| !(0.0..=1.0).contains(v)) {
+                return Err(IccError::CorruptedData(format!(
+                    "measurement {} has device values outside [0, 1]",
+                    i
+                )));
+            }
+            if !m.color.to_xyz().to_array().iter().all(|v| v.is_finite()) {
+                return Err(IccError::CorruptedData(format!(
+                    "measurement {} has a non-finite color",
+                    i
+                )));
+            }
+        }
+        Ok(channels)
+    }
+}
+
+/// Mean color of the patches printed at `device`, if any
+fn media_white(measurements: &[Measurement], device: &[f64]) -> Option<Xyz> {
+    let matches: Vec<Xyz> = measurements
+        .iter()
+        .filter(|m| {
+            m.device
+                .iter()
+                .zip(device)
+                .all(|(a, b)| (a - b).abs() < 1e-6)
+        })
+        .map(|m| m.color.to_xyz())
+        .collect();
+    if matches.is_empty() {
+        return None;
+    }
+    let sum = matches
+        .iter()
+        .fold(Xyz::new(0.0, 0.0, 0.0), |acc, &v| acc + v);
+    Some(sum * (1.0 / matches.len() as f64))
+}
+
+/// Moving least-squares fit: a weighted linear regression over the nearest
+/// samples of each evaluation point
+struct LocalLinearFit {
+    points: Vec<Vec<f64>>,
+    dims: usize,
+    neighbors: usize,
+}
+
+impl LocalLinearFit {
+    fn new(points: Vec<Vec<f64>>, dims: usize) -> Self {
+        let neighbors = (4 * (dims + 1)).max(12).min(points.len());
+        Self {
+            points,
+            dims,
+            neighbors,
+        }
+    }
+
+    /// Fitted value of `values` (one per sample point) at `x`
+    fn eval(&self, x: &[f64], values: &[[f64; 3]]) -> [f64; 3] {
+        let mut nearest: Vec<(f64, usize)> = self
+            .points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.iter().zip(x).map(|(a, b)| (a - b) * (a - b)).sum(), i))
+            .collect();
+        let k = self.neighbors;
+        if k < nearest.len() {
+            nearest.select_nth_unstable_by(k - 1, |a, b| a.0.total_cmp(&b.0));
+            nearest.truncate(k);
+        }
+
+        // Wendland-style weights that vanish just beyond the k-th neighbor
+        let radius = nearest.iter().map(|n| n.0).fold(0.0, f64::max) * 1.5 + 1e-12;
+        let n = self.dims + 1;
+        let mut a = vec![vec![0.0; n]; n];
+        let mut b = vec![[0.0; 3]; n];
+        let mut basis = vec![0.0; n];
+        for &(d2, i) in &nearest {
+            let w = (1.0 - d2 / radius).powi(2);
+            basis[0] = 1.0;
+            for d in 0..self.dims {
+                basis[d + 1] = self.points[i][d] - x[d];
+            }
+            for r in 0..n {
+                for c in 0..n {
+                    a[r][c] += w * basis[r] * basis[c];
+                }
+                for ch in 0..3 {
+                    b[r][ch] += w * basis[r] * values[i][ch];
+                }
+            }
+        }
+        // Slight ridge on the slopes keeps degenerate neighborhoods solvable
+        let ridge = a[0][0] * 1e-6;
+        for (d, row) in a.iter_mut().enumerate().skip(1) {
+            row[d] += ridge;
+        }
+
+        match solve(a, b) {
+            Some(coefficients) => coefficients[0],
+            None => {
+                // Weighted mean
+                let total: f64 = nearest.iter().map(|n| (1.0 - n.0 / radius).powi(2)).sum();
+                let mut mean = [0.0; 3];
+                for &(d2, i) in &nearest {
+                    let w = (1.0 - d2 / radius).powi(2) / total;
+                    for ch in 0..3 {
+                        mean[ch] += w * values[i][ch];
+                    }
+                }
+                mean
+            }
+        }
+    }
+}
+
+/// Gaussian elimination with partial pivoting for three right-hand sides
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<[f64; 3]>) -> Option<Vec<[f64; 3]>> {
+    let n = a.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-14 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..n {
+            let f = a[row][col] / a[col][col];
+            if f == 0.0 {
+                continue;
+            }
+            let (upper, lower) = a.split_at_mut(row);
+            for (v, p) in lower[0][col..].iter_mut().zip(&upper[col][col..]) {
+                *v -= f * p;
+            }
+            let pivot_rhs = b[col];
+            for (v, p) in b[row].iter_mut().zip(pivot_rhs) {
+                *v -= f * p;
+            }
+        }
+    }
+    let mut x = vec![[0.0; 3]; n];
+    for row in (0..n).rev() {
+        for ch in 0..3 {
+            let sum: f64 = (row + 1..n).map(|c| a[row][c] * x[c][ch]).sum();
+            x[row][ch] = (b[row][ch] - sum) / a[row][row];
+        }
+    }
+    Some(x)
+}
+
+/// Numerical inversion of the forward (device to Lab) table
+struct Inverter<'a> {
+    forward: &'a ClutData,
+    measurements: &'a [Measurement],
+    /// Media-relative Lab of each measurement
+    lab: &'a [[f64; 3]],
+    channels: usize,
+    has_black: bool,
+    ink_limit: Option<f64>,
+    black_generation: BlackGeneration,
+}
+
+impl Inverter<'_> {
+    /// Device values for `target` and the remaining ΔE*ab
+    fn invert(&self, target: Lab) -> (Vec<f64>, f64) {
+        let start = self.nearest_measurement(target);
+        if !self.has_black {
+            return self.solve(target, start, None);
+        }
+
+        let darkness = (1.0 - target.l / 100.0).clamp(0.0, 1.0);
+        let mut black = self.black_generation.black(darkness);
+        if let Some(limit) = self.ink_limit {
+            black = black.min(limit);
+        }
+
+        // Back off K until the target is reachable
+        let mut best: Option<(Vec<f64>, f64)> = None;
+        for step in [1.0, 0.75, 0.5, 0.25, 0.0] {
+            let k = black * step;
+            let (device, error) = self.solve(target, start.clone(), Some(k));
+            if error < 1.0 {
+                return (device, error);
+            }
+            if best.as_ref().is_none_or(|b| error < b.1) {
+                best = Some((device, error));
+            }
+            if black == 0.0 {
+                break;
+            }
+        }
+        best.unwrap_or_else(|| (vec![0.0; self.channels], f64::INFINITY))
+    }
+
+    fn nearest_measurement(&self, target: Lab) -> Vec<f64> {
+        let t = target.to_array();
+        let distance =
+            |lab: &[f64; 3]| -> f64 { lab.iter().zip(t).map(|(a, b)| (a - b).powi(2)).sum() };
+        self.lab
+            .iter()
+            .zip(self.measurements)
+            .min_by(|a, b| distance(a.0).total_cmp(&distance(b.0)))
+            .map(|(_, m)| m.device.clone())
+            .unwrap_or_else(|| vec![0.0; self.channels])
+    }
+
+    fn lab_at(&self, device: &[f64]) -> [f64; 3] {
+        decode_lab(&self.forward.interpolate(device)).to_array()
+    }
+
+    /// Clamp to [0, 1] and scale the free channels down to the ink limit
+    fn project(&self, device: &mut [f64], free: usize) {
+        for v in device.iter_mut() {
+            *v = v.clamp(0.0, 1.0);
+        }
+        if let Some(limit) = self.ink_limit {
+            let fixed: f64 = device[free..].iter().sum();
+            let total: f64 = device[..free].iter().sum();
+            if fixed + total > limit && total > 0.0 {
+                let scale = ((limit - fixed) / total).max(0.0);
+                device[..free].iter_mut().for_each(|v| *v *= scale);
+            }
+        }
+    }
+
+    /// Levenberg-Marquardt on the free channels; K (if given) stays fixed
+    fn solve(&self, target: Lab, mut device: Vec<f64>, black: Option<f64>) -> (Vec<f64>, f64) {
+        const ITERATIONS: usize = 50;
+        const STEP: f64 = 1e-4;
+
+        let target = target.to_array();
+        let free = if let Some(k) = black {
+            device[self.channels - 1] = k;
+            self.channels - 1
+        } else {
+            self.channels
+        };
+        self.project(&mut device, free);
+
+        let residual = |lab: [f64; 3]| [lab[0] - target[0], lab[1] - target[1], lab[2] - target[2]];
+        let norm = |r: [f64; 3]| (r[0] * r[0] + r[1] * r[1] + r[2] * r[2]).sqrt();
+
+        let mut r = residual(self.lab_at(&device));
+        let mut error = norm(r);
+        let mut damping = 1e-3;
+        for _ in 0..ITERATIONS {
+            if error < 1e-3 {
+                break;
+            }
+
+            // Forward differences, stepping inward at the upper bound
+            let mut jacobian = vec![[0.0; 3]; free];
+            for (j, column) in jacobian.iter_mut().enumerate() {
+                let h = if device[j] + STEP > 1.0 { -STEP } else { STEP };
+                let mut probe = device.clone();
+                probe[j] += h;
+                let lab = self.lab_at(&probe);
+                let base = [r[0] + target[0], r[1] + target[1], r[2] + target[2]];
+                for ch in 0..3 {
+                    column[ch] = (lab[ch] - base[ch]) / h;
+                }
+            }
+
+            // (JᵀJ + λ·diag(JᵀJ)) δ = -Jᵀr
+            let mut a = vec![vec![0.0; free]; free];
+            let mut g = vec![[0.0; 3]; free];
+            for i in 0..free {
+                for j in 0..free {
+                    a[i][j] = (0..3).map(|ch| jacobian[i][ch] * jacobian[j][ch]).sum();
+                }
+                g[i][0] = -(0..3).map(|ch| jacobian[i][ch] * r[ch]).sum::<f64>();
+            }
+            for (i, row) in a.iter_mut().enumerate() {
+                row[i] += damping * row[i] + 1e-9;
+            }
+            let Some(delta) = solve(a, g) else {
+                break;
+            };
+
+            let mut candidate = device.clone();
+            for i in 0..free {
+                candidate[i] += delta[i][0];
+            }
+            self.project(&mut candidate, free);
+            let step: f64 = candidate
+                .iter()
+                .zip(&device)
+                .map(|(a, b)| (a - b).abs())
+                .sum();
+
+            let candidate_r = residual(self.lab_at(&candidate));
+            let candidate_error = norm(candidate_r);
+            if candidate_error < error {
+                device = candidate;
+                r = candidate_r;
+                error = candidate_error;
+                damping = (damping / 3.0).max(1e-9);
+            } else {
+                damping *= 4.0;
+                if damping > 1e6 {
+                    break;
+                }
+            }
+            if step < 1e-7 {
+                break;
+            }
+        }
+        (device, error)
+    }
+}
+
+/// Separate the trailing channel of a CLUT into its own table
+fn split_last_channel(clut: &ClutData) -> (ClutData, ClutData) {
+    let outputs = clut.output_channels;
+    let mut head = Vec::with_capacity(clut.data.len() / outputs * (outputs - 1));
+    let mut last = Vec::with_capacity(clut.data.len() / outputs);
+    for node in clut.data.chunks(outputs) {
+        head.extend_from_slice(&node[..outputs - 1]);
+        last.push(node[outputs - 1]);
+    }
+    (
+        ClutData {
+            grid_points: clut.grid_points.clone(),
+            output_channels: outputs - 1,
+            data: head,
+        },
+        ClutData {
+            grid_points: clut.grid_points.clone(),
+            output_channels: 1,
+            data: last,
+        },
+    )
+}
+
+/// lutBToA tag holding only a CLUT
+fn btoa(clut: &ClutData) -> TagData {
+    TagData::LutBToA(LutBToAData {
+        input_channels: 3,
+        output_channels: clut.output_channels as u8,
+        b_curves: Some(vec![CurveSegment::Identity; 3]),
+        matrix: None,
+        m_curves: None,
+        clut: Some(clut.to_lut_clut()),
+        a_curves: Some(vec![CurveSegment::Identity; clut.output_channels]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Toy subtractive printer: each ink absorbs mostly one band
+    fn printer(device: &[f64]) -> Xyz {
+        let (c, m, y, k) = (device[0], device[1], device[2], device[3]);
+        let black = 1.0 - 0.92 * k;
+        let r = (1.0 - 0.9 * c) * (1.0 - 0.15 * m) * (1.0 - 0.05 * y) * black;
+        let g = (1.0 - 0.2 * c) * (1.0 - 0.85 * m) * (1.0 - 0.15 * y) * black;
+        let b = (1.0 - 0.1 * c) * (1.0 - 0.25 * m) * (1.0 - 0.9 * y) * black;
+        let paper = 0.9;
+        Xyz::new(
+            paper * (0.4361 * r + 0.3851 * g + 0.1431 * b),
+            paper * (0.2225 * r + 0.7169 * g + 0.0606 * b),
+            paper * (0.0139 * r + 0.0971 * g + 0.7141 * b),
+        )
+    }
+
+    /// Media-relative Lab of the toy printer
+    fn printer_lab(device: &[f64]) -> Lab {
+        let white = printer(&[0.0; 4]);
+        let v = printer(device);
+        Lab::from_xyz(Xyz::new(
+            v.x * D50.xyz.x / white.x,
+            v.y * D50.xyz.y / white.y,
+            v.z * D50.xyz.z / white.z,
+        ))
+    }
+
+    fn chart(levels: usize) -> Vec<Measurement> {
+        let step = |i: usize| i as f64 / (levels - 1) as f64;
+        let mut out = Vec::new();
+        for c in 0..levels {
+            for m in 0..levels {
+                for y in 0..levels {
+                    for k in 0..levels {
+                        let device = vec![step(c), step(m), step(y), step(k)];
+                        let xyz = printer(&device);
+                        out.push(Measurement::xyz(device, xyz));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn clut(profile: &IccProfile, sig: TagSignature) -> ClutData {
+        let clut = match profile.get_tag(sig) {
+            Some(TagData::LutAToB(lut)) => lut.clut.clone(),
+            Some(TagData::LutBToA(lut)) => lut.clut.clone(),
+            _ => None,
+        }
+        .unwrap();
+        ClutData {
+            grid_points: clut.grid_points.iter().map(|&g| g as usize).collect(),
+            output_channels: clut.output_channels as usize,
+            data: clut.data,
+        }
+    }
+
+    fn delta_e(a: Lab, b: Lab) -> f64 {
+        ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn test_cmyk_profile() {
+        let profile = CharacterizationBuilder::new(ColorSpace::Cmyk, chart(5))
+            .with_a2b_grid_points(9)
+            .with_b2a_grid_points(9)
+            .with_ink_limit(3.0)
+            .build()
+            .unwrap();
+        assert!(profile.validate().is_valid(), "{:?}", profile.validate());
+        let bytes = profile.to_bytes().unwrap();
+        assert!(IccProfile::parse(&bytes).is_ok());
+
+        let white = profile.media_white_point().unwrap();
+        assert!(white.approx_eq(&printer(&[0.0; 4]), 1e-3));
+
+        // Forward table reproduces the device between chart nodes
+        let forward = clut(&profile, TagSignature::A2B1);
+        for device in [
+            [0.1, 0.6, 0.3, 0.2],
+            [0.8, 0.1, 0.45, 0.05],
+            [0.3, 0.3, 0.3, 0.7],
+        ] {
+            let lab = decode_lab(&forward.interpolate(&device));
+            let expected = printer_lab(&device);
+            assert!(delta_e(lab, expected) < 2.0, "{:?} vs {:?}", lab, expected);
+        }
+
+        // Every separation respects the ink limit
+        let reverse = clut(&profile, TagSignature::B2A1);
+        for node in reverse.data.chunks(4) {
+            assert!(node.iter().sum::<f64>() <= 3.0 + 1e-3, "{:?}", node);
+        }
+    }
+
+    #[test]
+    fn test_inversion_roundtrip() {
+        let measurements = chart(5);
+        let builder = CharacterizationBuilder::new(ColorSpace::Cmyk, measurements.clone())
+            .with_a2b_grid_points(9)
+            .with_ink_limit(2.6);
+        let fit = LocalLinearFit::new(measurements.iter().map(|m| m.device.clone()).collect(), 4);
+        let lab: Vec<[f64; 3]> = measurements
+            .iter()
+            .map(|m| printer_lab(&m.device).to_array())
+            .collect();
+        let forward = ClutData::sample(4, 9, 3, |p| {
+            let [l, a, b] = fit.eval(p, &lab);
+            encode_lab(Lab::new(l, a, b)).to_vec()
+        });
+        let inverter = Inverter {
+            forward: &forward,
+            measurements: &measurements,
+            lab: &lab,
+            channels: 4,
+            has_black: true,
+            ink_limit: builder.ink_limit,
+            black_generation: BlackGeneration::Medium,
+        };
+
+        for device in [
+            [0.2, 0.5, 0.1, 0.0],
+            [0.6, 0.4, 0.3, 0.3],
+            [0.05, 0.05, 0.05, 0.5],
+        ] {
+            let target = printer_lab(&device);
+            let (separation, error) = inverter.invert(target);
+            assert!(error < 1.0);
+            assert!(separation.iter().sum::<f64>() <= 2.6 + 1e-6);
+            assert!(delta_e(printer_lab(&separation), target) < 2.0);
+        }
+
+        // Unreachable colors are clipped and reported
+        let (_, error) = inverter.invert(Lab::new(50.0, 120.0, -120.0));
+        assert!(error > GAMUT_THRESHOLD);
+    }
+
+    #[test]
+    fn test_black_generation() {
+        assert_eq!(BlackGeneration::None.black(1.0), 0.0);
+        assert_eq!(BlackGeneration::Light.black(0.4), 0.0);
+        assert!((BlackGeneration::Maximum.black(0.6) - 0.6).abs() < 1e-12);
+        assert!(BlackGeneration::Heavy.black(0.8) > BlackGeneration::Medium.black(0.8));
+
+        let build = |bg| {
+            let profile = CharacterizationBuilder::new(ColorSpace::Cmyk, chart(4))
+                .with_a2b_grid_points(5)
+                .with_b2a_grid_points(5)
+                .with_black_generation(bg)
+                .build()
+                .unwrap();
+            clut(&profile, TagSignature::B2A0)
+        };
+        // Lab node (L* 25, a* 0, b* 0): a dark neutral
+        let dark_neutral = [0.25, 128.0 / 255.0, 128.0 / 255.0];
+        let none = build(BlackGeneration::None);
+        assert!(none.data.chunks(4).all(|node| node[3] == 0.0));
+        let max = build(BlackGeneration::Maximum);
+        assert!(max.interpolate(&dark_neutral)[3] > 0.3);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let bad_length = vec![Measurement::lab(vec![0.0; 3], Lab::new(50.0, 0.0, 0.0)); 8];
+        assert!(
+            CharacterizationBuilder::new(ColorSpace::Cmyk, bad_length)
+                .build()
+                .is_err()
+        );
+        assert!(
+            CharacterizationBuilder::new(ColorSpace::Lab, chart(2))
+                .build()
+                .is_err()
+        );
+        assert!(
+            CharacterizationBuilder::new(ColorSpace::Cmyk, chart(2))
+                .with_a2b_grid_points(1)
+                .build()
+                .is_err()
+        );
+    }
+}
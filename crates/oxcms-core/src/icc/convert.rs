@@ -0,0 +1,782 @@
+//! Profile Version Conversion
+//!
+//! Rewrites v4 profiles as v2 and upgrades v2 profiles to v4.
+//!
+//! v4 → v2:
+//! - `mluc` text becomes `desc`/`text` (only the default language is kept)
+//! - `para` curves become `curv` (gamma, or a sampled table)
+//! - `mAB`/`mBA` become `mft2`, re-encoding Lab PCS values to the legacy
+//!   16-bit encoding (L* = 100 at 0xFF00)
+//! - `chad` is removed; colorants stay D50-adapted and the display media
+//!   white point is restored from `chad`
+//! - tags with no v2 type (`meta`, `cicp`, `mpet`) are dropped
+//!
+//! v2 → v4:
+//! - display profiles with a non-D50 media white point get a Bradford
+//!   `chad` and a D50 `wtpt`
+//!
+//! Every step that may change the profile's behavior is recorded in a
+//! [`ConversionReport`].
+
+use super::error::IccError;
+use super::header::{ColorSpace, ProfileClass, ProfileVersion};
+use super::parser::IccProfile;
+use super::tags::{
+    CurveData, CurveSegment, Lut16Data, LutAToBData, LutBToAData, LutClut, LutMatrix,
+    ParametricCurveData, TagData, TextData, XyzTagData,
+};
+use super::types::{S15Fixed16, TagSignature, TypeSignature, XyzNumber};
+use crate::color::WhitePoint;
+use crate::math::{Matrix3x3, bradford_matrix, parametric_curve_eval};
+use crate::pipeline::{ClutData, eval_parametric};
+
+/// Table size used when a curve must be sampled
+const CURVE_ENTRIES: usize = 4096;
+
+/// Grid size used when a matrix must be sampled into a CLUT
+const MATRIX_GRID: usize = 33;
+
+/// v2 16-bit Lab encodes L* = 100 as 0xFF00; v4 as 0xFFFF
+const LAB_V2_SCALE: f64 = 65280.0 / 65535.0;
+
+/// A single change made during conversion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionStep {
+    /// Tag the step applies to, if any
+    pub tag: Option<TagSignature>,
+    /// Whether the converted profile may behave differently
+    pub lossy: bool,
+    /// Human-readable description
+    pub detail: String,
+}
+
+/// Changes made while converting a profile between versions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    /// Steps in the order they were taken
+    pub steps: Vec<ConversionStep>,
+}
+
+impl ConversionReport {
+    /// Check if no step lost information
+    pub fn is_lossless(&self) -> bool {
+        self.steps.iter().all(|s| !s.lossy)
+    }
+
+    /// Steps that may change the profile's behavior
+    pub fn lossy_steps(&self) -> impl Iterator<Item = &ConversionStep> {
+        self.steps.iter().filter(|s| s.lossy)
+    }
+
+    fn note(&mut self, tag: TagSignature, lossy: bool, detail: impl Into<String>) {
+        self.steps.push(ConversionStep {
+            tag: Some(tag),
+            lossy,
+            detail: detail.into(),
+        });
+    }
+}
+
+/// Convert a profile to v2.1
+pub(super) fn to_v2(profile: &IccProfile) -> Result<(IccProfile, ConversionReport), IccError> {
+    let mut report = ConversionReport::default();
+    let mut header = profile.header.clone();
+    check_source_version(header.version)?;
+    header.version = ProfileVersion {
+        major: 2,
+        minor: 1,
+        patch: 0,
+    };
+    // v2 has no profile ID
+    header.profile_id = [0; 16];
+
+    let mut out = IccProfile::new(header);
+    if profile.header.version.major == 2 {
+        copy_tags(profile, &mut out);
+        return Ok((out, report));
+    }
+
+    let mut signatures: Vec<TagSignature> = profile.tag_signatures().collect();
+    signatures.sort_by_key(|s| s.0);
+
+    for sig in signatures {
+        let Some(tag) = profile.get_tag(sig) else {
+            continue;
+        };
+        let converted = match tag {
+            TagData::MultiLocalizedUnicode(text) => {
+                let lossy = text.localized.len() > 1 || text.text.chars().any(|c| c as u32 > 0xFF);
+                report.note(sig, lossy, "mluc converted to single-language text");
+                Some(TagData::Text(TextData::new(text.text.clone())))
+            }
+            TagData::ParametricCurve(para) => {
+                Some(TagData::Curve(para_to_curv(para, sig, &mut report)))
+            }
+            TagData::LutAToB(lut) => {
+                let (input_lab, output_lab) = lab_sides(profile, sig);
+                let lut16 = atob_to_lut16(lut, input_lab, output_lab)?;
+                report.note(sig, lut16.1, "mAB converted to mft2");
+                Some(TagData::Lut16(lut16.0))
+            }
+            TagData::LutBToA(lut) => {
+                let (input_lab, output_lab) = lab_sides(profile, sig);
+                let lut16 = btoa_to_lut16(lut, input_lab, output_lab)?;
+                report.note(sig, lut16.1, "mBA converted to mft2");
+                Some(TagData::Lut16(lut16.0))
+            }
+            TagData::ChromaticAdaptation(_) => None,
+            TagData::Dict(_) | TagData::Cicp(_) => {
+                report.note(sig, true, "dropped; no v2 equivalent");
+                None
+            }
+            TagData::Unknown { type_sig, .. } if TypeSignature(*type_sig) == MULTI_PROCESS => {
+                report.note(sig, true, "multiProcessElements dropped; no v2 equivalent");
+                None
+            }
+            other => Some(other.clone()),
+        };
+        if let Some(data) = converted {
+            out.set_tag(sig, data);
+        }
+    }
+
+    if let Some(chad) = profile.chromatic_adaptation_matrix() {
+        remove_chad(profile, &mut out, chad, &mut report);
+    }
+
+    Ok((out, report))
+}
+
+/// Convert a profile to v4.4
+pub(super) fn to_v4(profile: &IccProfile) -> Result<(IccProfile, ConversionReport), IccError> {
+    let mut report = ConversionReport::default();
+    let mut header = profile.header.clone();
+    check_source_version(header.version)?;
+    header.version = ProfileVersion {
+        major: 4,
+        minor: 4,
+        patch: 0,
+    };
+
+    let mut out = IccProfile::new(header);
+    copy_tags(profile, &mut out);
+    if profile.header.version.major == 4 {
+        return Ok((out, report));
+    }
+
+    // v4 display profiles store a D50 white point and carry the adaptation in chad
+    let white = profile.media_white_point();
+    let d50 = XyzNumber::D50.to_xyz();
+    if profile.header.device_class == ProfileClass::Display
+        && profile.get_tag(TagSignature::CHAD).is_none()
+    {
+        if let Some(white) = white.filter(|w| !w.approx_eq(&d50, 1e-3)) {
+            let chad = bradford_matrix(&WhitePoint::from_xyz(white), &WhitePoint::from_xyz(d50));
+            out.set_tag(TagSignature::CHAD, TagData::ChromaticAdaptation(chad.m));
+            out.set_tag(TagSignature::MEDIA_WHITE, xyz_tag(d50));
+            report.note(
+                TagSignature::CHAD,
+                false,
+                "added Bradford adaptation from the media white point; wtpt set to D50",
+            );
+        }
+    }
+
+    Ok((out, report))
+}
+
+/// multiProcessElementsType, which has no v2 equivalent
+const MULTI_PROCESS: TypeSignature = TypeSignature::from_bytes(*b"mpet");
+
+fn check_source_version(version: ProfileVersion) -> Result<(), IccError> {
+    match version.major {
+        2 | 4 => Ok(()),
+        major => Err(IccError::UnsupportedVersion {
+            major,
+            minor: version.minor,
+        }),
+    }
+}
+
+fn copy_tags(from: &IccProfile, to: &mut IccProfile) {
+    for sig in from.tag_signatures() {
+        if let Some(tag) = from.get_tag(sig) {
+            to.set_tag(sig, tag.clone());
+        }
+    }
+}
+
+pub(super) fn xyz_tag(xyz: crate::color::Xyz) -> TagData {
+    TagData::Xyz(XyzTagData {
+        values: vec![XyzNumber::from_xyz(xyz)],
+    })
+}
+
+/// Drop `chad`, restoring the unadapted media white for display profiles
+///
+/// Colorants and LUTs are already PCS-relative (adapted to D50), so they
+/// need no change; only absolute colorimetric rendering is affected.
+fn remove_chad(
+    source: &IccProfile,
+    out: &mut IccProfile,
+    chad: [[f64; 3]; 3],
+    report: &mut ConversionReport,
+) {
+    let inverse = Matrix3x3::new(chad).inverse();
+    let white = source.media_white_point();
+
+    match (source.header.device_class, inverse, white) {
+        (ProfileClass::Display, Some(inverse), Some(white)) => {
+            let native = inverse.multiply_vec(white.to_array());
+            out.set_tag(
+                TagSignature::MEDIA_WHITE,
+                xyz_tag(crate::color::Xyz::new(native[0], native[1], native[2])),
+            );
+            report.note(
+                TagSignature::CHAD,
+                true,
+                "removed; wtpt now holds the display white, so absolute colorimetric \
+                 uses wtpt scaling instead of chad",
+            );
+        }
+        _ => report.note(TagSignature::CHAD, true, "removed"),
+    }
+}
+
+/// Which sides of a LUT tag carry Lab PCS values: (input, output)
+fn lab_sides(profile: &IccProfile, sig: TagSignature) -> (bool, bool) {
+    let header = &profile.header;
+    let data_lab = header.color_space == ColorSpace::Lab;
+    let pcs_lab = header.pcs == ColorSpace::Lab;
+    match sig {
+        TagSignature::A2B0 | TagSignature::A2B1 | TagSignature::A2B2 => (data_lab, pcs_lab),
+        TagSignature::B2A0 | TagSignature::B2A1 | TagSignature::B2A2 => (pcs_lab, data_lab),
+        TagSignature::GAMUT => (pcs_lab, false),
+        TagSignature::PREVIEW0 | TagSignature::PREVIEW1 | TagSignature::PREVIEW2 => {
+            (pcs_lab, pcs_lab)
+        }
+        _ => (false, false),
+    }
+}
+
+fn para_to_curv(
+    para: &ParametricCurveData,
+    sig: TagSignature,
+    report: &mut ConversionReport,
+) -> CurveData {
+    use crate::math::ParametricCurveType;
+
+    let curve = &para.curve;
+    if curve.curve_type == ParametricCurveType::Gamma {
+        // curv stores gamma as u8Fixed8
+        let quantized = (curve.g * 256.0).round() / 256.0;
+        let lossy = (quantized - curve.g).abs() > 1e-9;
+        report.note(sig, lossy, format!("para gamma {} stored as curv", curve.g));
+        return CurveData::Gamma(curve.g);
+    }
+
+    report.note(
+        sig,
+        true,
+        format!("para sampled to {}-entry curv table", CURVE_ENTRIES),
+    );
+    CurveData::Table(sample_curve(CURVE_ENTRIES, |x| {
+        parametric_curve_eval(curve, x)
+    }))
+}
+
+// ============================================================================
+// lutAToB / lutBToA → lut16
+// ============================================================================
+
+fn eval_segment(segment: &CurveSegment, x: f64) -> f64 {
+    let x = x.clamp(0.0, 1.0);
+    match segment {
+        CurveSegment::Identity => x,
+        CurveSegment::Table(table) => match table.len() {
+            0 => x,
+            1 => table[0],
+            n => {
+                let pos = x * (n - 1) as f64;
+                let i = (pos.floor() as usize).min(n - 2);
+                let t = pos - i as f64;
+                table[i] + t * (table[i + 1] - table[i])
+            }
+        },
+        CurveSegment::Parametric { curve_type, params } => eval_parametric(*curve_type, params, x),
+    }
+}
+
+fn eval_segments(segments: Option<&[CurveSegment]>, values: &mut [f64]) {
+    if let Some(segments) = segments {
+        for (v, segment) in values.iter_mut().zip(segments) {
+            *v = eval_segment(segment, *v);
+        }
+    }
+}
+
+fn is_identity(segments: Option<&[CurveSegment]>) -> bool {
+    segments.is_none_or(|s| s.iter().all(|c| matches!(c, CurveSegment::Identity)))
+}
+
+fn apply_matrix(matrix: &LutMatrix, values: &mut [f64]) {
+    let v = [values[0], values[1], values[2]];
+    for (row, out) in values.iter_mut().take(3).enumerate() {
+        let m = matrix.matrix[row];
+        *out = m[0] * v[0] + m[1] * v[1] + m[2] * v[2] + matrix.offset[row];
+    }
+}
+
+fn to_u16(v: f64) -> u16 {
+    (v.clamp(0.0, 1.0) * 65535.0).round() as u16
+}
+
+fn sample_curve(entries: usize, f: impl Fn(f64) -> f64) -> Vec<u16> {
+    (0..entries)
+        .map(|i| to_u16(f(i as f64 / (entries - 1) as f64)))
+        .collect()
+}
+
+/// Evaluate `f` at every node of a uniform grid (first input varies slowest)
+fn sample_grid(
+    inputs: usize,
+    grid: usize,
+    outputs: usize,
+    f: impl Fn(&[f64]) -> Vec<f64>,
+) -> Vec<u16> {
+    ClutData::sample(inputs, grid, outputs, f)
+        .data
+        .into_iter()
+        .map(to_u16)
+        .collect()
+}
+
+/// Uniform grid size of a CLUT, if all dimensions agree
+fn uniform_grid(clut: &LutClut) -> Option<u8> {
+    let first = *clut.grid_points.first()?;
+    clut.grid_points
+        .iter()
+        .all(|&g| g == first)
+        .then_some(first)
+}
+
+pub(super) fn lut16(
+    inputs: usize,
+    outputs: usize,
+    grid: usize,
+    input_curves: Vec<Vec<u16>>,
+    clut: Vec<u16>,
+    output_curves: Vec<Vec<u16>>,
+) -> Lut16Data {
+    let one = S15Fixed16::from_f64(1.0);
+    let zero = S15Fixed16::default();
+    Lut16Data {
+        input_channels: inputs as u8,
+        output_channels: outputs as u8,
+        grid_points: grid as u8,
+        matrix: [[one, zero, zero], [zero, one, zero], [zero, zero, one]],
+        input_entries: input_curves.first().map_or(0, |c| c.len()) as u16,
+        output_entries: output_curves.first().map_or(0, |c| c.len()) as u16,
+        input_curves,
+        clut,
+        output_curves,
+    }
+}
+
+/// Input curves: v2 Lab re-encoding followed by `segments`
+fn lut16_input_curves(
+    channels: usize,
+    segments: Option<&[CurveSegment]>,
+    lab: bool,
+) -> Vec<Vec<u16>> {
+    let entries = if lab || !is_identity(segments) {
+        CURVE_ENTRIES
+    } else {
+        2
+    };
+    (0..channels)
+        .map(|c| {
+            sample_curve(entries, |x| {
+                let x = if lab { (x / LAB_V2_SCALE).min(1.0) } else { x };
+                segments.map_or(x, |s| eval_segment(&s[c], x))
+            })
+        })
+        .collect()
+}
+
+/// Output curves: `segments` followed by v2 Lab re-encoding
+fn lut16_output_curves(
+    channels: usize,
+    segments: Option<&[CurveSegment]>,
+    lab: bool,
+) -> Vec<Vec<u16>> {
+    let entries = if lab || !is_identity(segments) {
+        CURVE_ENTRIES
+    } else {
+        2
+    };
+    (0..channels)
+        .map(|c| {
+            sample_curve(entries, |x| {
+                let y = segments.map_or(x, |s| eval_segment(&s[c], x));
+                if lab { y * LAB_V2_SCALE } else { y }
+            })
+        })
+        .collect()
+}
+
+/// Convert lutAToB (A → CLUT → M → matrix → B) to lut16
+///
+/// Returns the LUT and whether the conversion was lossy.
+fn atob_to_lut16(
+    lut: &LutAToBData,
+    input_lab: bool,
+    output_lab: bool,
+) -> Result<(Lut16Data, bool), IccError> {
+    let (inputs, outputs) = (lut.input_channels as usize, lut.output_channels as usize);
+    let m = lut.m_curves.as_deref();
+    let matrix = lut.matrix.as_ref();
+    let post_clut = |values: &mut Vec<f64>| {
+        eval_segments(m, values);
+        if let Some(matrix) = matrix {
+            apply_matrix(matrix, values);
+        }
+    };
+
+    let (first, grid, clut, lossy) = match &lut.clut {
+        Some(clut) => {
+            // M curves are nonlinear, so folding them into the nodes is approximate
+            let lossy = !is_identity(m);
+            match uniform_grid(clut) {
+                Some(grid) => {
+                    let data = clut
+                        .data
+                        .chunks(clut.output_channels as usize)
+                        .flat_map(|node| {
+                            let mut values = node.to_vec();
+                            post_clut(&mut values);
+                            values.into_iter().map(to_u16).collect::<Vec<_>>()
+                        })
+                        .collect();
+                    (lut.a_curves.as_deref(), grid as usize, data, lossy)
+                }
+                None => {
+                    let grid = *clut.grid_points.iter().max().unwrap_or(&2) as usize;
+                    let table = ClutData::from_lut_clut(clut);
+                    let data = sample_grid(inputs, grid, outputs, |x| {
+                        let mut values = table.interpolate(x);
+                        post_clut(&mut values);
+                        values
+                    });
+                    (lut.a_curves.as_deref(), grid, data, true)
+                }
+            }
+        }
+        None => {
+            if inputs != outputs {
+                return Err(IccError::CorruptedData(
+                    "lutAToB without CLUT must preserve channel count".to_string(),
+                ));
+            }
+            // M curves become the input curves; the matrix is sampled into the CLUT
+            let grid = if matrix.is_some() { MATRIX_GRID } else { 2 };
+            let data = sample_grid(inputs, grid, outputs, |x| {
+                let mut values = x.to_vec();
+                if let Some(matrix) = matrix {
+                    apply_matrix(matrix, &mut values);
+                }
+                values
+            });
+            (m, grid, data, matrix.is_some())
+        }
+    };
+
+    let lut16 = lut16(
+        inputs,
+        outputs,
+        grid,
+        lut16_input_curves(inputs, first, input_lab),
+        clut,
+        lut16_output_curves(outputs, lut.b_curves.as_deref(), output_lab),
+    );
+    Ok((lut16, lossy))
+}
+
+/// Convert lutBToA (B → matrix → M → CLUT → A) to lut16
+///
+/// Returns the LUT and whether the conversion was lossy.
+fn btoa_to_lut16(
+    lut: &LutBToAData,
+    input_lab: bool,
+    output_lab: bool,
+) -> Result<(Lut16Data, bool), IccError> {
+    let (inputs, outputs) = (lut.input_channels as usize, lut.output_channels as usize);
+    let m = lut.m_curves.as_deref();
+    let matrix = lut.matrix.as_ref();
+    let pre_clut = |values: &mut Vec<f64>| {
+        if let Some(matrix) = matrix {
+            apply_matrix(matrix, values);
+        }
+        eval_segments(m, values);
+    };
+    let direct = matrix.is_none() && is_identity(m);
+
+    let (grid, clut, last, lossy) = match &lut.clut {
+        Some(clut) => match uniform_grid(clut).filter(|_| direct) {
+            Some(grid) => {
+                let data = clut.data.iter().map(|&v| to_u16(v)).collect();
+                (grid as usize, data, lut.a_curves.as_deref(), false)
+            }
+            None => {
+                // Matrix and M must run before the CLUT, so the CLUT is resampled
+                let grid = *clut.grid_points.iter().max().unwrap_or(&2) as usize;
+                let table = ClutData::from_lut_clut(clut);
+                let data = sample_grid(inputs, grid, outputs, |x| {
+                    let mut values = x.to_vec();
+                    pre_clut(&mut values);
+                    table.interpolate(&values)
+                });
+                (grid, data, lut.a_curves.as_deref(), true)
+            }
+        },
+        None => {
+            if inputs != outputs {
+                return Err(IccError::CorruptedData(
+                    "lutBToA without CLUT must preserve channel count".to_string(),
+                ));
+            }
+            // The matrix is sampled into the CLUT; M curves become the output curves
+            let grid = if matrix.is_some() { MATRIX_GRID } else { 2 };
+            let data = sample_grid(inputs, grid, outputs, |x| {
+                let mut values = x.to_vec();
+                if let Some(matrix) = matrix {
+                    apply_matrix(matrix, &mut values);
+                }
+                values
+            });
+            (grid, data, m, matrix.is_some())
+        }
+    };
+
+    let lut16 = lut16(
+        inputs,
+        outputs,
+        grid,
+        lut16_input_curves(inputs, lut.b_curves.as_deref(), input_lab),
+        clut,
+        lut16_output_curves(outputs, last, output_lab),
+    );
+    Ok((lut16, lossy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icc::header::IccHeader;
+    use crate::math::{ParametricCurve, ParametricCurveType};
+    use crate::pipeline::LutPipeline;
+
+    fn srgb_para() -> TagData {
+        TagData::ParametricCurve(ParametricCurveData {
+            curve: ParametricCurve {
+                curve_type: ParametricCurveType::IEC61966_2_1,
+                g: 2.4,
+                a: 1.0 / 1.055,
+                b: 0.055 / 1.055,
+                c: 1.0 / 12.92,
+                d: 0.04045,
+                e: 0.0,
+                f: 0.0,
+            },
+        })
+    }
+
+    fn v4_display() -> IccProfile {
+        let header = IccHeader::new(ProfileClass::Display, ColorSpace::Rgb, ColorSpace::Xyz);
+        let mut profile = IccProfile::new(header);
+        let mut desc = TextData::new("Display".to_string());
+        desc.localized = vec![
+            ("en-US".to_string(), "Display".to_string()),
+            ("de-DE".to_string(), "Bildschirm".to_string()),
+        ];
+        profile.set_tag(TagSignature::DESC, TagData::MultiLocalizedUnicode(desc));
+        profile.set_tag(
+            TagSignature::COPYRIGHT,
+            TagData::MultiLocalizedUnicode(TextData::new("none".to_string())),
+        );
+        profile.set_tag(TagSignature::MEDIA_WHITE, xyz_tag(XyzNumber::D50.to_xyz()));
+        let chad = bradford_matrix(&crate::color::D65, &crate::color::D50);
+        profile.set_tag(TagSignature::CHAD, TagData::ChromaticAdaptation(chad.m));
+        for (sig, xyz) in [
+            (TagSignature::RED_COLORANT, (0.4361, 0.2225, 0.0139)),
+            (TagSignature::GREEN_COLORANT, (0.3851, 0.7169, 0.0971)),
+            (TagSignature::BLUE_COLORANT, (0.1431, 0.0606, 0.7141)),
+        ] {
+            profile.set_tag(sig, xyz_tag(crate::color::Xyz::new(xyz.0, xyz.1, xyz.2)));
+        }
+        for sig in [
+            TagSignature::RED_TRC,
+            TagSignature::GREEN_TRC,
+            TagSignature::BLUE_TRC,
+        ] {
+            profile.set_tag(sig, srgb_para());
+        }
+        profile
+    }
+
+    #[test]
+    fn test_v4_to_v2_matrix_shaper() {
+        let source = v4_display();
+        let (v2, report) = source.to_v2().unwrap();
+
+        assert_eq!(v2.header.version.major, 2);
+        assert!(v2.get_tag(TagSignature::CHAD).is_none());
+        assert!(matches!(
+            v2.get_tag(TagSignature::DESC),
+            Some(TagData::Text(t)) if t.text == "Display"
+        ));
+        assert!(matches!(v2.red_trc(), Some(CurveData::Table(t)) if t.len() == CURVE_ENTRIES));
+
+        // wtpt restored to the display's native D65 white
+        let white = v2.media_white_point().unwrap();
+        assert!((white.x - 0.9505).abs() < 2e-3, "{:?}", white);
+        assert!((white.z - 1.089).abs() < 2e-3, "{:?}", white);
+
+        // Second language, sampled curves and chad removal are lossy
+        assert!(!report.is_lossless());
+        assert!(
+            report
+                .lossy_steps()
+                .any(|s| s.tag == Some(TagSignature::DESC))
+        );
+        assert!(
+            report
+                .lossy_steps()
+                .any(|s| s.tag == Some(TagSignature::CHAD))
+        );
+
+        // Serializes as a conformant v2 profile
+        let reparsed = IccProfile::parse(&v2.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            reparsed.tag_type(TagSignature::DESC),
+            Some(TypeSignature::DESC)
+        );
+        assert_eq!(
+            reparsed.tag_type(TagSignature::COPYRIGHT),
+            Some(TypeSignature::TEXT)
+        );
+        assert!(reparsed.validate().is_valid(), "{:?}", reparsed.validate());
+    }
+
+    #[test]
+    fn test_v2_to_v4_adds_chad() {
+        let (v2, _) = v4_display().to_v2().unwrap();
+        let (v4, report) = v2.to_v4().unwrap();
+
+        assert_eq!(v4.header.version.major, 4);
+        assert!(report.is_lossless());
+        let white = v4.media_white_point().unwrap();
+        assert!(white.approx_eq(&XyzNumber::D50.to_xyz(), 1e-3));
+
+        let chad = Matrix3x3::new(v4.chromatic_adaptation_matrix().unwrap());
+        let expected = bradford_matrix(&crate::color::D65, &crate::color::D50);
+        assert!(chad.approx_eq(&expected, 2e-3));
+
+        let reparsed = IccProfile::parse(&v4.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            reparsed.tag_type(TagSignature::DESC),
+            Some(TypeSignature::MLUC)
+        );
+        assert!(reparsed.validate().is_valid(), "{:?}", reparsed.validate());
+    }
+
+    fn test_clut(grid: u8, outputs: u8) -> LutClut {
+        // Each output is a different linear ramp of the inputs
+        let g = grid as usize;
+        let mut data = Vec::new();
+        for r in 0..g {
+            for gr in 0..g {
+                for b in 0..g {
+                    let (r, gr, b) = (
+                        r as f64 / (g - 1) as f64,
+                        gr as f64 / (g - 1) as f64,
+                        b as f64 / (g - 1) as f64,
+                    );
+                    let node = [0.2 * r + 0.7 * gr + 0.1 * b, r * gr, 0.5 * b + 0.25, r];
+                    data.extend_from_slice(&node[..outputs as usize]);
+                }
+            }
+        }
+        LutClut {
+            grid_points: vec![grid; 3],
+            precision: 2,
+            data,
+            output_channels: outputs,
+        }
+    }
+
+    #[test]
+    fn test_atob_to_lut16_lab_encoding() {
+        let lut = LutAToBData {
+            input_channels: 3,
+            output_channels: 3,
+            b_curves: Some(vec![CurveSegment::Identity; 3]),
+            matrix: None,
+            m_curves: None,
+            clut: Some(test_clut(9, 3)),
+            a_curves: Some(vec![
+                CurveSegment::Parametric {
+                    curve_type: 0,
+                    params: vec![2.2],
+                };
+                3
+            ]),
+        };
+        let (lut16, lossy) = atob_to_lut16(&lut, false, true).unwrap();
+        assert!(!lossy);
+
+        let v4 = LutPipeline::from_lut_atob(&lut);
+        let v2 = LutPipeline::from_lut16(&lut16);
+        for rgb in [[0.0, 0.0, 0.0], [0.3, 0.6, 0.9], [1.0, 0.5, 0.2]] {
+            let a = v4.transform(&rgb);
+            let b = v2.transform(&rgb);
+            for (a, b) in a.iter().zip(&b) {
+                assert!((a * LAB_V2_SCALE - b).abs() < 1e-3, "{} vs {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_btoa_matrix_is_resampled() {
+        let scale = LutMatrix {
+            matrix: [[0.5, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            offset: [0.0; 3],
+        };
+        let lut = LutBToAData {
+            input_channels: 3,
+            output_channels: 3,
+            b_curves: Some(vec![CurveSegment::Identity; 3]),
+            matrix: Some(scale),
+            m_curves: Some(vec![CurveSegment::Identity; 3]),
+            clut: Some(test_clut(9, 3)),
+            a_curves: Some(vec![CurveSegment::Identity; 3]),
+        };
+        let (lut16, lossy) = btoa_to_lut16(&lut, false, false).unwrap();
+        assert!(lossy);
+
+        let v2 = LutPipeline::from_lut16(&lut16);
+        let out = v2.transform(&[0.8, 0.4, 0.6]);
+        let direct =
+            ClutData::from_lut_clut(lut.clut.as_ref().unwrap()).interpolate(&[0.4, 0.4, 0.6]);
+        for (a, b) in out.iter().zip(&direct) {
+            assert!((a - b).abs() < 2e-3, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_interp_clut_exact_on_linear() {
+        let clut = ClutData::from_lut_clut(&test_clut(5, 1));
+        let v = clut.interpolate(&[0.3, 0.55, 0.8]);
+        assert!((v[0] - (0.2 * 0.3 + 0.7 * 0.55 + 0.1 * 0.8)).abs() < 1e-12);
+    }
+}
@@ -2,6 +2,8 @@
 
 use std::fmt;
 
+use super::report::ParseIssue;
+
 /// Errors that can occur when parsing ICC profiles
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -38,6 +40,10 @@ pub enum IccError {
     CorruptedData(String),
     /// Unsupported feature
     Unsupported(String),
+    /// Invalid caller-supplied value, e.g. a builder parameter
+    InvalidArgument(String),
+    /// Issue that is fatal under the selected parse options
+    Rejected(ParseIssue),
 }
 
 impl fmt::Display for IccError {
@@ -103,6 +109,12 @@ impl fmt::Display for IccError {
             Self::Unsupported(msg) => {
                 write!(f, "Unsupported feature: {}", msg)
             }
+            Self::InvalidArgument(msg) => {
+                write!(f, "Invalid argument: {}", msg)
+            }
+            Self::Rejected(issue) => {
+                write!(f, "Profile rejected: {}", issue)
+            }
         }
     }
 }
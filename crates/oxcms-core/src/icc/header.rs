@@ -52,6 +52,59 @@ pub struct IccHeader {
 }
 
 impl IccHeader {
+    /// Create a v4.4 header with D50 illuminant and default fields
+    ///
+    /// Size is filled in when the profile is serialized.
+    pub fn new(device_class: ProfileClass, color_space: ColorSpace, pcs: ColorSpace) -> Self {
+        Self {
+            size: 0,
+            cmm_type: 0,
+            version: ProfileVersion {
+                major: 4,
+                minor: 4,
+                patch: 0,
+            },
+            device_class,
+            color_space,
+            pcs,
+            creation_date: DateTimeNumber::default(),
+            signature: PROFILE_SIGNATURE,
+            platform: 0,
+            flags: 0,
+            manufacturer: 0,
+            model: 0,
+            attributes: 0,
+            rendering_intent: RenderingIntent::Perceptual,
+            illuminant: XyzNumber::D50,
+            creator: 0,
+            profile_id: [0; 16],
+        }
+    }
+
+    /// Serialize the header to 128 bytes
+    pub fn to_bytes(&self) -> [u8; MIN_PROFILE_SIZE] {
+        let mut data = [0u8; MIN_PROFILE_SIZE];
+        data[0..4].copy_from_slice(&self.size.to_be_bytes());
+        data[4..8].copy_from_slice(&self.cmm_type.to_be_bytes());
+        data[8] = self.version.major;
+        data[9] = (self.version.minor << 4) | (self.version.patch & 0x0F);
+        data[12..16].copy_from_slice(&self.device_class.to_u32().to_be_bytes());
+        data[16..20].copy_from_slice(&self.color_space.to_u32().to_be_bytes());
+        data[20..24].copy_from_slice(&self.pcs.to_u32().to_be_bytes());
+        data[24..36].copy_from_slice(&self.creation_date.to_bytes());
+        data[36..40].copy_from_slice(&self.signature.to_be_bytes());
+        data[40..44].copy_from_slice(&self.platform.to_be_bytes());
+        data[44..48].copy_from_slice(&self.flags.to_be_bytes());
+        data[48..52].copy_from_slice(&self.manufacturer.to_be_bytes());
+        data[52..56].copy_from_slice(&self.model.to_be_bytes());
+        data[56..64].copy_from_slice(&self.attributes.to_be_bytes());
+        data[64..68].copy_from_slice(&self.rendering_intent.to_u32().to_be_bytes());
+        data[68..80].copy_from_slice(&self.illuminant.to_bytes());
+        data[80..84].copy_from_slice(&self.creator.to_be_bytes());
+        data[84..100].copy_from_slice(&self.profile_id);
+        data
+    }
+
     /// Parse header from bytes
     pub fn parse(data: &[u8]) -> Result<Self, IccError> {
         if data.len() < MIN_PROFILE_SIZE {
@@ -467,4 +520,15 @@ mod tests {
         assert!(v4.at_least(2, 0));
         assert!(v4.at_least(4, 3));
     }
+
+    #[test]
+    fn test_header_roundtrip() {
+        let mut header = IccHeader::new(ProfileClass::Display, ColorSpace::Rgb, ColorSpace::Xyz);
+        header.size = MIN_PROFILE_SIZE as u32;
+        header.rendering_intent = RenderingIntent::RelativeColorimetric;
+
+        let bytes = header.to_bytes();
+        let parsed = IccHeader::parse(&bytes).unwrap();
+        assert_eq!(parsed, header);
+    }
 }
@@ -26,15 +26,34 @@
 //! }
 //! ```
 
+pub mod cgats;
 pub mod header;
 pub mod tags;
 
+mod builder;
+mod characterization;
+mod convert;
 mod error;
 mod parser;
+mod report;
 mod types;
+mod validate;
+mod writer;
 
+pub use builder::{
+    AbstractProfileBuilder, GrayProfileBuilder, LabIdentityBuilder, LinearizationBuilder,
+    RgbProfileBuilder, Trc,
+};
+pub use cgats::{CgatsFile, CgatsTable, SpectralData};
+pub use characterization::{
+    BlackGeneration, CharacterizationBuilder, GAMUT_THRESHOLD, MeasuredColor, Measurement,
+};
+pub use convert::{ConversionReport, ConversionStep};
 pub use error::IccError;
 pub use header::{ColorSpace, IccHeader, ProfileClass, RenderingIntent as IccRenderingIntent};
 pub use parser::IccProfile;
-pub use tags::{CurveData, ParametricCurveData, TagData};
-pub use types::{DateTimeNumber, S15Fixed16, TagSignature, XyzNumber};
+pub use report::{ParseIssue, ParseIssueKind, ParseMode, ParseOptions, ParseReport};
+pub use tags::{CicpData, CurveData, DictData, DictEntry, ParametricCurveData, TagData, VcgtData};
+pub use types::{DateTimeNumber, S15Fixed16, TagSignature, TypeSignature, XyzNumber};
+pub use validate::{Severity, ValidationCategory, ValidationIssue, ValidationReport};
+pub use writer::write_tag;
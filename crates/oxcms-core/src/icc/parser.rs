@@ -18,10 +18,14 @@
 
 use std::collections::HashMap;
 
+use super::cgats::CgatsFile;
+use super::convert::ConversionReport;
 use super::error::IccError;
 use super::header::{IccHeader, MIN_PROFILE_SIZE};
-use super::tags::TagData;
-use super::types::TagSignature;
+use super::report::{ParseIssue, ParseIssueKind, ParseOptions, ParseReport};
+use super::tags::{CicpData, DictData, TagData, VcgtData};
+use super::types::{TagSignature, TypeSignature, XyzNumber};
+use super::validate::ValidationReport;
 
 /// An ICC profile parsed from bytes
 #[derive(Debug, Clone)]
@@ -46,13 +50,125 @@ struct TagTableEntry {
 }
 
 impl IccProfile {
+    /// Create an empty profile with the given header
+    pub fn new(header: IccHeader) -> Self {
+        Self {
+            header,
+            tags: HashMap::new(),
+            raw_data: Vec::new(),
+        }
+    }
+
+    /// Insert or replace a tag
+    pub fn set_tag(&mut self, sig: TagSignature, data: TagData) {
+        self.tags.insert(sig.0, data);
+    }
+
+    /// Remove a tag, returning its data
+    pub fn remove_tag(&mut self, sig: TagSignature) -> Option<TagData> {
+        self.tags.remove(&sig.0)
+    }
+
+    /// Serialize the profile to ICC bytes
+    ///
+    /// The header size field is recomputed; tags are re-encoded from their
+    /// parsed form.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IccError> {
+        super::writer::write_profile(self)
+    }
+
     /// Parse an ICC profile from bytes
+    ///
+    /// Uses browser-compatible recovery; see [`ParseOptions`].
     pub fn parse(data: &[u8]) -> Result<Self, IccError> {
+        Self::parse_with_options(data, &ParseOptions::default()).map(|(profile, _)| profile)
+    }
+
+    /// Parse an ICC profile, reporting every problem that was recovered from
+    ///
+    /// Issues that [`IccProfile::parse`] has always rejected keep their error
+    /// variants when fatal: truncated data is [`IccError::SizeMismatch`] and a
+    /// tag past the end is [`IccError::TagOutOfBounds`]. Other fatal issues
+    /// are [`IccError::Rejected`].
+    pub fn parse_with_options(
+        data: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(Self, ParseReport), IccError> {
+        Self::parse_impl(data, options, true)
+    }
+
+    /// Check the profile structure under `options` without decoding tag data
+    ///
+    /// Reports every issue [`IccProfile::parse_with_options`] would, except
+    /// [`ParseIssueKind::TagParseFailed`].
+    pub(crate) fn check_with_options(
+        data: &[u8],
+        options: &ParseOptions,
+    ) -> Result<ParseReport, IccError> {
+        Self::parse_impl(data, options, false).map(|(_, report)| report)
+    }
+
+    fn parse_impl(
+        data: &[u8],
+        options: &ParseOptions,
+        decode_tags: bool,
+    ) -> Result<(Self, ParseReport), IccError> {
+        let mut report = ParseReport::default();
+        let mut note = |issue: ParseIssue| -> Result<(), IccError> {
+            if options.is_fatal(issue.kind) {
+                Err(IccError::Rejected(issue))
+            } else {
+                report.issues.push(issue);
+                Ok(())
+            }
+        };
+
         // Parse header
         let header = IccHeader::parse(data)?;
+        if options.is_fatal(ParseIssueKind::Truncated) {
+            header.validate(data.len())?;
+        }
+
+        if header.size as usize > data.len() {
+            note(ParseIssue {
+                kind: ParseIssueKind::Truncated,
+                tag: None,
+                offset: Some(0),
+                size: Some(header.size),
+                detail: format!(
+                    "header says {} bytes, data is {} bytes",
+                    header.size,
+                    data.len()
+                ),
+            })?;
+        } else if (header.size as usize) < data.len() {
+            note(ParseIssue {
+                kind: ParseIssueKind::HeaderSizeMismatch,
+                tag: None,
+                offset: Some(0),
+                size: Some(header.size),
+                detail: format!(
+                    "header says {} bytes, data is {} bytes",
+                    header.size,
+                    data.len()
+                ),
+            })?;
+        }
 
-        // Validate header
-        header.validate(data.len())?;
+        if !header
+            .illuminant
+            .to_xyz()
+            .approx_eq(&XyzNumber::D50.to_xyz(), 1e-3)
+        {
+            let xyz = header.illuminant.to_xyz();
+            note(ParseIssue {
+                kind: ParseIssueKind::NonD50Illuminant,
+                tag: None,
+                offset: Some(68),
+                size: Some(12),
+                detail: format!("illuminant is ({:.4}, {:.4}, {:.4})", xyz.x, xyz.y, xyz.z),
+            })?;
+        }
 
         // Parse tag table
         let tag_count = Self::parse_tag_count(data)?;
@@ -60,40 +176,103 @@ impl IccProfile {
 
         // Parse each tag
         let mut tags = HashMap::with_capacity(tag_entries.len());
+        let mut accepted: Vec<TagTableEntry> = Vec::with_capacity(tag_entries.len());
 
         for entry in &tag_entries {
+            let tag = TagSignature(entry.signature);
+            let issue = |kind, detail: String| ParseIssue {
+                kind,
+                tag: Some(tag),
+                offset: Some(entry.offset),
+                size: Some(entry.size),
+                detail,
+            };
+
+            // The first occurrence of a signature wins
+            if accepted.iter().any(|e| e.signature == entry.signature) {
+                note(issue(
+                    ParseIssueKind::DuplicateTag,
+                    "tag already defined; later entry ignored".to_string(),
+                ))?;
+                continue;
+            }
+
             // Validate bounds
             let end = entry.offset as usize + entry.size as usize;
             if end > data.len() {
-                return Err(IccError::TagOutOfBounds {
-                    tag: entry.signature,
-                    offset: entry.offset,
-                    size: entry.size,
-                    profile_size: data.len(),
-                });
+                if options.is_fatal(ParseIssueKind::TagOutOfBounds) {
+                    return Err(IccError::TagOutOfBounds {
+                        tag: entry.signature,
+                        offset: entry.offset,
+                        size: entry.size,
+                        profile_size: data.len(),
+                    });
+                }
+                note(issue(
+                    ParseIssueKind::TagOutOfBounds,
+                    format!("tag ends at {}, profile is {} bytes", end, data.len()),
+                ))?;
+                continue;
+            }
+
+            if entry.offset % 4 != 0 {
+                note(issue(
+                    ParseIssueKind::MisalignedOffset,
+                    "offset is not a multiple of 4".to_string(),
+                ))?;
             }
 
+            // Tags may share identical data, but must not partially overlap
+            let overlapping = accepted.iter().find(|other| {
+                let other_end = other.offset as usize + other.size as usize;
+                let shared = other.offset == entry.offset && other.size == entry.size;
+                !shared && (entry.offset as usize) < other_end && (other.offset as usize) < end
+            });
+            if let Some(other) = overlapping {
+                note(issue(
+                    ParseIssueKind::OverlappingTags,
+                    format!(
+                        "overlaps '{}' at offset {} (size {})",
+                        TagSignature(other.signature).to_string(),
+                        other.offset,
+                        other.size
+                    ),
+                ))?;
+            }
+            accepted.push(*entry);
+
             // Parse tag data
             let tag_data = &data[entry.offset as usize..end];
+            let type_sig = if tag_data.len() >= 4 {
+                u32::from_be_bytes([tag_data[0], tag_data[1], tag_data[2], tag_data[3]])
+            } else {
+                0
+            };
+
+            if let Some(allowed) = tag.allowed_types() {
+                if !allowed.contains(&TypeSignature(type_sig)) {
+                    note(issue(
+                        ParseIssueKind::InvalidTagType,
+                        format!("type '{}' not allowed", TagSignature(type_sig).to_string()),
+                    ))?;
+                }
+            }
+
+            if !decode_tags {
+                continue;
+            }
             match TagData::parse(tag_data, entry.signature) {
                 Ok(parsed) => {
                     tags.insert(entry.signature, parsed);
                 }
-                Err(_) => {
+                Err(e) => {
+                    note(issue(ParseIssueKind::TagParseFailed, e.to_string()))?;
+
                     // Store as unknown if parsing fails
                     tags.insert(
                         entry.signature,
                         TagData::Unknown {
-                            type_sig: if tag_data.len() >= 4 {
-                                u32::from_be_bytes([
-                                    tag_data[0],
-                                    tag_data[1],
-                                    tag_data[2],
-                                    tag_data[3],
-                                ])
-                            } else {
-                                0
-                            },
+                            type_sig,
                             data: tag_data.to_vec(),
                         },
                     );
@@ -101,11 +280,16 @@ impl IccProfile {
             }
         }
 
-        Ok(Self {
+        let profile = Self {
             header,
             tags,
-            raw_data: data.to_vec(),
-        })
+            raw_data: if decode_tags {
+                data.to_vec()
+            } else {
+                Vec::new()
+            },
+        };
+        Ok((profile, report))
     }
 
     /// Get the number of tags in the profile
@@ -173,6 +357,68 @@ impl IccProfile {
         self.tags.get(&sig.0)
     }
 
+    /// Get the type signature of a tag
+    ///
+    /// Text tags do not record whether they were stored as `text`, `desc` or
+    /// `mluc`, so their type is read back from the source data when the
+    /// profile was parsed, and otherwise is the type the writer would use.
+    pub fn tag_type(&self, sig: TagSignature) -> Option<TypeSignature> {
+        let implied = match self.get_tag(sig)? {
+            TagData::Xyz(_) => TypeSignature::XYZ,
+            TagData::Curve(_) => TypeSignature::CURVE,
+            TagData::ParametricCurve(_) => TypeSignature::PARA,
+            TagData::MultiLocalizedUnicode(_) => TypeSignature::MLUC,
+            TagData::Lut8(_) => TypeSignature::LUT8,
+            TagData::Lut16(_) => TypeSignature::LUT16,
+            TagData::LutAToB(_) => TypeSignature::LUTA2B,
+            TagData::LutBToA(_) => TypeSignature::LUTB2A,
+            TagData::ChromaticAdaptation(_) => TypeSignature::SF32,
+            TagData::Cicp(_) => TypeSignature::CICP,
+            TagData::Vcgt(_) => TypeSignature::VCGT,
+            TagData::Dict(_) => TypeSignature::DICT,
+            TagData::Unknown { type_sig, .. } => TypeSignature(*type_sig),
+            TagData::Text(_) => self
+                .stored_type(sig)
+                .unwrap_or_else(|| super::writer::text_type(sig, self.header.version)),
+        };
+        Some(implied)
+    }
+
+    /// Read a tag's type signature from the raw profile data
+    fn stored_type(&self, sig: TagSignature) -> Option<TypeSignature> {
+        let data = &self.raw_data;
+        let count = Self::parse_tag_count(data).ok()?;
+        let entry = Self::parse_tag_table(data, count)
+            .ok()?
+            .into_iter()
+            .find(|e| e.signature == sig.0)?;
+        let start = entry.offset as usize;
+        let bytes = data.get(start..start + 4)?;
+        Some(TypeSignature(u32::from_be_bytes([
+            bytes[0], bytes[1], bytes[2], bytes[3],
+        ])))
+    }
+
+    /// Rewrite the profile as v2.1
+    ///
+    /// v2 profiles are copied unchanged. See [`ConversionReport`] for the
+    /// steps that may alter behavior.
+    pub fn to_v2(&self) -> Result<(Self, ConversionReport), IccError> {
+        super::convert::to_v2(self)
+    }
+
+    /// Upgrade the profile to v4.4
+    ///
+    /// v4 profiles are copied unchanged.
+    pub fn to_v4(&self) -> Result<(Self, ConversionReport), IccError> {
+        super::convert::to_v4(self)
+    }
+
+    /// Check the profile against ICC.1:2022 conformance rules
+    pub fn validate(&self) -> ValidationReport {
+        super::validate::validate_profile(self)
+    }
+
     /// Get profile description
     pub fn description(&self) -> Option<String> {
         self.get_tag(TagSignature::DESC)
@@ -187,6 +433,21 @@ impl IccProfile {
             .map(|t| t.text.clone())
     }
 
+    /// Parse the characterization target (`targ`) as CGATS
+    ///
+    /// Returns `Ok(None)` if the tag is absent. Some profiles store only a
+    /// reference name such as `ICCHDAT FOGRA39`, which parses as a table with
+    /// a single keyword and no data.
+    pub fn char_target(&self) -> Result<Option<CgatsFile>, IccError> {
+        match self
+            .get_tag(TagSignature::CHAR_TARGET)
+            .and_then(|t| t.as_text())
+        {
+            Some(text) => CgatsFile::parse(&text.text).map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Get red colorant XYZ
     pub fn red_colorant(&self) -> Option<crate::color::Xyz> {
         self.get_tag(TagSignature::RED_COLORANT)
@@ -353,6 +614,26 @@ impl IccProfile {
             _ => None,
         }
     }
+
+    /// Get coding-independent code points (cicp tag)
+    pub fn cicp(&self) -> Option<&CicpData> {
+        self.get_tag(TagSignature::CICP)?.as_cicp()
+    }
+
+    /// Get video card calibration curves (vcgt tag)
+    pub fn vcgt(&self) -> Option<&VcgtData> {
+        self.get_tag(TagSignature::VCGT)?.as_vcgt()
+    }
+
+    /// Get the metadata dictionary (meta tag)
+    pub fn metadata(&self) -> Option<&DictData> {
+        self.get_tag(TagSignature::METADATA)?.as_dict()
+    }
+
+    /// Get a metadata value by name
+    pub fn metadata_value(&self, name: &str) -> Option<&str> {
+        self.metadata()?.value(name)
+    }
 }
 
 #[cfg(test)]
@@ -434,4 +715,158 @@ mod tests {
         let copyright = profile.copyright();
         assert_eq!(copyright, Some("Test".to_string()));
     }
+
+    #[test]
+    fn test_profile_with_cicp_tag() {
+        let mut data = create_minimal_profile();
+
+        let new_size = (data.len() + 12 + 12) as u32; // +12 tag entry +12 tag data
+        data[0..4].copy_from_slice(&new_size.to_be_bytes());
+        data[128..132].copy_from_slice(&1u32.to_be_bytes());
+
+        data.extend_from_slice(b"cicp");
+        data.extend_from_slice(&144u32.to_be_bytes());
+        data.extend_from_slice(&12u32.to_be_bytes());
+
+        data.extend_from_slice(b"cicp");
+        data.extend_from_slice(&[0u8; 4]);
+        data.extend_from_slice(&[9, 18, 0, 1]); // BT.2020 primaries, HLG
+
+        let profile = IccProfile::parse(&data).unwrap();
+        let cicp = profile.cicp().unwrap();
+        assert_eq!(cicp.color_primaries, 9);
+        assert_eq!(cicp.transfer_characteristics, 18);
+        assert!(cicp.full_range);
+    }
+
+    /// Build a profile with the given (signature, offset, size) entries and tag data
+    fn profile_with_entries(entries: &[(&[u8; 4], u32, u32)], tag_data: &[u8]) -> Vec<u8> {
+        let mut data = create_minimal_profile();
+        data[68..80].copy_from_slice(&XyzNumber::D50.to_bytes());
+        data[128..132].copy_from_slice(&(entries.len() as u32).to_be_bytes());
+        for (sig, offset, size) in entries {
+            data.extend_from_slice(*sig);
+            data.extend_from_slice(&offset.to_be_bytes());
+            data.extend_from_slice(&size.to_be_bytes());
+        }
+        data.extend_from_slice(tag_data);
+        let size = data.len() as u32;
+        data[0..4].copy_from_slice(&size.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn test_lenient_report() {
+        // Tag data starts at 132 + 4 * 12 = 180
+        let mut tag_data = Vec::new();
+        tag_data.extend_from_slice(b"text\0\0\0\0Hi\0\0"); // 180..192
+        tag_data.extend_from_slice(b"XYZ \0\0\0\0"); // 192..212
+        tag_data.extend_from_slice(&XyzNumber::D50.to_bytes());
+
+        let data = profile_with_entries(
+            &[
+                (b"cprt", 180, 12),
+                (b"cprt", 180, 12), // duplicate
+                (b"rTRC", 192, 20), // XYZ type is not allowed for a TRC
+                (b"wtpt", 190, 10), // misaligned, overlaps cprt
+            ],
+            &tag_data,
+        );
+
+        let (profile, report) =
+            IccProfile::parse_with_options(&data, &ParseOptions::lenient()).unwrap();
+        assert_eq!(profile.copyright(), Some("Hi".to_string()));
+        assert!(report.has(ParseIssueKind::DuplicateTag));
+        assert!(report.has(ParseIssueKind::InvalidTagType));
+        assert!(report.has(ParseIssueKind::MisalignedOffset));
+        assert!(report.has(ParseIssueKind::OverlappingTags));
+
+        let invalid = report
+            .of_kind(ParseIssueKind::InvalidTagType)
+            .next()
+            .unwrap();
+        assert_eq!(invalid.tag, Some(TagSignature::RED_TRC));
+        assert_eq!(invalid.offset, Some(192));
+
+        let err = IccProfile::parse_with_options(&data, &ParseOptions::strict()).unwrap_err();
+        assert!(matches!(
+            err,
+            IccError::Rejected(ParseIssue {
+                kind: ParseIssueKind::DuplicateTag,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_truncated_by_mode() {
+        let mut data = profile_with_entries(&[(b"cprt", 144, 12)], b"text\0\0\0\0Hi\0\0");
+        let size = data.len() as u32 + 100;
+        data[0..4].copy_from_slice(&size.to_be_bytes());
+
+        // Browser-compatible and strict parsing fail as the parser always has
+        let expected = IccError::SizeMismatch {
+            header_size: size,
+            actual_size: data.len(),
+        };
+        assert_eq!(IccProfile::parse(&data).unwrap_err(), expected);
+        assert_eq!(
+            IccProfile::parse_with_options(&data, &ParseOptions::strict()).unwrap_err(),
+            expected
+        );
+
+        let (profile, report) =
+            IccProfile::parse_with_options(&data, &ParseOptions::lenient()).unwrap();
+        assert!(report.has(ParseIssueKind::Truncated));
+        assert_eq!(profile.copyright(), Some("Hi".to_string()));
+    }
+
+    #[test]
+    fn test_tag_out_of_bounds_error() {
+        let data = profile_with_entries(&[(b"cprt", 144, 40)], b"text\0\0\0\0Hi\0\0");
+        let expected = IccError::TagOutOfBounds {
+            tag: TagSignature::COPYRIGHT.0,
+            offset: 144,
+            size: 40,
+            profile_size: data.len(),
+        };
+        assert_eq!(IccProfile::parse(&data).unwrap_err(), expected);
+        assert_eq!(
+            IccProfile::parse_with_options(&data, &ParseOptions::strict()).unwrap_err(),
+            expected
+        );
+
+        let (profile, report) =
+            IccProfile::parse_with_options(&data, &ParseOptions::lenient()).unwrap();
+        assert!(report.has(ParseIssueKind::TagOutOfBounds));
+        assert!(profile.copyright().is_none());
+    }
+
+    #[test]
+    fn test_check_matches_parse_report() {
+        let data = profile_with_entries(
+            &[(b"cprt", 144, 12), (b"cprt", 144, 12), (b"desc", 146, 8)],
+            b"text\0\0\0\0Hi\0\0",
+        );
+        let options = ParseOptions::lenient();
+        let (_, report) = IccProfile::parse_with_options(&data, &options).unwrap();
+        let checked = IccProfile::check_with_options(&data, &options).unwrap();
+        let structural: Vec<_> = report
+            .issues
+            .iter()
+            .filter(|i| i.kind != ParseIssueKind::TagParseFailed)
+            .cloned()
+            .collect();
+        assert_eq!(checked.issues, structural);
+        assert!(checked.has(ParseIssueKind::DuplicateTag));
+        assert!(checked.has(ParseIssueKind::OverlappingTags));
+    }
+
+    #[test]
+    fn test_non_d50_illuminant_reported() {
+        let data = create_minimal_profile();
+        let (_, report) = IccProfile::parse_with_options(&data, &ParseOptions::default()).unwrap();
+        assert!(report.has(ParseIssueKind::NonD50Illuminant));
+        assert!(IccProfile::parse_with_options(&data, &ParseOptions::strict()).is_err());
+    }
 }
@@ -0,0 +1,176 @@
+//! Parse Options and Diagnostics
+//!
+//! Real-world profiles frequently bend the spec: wrong header sizes,
+//! misaligned or overlapping tags, tags with the wrong type. [`ParseOptions`]
+//! selects how strictly these are treated, and [`ParseReport`] lists every
+//! problem that was recovered from.
+
+use std::fmt;
+
+use super::types::TagSignature;
+
+/// How strictly to treat spec violations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Reject the profile on the first issue
+    Strict,
+    /// Recover from every issue that leaves the tag data readable
+    Lenient,
+    /// Accept what web browsers accept
+    ///
+    /// Truncated data and out-of-bounds tags are fatal;
+    /// everything else is recovered. This is the behavior of
+    /// [`IccProfile::parse`](super::IccProfile::parse).
+    #[default]
+    BrowserCompatible,
+}
+
+/// Options for [`IccProfile::parse_with_options`](super::IccProfile::parse_with_options)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+    /// Strictness mode
+    pub mode: ParseMode,
+}
+
+impl ParseOptions {
+    /// Reject any spec violation
+    pub fn strict() -> Self {
+        Self {
+            mode: ParseMode::Strict,
+        }
+    }
+
+    /// Recover from as much as possible
+    pub fn lenient() -> Self {
+        Self {
+            mode: ParseMode::Lenient,
+        }
+    }
+
+    /// Accept what web browsers accept
+    pub fn browser_compatible() -> Self {
+        Self {
+            mode: ParseMode::BrowserCompatible,
+        }
+    }
+
+    /// Check whether an issue of this kind aborts parsing
+    pub fn is_fatal(&self, kind: ParseIssueKind) -> bool {
+        match self.mode {
+            ParseMode::Strict => true,
+            ParseMode::Lenient => false,
+            ParseMode::BrowserCompatible => matches!(
+                kind,
+                ParseIssueKind::Truncated | ParseIssueKind::TagOutOfBounds
+            ),
+        }
+    }
+}
+
+/// Category of a recovered parse problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseIssueKind {
+    /// Header size field is smaller than the data (trailing bytes)
+    HeaderSizeMismatch,
+    /// Header size field is larger than the data
+    Truncated,
+    /// PCS illuminant in the header is not D50
+    NonD50Illuminant,
+    /// Same tag signature appears more than once in the tag table
+    DuplicateTag,
+    /// Tag data offset is not 4-byte aligned
+    MisalignedOffset,
+    /// Tag data partially overlaps another tag
+    OverlappingTags,
+    /// Tag data extends past the end of the profile
+    TagOutOfBounds,
+    /// Tag type is not permitted for the tag signature
+    InvalidTagType,
+    /// Tag data could not be decoded (kept as raw data)
+    TagParseFailed,
+}
+
+/// A single recovered parse problem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIssue {
+    /// Issue category
+    pub kind: ParseIssueKind,
+    /// Tag the issue refers to, if any
+    pub tag: Option<TagSignature>,
+    /// Byte offset of the offending data within the profile
+    pub offset: Option<u32>,
+    /// Size of the offending data in bytes
+    pub size: Option<u32>,
+    /// Human-readable detail
+    pub detail: String,
+}
+
+impl fmt::Display for ParseIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.kind)?;
+        if let Some(tag) = self.tag {
+            write!(f, " in '{}'", tag.to_string())?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " at offset {}", offset)?;
+            if let Some(size) = self.size {
+                write!(f, " (size {})", size)?;
+            }
+        }
+        write!(f, ": {}", self.detail)
+    }
+}
+
+/// All problems recovered while parsing a profile
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    /// Issues in the order they were found
+    pub issues: Vec<ParseIssue>,
+}
+
+impl ParseReport {
+    /// Check if no issues were found
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Check if any issue of the given kind was found
+    pub fn has(&self, kind: ParseIssueKind) -> bool {
+        self.issues.iter().any(|i| i.kind == kind)
+    }
+
+    /// Issues of the given kind
+    pub fn of_kind(&self, kind: ParseIssueKind) -> impl Iterator<Item = &ParseIssue> {
+        self.issues.iter().filter(move |i| i.kind == kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fatal_by_mode() {
+        let kind = ParseIssueKind::MisalignedOffset;
+        assert!(ParseOptions::strict().is_fatal(kind));
+        assert!(!ParseOptions::lenient().is_fatal(kind));
+        assert!(!ParseOptions::browser_compatible().is_fatal(kind));
+        assert!(ParseOptions::browser_compatible().is_fatal(ParseIssueKind::TagOutOfBounds));
+        assert_eq!(ParseOptions::default(), ParseOptions::browser_compatible());
+    }
+
+    #[test]
+    fn test_issue_display() {
+        let issue = ParseIssue {
+            kind: ParseIssueKind::MisalignedOffset,
+            tag: Some(TagSignature::RED_TRC),
+            offset: Some(302),
+            size: Some(14),
+            detail: "offset not a multiple of 4".to_string(),
+        };
+        assert_eq!(
+            issue.to_string(),
+            "MisalignedOffset in 'rTRC' at offset 302 (size 14): offset not a multiple of 4"
+        );
+    }
+}
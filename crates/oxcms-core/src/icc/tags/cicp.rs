@@ -0,0 +1,65 @@
+//! Coding-Independent Code Points Tag Type
+//!
+//! The cicpType carries the ITU-T H.273 code points describing the
+//! primaries, transfer characteristics and matrix coefficients of the
+//! encoded data. Used by HDR profiles (PQ / HLG).
+//!
+//! See ICC.1:2022 Section 10.3
+
+use crate::icc::error::IccError;
+use crate::math::TransferFunction;
+
+/// CICP tag data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CicpData {
+    /// Colour primaries (H.273 Table 2)
+    pub color_primaries: u8,
+    /// Transfer characteristics (H.273 Table 3)
+    pub transfer_characteristics: u8,
+    /// Matrix coefficients (H.273 Table 4)
+    pub matrix_coefficients: u8,
+    /// Full range (true) or narrow/video range (false)
+    pub full_range: bool,
+}
+
+impl CicpData {
+    /// Parse cicp data from bytes (after type signature and reserved bytes)
+    pub fn parse(data: &[u8]) -> Result<Self, IccError> {
+        if data.len() < 4 {
+            return Err(IccError::CorruptedData("cicp tag too small".to_string()));
+        }
+
+        Ok(Self {
+            color_primaries: data[0],
+            transfer_characteristics: data[1],
+            matrix_coefficients: data[2],
+            full_range: data[3] != 0,
+        })
+    }
+
+    /// Get the analytic transfer function, if the code point is supported
+    pub fn transfer_function(&self) -> Option<TransferFunction> {
+        TransferFunction::from_cicp(self.transfer_characteristics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cicp() {
+        // BT.2100 PQ, full range
+        let cicp = CicpData::parse(&[9, 16, 0, 1]).unwrap();
+        assert_eq!(cicp.color_primaries, 9);
+        assert_eq!(cicp.transfer_characteristics, 16);
+        assert_eq!(cicp.matrix_coefficients, 0);
+        assert!(cicp.full_range);
+        assert_eq!(cicp.transfer_function(), Some(TransferFunction::Pq));
+    }
+
+    #[test]
+    fn test_parse_cicp_too_small() {
+        assert!(CicpData::parse(&[1, 13, 0]).is_err());
+    }
+}
@@ -7,7 +7,10 @@
 //!
 //! See ICC.1:2022 Sections 10.6 (curv) and 10.18 (para)
 
+use std::cmp::Ordering;
+
 use crate::icc::error::IccError;
+use crate::math::curve::{invert_monotonic, invert_with};
 use crate::math::gamma::{ParametricCurve, ParametricCurveType};
 
 /// Curve tag data (curv type)
@@ -97,6 +100,13 @@ impl CurveData {
     }
 
     /// Evaluate the inverse curve (for encoding)
+    ///
+    /// Tables are inverted like
+    /// [`SampledCurve::eval_inverse`](crate::math::SampledCurve::eval_inverse),
+    /// including non-monotonic and flat tables. Monotonic tables are checked
+    /// in one pass over the integers and then searched by bisection; others
+    /// are scanned. For many evaluations of a non-monotonic table build a
+    /// `SampledCurve`, which keeps a reversed table.
     pub fn eval_inverse(&self, y: f64) -> f64 {
         let y = y.clamp(0.0, 1.0);
 
@@ -109,37 +119,17 @@ impl CurveData {
                 y.powf(1.0 / *g)
             }
             CurveData::Table(table) => {
-                // Binary search for inverse
-                if table.is_empty() {
-                    return y;
-                }
-                let target = (y * 65535.0) as u16;
-
-                // Find the position where target would be
-                let mut lo = 0usize;
-                let mut hi = table.len() - 1;
-
-                while lo < hi {
-                    let mid = (lo + hi) / 2;
-                    if table[mid] < target {
-                        lo = mid + 1;
-                    } else {
-                        hi = mid;
-                    }
+                let sample = |i: usize| table[i] as f64 / 65535.0;
+                let monotonic = match table.first().cmp(&table.last()) {
+                    Ordering::Less => table.is_sorted(),
+                    Ordering::Greater => table.is_sorted_by(|a, b| a >= b),
+                    Ordering::Equal => false,
+                };
+                if monotonic {
+                    invert_monotonic(table.len(), sample, y)
+                } else {
+                    invert_with(table.len(), sample, y)
                 }
-
-                // Interpolate
-                if lo == 0 {
-                    return 0.0;
-                }
-                if lo >= table.len() {
-                    return 1.0;
-                }
-
-                let v0 = table[lo - 1] as f64;
-                let v1 = table[lo] as f64;
-                let t = (y * 65535.0 - v0) / (v1 - v0);
-                ((lo - 1) as f64 + t) / (table.len() - 1) as f64
             }
         }
     }
@@ -171,6 +161,18 @@ pub struct ParametricCurveData {
 }
 
 impl ParametricCurveData {
+    /// ICC function type and the parameters it uses
+    pub fn icc_params(&self) -> (u16, Vec<f64>) {
+        let c = &self.curve;
+        match c.curve_type {
+            ParametricCurveType::Gamma => (0, vec![c.g]),
+            ParametricCurveType::CIE122 => (1, vec![c.g, c.a, c.b]),
+            ParametricCurveType::IEC61966_3 => (2, vec![c.g, c.a, c.b, c.c]),
+            ParametricCurveType::IEC61966_2_1 => (3, vec![c.g, c.a, c.b, c.c, c.d]),
+            ParametricCurveType::Full => (4, vec![c.g, c.a, c.b, c.c, c.d, c.e, c.f]),
+        }
+    }
+
     /// Parse parametric curve from bytes (after type signature and reserved bytes)
     pub fn parse(data: &[u8]) -> Result<Self, IccError> {
         if data.len() < 4 {
@@ -294,6 +296,28 @@ mod tests {
         assert!((curve.eval(1.0) - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_table_inverse_matches_scan() {
+        let tables: [Vec<u16>; 4] = [
+            // Ascending with flat runs at both ends and in the middle
+            vec![0, 0, 1000, 20000, 20000, 20000, 50000, 65535, 65535],
+            // Descending with a flat run
+            vec![65535, 60000, 30000, 30000, 10000, 0],
+            // Not monotonic, so scanned
+            vec![0, 40000, 30000, 65535],
+            (0..=4096u32).map(|i| (i * 16).min(65535) as u16).collect(),
+        ];
+        for table in tables {
+            let curve = CurveData::Table(table.clone());
+            let mut ys: Vec<f64> = (0..=1000).map(|i| i as f64 / 1000.0).collect();
+            ys.extend(table.iter().map(|&v| v as f64 / 65535.0));
+            for y in ys {
+                let scan = invert_with(table.len(), |i| table[i] as f64 / 65535.0, y);
+                assert_eq!(curve.eval_inverse(y), scan, "{:?} at {}", &table[..4], y);
+            }
+        }
+    }
+
     #[test]
     fn test_parametric_type0() {
         // Type 0: Y = X^g, with g = 2.2
@@ -0,0 +1,223 @@
+//! Dictionary Tag Type
+//!
+//! The dictType holds name/value string pairs, each optionally carrying
+//! localized display names and display values. Used by the `meta` tag to
+//! record profile provenance (creator tool, EDID data, calibration info).
+//!
+//! See ICC.1:2022 Section 10.8
+
+use crate::icc::error::IccError;
+use crate::icc::types::TypeSignature;
+
+use super::text::{TextData, decode_utf16be};
+
+/// A single dictionary entry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DictEntry {
+    /// Entry name (key)
+    pub name: String,
+    /// Entry value (`None` when the value offset is null)
+    pub value: Option<String>,
+    /// Localized display name (mluc)
+    pub display_name: Option<TextData>,
+    /// Localized display value (mluc)
+    pub display_value: Option<TextData>,
+}
+
+impl DictEntry {
+    /// Create an entry with a name and value
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: Some(value.into()),
+            display_name: None,
+            display_value: None,
+        }
+    }
+}
+
+/// Dictionary tag data (dict type)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DictData {
+    /// Entries in tag order
+    pub entries: Vec<DictEntry>,
+}
+
+impl DictData {
+    /// Parse dict data from bytes (after type signature and reserved bytes)
+    pub fn parse(data: &[u8]) -> Result<Self, IccError> {
+        if data.len() < 8 {
+            return Err(IccError::CorruptedData("dict tag too small".to_string()));
+        }
+
+        let count = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        let record_len = u32::from_be_bytes([data[4], data[5], data[6], data[7]]) as usize;
+
+        if !matches!(record_len, 16 | 24 | 32) {
+            return Err(IccError::CorruptedData(format!(
+                "dict record length {} invalid",
+                record_len
+            )));
+        }
+        if data.len() < 8 + count * record_len {
+            return Err(IccError::CorruptedData(
+                "dict records truncated".to_string(),
+            ));
+        }
+
+        let mut entries = Vec::with_capacity(count);
+        for i in 0..count {
+            let record = &data[8 + i * record_len..8 + (i + 1) * record_len];
+            let position = |n: usize| {
+                let o = n * 8;
+                let offset =
+                    u32::from_be_bytes([record[o], record[o + 1], record[o + 2], record[o + 3]]);
+                let size = u32::from_be_bytes([
+                    record[o + 4],
+                    record[o + 5],
+                    record[o + 6],
+                    record[o + 7],
+                ]);
+                (offset as usize, size as usize)
+            };
+
+            let name = read_string(data, position(0))?.unwrap_or_default();
+            let value = read_string(data, position(1))?;
+            let display_name = if record_len >= 24 {
+                read_mluc(data, position(2))?
+            } else {
+                None
+            };
+            let display_value = if record_len >= 32 {
+                read_mluc(data, position(3))?
+            } else {
+                None
+            };
+
+            entries.push(DictEntry {
+                name,
+                value,
+                display_name,
+                display_value,
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Find an entry by name
+    pub fn get(&self, name: &str) -> Option<&DictEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Get the value of an entry by name
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(|e| e.value.as_deref())
+    }
+
+    /// Insert or replace an entry, keeping existing order
+    pub fn insert(&mut self, entry: DictEntry) {
+        match self.entries.iter_mut().find(|e| e.name == entry.name) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Check if the dictionary has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Slice an element given an offset relative to the tag start
+fn element(data: &[u8], (offset, size): (usize, usize)) -> Result<Option<&[u8]>, IccError> {
+    if offset == 0 {
+        return Ok(None);
+    }
+    // `data` starts after the 8-byte type signature and reserved field
+    match offset.checked_sub(8) {
+        Some(start) if start + size <= data.len() => Ok(Some(&data[start..start + size])),
+        _ => Err(IccError::CorruptedData(format!(
+            "dict element at offset {} out of range",
+            offset
+        ))),
+    }
+}
+
+fn read_string(data: &[u8], position: (usize, usize)) -> Result<Option<String>, IccError> {
+    match element(data, position)? {
+        None => Ok(None),
+        Some(bytes) => decode_utf16be(bytes)
+            .map(Some)
+            .ok_or_else(|| IccError::CorruptedData("dict string is not UTF-16".to_string())),
+    }
+}
+
+fn read_mluc(data: &[u8], position: (usize, usize)) -> Result<Option<TextData>, IccError> {
+    let Some(bytes) = element(data, position)? else {
+        return Ok(None);
+    };
+    if bytes.len() < 8 || bytes[0..4] != TypeSignature::MLUC.0.to_be_bytes() {
+        return Err(IccError::CorruptedData(
+            "dict display element is not mluc".to_string(),
+        ));
+    }
+    TextData::parse_mluc(&bytes[8..]).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(|c| c.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn test_parse_dict() {
+        // Two 16-byte records: name/value only
+        let mut data = vec![0, 0, 0, 2, 0, 0, 0, 16];
+        let strings = [utf16("model"), utf16("X1"), utf16("vendor"), utf16("Acme")];
+
+        // Strings start after the records (8 header + 8 count/len + 32 records)
+        let mut offset = 8 + 8 + 32;
+        let mut positions = Vec::new();
+        for s in &strings {
+            positions.push((offset as u32, s.len() as u32));
+            offset += s.len();
+        }
+        for (o, len) in positions {
+            data.extend_from_slice(&o.to_be_bytes());
+            data.extend_from_slice(&len.to_be_bytes());
+        }
+        for s in &strings {
+            data.extend_from_slice(s);
+        }
+
+        let dict = DictData::parse(&data).unwrap();
+        assert_eq!(dict.entries.len(), 2);
+        assert_eq!(dict.value("model"), Some("X1"));
+        assert_eq!(dict.value("vendor"), Some("Acme"));
+        assert!(dict.get("serial").is_none());
+    }
+
+    #[test]
+    fn test_parse_dict_null_value() {
+        let mut data = vec![0, 0, 0, 1, 0, 0, 0, 16];
+        let name = utf16("key");
+        data.extend_from_slice(&32u32.to_be_bytes());
+        data.extend_from_slice(&(name.len() as u32).to_be_bytes());
+        data.extend_from_slice(&[0u8; 8]); // null value
+        data.extend_from_slice(&name);
+
+        let dict = DictData::parse(&data).unwrap();
+        assert_eq!(dict.entries[0].name, "key");
+        assert_eq!(dict.entries[0].value, None);
+    }
+
+    #[test]
+    fn test_parse_dict_bad_record_length() {
+        let data = [0, 0, 0, 1, 0, 0, 0, 20];
+        assert!(DictData::parse(&data).is_err());
+    }
+}
@@ -279,32 +279,32 @@ impl LutAToBData {
         // data[2..4] reserved
 
         // Offsets (0 means not present)
-        let b_offset = u32::from_be_bytes([data[4], data[5], data[6], data[7]]) as usize;
-        let matrix_offset = u32::from_be_bytes([data[8], data[9], data[10], data[11]]) as usize;
-        let m_offset = u32::from_be_bytes([data[12], data[13], data[14], data[15]]) as usize;
-        let clut_offset = u32::from_be_bytes([data[16], data[17], data[18], data[19]]) as usize;
-        let a_offset = u32::from_be_bytes([data[20], data[21], data[22], data[23]]) as usize;
+        let b_offset = element_offset(data, 4)?;
+        let matrix_offset = element_offset(data, 8)?;
+        let m_offset = element_offset(data, 12)?;
+        let clut_offset = element_offset(data, 16)?;
+        let a_offset = element_offset(data, 20)?;
 
         // Parse optional components
-        let b_curves = if b_offset != 0 {
+        let b_curves = if let Some(b_offset) = b_offset {
             Some(parse_curve_set(data, b_offset, output_channels as usize)?)
         } else {
             None
         };
 
-        let matrix = if matrix_offset != 0 {
+        let matrix = if let Some(matrix_offset) = matrix_offset {
             Some(LutMatrix::parse(&data[matrix_offset..])?)
         } else {
             None
         };
 
-        let m_curves = if m_offset != 0 {
+        let m_curves = if let Some(m_offset) = m_offset {
             Some(parse_curve_set(data, m_offset, output_channels as usize)?)
         } else {
             None
         };
 
-        let clut = if clut_offset != 0 {
+        let clut = if let Some(clut_offset) = clut_offset {
             Some(LutClut::parse(
                 &data[clut_offset..],
                 input_channels,
@@ -314,7 +314,7 @@ impl LutAToBData {
             None
         };
 
-        let a_curves = if a_offset != 0 {
+        let a_curves = if let Some(a_offset) = a_offset {
             Some(parse_curve_set(data, a_offset, input_channels as usize)?)
         } else {
             None
@@ -362,32 +362,32 @@ impl LutBToAData {
         let output_channels = data[1];
 
         // Offsets (0 means not present)
-        let b_offset = u32::from_be_bytes([data[4], data[5], data[6], data[7]]) as usize;
-        let matrix_offset = u32::from_be_bytes([data[8], data[9], data[10], data[11]]) as usize;
-        let m_offset = u32::from_be_bytes([data[12], data[13], data[14], data[15]]) as usize;
-        let clut_offset = u32::from_be_bytes([data[16], data[17], data[18], data[19]]) as usize;
-        let a_offset = u32::from_be_bytes([data[20], data[21], data[22], data[23]]) as usize;
+        let b_offset = element_offset(data, 4)?;
+        let matrix_offset = element_offset(data, 8)?;
+        let m_offset = element_offset(data, 12)?;
+        let clut_offset = element_offset(data, 16)?;
+        let a_offset = element_offset(data, 20)?;
 
         // Parse optional components
-        let b_curves = if b_offset != 0 {
+        let b_curves = if let Some(b_offset) = b_offset {
             Some(parse_curve_set(data, b_offset, input_channels as usize)?)
         } else {
             None
         };
 
-        let matrix = if matrix_offset != 0 {
+        let matrix = if let Some(matrix_offset) = matrix_offset {
             Some(LutMatrix::parse(&data[matrix_offset..])?)
         } else {
             None
         };
 
-        let m_curves = if m_offset != 0 {
+        let m_curves = if let Some(m_offset) = m_offset {
             Some(parse_curve_set(data, m_offset, input_channels as usize)?)
         } else {
             None
         };
 
-        let clut = if clut_offset != 0 {
+        let clut = if let Some(clut_offset) = clut_offset {
             Some(LutClut::parse(
                 &data[clut_offset..],
                 input_channels,
@@ -397,7 +397,7 @@ impl LutBToAData {
             None
         };
 
-        let a_curves = if a_offset != 0 {
+        let a_curves = if let Some(a_offset) = a_offset {
             Some(parse_curve_set(data, a_offset, output_channels as usize)?)
         } else {
             None
@@ -543,6 +543,24 @@ pub enum CurveSegment {
     Parametric { curve_type: u16, params: Vec<f64> },
 }
 
+/// Read an element offset from the lutAToB/lutBToA header
+///
+/// Offsets are relative to the start of the tag, while `data` starts after the
+/// 8-byte type signature and reserved field. Returns `None` for absent elements.
+fn element_offset(data: &[u8], pos: usize) -> Result<Option<usize>, IccError> {
+    let raw = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+    if raw == 0 {
+        return Ok(None);
+    }
+    match raw.checked_sub(8) {
+        Some(offset) if offset >= 24 && offset < data.len() => Ok(Some(offset)),
+        _ => Err(IccError::CorruptedData(format!(
+            "LUT element offset {} out of range",
+            raw
+        ))),
+    }
+}
+
 /// Parse a set of curves from data
 fn parse_curve_set(
     data: &[u8],
@@ -713,4 +731,32 @@ mod tests {
         assert_eq!(lut.grid_points, 2);
         assert!(lut.matrix_is_identity());
     }
+
+    #[test]
+    fn test_lut_a_to_b_offsets_from_tag_start() {
+        // 3-in/3-out lutAToB with only B curves; offsets count the 8-byte
+        // type signature and reserved field ahead of `data`
+        let mut data = vec![3, 3, 0, 0];
+        data.extend_from_slice(&32u32.to_be_bytes()); // B curves right after the header
+        data.extend_from_slice(&[0u8; 16]); // no matrix, M curves, CLUT or A curves
+        for _ in 0..3 {
+            data.extend_from_slice(b"curv");
+            data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1, 2, 0, 0, 0]); // gamma 2.0, padded
+        }
+
+        let lut = LutAToBData::parse(&data).unwrap();
+        let b_curves = lut.b_curves.unwrap();
+        assert_eq!(b_curves.len(), 3);
+        for curve in &b_curves {
+            assert!(
+                matches!(curve, CurveSegment::Parametric { curve_type: 0, params } if params == &[2.0]),
+                "{:?}",
+                curve
+            );
+        }
+
+        // An offset pointing back into the header is rejected
+        data[4..8].copy_from_slice(&24u32.to_be_bytes());
+        assert!(LutAToBData::parse(&data).is_err());
+    }
 }
@@ -8,14 +8,20 @@
 //!
 //! See ICC.1:2022 Section 9.
 
+mod cicp;
 mod curves;
+mod dict;
 mod lut;
 mod text;
+mod vcgt;
 mod xyz;
 
+pub use cicp::CicpData;
 pub use curves::{CurveData, ParametricCurveData};
-pub use lut::{CurveSegment, Lut8Data, Lut16Data, LutAToBData, LutBToAData, LutClut};
+pub use dict::{DictData, DictEntry};
+pub use lut::{CurveSegment, Lut8Data, Lut16Data, LutAToBData, LutBToAData, LutClut, LutMatrix};
 pub use text::TextData;
+pub use vcgt::VcgtData;
 pub use xyz::XyzTagData;
 
 use super::error::IccError;
@@ -44,6 +50,12 @@ pub enum TagData {
     LutBToA(LutBToAData),
     /// Chromatic adaptation matrix (sf32)
     ChromaticAdaptation([[f64; 3]; 3]),
+    /// Coding-independent code points (cicp)
+    Cicp(CicpData),
+    /// Video card gamma (vcgt)
+    Vcgt(VcgtData),
+    /// Metadata dictionary (dict)
+    Dict(DictData),
     /// Unknown/unsupported tag type
     Unknown { type_sig: u32, data: Vec<u8> },
 }
@@ -112,6 +124,18 @@ impl TagData {
                 let matrix = parse_sf32_matrix(type_data)?;
                 Ok(TagData::ChromaticAdaptation(matrix))
             }
+            TypeSignature::CICP => {
+                let cicp = CicpData::parse(type_data)?;
+                Ok(TagData::Cicp(cicp))
+            }
+            TypeSignature::VCGT => {
+                let vcgt = VcgtData::parse(type_data)?;
+                Ok(TagData::Vcgt(vcgt))
+            }
+            TypeSignature::DICT => {
+                let dict = DictData::parse(type_data)?;
+                Ok(TagData::Dict(dict))
+            }
             _ => {
                 // Unknown type - store raw data
                 Ok(TagData::Unknown {
@@ -186,6 +210,30 @@ impl TagData {
         }
     }
 
+    /// Get as cicp data
+    pub fn as_cicp(&self) -> Option<&CicpData> {
+        match self {
+            TagData::Cicp(cicp) => Some(cicp),
+            _ => None,
+        }
+    }
+
+    /// Get as vcgt data
+    pub fn as_vcgt(&self) -> Option<&VcgtData> {
+        match self {
+            TagData::Vcgt(vcgt) => Some(vcgt),
+            _ => None,
+        }
+    }
+
+    /// Get as dict data
+    pub fn as_dict(&self) -> Option<&DictData> {
+        match self {
+            TagData::Dict(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
     /// Check if this is any kind of LUT tag
     pub fn is_lut(&self) -> bool {
         matches!(
@@ -10,7 +10,7 @@
 use crate::icc::error::IccError;
 
 /// Text tag data
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TextData {
     /// Primary text content (English or default)
     pub text: String,
@@ -109,7 +109,11 @@ impl TextData {
             let str_offset =
                 u32::from_be_bytes([record[8], record[9], record[10], record[11]]) as usize;
 
-            // The offset is relative to the start of the tag type data (not the record)
+            // The offset is relative to the start of the tag, which is 8 bytes
+            // (type signature + reserved) before the start of `data`
+            let Some(str_offset) = str_offset.checked_sub(8) else {
+                continue;
+            };
             if str_offset + str_len <= data.len() && str_len >= 2 {
                 // UTF-16BE string
                 let utf16_data = &data[str_offset..str_offset + str_len];
@@ -145,7 +149,7 @@ impl TextData {
 }
 
 /// Decode UTF-16BE bytes to String
-fn decode_utf16be(data: &[u8]) -> Option<String> {
+pub(super) fn decode_utf16be(data: &[u8]) -> Option<String> {
     if data.len() % 2 != 0 {
         return None;
     }
@@ -195,7 +199,7 @@ mod tests {
             b'e', b'n', // language
             b'U', b'S', // country
             0, 0, 0, 10, // string length = 10 bytes (5 UTF-16 chars)
-            0, 0, 0, 20, // string offset = 20 (after this record)
+            0, 0, 0, 28, // string offset = 28 (after this record, from tag start)
         ]);
 
         // UTF-16BE string "Test" (with null)
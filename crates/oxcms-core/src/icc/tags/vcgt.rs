@@ -0,0 +1,216 @@
+//! Video Card Gamma Tag Type
+//!
+//! The `vcgt` tag is a private Apple tag written by display calibration
+//! tools. It holds per-channel curves that are loaded into the video card
+//! LUT, either as sampled tables or as a gamma/min/max formula.
+
+use crate::icc::error::IccError;
+use crate::icc::types::S15Fixed16;
+
+/// vcgt tag data
+#[derive(Debug, Clone, PartialEq)]
+pub enum VcgtData {
+    /// Sampled curves, normalized to [0, 1]
+    ///
+    /// Holds either one curve shared by all channels or one per channel (R, G, B).
+    Table(Vec<Vec<f64>>),
+    /// Per-channel formula: `min + (max - min) * x^gamma`
+    Formula {
+        /// Gamma per channel (R, G, B)
+        gamma: [f64; 3],
+        /// Minimum output per channel
+        min: [f64; 3],
+        /// Maximum output per channel
+        max: [f64; 3],
+    },
+}
+
+impl VcgtData {
+    /// Parse vcgt data from bytes (after type signature and reserved bytes)
+    pub fn parse(data: &[u8]) -> Result<Self, IccError> {
+        if data.len() < 4 {
+            return Err(IccError::CorruptedData("vcgt tag too small".to_string()));
+        }
+
+        let gamma_type = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+        let body = &data[4..];
+
+        match gamma_type {
+            0 => Self::parse_table(body),
+            1 => Self::parse_formula(body),
+            other => Err(IccError::CorruptedData(format!(
+                "Unknown vcgt gamma type {}",
+                other
+            ))),
+        }
+    }
+
+    fn parse_table(data: &[u8]) -> Result<Self, IccError> {
+        if data.len() < 6 {
+            return Err(IccError::CorruptedData(
+                "vcgt table header missing".to_string(),
+            ));
+        }
+
+        let channels = u16::from_be_bytes([data[0], data[1]]) as usize;
+        let entries = u16::from_be_bytes([data[2], data[3]]) as usize;
+        let entry_size = u16::from_be_bytes([data[4], data[5]]) as usize;
+
+        if channels != 1 && channels != 3 {
+            return Err(IccError::CorruptedData(format!(
+                "vcgt table has {} channels",
+                channels
+            )));
+        }
+        if entries < 2 {
+            return Err(IccError::CorruptedData(
+                "vcgt table needs at least 2 entries".to_string(),
+            ));
+        }
+        if entry_size != 1 && entry_size != 2 {
+            return Err(IccError::CorruptedData(format!(
+                "vcgt table entry size {} not supported",
+                entry_size
+            )));
+        }
+
+        let required_len = 6 + channels * entries * entry_size;
+        if data.len() < required_len {
+            return Err(IccError::CorruptedData(format!(
+                "vcgt table too small: need {} bytes, have {}",
+                required_len,
+                data.len()
+            )));
+        }
+
+        let values = &data[6..required_len];
+        let curves = values
+            .chunks_exact(entries * entry_size)
+            .map(|channel| {
+                if entry_size == 1 {
+                    channel.iter().map(|&v| v as f64 / 255.0).collect()
+                } else {
+                    channel
+                        .chunks_exact(2)
+                        .map(|v| u16::from_be_bytes([v[0], v[1]]) as f64 / 65535.0)
+                        .collect()
+                }
+            })
+            .collect();
+
+        Ok(VcgtData::Table(curves))
+    }
+
+    fn parse_formula(data: &[u8]) -> Result<Self, IccError> {
+        if data.len() < 36 {
+            return Err(IccError::CorruptedData(
+                "vcgt formula too small".to_string(),
+            ));
+        }
+
+        let read = |i: usize| {
+            let offset = i * 4;
+            S15Fixed16::from_be_bytes([
+                data[offset],
+                data[offset + 1],
+                data[offset + 2],
+                data[offset + 3],
+            ])
+            .to_f64()
+        };
+
+        // Stored as (gamma, min, max) for red, then green, then blue
+        let mut gamma = [1.0; 3];
+        let mut min = [0.0; 3];
+        let mut max = [1.0; 3];
+        for c in 0..3 {
+            gamma[c] = read(c * 3);
+            min[c] = read(c * 3 + 1);
+            max[c] = read(c * 3 + 2);
+        }
+
+        Ok(VcgtData::Formula { gamma, min, max })
+    }
+
+    /// Evaluate the calibration curve of a channel (0 = R, 1 = G, 2 = B)
+    pub fn eval(&self, channel: usize, x: f64) -> f64 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            VcgtData::Table(curves) => {
+                let Some(table) = curves.get(channel.min(curves.len().saturating_sub(1))) else {
+                    return x;
+                };
+                if table.len() < 2 {
+                    return x;
+                }
+
+                let pos = x * (table.len() - 1) as f64;
+                let idx = pos.floor() as usize;
+                if idx >= table.len() - 1 {
+                    return table[table.len() - 1];
+                }
+                let frac = pos - idx as f64;
+                table[idx] + frac * (table[idx + 1] - table[idx])
+            }
+            VcgtData::Formula { gamma, min, max } => {
+                let c = channel.min(2);
+                min[c] + (max[c] - min[c]) * x.powf(gamma[c])
+            }
+        }
+    }
+
+    /// Check if the curves leave every channel unchanged
+    pub fn is_identity(&self) -> bool {
+        (0..3).all(|c| {
+            (0..=32).all(|i| {
+                let x = i as f64 / 32.0;
+                (self.eval(c, x) - x).abs() < 1.0 / 65535.0
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vcgt_table() {
+        let mut data = vec![0, 0, 0, 0]; // table type
+        data.extend_from_slice(&3u16.to_be_bytes()); // channels
+        data.extend_from_slice(&2u16.to_be_bytes()); // entries
+        data.extend_from_slice(&2u16.to_be_bytes()); // entry size
+        for _ in 0..3 {
+            data.extend_from_slice(&0u16.to_be_bytes());
+            data.extend_from_slice(&32768u16.to_be_bytes());
+        }
+
+        let vcgt = VcgtData::parse(&data).unwrap();
+        match &vcgt {
+            VcgtData::Table(curves) => assert_eq!(curves.len(), 3),
+            _ => panic!("Expected table vcgt"),
+        }
+        assert!((vcgt.eval(1, 1.0) - 32768.0 / 65535.0).abs() < 1e-9);
+        assert!((vcgt.eval(2, 0.5) - 16384.0 / 65535.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_vcgt_formula() {
+        let mut data = vec![0, 0, 0, 1]; // formula type
+        for _ in 0..3 {
+            data.extend_from_slice(&(2 * 65536i32).to_be_bytes()); // gamma 2.0
+            data.extend_from_slice(&0i32.to_be_bytes()); // min 0.0
+            data.extend_from_slice(&65536i32.to_be_bytes()); // max 1.0
+        }
+
+        let vcgt = VcgtData::parse(&data).unwrap();
+        assert!((vcgt.eval(0, 0.5) - 0.25).abs() < 1e-9);
+        assert!(!vcgt.is_identity());
+    }
+
+    #[test]
+    fn test_parse_vcgt_truncated() {
+        let data = [0, 0, 0, 0, 0, 3, 1, 0, 0, 2];
+        assert!(VcgtData::parse(&data).is_err());
+    }
+}
@@ -30,6 +30,8 @@ impl TagSignature {
     pub const BLUE_COLORANT: Self = Self::from_bytes(*b"bXYZ");
     pub const BLUE_TRC: Self = Self::from_bytes(*b"bTRC");
     pub const CHAD: Self = Self::from_bytes(*b"chad");
+    pub const CHAR_TARGET: Self = Self::from_bytes(*b"targ");
+    pub const CICP: Self = Self::from_bytes(*b"cicp");
     pub const COPYRIGHT: Self = Self::from_bytes(*b"cprt");
     pub const DESC: Self = Self::from_bytes(*b"desc");
     pub const DMDD: Self = Self::from_bytes(*b"dmdd");
@@ -39,21 +41,58 @@ impl TagSignature {
     pub const GREEN_COLORANT: Self = Self::from_bytes(*b"gXYZ");
     pub const GREEN_TRC: Self = Self::from_bytes(*b"gTRC");
     pub const LUMINANCE: Self = Self::from_bytes(*b"lumi");
+    pub const METADATA: Self = Self::from_bytes(*b"meta");
     pub const MEDIA_WHITE: Self = Self::from_bytes(*b"wtpt");
     pub const MEDIA_BLACK: Self = Self::from_bytes(*b"bkpt");
+    pub const NAMED_COLOR2: Self = Self::from_bytes(*b"ncl2");
     pub const PREVIEW0: Self = Self::from_bytes(*b"pre0");
     pub const PREVIEW1: Self = Self::from_bytes(*b"pre1");
     pub const PREVIEW2: Self = Self::from_bytes(*b"pre2");
     pub const PROFILE_DESC: Self = Self::from_bytes(*b"desc");
+    pub const PROFILE_SEQUENCE: Self = Self::from_bytes(*b"pseq");
     pub const RED_COLORANT: Self = Self::from_bytes(*b"rXYZ");
     pub const RED_TRC: Self = Self::from_bytes(*b"rTRC");
     pub const TECH: Self = Self::from_bytes(*b"tech");
+    pub const VCGT: Self = Self::from_bytes(*b"vcgt");
     pub const VIEW_COND_DESC: Self = Self::from_bytes(*b"vued");
     pub const VIEW_COND: Self = Self::from_bytes(*b"view");
+
+    /// Tag types permitted for this tag (ICC.1:2022 Section 9.2)
+    ///
+    /// Returns `None` for private or unrecognized tags.
+    pub fn allowed_types(&self) -> Option<&'static [TypeSignature]> {
+        use TypeSignature as T;
+        let types: &'static [TypeSignature] = match *self {
+            Self::A2B0 | Self::A2B1 | Self::A2B2 => &[T::LUT8, T::LUT16, T::LUTA2B],
+            Self::B2A0 | Self::B2A1 | Self::B2A2 => &[T::LUT8, T::LUT16, T::LUTB2A],
+            Self::GAMUT | Self::PREVIEW0 | Self::PREVIEW1 | Self::PREVIEW2 => {
+                &[T::LUT8, T::LUT16, T::LUTA2B, T::LUTB2A]
+            }
+            Self::RED_COLORANT
+            | Self::GREEN_COLORANT
+            | Self::BLUE_COLORANT
+            | Self::MEDIA_WHITE
+            | Self::MEDIA_BLACK
+            | Self::LUMINANCE => &[T::XYZ],
+            Self::RED_TRC | Self::GREEN_TRC | Self::BLUE_TRC | Self::GRAY_TRC => {
+                &[T::CURVE, T::PARA]
+            }
+            Self::DESC | Self::DMND | Self::DMDD | Self::VIEW_COND_DESC => &[T::DESC, T::MLUC],
+            Self::COPYRIGHT => &[T::TEXT, T::MLUC],
+            Self::CHAR_TARGET => &[T::TEXT],
+            Self::CHAD => &[T::SF32],
+            Self::CICP => &[T::CICP],
+            Self::METADATA => &[T::DICT],
+            Self::TECH => &[T::SIG],
+            Self::VCGT => &[T::VCGT],
+            _ => return None,
+        };
+        Some(types)
+    }
 }
 
 /// Type signatures for ICC tag data
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TypeSignature(pub u32);
 
 impl TypeSignature {
@@ -75,6 +114,9 @@ impl TypeSignature {
     pub const SF32: Self = Self::from_bytes(*b"sf32");
     pub const CHAD: Self = Self::from_bytes(*b"sf32"); // chromatic adaptation uses sf32
     pub const SIG: Self = Self::from_bytes(*b"sig ");
+    pub const CICP: Self = Self::from_bytes(*b"cicp");
+    pub const VCGT: Self = Self::from_bytes(*b"vcgt");
+    pub const DICT: Self = Self::from_bytes(*b"dict");
 }
 
 /// s15Fixed16Number - 16.16 fixed point
@@ -89,7 +131,7 @@ impl S15Fixed16 {
 
     /// Create from f64 value
     pub fn from_f64(val: f64) -> Self {
-        Self((val * 65536.0) as i32)
+        Self((val * 65536.0).round() as i32)
     }
 
     /// Convert to f64
@@ -101,6 +143,11 @@ impl S15Fixed16 {
     pub fn from_be_bytes(bytes: [u8; 4]) -> Self {
         Self(i32::from_be_bytes(bytes))
     }
+
+    /// Serialize to big-endian bytes
+    pub fn to_be_bytes(self) -> [u8; 4] {
+        self.0.to_be_bytes()
+    }
 }
 
 /// u16Fixed16Number - unsigned 16.16 fixed point
@@ -148,6 +195,13 @@ pub struct XyzNumber {
 }
 
 impl XyzNumber {
+    /// PCS illuminant as encoded in ICC headers (0.9642, 1.0, 0.8249)
+    pub const D50: Self = Self {
+        x: S15Fixed16(0xF6D6),
+        y: S15Fixed16(0x10000),
+        z: S15Fixed16(0xD32D),
+    };
+
     /// Parse from 12 bytes (big-endian)
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
         if bytes.len() < 12 {
@@ -164,6 +218,24 @@ impl XyzNumber {
     pub fn to_xyz(&self) -> Xyz {
         Xyz::new(self.x.to_f64(), self.y.to_f64(), self.z.to_f64())
     }
+
+    /// Create from Xyz color type
+    pub fn from_xyz(xyz: Xyz) -> Self {
+        Self {
+            x: S15Fixed16::from_f64(xyz.x),
+            y: S15Fixed16::from_f64(xyz.y),
+            z: S15Fixed16::from_f64(xyz.z),
+        }
+    }
+
+    /// Serialize to 12 bytes (big-endian)
+    pub fn to_bytes(&self) -> [u8; 12] {
+        let mut bytes = [0u8; 12];
+        bytes[0..4].copy_from_slice(&self.x.to_be_bytes());
+        bytes[4..8].copy_from_slice(&self.y.to_be_bytes());
+        bytes[8..12].copy_from_slice(&self.z.to_be_bytes());
+        bytes
+    }
 }
 
 /// dateTimeNumber - ICC date/time
@@ -192,6 +264,25 @@ impl DateTimeNumber {
             second: u16::from_be_bytes([bytes[10], bytes[11]]),
         })
     }
+
+    /// Serialize to 12 bytes (big-endian)
+    pub fn to_bytes(&self) -> [u8; 12] {
+        let mut bytes = [0u8; 12];
+        for (i, v) in [
+            self.year,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second,
+        ]
+        .iter()
+        .enumerate()
+        {
+            bytes[i * 2..i * 2 + 2].copy_from_slice(&v.to_be_bytes());
+        }
+        bytes
+    }
 }
 
 /// Response16Number for device calibration
@@ -215,6 +306,12 @@ mod tests {
 
         let neg = S15Fixed16::from_f64(-1.5);
         assert!((neg.to_f64() - (-1.5)).abs() < 1e-6);
+
+        // Values between codes round to the nearest one rather than toward
+        // zero, so the ICC D50 constants encode to their published bytes
+        assert_eq!(S15Fixed16::from_f64(0.9642).0, 0xF6D6);
+        assert_eq!(S15Fixed16::from_f64(0.8249).0, 0xD32D);
+        assert_eq!(S15Fixed16::from_f64(-0.8249).0, -0xD32D);
     }
 
     #[test]
@@ -0,0 +1,769 @@
+//! Profile Conformance Validation
+//!
+//! Parsing only checks that a profile can be read. [`ValidationReport`] goes
+//! further and checks the profile against ICC.1:2022: required tags per
+//! profile class (Section 8), permitted tag types (Section 9), version
+//! differences between v2 and v4, the PCS illuminant, `chad` consistency,
+//! TRC monotonicity and LUT/CLUT dimensions.
+
+use std::fmt;
+
+use super::header::{ColorSpace, ProfileClass};
+use super::parser::IccProfile;
+use super::tags::{CurveData, CurveSegment, LutClut, TagData};
+use super::types::{TagSignature, TypeSignature, XyzNumber};
+
+/// Severity of a validation finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Allowed by the spec but likely to cause problems
+    Warning,
+    /// Violates the spec; the profile should be rejected
+    Error,
+}
+
+/// Area of the spec a finding relates to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationCategory {
+    /// Header fields (class, color spaces)
+    Header,
+    /// A tag required for the profile class is missing
+    MissingTag,
+    /// Tag type is not permitted for the tag
+    TagType,
+    /// Tag or tag type does not exist in the profile's version
+    Version,
+    /// PCS illuminant is not D50
+    Illuminant,
+    /// Chromatic adaptation (`chad`) is invalid or inconsistent with `wtpt`
+    ChromaticAdaptation,
+    /// Tone reproduction curve problem
+    Curve,
+    /// LUT channel counts or CLUT dimensions are inconsistent
+    Lut,
+}
+
+/// A single validation finding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// How serious the finding is
+    pub severity: Severity,
+    /// Finding category
+    pub category: ValidationCategory,
+    /// Tag the finding refers to, if any
+    pub tag: Option<TagSignature>,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{} [{:?}]", severity, self.category)?;
+        if let Some(tag) = self.tag {
+            write!(f, " '{}'", tag.to_string())?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Result of validating a profile
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Findings in the order they were made
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Check if the profile has no errors (warnings are allowed)
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Findings with error severity
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error)
+    }
+
+    /// Findings with warning severity
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == Severity::Warning)
+    }
+
+    /// Check if any finding of the given category was made
+    pub fn has(&self, category: ValidationCategory) -> bool {
+        self.issues.iter().any(|i| i.category == category)
+    }
+
+    /// Findings of the given category
+    pub fn of_category(
+        &self,
+        category: ValidationCategory,
+    ) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(move |i| i.category == category)
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        category: ValidationCategory,
+        tag: Option<TagSignature>,
+        message: String,
+    ) {
+        self.issues.push(ValidationIssue {
+            severity,
+            category,
+            tag,
+            message,
+        });
+    }
+
+    fn error(&mut self, category: ValidationCategory, tag: Option<TagSignature>, message: String) {
+        self.push(Severity::Error, category, tag, message);
+    }
+
+    fn warn(&mut self, category: ValidationCategory, tag: Option<TagSignature>, message: String) {
+        self.push(Severity::Warning, category, tag, message);
+    }
+}
+
+/// Tolerance for XYZ comparisons (a few s15Fixed16 steps plus rounding in chad)
+const XYZ_TOLERANCE: f64 = 2e-3;
+
+pub(super) fn validate_profile(profile: &IccProfile) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    check_header(profile, &mut report);
+    check_required_tags(profile, &mut report);
+    check_tag_types(profile, &mut report);
+    check_adaptation(profile, &mut report);
+    check_curves(profile, &mut report);
+    check_luts(profile, &mut report);
+    report
+}
+
+fn check_header(profile: &IccProfile, report: &mut ValidationReport) {
+    let header = &profile.header;
+
+    if !matches!(header.version.major, 2 | 4) {
+        report.error(
+            ValidationCategory::Header,
+            None,
+            format!("unsupported version {}", header.version.major),
+        );
+    }
+
+    let pcs_ok = match header.device_class {
+        // Device links store the output color space in the PCS field
+        ProfileClass::DeviceLink => true,
+        _ => matches!(header.pcs, ColorSpace::Xyz | ColorSpace::Lab),
+    };
+    if !pcs_ok {
+        report.error(
+            ValidationCategory::Header,
+            None,
+            format!("PCS must be XYZ or Lab, found {:?}", header.pcs),
+        );
+    }
+
+    if header.device_class == ProfileClass::Abstract
+        && !matches!(header.color_space, ColorSpace::Xyz | ColorSpace::Lab)
+    {
+        report.error(
+            ValidationCategory::Header,
+            None,
+            format!(
+                "abstract profile data color space must be XYZ or Lab, found {:?}",
+                header.color_space
+            ),
+        );
+    }
+
+    let illuminant = header.illuminant.to_xyz();
+    if !illuminant.approx_eq(&XyzNumber::D50.to_xyz(), XYZ_TOLERANCE) {
+        report.error(
+            ValidationCategory::Illuminant,
+            None,
+            format!(
+                "PCS illuminant must be D50, found ({:.4}, {:.4}, {:.4})",
+                illuminant.x, illuminant.y, illuminant.z
+            ),
+        );
+    }
+}
+
+/// Required tags per profile class (ICC.1:2022 Section 8)
+fn check_required_tags(profile: &IccProfile, report: &mut ValidationReport) {
+    use TagSignature as T;
+
+    let header = &profile.header;
+    let has = |sig: TagSignature| profile.get_tag(sig).is_some();
+    let mut required = vec![T::DESC, T::COPYRIGHT];
+
+    if header.device_class != ProfileClass::DeviceLink {
+        required.push(T::MEDIA_WHITE);
+    }
+
+    let gray = header.color_space == ColorSpace::Gray;
+    let matrix_shaper =
+        header.color_space == ColorSpace::Rgb && header.pcs == ColorSpace::Xyz && !has(T::A2B0);
+    let shaper_tags = [
+        T::RED_COLORANT,
+        T::GREEN_COLORANT,
+        T::BLUE_COLORANT,
+        T::RED_TRC,
+        T::GREEN_TRC,
+        T::BLUE_TRC,
+    ];
+
+    match header.device_class {
+        ProfileClass::Input | ProfileClass::Display if gray && !has(T::A2B0) => {
+            required.push(T::GRAY_TRC);
+        }
+        ProfileClass::Input | ProfileClass::Display if matrix_shaper => {
+            required.extend(shaper_tags);
+        }
+        ProfileClass::Input => required.push(T::A2B0),
+        ProfileClass::Display => required.extend([T::A2B0, T::B2A0]),
+        ProfileClass::Output if gray && !has(T::A2B0) => required.push(T::GRAY_TRC),
+        ProfileClass::Output => {
+            required.extend([
+                T::A2B0,
+                T::A2B1,
+                T::A2B2,
+                T::B2A0,
+                T::B2A1,
+                T::B2A2,
+                T::GAMUT,
+            ]);
+        }
+        ProfileClass::DeviceLink => required.extend([T::PROFILE_SEQUENCE, T::A2B0]),
+        ProfileClass::ColorSpace => required.extend([T::A2B0, T::B2A0]),
+        ProfileClass::Abstract => required.push(T::A2B0),
+        ProfileClass::NamedColor => required.push(T::NAMED_COLOR2),
+    }
+
+    for sig in required {
+        if !has(sig) {
+            report.error(
+                ValidationCategory::MissingTag,
+                Some(sig),
+                format!("required for {:?} profiles", header.device_class),
+            );
+        }
+    }
+
+    // Other classes store the (already adapted) media white, which is
+    // legitimately not D50
+    if header.version.major >= 4
+        && header.device_class == ProfileClass::Display
+        && !has(T::CHAD)
+        && profile
+            .media_white_point()
+            .is_some_and(|w| !w.approx_eq(&XyzNumber::D50.to_xyz(), XYZ_TOLERANCE))
+    {
+        report.error(
+            ValidationCategory::MissingTag,
+            Some(T::CHAD),
+            "required in v4 display profiles when the media white point is not D50".to_string(),
+        );
+    }
+}
+
+/// Permitted tag types, including v2/v4 differences
+fn check_tag_types(profile: &IccProfile, report: &mut ValidationReport) {
+    let v4 = profile.header.version.major >= 4;
+    let mut signatures: Vec<TagSignature> = profile.tag_signatures().collect();
+    signatures.sort_by_key(|s| s.0);
+
+    for sig in signatures {
+        let Some(type_sig) = profile.tag_type(sig) else {
+            continue;
+        };
+        let type_name = TagSignature(type_sig.0).to_string();
+
+        // Private tags have no type rules
+        let Some(allowed) = sig.allowed_types() else {
+            continue;
+        };
+        if !allowed.contains(&type_sig) {
+            report.error(
+                ValidationCategory::TagType,
+                Some(sig),
+                format!("type '{}' is not permitted", type_name),
+            );
+            continue;
+        }
+
+        let v4_only = [
+            TypeSignature::MLUC,
+            TypeSignature::PARA,
+            TypeSignature::LUTA2B,
+            TypeSignature::LUTB2A,
+        ];
+        let v2_only = [TypeSignature::DESC, TypeSignature::TEXT];
+
+        if !v4 && v4_only.contains(&type_sig) {
+            report.error(
+                ValidationCategory::Version,
+                Some(sig),
+                format!("type '{}' requires a v4 profile", type_name),
+            );
+        } else if !v4 && matches!(type_sig, TypeSignature::DICT | TypeSignature::CICP) {
+            // Later additions; harmless in v2 but only v4.3+ readers know them
+            report.warn(
+                ValidationCategory::Version,
+                Some(sig),
+                format!("type '{}' is not defined for v2 profiles", type_name),
+            );
+        } else if v4 && v2_only.contains(&type_sig) && sig != TagSignature::CHAR_TARGET {
+            report.error(
+                ValidationCategory::Version,
+                Some(sig),
+                format!("type '{}' is not permitted in v4; use 'mluc'", type_name),
+            );
+        }
+    }
+}
+
+/// `chad` must be invertible and map the media white to D50
+fn check_adaptation(profile: &IccProfile, report: &mut ValidationReport) {
+    let d50 = XyzNumber::D50.to_xyz();
+    let white = profile.media_white_point();
+
+    let Some(chad) = profile.chromatic_adaptation_matrix() else {
+        return;
+    };
+
+    let det = chad[0][0] * (chad[1][1] * chad[2][2] - chad[1][2] * chad[2][1])
+        - chad[0][1] * (chad[1][0] * chad[2][2] - chad[1][2] * chad[2][0])
+        + chad[0][2] * (chad[1][0] * chad[2][1] - chad[1][1] * chad[2][0]);
+    if det.abs() < 1e-9 {
+        report.error(
+            ValidationCategory::ChromaticAdaptation,
+            Some(TagSignature::CHAD),
+            "matrix is singular".to_string(),
+        );
+        return;
+    }
+
+    // Only display profiles tie wtpt to the adopted white
+    if profile.header.device_class != ProfileClass::Display {
+        return;
+    }
+    let Some(white) = white else {
+        return;
+    };
+
+    // v4 stores the adapted (D50) white; v2 writers store the source white,
+    // which chad must then take to D50
+    let adapted = crate::color::Xyz::new(
+        chad[0][0] * white.x + chad[0][1] * white.y + chad[0][2] * white.z,
+        chad[1][0] * white.x + chad[1][1] * white.y + chad[1][2] * white.z,
+        chad[2][0] * white.x + chad[2][1] * white.y + chad[2][2] * white.z,
+    );
+    if !white.approx_eq(&d50, XYZ_TOLERANCE) && !adapted.approx_eq(&d50, XYZ_TOLERANCE) {
+        report.error(
+            ValidationCategory::ChromaticAdaptation,
+            Some(TagSignature::CHAD),
+            format!(
+                "maps media white ({:.4}, {:.4}, {:.4}) to ({:.4}, {:.4}, {:.4}), not D50",
+                white.x, white.y, white.z, adapted.x, adapted.y, adapted.z
+            ),
+        );
+    }
+}
+
+/// TRCs must be monotonic to be invertible
+fn check_curves(profile: &IccProfile, report: &mut ValidationReport) {
+    const SAMPLES: usize = 1024;
+
+    for sig in [
+        TagSignature::RED_TRC,
+        TagSignature::GREEN_TRC,
+        TagSignature::BLUE_TRC,
+        TagSignature::GRAY_TRC,
+    ] {
+        let values: Vec<f64> = match profile.get_tag(sig) {
+            Some(TagData::Curve(CurveData::Gamma(g))) if *g <= 0.0 => {
+                report.error(
+                    ValidationCategory::Curve,
+                    Some(sig),
+                    format!("gamma {} is not positive", g),
+                );
+                continue;
+            }
+            Some(TagData::Curve(CurveData::Table(table))) => {
+                table.iter().map(|&v| v as f64).collect()
+            }
+            Some(TagData::ParametricCurve(para)) => (0..SAMPLES)
+                .map(|i| para.eval(i as f64 / (SAMPLES - 1) as f64))
+                .collect(),
+            _ => continue,
+        };
+
+        if values.iter().any(|v| !v.is_finite()) {
+            report.error(
+                ValidationCategory::Curve,
+                Some(sig),
+                "curve produces non-finite values".to_string(),
+            );
+        } else if !is_monotonic(&values) {
+            report.error(
+                ValidationCategory::Curve,
+                Some(sig),
+                "curve is not monotonic and cannot be inverted".to_string(),
+            );
+        } else if values.first() == values.last() {
+            report.warn(
+                ValidationCategory::Curve,
+                Some(sig),
+                "curve is constant".to_string(),
+            );
+        }
+    }
+}
+
+fn is_monotonic(values: &[f64]) -> bool {
+    let rising = values.windows(2).all(|w| w[1] >= w[0]);
+    let falling = values.windows(2).all(|w| w[1] <= w[0]);
+    rising || falling
+}
+
+/// LUT channel counts must match the header, and CLUTs their grid
+fn check_luts(profile: &IccProfile, report: &mut ValidationReport) {
+    use TagSignature as T;
+
+    let header = &profile.header;
+    let device = header.color_space.channels();
+    let pcs = header.pcs.channels();
+
+    let (forward, reverse) = match header.device_class {
+        // Device links map data space to the space in the PCS field
+        ProfileClass::DeviceLink => ((device, pcs), (pcs, device)),
+        ProfileClass::Abstract => ((pcs, pcs), (pcs, pcs)),
+        _ => ((device, pcs), (pcs, device)),
+    };
+    let expected = |sig: TagSignature| match sig {
+        T::A2B0 | T::A2B1 | T::A2B2 => Some(forward),
+        T::B2A0 | T::B2A1 | T::B2A2 => Some(reverse),
+        T::GAMUT => Some((pcs, 1)),
+        T::PREVIEW0 | T::PREVIEW1 | T::PREVIEW2 => Some((pcs, pcs)),
+        _ => None,
+    };
+
+    let mut signatures: Vec<TagSignature> = profile.tag_signatures().collect();
+    signatures.sort_by_key(|s| s.0);
+
+    for sig in signatures {
+        let Some(tag) = profile.get_tag(sig) else {
+            continue;
+        };
+        let mut problems = Vec::new();
+
+        let channels = match tag {
+            TagData::Lut8(lut) => {
+                let (i, o) = (lut.input_channels as usize, lut.output_channels as usize);
+                check_grid(&[lut.grid_points], i, o, lut.clut.len(), &mut problems);
+                check_curve_count("input", lut.input_curves.len(), i, &mut problems);
+                check_curve_count("output", lut.output_curves.len(), o, &mut problems);
+                (i, o)
+            }
+            TagData::Lut16(lut) => {
+                let (i, o) = (lut.input_channels as usize, lut.output_channels as usize);
+                check_grid(&[lut.grid_points], i, o, lut.clut.len(), &mut problems);
+                check_curve_count("input", lut.input_curves.len(), i, &mut problems);
+                check_curve_count("output", lut.output_curves.len(), o, &mut problems);
+                (i, o)
+            }
+            TagData::LutAToB(lut) => {
+                let (i, o) = (lut.input_channels as usize, lut.output_channels as usize);
+                // Element order: A (inputs) -> CLUT -> M -> matrix -> B (outputs)
+                check_elements(
+                    (i, o),
+                    lut.a_curves.as_deref(),
+                    lut.clut.as_ref(),
+                    lut.m_curves.as_deref(),
+                    lut.b_curves.as_deref(),
+                    false,
+                    &mut problems,
+                );
+                (i, o)
+            }
+            TagData::LutBToA(lut) => {
+                let (i, o) = (lut.input_channels as usize, lut.output_channels as usize);
+                // Element order: B (inputs) -> matrix -> M -> CLUT -> A (outputs)
+                check_elements(
+                    (i, o),
+                    lut.a_curves.as_deref(),
+                    lut.clut.as_ref(),
+                    lut.m_curves.as_deref(),
+                    lut.b_curves.as_deref(),
+                    true,
+                    &mut problems,
+                );
+                (i, o)
+            }
+            _ => continue,
+        };
+
+        if let Some((ei, eo)) = expected(sig) {
+            if channels != (ei, eo) {
+                problems.push(format!(
+                    "maps {} -> {} channels, header implies {} -> {}",
+                    channels.0, channels.1, ei, eo
+                ));
+            }
+        }
+
+        for message in problems {
+            report.error(ValidationCategory::Lut, Some(sig), message);
+        }
+    }
+}
+
+fn check_grid(grid: &[u8], inputs: usize, outputs: usize, len: usize, problems: &mut Vec<String>) {
+    if grid.iter().any(|&g| g < 2) {
+        problems.push(format!("CLUT grid {:?} has fewer than 2 points", grid));
+        return;
+    }
+    // mft1/mft2 share one grid size across all inputs
+    let points = if grid.len() == 1 {
+        u32::try_from(inputs)
+            .ok()
+            .and_then(|n| (grid[0] as usize).checked_pow(n))
+    } else {
+        grid.iter()
+            .try_fold(1usize, |acc, &g| acc.checked_mul(g as usize))
+    };
+    let Some(needed) = points.and_then(|p| p.checked_mul(outputs)) else {
+        problems.push(format!(
+            "CLUT grid {:?} with {} inputs and {} outputs is too large to address",
+            grid, inputs, outputs
+        ));
+        return;
+    };
+    if needed != len {
+        problems.push(format!(
+            "CLUT has {} values, grid {:?} with {} outputs needs {}",
+            len, grid, outputs, needed
+        ));
+    }
+}
+
+fn check_curve_count(name: &str, count: usize, expected: usize, problems: &mut Vec<String>) {
+    if count != expected {
+        problems.push(format!(
+            "{} {} curves for {} channels",
+            count, name, expected
+        ));
+    }
+}
+
+/// Check mAB/mBA element channel counts
+///
+/// mAB runs A -> CLUT -> M -> matrix -> B; mBA runs B -> matrix -> M ->
+/// CLUT -> A. `reverse` selects the mBA order.
+fn check_elements(
+    (inputs, outputs): (usize, usize),
+    a: Option<&[CurveSegment]>,
+    clut: Option<&LutClut>,
+    m: Option<&[CurveSegment]>,
+    b: Option<&[CurveSegment]>,
+    reverse: bool,
+    problems: &mut Vec<String>,
+) {
+    let (a_channels, m_channels, b_channels) = if reverse {
+        (outputs, inputs, inputs)
+    } else {
+        (inputs, outputs, outputs)
+    };
+
+    match b {
+        Some(b) => check_curve_count("B", b.len(), b_channels, problems),
+        None => problems.push("B curves are required".to_string()),
+    }
+    if let Some(m) = m {
+        check_curve_count("M", m.len(), m_channels, problems);
+    }
+    if let Some(a) = a {
+        check_curve_count("A", a.len(), a_channels, problems);
+    }
+    if a.is_some() != clut.is_some() {
+        problems.push("A curves and CLUT must be present together".to_string());
+    }
+    if let Some(clut) = clut {
+        if clut.grid_points.len() != inputs {
+            problems.push(format!(
+                "CLUT has {} dimensions for {} inputs",
+                clut.grid_points.len(),
+                inputs
+            ));
+        }
+        check_grid(
+            &clut.grid_points,
+            clut.grid_points.len(),
+            clut.output_channels as usize,
+            clut.data.len(),
+            problems,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icc::header::IccHeader;
+    use crate::icc::tags::{TextData, XyzTagData};
+
+    fn text(s: &str) -> TagData {
+        TagData::Text(TextData::new(s.to_string()))
+    }
+
+    fn xyz(x: f64, y: f64, z: f64) -> TagData {
+        TagData::Xyz(XyzTagData {
+            values: vec![XyzNumber::from_xyz(crate::color::Xyz::new(x, y, z))],
+        })
+    }
+
+    fn srgb_like() -> IccProfile {
+        let header = IccHeader::new(ProfileClass::Display, ColorSpace::Rgb, ColorSpace::Xyz);
+        let mut profile = IccProfile::new(header);
+        profile.set_tag(TagSignature::DESC, text("test"));
+        profile.set_tag(TagSignature::COPYRIGHT, text("none"));
+        profile.set_tag(TagSignature::MEDIA_WHITE, xyz(0.9642, 1.0, 0.8249));
+        profile.set_tag(TagSignature::RED_COLORANT, xyz(0.4361, 0.2225, 0.0139));
+        profile.set_tag(TagSignature::GREEN_COLORANT, xyz(0.3851, 0.7169, 0.0971));
+        profile.set_tag(TagSignature::BLUE_COLORANT, xyz(0.1431, 0.0606, 0.7141));
+        for sig in [
+            TagSignature::RED_TRC,
+            TagSignature::GREEN_TRC,
+            TagSignature::BLUE_TRC,
+        ] {
+            profile.set_tag(sig, TagData::Curve(CurveData::Gamma(2.2)));
+        }
+        profile
+    }
+
+    #[test]
+    fn test_valid_matrix_shaper() {
+        let report = srgb_like().validate();
+        assert!(report.is_valid(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_missing_required_tag() {
+        let mut profile = srgb_like();
+        profile.remove_tag(TagSignature::GREEN_TRC);
+        let report = profile.validate();
+        assert!(!report.is_valid());
+        let missing: Vec<_> = report.of_category(ValidationCategory::MissingTag).collect();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].tag, Some(TagSignature::GREEN_TRC));
+    }
+
+    #[test]
+    fn test_non_monotonic_trc() {
+        let mut profile = srgb_like();
+        profile.set_tag(
+            TagSignature::RED_TRC,
+            TagData::Curve(CurveData::Table(vec![0, 40000, 30000, 65535])),
+        );
+        let report = profile.validate();
+        assert_eq!(report.of_category(ValidationCategory::Curve).count(), 1);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_v4_requires_chad_for_non_d50_white() {
+        let mut profile = srgb_like();
+        profile.set_tag(TagSignature::MEDIA_WHITE, xyz(0.9505, 1.0, 1.089));
+        let report = profile.validate();
+        assert!(report.errors().any(|i| i.tag == Some(TagSignature::CHAD)));
+
+        // Bradford D65 -> D50
+        let chad = [
+            [1.0479, 0.0229, -0.0502],
+            [0.0296, 0.9904, -0.0171],
+            [-0.0092, 0.0151, 0.7519],
+        ];
+        profile.set_tag(TagSignature::CHAD, TagData::ChromaticAdaptation(chad));
+        assert!(profile.validate().is_valid());
+
+        profile.set_tag(
+            TagSignature::CHAD,
+            TagData::ChromaticAdaptation([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]),
+        );
+        let report = profile.validate();
+        assert!(report.has(ValidationCategory::ChromaticAdaptation));
+    }
+
+    #[test]
+    fn test_version_specific_types() {
+        let mut profile = srgb_like();
+        profile.header.version.major = 2;
+        profile.set_tag(
+            TagSignature::COPYRIGHT,
+            TagData::MultiLocalizedUnicode(TextData::new("none".to_string())),
+        );
+        let report = profile.validate();
+        let version: Vec<_> = report.of_category(ValidationCategory::Version).collect();
+        assert_eq!(version.len(), 1);
+        assert_eq!(version[0].tag, Some(TagSignature::COPYRIGHT));
+    }
+
+    #[test]
+    fn test_wrong_tag_type() {
+        let mut profile = srgb_like();
+        profile.set_tag(TagSignature::RED_COLORANT, text("oops"));
+        profile.set_tag(
+            TagSignature::MEDIA_WHITE,
+            TagData::Curve(CurveData::Identity),
+        );
+        let report = profile.validate();
+        // Text is written as mluc in v4, which is not an XYZ type either
+        let tags: Vec<_> = report
+            .of_category(ValidationCategory::TagType)
+            .map(|i| i.tag)
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                Some(TagSignature::RED_COLORANT),
+                Some(TagSignature::MEDIA_WHITE)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_huge_clut_grid() {
+        // 255^15 grid points overflow usize; reported instead of panicking
+        let mut problems = Vec::new();
+        check_grid(&[255], 15, 3, 48, &mut problems);
+        check_grid(&[255; 15], 15, 3, 48, &mut problems);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems.iter().all(|p| p.contains("too large")));
+
+        problems.clear();
+        check_grid(&[2], 4, 3, 48, &mut problems);
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_issue_display() {
+        let issue = ValidationIssue {
+            severity: Severity::Error,
+            category: ValidationCategory::MissingTag,
+            tag: Some(TagSignature::A2B0),
+            message: "required for Output profiles".to_string(),
+        };
+        assert_eq!(
+            issue.to_string(),
+            "error [MissingTag] 'A2B0': required for Output profiles"
+        );
+    }
+}
@@ -0,0 +1,747 @@
+//! ICC Profile Writer
+//!
+//! Serializes an [`IccProfile`] back to ICC bytes.
+//!
+//! Tags are written in signature order and aligned to 4 bytes. Tags whose
+//! serialized data is identical (e.g. shared rTRC/gTRC/bTRC) are stored once
+//! and referenced from several tag table entries.
+//!
+//! See ICC.1:2022 Section 7.
+
+use super::error::IccError;
+use super::header::{MIN_PROFILE_SIZE, ProfileVersion};
+use super::parser::IccProfile;
+use super::tags::{
+    CicpData, CurveData, CurveSegment, DictData, Lut8Data, Lut16Data, LutAToBData, LutBToAData,
+    LutClut, ParametricCurveData, TagData, TextData, VcgtData,
+};
+use super::types::{S15Fixed16, TagSignature, TypeSignature};
+
+/// Serialize a profile to ICC bytes
+pub fn write_profile(profile: &IccProfile) -> Result<Vec<u8>, IccError> {
+    let version = profile.header.version;
+
+    let mut signatures: Vec<u32> = profile.tags.keys().copied().collect();
+    signatures.sort_unstable();
+
+    let table_size = 4 + signatures.len() * 12;
+    let mut offset = MIN_PROFILE_SIZE + table_size;
+
+    // (signature, offset, size) for each tag, plus the unique data blocks
+    let mut entries = Vec::with_capacity(signatures.len());
+    let mut blocks: Vec<(usize, Vec<u8>)> = Vec::new();
+
+    for sig in signatures {
+        let bytes = write_tag(&profile.tags[&sig], TagSignature(sig), version)?;
+
+        let shared = blocks.iter().find(|(_, data)| *data == bytes);
+        let tag_offset = match shared {
+            Some((existing, _)) => *existing,
+            None => {
+                let tag_offset = offset;
+                offset = align4(offset + bytes.len());
+                blocks.push((tag_offset, bytes.clone()));
+                tag_offset
+            }
+        };
+        entries.push((sig, tag_offset, bytes.len()));
+    }
+
+    let total_size = offset;
+    let mut header = profile.header.clone();
+    header.size = total_size as u32;
+
+    let mut out = Vec::with_capacity(total_size);
+    out.extend_from_slice(&header.to_bytes());
+    out.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+    for (sig, tag_offset, size) in &entries {
+        out.extend_from_slice(&sig.to_be_bytes());
+        out.extend_from_slice(&(*tag_offset as u32).to_be_bytes());
+        out.extend_from_slice(&(*size as u32).to_be_bytes());
+    }
+    for (_, data) in &blocks {
+        out.extend_from_slice(data);
+        pad4(&mut out);
+    }
+
+    debug_assert_eq!(out.len(), total_size);
+    Ok(out)
+}
+
+/// Serialize a single tag (type signature, reserved bytes and data)
+///
+/// The tag signature and profile version select the text encoding: v4
+/// profiles use `mluc`, v2 profiles use `desc` for description tags and
+/// `text` otherwise.
+pub fn write_tag(
+    tag: &TagData,
+    sig: TagSignature,
+    version: ProfileVersion,
+) -> Result<Vec<u8>, IccError> {
+    let mut out = Vec::new();
+
+    match tag {
+        TagData::Xyz(xyz) => {
+            type_header(&mut out, TypeSignature::XYZ);
+            for value in &xyz.values {
+                out.extend_from_slice(&value.to_bytes());
+            }
+        }
+        TagData::Curve(curve) => write_curv(&mut out, curve),
+        TagData::ParametricCurve(para) => write_para_data(&mut out, para),
+        TagData::Text(text) => match text_type(sig, version) {
+            TypeSignature::MLUC => write_mluc(&mut out, text),
+            TypeSignature::DESC => write_desc(&mut out, text),
+            _ => write_text(&mut out, text),
+        },
+        TagData::MultiLocalizedUnicode(text) => write_mluc(&mut out, text),
+        TagData::Lut8(lut) => write_lut8(&mut out, lut)?,
+        TagData::Lut16(lut) => write_lut16(&mut out, lut)?,
+        TagData::LutAToB(lut) => write_lut_atob(&mut out, lut)?,
+        TagData::LutBToA(lut) => write_lut_btoa(&mut out, lut)?,
+        TagData::ChromaticAdaptation(matrix) => {
+            type_header(&mut out, TypeSignature::SF32);
+            for row in matrix {
+                for &v in row {
+                    push_s15(&mut out, v);
+                }
+            }
+        }
+        TagData::Cicp(cicp) => write_cicp(&mut out, cicp),
+        TagData::Vcgt(vcgt) => write_vcgt(&mut out, vcgt)?,
+        TagData::Dict(dict) => write_dict(&mut out, dict)?,
+        TagData::Unknown { data, .. } => out.extend_from_slice(data),
+    }
+
+    Ok(out)
+}
+
+// ============================================================================
+// Primitives
+// ============================================================================
+
+fn align4(n: usize) -> usize {
+    (n + 3) & !3
+}
+
+fn pad4(out: &mut Vec<u8>) {
+    out.resize(align4(out.len()), 0);
+}
+
+fn type_header(out: &mut Vec<u8>, type_sig: TypeSignature) {
+    out.extend_from_slice(&type_sig.0.to_be_bytes());
+    out.extend_from_slice(&[0u8; 4]);
+}
+
+fn push_u16(out: &mut Vec<u8>, v: u16) {
+    out.extend_from_slice(&v.to_be_bytes());
+}
+
+fn push_u32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_be_bytes());
+}
+
+fn push_s15(out: &mut Vec<u8>, v: f64) {
+    out.extend_from_slice(&S15Fixed16::from_f64(v).to_be_bytes());
+}
+
+/// Quantize a normalized value to u16
+fn to_u16(v: f64) -> u16 {
+    (v.clamp(0.0, 1.0) * 65535.0).round() as u16
+}
+
+/// Convert a length to u32, rejecting values the format cannot hold
+fn to_u32(n: usize, what: &str) -> Result<u32, IccError> {
+    u32::try_from(n).map_err(|_| IccError::Unsupported(format!("{} too large: {}", what, n)))
+}
+
+/// Type a plain text tag is written as
+///
+/// v4 uses mluc everywhere except `targ`; v2 uses desc for description
+/// tags and text otherwise.
+pub(super) fn text_type(sig: TagSignature, version: ProfileVersion) -> TypeSignature {
+    if version.major >= 4 && sig != TagSignature::CHAR_TARGET {
+        TypeSignature::MLUC
+    } else if is_description_tag(sig) {
+        TypeSignature::DESC
+    } else {
+        TypeSignature::TEXT
+    }
+}
+
+fn is_description_tag(sig: TagSignature) -> bool {
+    sig == TagSignature::PROFILE_DESC
+        || sig == TagSignature::DMND
+        || sig == TagSignature::DMDD
+        || sig == TagSignature::VIEW_COND_DESC
+}
+
+// ============================================================================
+// Curves
+// ============================================================================
+
+fn write_curv(out: &mut Vec<u8>, curve: &CurveData) {
+    type_header(out, TypeSignature::CURVE);
+    match curve {
+        CurveData::Identity => push_u32(out, 0),
+        CurveData::Gamma(g) => {
+            push_u32(out, 1);
+            push_u16(out, (g * 256.0).round().clamp(0.0, 65535.0) as u16);
+        }
+        CurveData::Table(table) => {
+            push_u32(out, table.len() as u32);
+            for &v in table {
+                push_u16(out, v);
+            }
+        }
+    }
+}
+
+fn write_para_data(out: &mut Vec<u8>, para: &ParametricCurveData) {
+    let (function_type, params) = para.icc_params();
+    write_para(out, function_type, &params);
+}
+
+fn write_para(out: &mut Vec<u8>, function_type: u16, params: &[f64]) {
+    type_header(out, TypeSignature::PARA);
+    push_u16(out, function_type);
+    push_u16(out, 0);
+    for &p in params {
+        push_s15(out, p);
+    }
+}
+
+fn write_segment(out: &mut Vec<u8>, segment: &CurveSegment) {
+    match segment {
+        CurveSegment::Identity => {
+            type_header(out, TypeSignature::CURVE);
+            push_u32(out, 0);
+        }
+        CurveSegment::Table(table) => {
+            type_header(out, TypeSignature::CURVE);
+            push_u32(out, table.len() as u32);
+            for &v in table {
+                push_u16(out, to_u16(v));
+            }
+        }
+        CurveSegment::Parametric { curve_type, params } => write_para(out, *curve_type, params),
+    }
+    pad4(out);
+}
+
+// ============================================================================
+// Text
+// ============================================================================
+
+fn write_text(out: &mut Vec<u8>, text: &TextData) {
+    type_header(out, TypeSignature::TEXT);
+    out.extend(text.text.chars().map(latin1_byte));
+    out.push(0);
+}
+
+fn write_desc(out: &mut Vec<u8>, text: &TextData) {
+    type_header(out, TypeSignature::DESC);
+    push_u32(out, text.text.chars().count() as u32 + 1);
+    out.extend(text.text.chars().map(latin1_byte));
+    out.push(0);
+    push_u32(out, 0); // Unicode language code
+    push_u32(out, 0); // Unicode count
+    push_u16(out, 0); // ScriptCode code
+    out.push(0); // ScriptCode count
+    out.extend_from_slice(&[0u8; 67]);
+}
+
+/// Byte for a char read back by the parser (which maps bytes to chars 1:1)
+fn latin1_byte(c: char) -> u8 {
+    u8::try_from(c).unwrap_or(b'?')
+}
+
+fn write_mluc(out: &mut Vec<u8>, text: &TextData) {
+    let records: Vec<(&str, &str)> = if text.localized.is_empty() {
+        vec![("en-US", text.text.as_str())]
+    } else {
+        text.localized
+            .iter()
+            .map(|(locale, s)| (locale.as_str(), s.as_str()))
+            .collect()
+    };
+
+    let start = out.len();
+    type_header(out, TypeSignature::MLUC);
+    push_u32(out, records.len() as u32);
+    push_u32(out, 12);
+
+    let strings: Vec<Vec<u8>> = records
+        .iter()
+        .map(|(_, s)| s.encode_utf16().flat_map(|c| c.to_be_bytes()).collect())
+        .collect();
+
+    // Offsets are relative to the start of this mluc element
+    let mut string_offset = 16 + 12 * records.len();
+    for ((locale, _), encoded) in records.iter().zip(&strings) {
+        let mut code = locale.split(['-', '_']);
+        out.extend_from_slice(&locale_code(code.next()));
+        out.extend_from_slice(&locale_code(code.next()));
+        push_u32(out, encoded.len() as u32);
+        push_u32(out, string_offset as u32);
+        string_offset += encoded.len();
+    }
+    for encoded in &strings {
+        out.extend_from_slice(encoded);
+    }
+    debug_assert_eq!(out.len() - start, string_offset);
+}
+
+/// Two-letter ISO 639 / ISO 3166 code, space-padded
+fn locale_code(code: Option<&str>) -> [u8; 2] {
+    let mut bytes = [b' '; 2];
+    for (dst, src) in bytes.iter_mut().zip(code.unwrap_or("").bytes()) {
+        *dst = src;
+    }
+    bytes
+}
+
+// ============================================================================
+// LUTs
+// ============================================================================
+
+fn write_lut_matrix(out: &mut Vec<u8>, matrix: &[[S15Fixed16; 3]; 3]) {
+    for row in matrix {
+        for v in row {
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+    }
+}
+
+fn write_lut8(out: &mut Vec<u8>, lut: &Lut8Data) -> Result<(), IccError> {
+    let curves_ok = lut
+        .input_curves
+        .iter()
+        .chain(&lut.output_curves)
+        .all(|c| c.len() == 256);
+    if !curves_ok {
+        return Err(IccError::Unsupported(
+            "Lut8 curves must have 256 entries".to_string(),
+        ));
+    }
+
+    type_header(out, TypeSignature::LUT8);
+    out.extend_from_slice(&[lut.input_channels, lut.output_channels, lut.grid_points, 0]);
+    write_lut_matrix(out, &lut.matrix);
+    for curve in &lut.input_curves {
+        out.extend_from_slice(curve);
+    }
+    out.extend_from_slice(&lut.clut);
+    for curve in &lut.output_curves {
+        out.extend_from_slice(curve);
+    }
+    Ok(())
+}
+
+fn write_lut16(out: &mut Vec<u8>, lut: &Lut16Data) -> Result<(), IccError> {
+    let input_entries = lut.input_curves.first().map_or(0, |c| c.len());
+    let output_entries = lut.output_curves.first().map_or(0, |c| c.len());
+    let entries_ok = lut.input_curves.iter().all(|c| c.len() == input_entries)
+        && lut.output_curves.iter().all(|c| c.len() == output_entries)
+        && input_entries <= u16::MAX as usize
+        && output_entries <= u16::MAX as usize;
+    if !entries_ok {
+        return Err(IccError::Unsupported(
+            "Lut16 curves must share one table size per side".to_string(),
+        ));
+    }
+
+    type_header(out, TypeSignature::LUT16);
+    out.extend_from_slice(&[lut.input_channels, lut.output_channels, lut.grid_points, 0]);
+    write_lut_matrix(out, &lut.matrix);
+    push_u16(out, input_entries as u16);
+    push_u16(out, output_entries as u16);
+    for &v in lut.input_curves.iter().flatten() {
+        push_u16(out, v);
+    }
+    for &v in &lut.clut {
+        push_u16(out, v);
+    }
+    for &v in lut.output_curves.iter().flatten() {
+        push_u16(out, v);
+    }
+    Ok(())
+}
+
+/// Elements of a lutAToB / lutBToA tag in header offset order (B, matrix, M, CLUT, A)
+struct LutElements<'a> {
+    input_channels: u8,
+    output_channels: u8,
+    b_curves: Option<&'a [CurveSegment]>,
+    matrix: Option<([[f64; 3]; 3], [f64; 3])>,
+    m_curves: Option<&'a [CurveSegment]>,
+    clut: Option<&'a LutClut>,
+    a_curves: Option<&'a [CurveSegment]>,
+}
+
+fn write_lut_atob(out: &mut Vec<u8>, lut: &LutAToBData) -> Result<(), IccError> {
+    type_header(out, TypeSignature::LUTA2B);
+    write_lut_elements(
+        out,
+        LutElements {
+            input_channels: lut.input_channels,
+            output_channels: lut.output_channels,
+            b_curves: lut.b_curves.as_deref(),
+            matrix: lut.matrix.as_ref().map(|m| (m.matrix, m.offset)),
+            m_curves: lut.m_curves.as_deref(),
+            clut: lut.clut.as_ref(),
+            a_curves: lut.a_curves.as_deref(),
+        },
+    )
+}
+
+fn write_lut_btoa(out: &mut Vec<u8>, lut: &LutBToAData) -> Result<(), IccError> {
+    type_header(out, TypeSignature::LUTB2A);
+    write_lut_elements(
+        out,
+        LutElements {
+            input_channels: lut.input_channels,
+            output_channels: lut.output_channels,
+            b_curves: lut.b_curves.as_deref(),
+            matrix: lut.matrix.as_ref().map(|m| (m.matrix, m.offset)),
+            m_curves: lut.m_curves.as_deref(),
+            clut: lut.clut.as_ref(),
+            a_curves: lut.a_curves.as_deref(),
+        },
+    )
+}
+
+fn write_lut_elements(out: &mut Vec<u8>, lut: LutElements<'_>) -> Result<(), IccError> {
+    // `out` already holds the 8-byte type header of this tag
+    let start = out.len() - 8;
+    out.extend_from_slice(&[lut.input_channels, lut.output_channels, 0, 0]);
+    let offsets_pos = out.len();
+    out.extend_from_slice(&[0u8; 20]);
+
+    let mut offsets = [0u32; 5];
+
+    if let Some(curves) = lut.b_curves {
+        offsets[0] = (out.len() - start) as u32;
+        curves.iter().for_each(|c| write_segment(out, c));
+    }
+    if let Some((matrix, offset)) = lut.matrix {
+        offsets[1] = (out.len() - start) as u32;
+        for row in &matrix {
+            for &v in row {
+                push_s15(out, v);
+            }
+        }
+        for &v in &offset {
+            push_s15(out, v);
+        }
+    }
+    if let Some(curves) = lut.m_curves {
+        offsets[2] = (out.len() - start) as u32;
+        curves.iter().for_each(|c| write_segment(out, c));
+    }
+    if let Some(clut) = lut.clut {
+        offsets[3] = (out.len() - start) as u32;
+        write_clut(out, clut)?;
+    }
+    if let Some(curves) = lut.a_curves {
+        offsets[4] = (out.len() - start) as u32;
+        curves.iter().for_each(|c| write_segment(out, c));
+    }
+
+    for (i, offset) in offsets.iter().enumerate() {
+        let pos = offsets_pos + i * 4;
+        out[pos..pos + 4].copy_from_slice(&offset.to_be_bytes());
+    }
+    Ok(())
+}
+
+fn write_clut(out: &mut Vec<u8>, clut: &LutClut) -> Result<(), IccError> {
+    if clut.grid_points.len() > 16 {
+        return Err(IccError::Unsupported(format!(
+            "CLUT with {} inputs",
+            clut.grid_points.len()
+        )));
+    }
+
+    let mut grid = [0u8; 16];
+    grid[..clut.grid_points.len()].copy_from_slice(&clut.grid_points);
+    out.extend_from_slice(&grid);
+
+    let precision = if clut.precision == 1 { 1 } else { 2 };
+    out.extend_from_slice(&[precision, 0, 0, 0]);
+    for &v in &clut.data {
+        if precision == 1 {
+            out.push((v.clamp(0.0, 1.0) * 255.0).round() as u8);
+        } else {
+            push_u16(out, to_u16(v));
+        }
+    }
+    pad4(out);
+    Ok(())
+}
+
+// ============================================================================
+// Other tag types
+// ============================================================================
+
+fn write_cicp(out: &mut Vec<u8>, cicp: &CicpData) {
+    type_header(out, TypeSignature::CICP);
+    out.extend_from_slice(&[
+        cicp.color_primaries,
+        cicp.transfer_characteristics,
+        cicp.matrix_coefficients,
+        cicp.full_range as u8,
+    ]);
+}
+
+fn write_vcgt(out: &mut Vec<u8>, vcgt: &VcgtData) -> Result<(), IccError> {
+    type_header(out, TypeSignature::VCGT);
+    match vcgt {
+        VcgtData::Table(curves) => {
+            let entries = curves.first().map_or(0, |c| c.len());
+            let valid = matches!(curves.len(), 1 | 3)
+                && entries <= u16::MAX as usize
+                && curves.iter().all(|c| c.len() == entries);
+            if !valid {
+                return Err(IccError::Unsupported(
+                    "vcgt tables must be 1 or 3 equal-length curves".to_string(),
+                ));
+            }
+            push_u32(out, 0);
+            push_u16(out, curves.len() as u16);
+            push_u16(out, entries as u16);
+            push_u16(out, 2);
+            for &v in curves.iter().flatten() {
+                push_u16(out, to_u16(v));
+            }
+        }
+        VcgtData::Formula { gamma, min, max } => {
+            push_u32(out, 1);
+            for c in 0..3 {
+                push_s15(out, gamma[c]);
+                push_s15(out, min[c]);
+                push_s15(out, max[c]);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_dict(out: &mut Vec<u8>, dict: &DictData) -> Result<(), IccError> {
+    let record_len = if dict.entries.iter().any(|e| e.display_value.is_some()) {
+        32
+    } else if dict.entries.iter().any(|e| e.display_name.is_some()) {
+        24
+    } else {
+        16
+    };
+    let fields = record_len / 8;
+
+    let start = out.len();
+    type_header(out, TypeSignature::DICT);
+    push_u32(out, to_u32(dict.entries.len(), "dict entry count")?);
+    push_u32(out, record_len as u32);
+
+    let records_pos = out.len();
+    out.resize(records_pos + dict.entries.len() * record_len, 0);
+
+    let utf16 = |s: &str| -> Vec<u8> { s.encode_utf16().flat_map(|c| c.to_be_bytes()).collect() };
+
+    for (i, entry) in dict.entries.iter().enumerate() {
+        let elements: [Option<Vec<u8>>; 4] = [
+            Some(utf16(&entry.name)),
+            entry.value.as_deref().map(utf16),
+            entry.display_name.as_ref().map(|t| {
+                let mut bytes = Vec::new();
+                write_mluc(&mut bytes, t);
+                bytes
+            }),
+            entry.display_value.as_ref().map(|t| {
+                let mut bytes = Vec::new();
+                write_mluc(&mut bytes, t);
+                bytes
+            }),
+        ];
+
+        for (field, element) in elements.iter().take(fields).enumerate() {
+            let (offset, size) = match element {
+                Some(bytes) => {
+                    // Embedded mluc elements must start on a 4-byte boundary
+                    if field >= 2 {
+                        pad4(out);
+                    }
+                    let offset = out.len() - start;
+                    out.extend_from_slice(bytes);
+                    (
+                        to_u32(offset, "dict offset")?,
+                        to_u32(bytes.len(), "dict element")?,
+                    )
+                }
+                None => (0, 0),
+            };
+            let pos = records_pos + i * record_len + field * 8;
+            out[pos..pos + 4].copy_from_slice(&offset.to_be_bytes());
+            out[pos + 4..pos + 8].copy_from_slice(&size.to_be_bytes());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icc::header::{ColorSpace, IccHeader, ProfileClass};
+    use crate::icc::tags::{DictEntry, XyzTagData};
+    use crate::icc::types::XyzNumber;
+
+    fn display_profile() -> IccProfile {
+        let header = IccHeader::new(ProfileClass::Display, ColorSpace::Rgb, ColorSpace::Xyz);
+        let mut profile = IccProfile::new(header);
+        profile.set_tag(
+            TagSignature::MEDIA_WHITE,
+            TagData::Xyz(XyzTagData {
+                values: vec![XyzNumber::from_xyz(crate::color::white_point::D50.xyz)],
+            }),
+        );
+        profile.set_tag(
+            TagSignature::PROFILE_DESC,
+            TagData::Text(TextData::new("Test RGB".to_string())),
+        );
+        for sig in [
+            TagSignature::RED_TRC,
+            TagSignature::GREEN_TRC,
+            TagSignature::BLUE_TRC,
+        ] {
+            profile.set_tag(sig, TagData::Curve(CurveData::Gamma(2.2)));
+        }
+        profile
+    }
+
+    #[test]
+    fn test_write_and_reparse() {
+        let profile = display_profile();
+        let bytes = profile.to_bytes().unwrap();
+        assert_eq!(bytes.len() % 4, 0);
+
+        let parsed = IccProfile::parse(&bytes).unwrap();
+        assert_eq!(parsed.header.device_class, ProfileClass::Display);
+        assert_eq!(parsed.tag_count(), 5);
+        assert_eq!(parsed.description(), Some("Test RGB".to_string()));
+        match parsed.red_trc() {
+            Some(CurveData::Gamma(g)) => assert!((g - 2.2).abs() < 0.01),
+            other => panic!("Expected gamma TRC, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_shared_tag_data() {
+        let bytes = display_profile().to_bytes().unwrap();
+
+        // rTRC, gTRC and bTRC point at the same data block
+        let offsets: Vec<u32> = (0..5)
+            .filter_map(|i| {
+                let e = 132 + i * 12;
+                let sig = &bytes[e..e + 4];
+                (sig.ends_with(b"TRC")).then(|| {
+                    u32::from_be_bytes([bytes[e + 4], bytes[e + 5], bytes[e + 6], bytes[e + 7]])
+                })
+            })
+            .collect();
+        assert_eq!(offsets.len(), 3);
+        assert!(offsets.iter().all(|&o| o == offsets[0]));
+    }
+
+    #[test]
+    fn test_v2_desc_encoding() {
+        let version = ProfileVersion {
+            major: 2,
+            minor: 1,
+            patch: 0,
+        };
+        let text = TagData::Text(TextData::new("sRGB".to_string()));
+        let bytes = write_tag(&text, TagSignature::PROFILE_DESC, version).unwrap();
+        assert_eq!(&bytes[0..4], b"desc");
+
+        let reparsed = TagData::parse(&bytes, TagSignature::PROFILE_DESC.0).unwrap();
+        assert_eq!(reparsed.as_text().unwrap().text, "sRGB");
+    }
+
+    #[test]
+    fn test_mluc_roundtrip() {
+        let text = TextData {
+            text: "Colour".to_string(),
+            localized: vec![
+                ("en-GB".to_string(), "Colour".to_string()),
+                ("de-DE".to_string(), "Farbe".to_string()),
+            ],
+        };
+        let mut bytes = Vec::new();
+        write_mluc(&mut bytes, &text);
+
+        let reparsed = TagData::parse(&bytes, TagSignature::COPYRIGHT.0).unwrap();
+        let reparsed = reparsed.as_text().unwrap();
+        assert_eq!(reparsed.text, "Colour");
+        assert_eq!(reparsed.get_locale("de"), Some("Farbe"));
+    }
+
+    #[test]
+    fn test_dict_roundtrip() {
+        let mut dict = DictData::default();
+        dict.insert(DictEntry::new("ARGYLL_CREATED", "yes"));
+        dict.insert(DictEntry {
+            name: "EDID_model".to_string(),
+            value: Some("Display X".to_string()),
+            display_name: Some(TextData {
+                text: "Model".to_string(),
+                localized: vec![("en-US".to_string(), "Model".to_string())],
+            }),
+            display_value: None,
+        });
+
+        let bytes = write_tag(
+            &TagData::Dict(dict.clone()),
+            TagSignature::METADATA,
+            ProfileVersion::default(),
+        )
+        .unwrap();
+        let reparsed = TagData::parse(&bytes, TagSignature::METADATA.0).unwrap();
+        assert_eq!(reparsed.as_dict(), Some(&dict));
+    }
+
+    #[test]
+    fn test_lut_atob_roundtrip() {
+        let lut = LutAToBData {
+            input_channels: 3,
+            output_channels: 3,
+            b_curves: Some(vec![CurveSegment::Identity; 3]),
+            matrix: None,
+            m_curves: None,
+            clut: Some(LutClut {
+                grid_points: vec![2, 2, 2],
+                precision: 2,
+                data: (0..24).map(|i| (i % 2) as f64).collect(),
+                output_channels: 3,
+            }),
+            a_curves: Some(vec![
+                CurveSegment::Parametric {
+                    curve_type: 0,
+                    params: vec![2.2],
+                };
+                3
+            ]),
+        };
+
+        let bytes = write_tag(
+            &TagData::LutAToB(lut),
+            TagSignature::A2B0,
+            ProfileVersion::default(),
+        )
+        .unwrap();
+        let reparsed = TagData::parse(&bytes, TagSignature::A2B0.0).unwrap();
+        let reparsed = reparsed.as_lut_a2b().unwrap();
+        assert_eq!(reparsed.clut.as_ref().unwrap().grid_points, vec![2, 2, 2]);
+        assert_eq!(reparsed.clut.as_ref().unwrap().data[1], 1.0);
+        assert_eq!(reparsed.a_curves.as_ref().unwrap().len(), 3);
+        assert!(reparsed.matrix.is_none());
+    }
+}
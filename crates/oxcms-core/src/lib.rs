@@ -47,6 +47,7 @@
 
 // Native oxcms modules (Phase 2 implementation)
 pub mod color;
+pub mod gamut_boundary;
 pub mod icc;
 pub mod math;
 pub mod pipeline;
@@ -3,9 +3,15 @@
 //! Chromatic adaptation transforms convert colors from one white point to another.
 //! The most commonly used method is Bradford, which is the ICC default.
 //!
+//! All methods are von Kries-style: scale the cone (or sharpened) responses
+//! by the ratio of the two whites. A degree of adaptation D < 1 blends the
+//! gains toward 1 for incomplete adaptation, as in CIECAM02.
+//!
 //! References:
 //! - ICC.1:2022 Annex E
 //! - Lindbloom: http://www.brucelindbloom.com/index.html?Eqn_ChromAdapt.html
+//! - Bianco & Schettini, Two new von Kries based chromatic adaptation transforms
+//!   found by numerical optimization (2010)
 
 use crate::color::{WhitePoint, Xyz};
 use crate::math::Matrix3x3;
@@ -20,6 +26,16 @@ pub enum ChromaticAdaptationMethod {
     VonKries,
     /// XYZ Scaling (simple but less accurate)
     XyzScaling,
+    /// CAT02, the CIECAM02 transform
+    Cat02,
+    /// CAT16, the CAM16 transform
+    Cat16,
+    /// Sharp (Finlayson & Süsstrunk sharpened sensors)
+    Sharp,
+    /// CMCCAT2000 (linear form, full adaptation by default)
+    Cmccat2000,
+    /// Bianco–Schettini, optimized for corresponding-color data
+    BiancoSchettini,
     /// No adaptation (identity)
     None,
 }
@@ -56,11 +72,51 @@ const VON_KRIES_LMS_TO_XYZ: Matrix3x3 = Matrix3x3::new([
     [0.0000000, 0.0000000, 1.0890636],
 ]);
 
+/// CAT02 matrix (CIECAM02): XYZ → sharpened RGB
+pub(crate) const CAT02_XYZ_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [0.7328, 0.4296, -0.1624],
+    [-0.7036, 1.6975, 0.0061],
+    [0.0030, 0.0136, 0.9834],
+]);
+
+/// CAT16 matrix (CAM16): XYZ → sharpened RGB
+pub(crate) const CAT16_XYZ_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [0.401288, 0.650173, -0.051461],
+    [-0.250268, 1.204414, 0.045854],
+    [-0.002079, 0.048952, 0.953127],
+]);
+
+/// Sharp matrix: XYZ → sharpened RGB
+const SHARP_XYZ_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [1.2694, -0.0988, -0.1706],
+    [-0.8364, 1.8006, 0.0357],
+    [0.0297, -0.0315, 1.0018],
+]);
+
+/// CMCCAT2000 matrix: XYZ → sharpened RGB
+const CMCCAT2000_XYZ_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [0.7982, 0.3389, -0.1371],
+    [-0.5918, 1.5512, 0.0406],
+    [0.0008, 0.0239, 0.9753],
+]);
+
+/// Bianco–Schettini matrix: XYZ → sharpened RGB
+const BIANCO_SCHETTINI_XYZ_TO_LMS: Matrix3x3 = Matrix3x3::new([
+    [0.8752, 0.2787, -0.1539],
+    [-0.8904, 1.8709, 0.0195],
+    [-0.0061, 0.0162, 0.9899],
+]);
+
 /// Get the XYZ to LMS matrix for a given method
 fn xyz_to_lms_matrix(method: ChromaticAdaptationMethod) -> Matrix3x3 {
     match method {
         ChromaticAdaptationMethod::Bradford => BRADFORD_XYZ_TO_LMS,
         ChromaticAdaptationMethod::VonKries => VON_KRIES_XYZ_TO_LMS,
+        ChromaticAdaptationMethod::Cat02 => CAT02_XYZ_TO_LMS,
+        ChromaticAdaptationMethod::Cat16 => CAT16_XYZ_TO_LMS,
+        ChromaticAdaptationMethod::Sharp => SHARP_XYZ_TO_LMS,
+        ChromaticAdaptationMethod::Cmccat2000 => CMCCAT2000_XYZ_TO_LMS,
+        ChromaticAdaptationMethod::BiancoSchettini => BIANCO_SCHETTINI_XYZ_TO_LMS,
         ChromaticAdaptationMethod::XyzScaling | ChromaticAdaptationMethod::None => {
             Matrix3x3::identity()
         }
@@ -75,9 +131,20 @@ fn lms_to_xyz_matrix(method: ChromaticAdaptationMethod) -> Matrix3x3 {
         ChromaticAdaptationMethod::XyzScaling | ChromaticAdaptationMethod::None => {
             Matrix3x3::identity()
         }
+        _ => xyz_to_lms_matrix(method)
+            .inverse()
+            .unwrap_or_else(Matrix3x3::identity),
     }
 }
 
+/// Degree of adaptation D from the adapting luminance, as in CIECAM02
+///
+/// `surround_factor` is F: 1.0 for an average surround, 0.9 dim, 0.8 dark.
+pub fn degree_of_adaptation(adapting_luminance: f64, surround_factor: f64) -> f64 {
+    let d = surround_factor * (1.0 - (1.0 / 3.6) * ((-adapting_luminance - 42.0) / 92.0).exp());
+    d.clamp(0.0, 1.0)
+}
+
 /// Compute the chromatic adaptation matrix for converting from one white point to another
 ///
 /// The returned matrix M can be used as: XYZ_dest = M × XYZ_src
@@ -90,6 +157,19 @@ pub fn adaptation_matrix(
     src_white: &WhitePoint,
     dst_white: &WhitePoint,
     method: ChromaticAdaptationMethod,
+) -> Matrix3x3 {
+    partial_adaptation_matrix(src_white, dst_white, method, 1.0)
+}
+
+/// Compute an adaptation matrix for a degree of adaptation `degree` (D)
+///
+/// D = 1 is complete adaptation to `dst_white`, D = 0 leaves colors
+/// unchanged; each channel gain is D·dst/src + (1 − D).
+pub fn partial_adaptation_matrix(
+    src_white: &WhitePoint,
+    dst_white: &WhitePoint,
+    method: ChromaticAdaptationMethod,
+    degree: f64,
 ) -> Matrix3x3 {
     if method == ChromaticAdaptationMethod::None {
         return Matrix3x3::identity();
@@ -99,45 +179,23 @@ pub fn adaptation_matrix(
     let m_a = xyz_to_lms_matrix(method);
     let m_a_inv = lms_to_xyz_matrix(method);
 
-    // Convert white points to LMS
+    // Convert white points to LMS (identity for XYZ scaling)
     let src_lms = m_a.multiply_vec(src_white.xyz.to_array());
     let dst_lms = m_a.multiply_vec(dst_white.xyz.to_array());
 
     // Build the diagonal scaling matrix
-    let scale = if method == ChromaticAdaptationMethod::XyzScaling {
-        // For XYZ scaling, use XYZ directly
-        Matrix3x3::diagonal(
-            dst_white.xyz.x / src_white.xyz.x,
-            dst_white.xyz.y / src_white.xyz.y,
-            dst_white.xyz.z / src_white.xyz.z,
-        )
-    } else {
-        // For Bradford/Von Kries, scale in LMS space
-        Matrix3x3::diagonal(
-            if src_lms[0].abs() > 1e-10 {
-                dst_lms[0] / src_lms[0]
-            } else {
-                1.0
-            },
-            if src_lms[1].abs() > 1e-10 {
-                dst_lms[1] / src_lms[1]
-            } else {
-                1.0
-            },
-            if src_lms[2].abs() > 1e-10 {
-                dst_lms[2] / src_lms[2]
-            } else {
-                1.0
-            },
-        )
+    let gain = |c: usize| {
+        let full = if src_lms[c].abs() > 1e-10 {
+            dst_lms[c] / src_lms[c]
+        } else {
+            1.0
+        };
+        degree * full + (1.0 - degree)
     };
+    let scale = Matrix3x3::diagonal(gain(0), gain(1), gain(2));
 
-    if method == ChromaticAdaptationMethod::XyzScaling {
-        scale
-    } else {
-        // M = M_A^-1 × Scale × M_A
-        m_a_inv.multiply(&scale.multiply(&m_a))
-    }
+    // M = M_A^-1 × Scale × M_A
+    m_a_inv.multiply(&scale.multiply(&m_a))
 }
 
 /// Compute the Bradford adaptation matrix (convenience function)
@@ -256,6 +314,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_all_methods_map_white() {
+        for method in [
+            ChromaticAdaptationMethod::Bradford,
+            ChromaticAdaptationMethod::VonKries,
+            ChromaticAdaptationMethod::XyzScaling,
+            ChromaticAdaptationMethod::Cat02,
+            ChromaticAdaptationMethod::Cat16,
+            ChromaticAdaptationMethod::Sharp,
+            ChromaticAdaptationMethod::Cmccat2000,
+            ChromaticAdaptationMethod::BiancoSchettini,
+        ] {
+            let adapted = adapt_xyz(D65.xyz, &D65, &D50, method);
+            assert!(
+                adapted.approx_eq(&D50.xyz, 1e-4),
+                "{:?}: {:?}",
+                method,
+                adapted
+            );
+        }
+    }
+
+    #[test]
+    fn test_partial_adaptation() {
+        let method = ChromaticAdaptationMethod::Cat02;
+        let none = partial_adaptation_matrix(&D65, &D50, method, 0.0);
+        assert!(none.is_identity(1e-9));
+
+        // Half adaptation lands between the two whites
+        let half = partial_adaptation_matrix(&D65, &D50, method, 0.5);
+        let white = half.multiply_vec(D65.xyz.to_array());
+        assert!(white[2] < D65.xyz.z && white[2] > D50.xyz.z);
+
+        assert!((degree_of_adaptation(318.31, 1.0) - 0.9944).abs() < 1e-3);
+        assert_eq!(degree_of_adaptation(0.0, 0.0), 0.0);
+    }
+
     #[test]
     fn test_xyz_scaling() {
         let matrix = adaptation_matrix(&D65, &D50, ChromaticAdaptationMethod::XyzScaling);
@@ -0,0 +1,457 @@
+//! Sampled Tone Curves
+//!
+//! [`SampledCurve`] is a 1D table evaluated by linear interpolation. Its
+//! inverse is the exact inverse of that piecewise-linear curve, found
+//! through a reversed table built once per curve: the output range is split
+//! into equal buckets, each listing the segments that reach it, so
+//! inverting a sample is a short scan instead of a search over the whole
+//! table. Curves that are only evaluated forwards never build one.
+//!
+//! Curves need not be monotonic. As in lcms2's `cmsReverseToneCurveEx`, an
+//! overall ascending curve (first sample below the last) is inverted through
+//! the highest segment containing the value and a descending one through the
+//! lowest; a flat segment inverts to its upper end on ascending curves and
+//! its lower end on descending ones. Values outside the range of the curve
+//! are clamped to it, and non-finite samples are ignored.
+
+use std::sync::OnceLock;
+
+/// Default number of buckets in a reversed table
+pub const DEFAULT_REVERSE_SIZE: usize = 4096;
+
+/// Tone curve sampled at uniform inputs over [0, 1]
+#[derive(Debug, Clone)]
+pub struct SampledCurve {
+    values: Vec<f64>,
+    reverse_size: usize,
+    /// Built on the first inversion, or by [`SampledCurve::with_reversed_table`]
+    reversed: OnceLock<Box<ReversedTable>>,
+}
+
+impl SampledCurve {
+    /// Create a curve from samples
+    pub fn new(values: Vec<f64>) -> Self {
+        Self {
+            values,
+            reverse_size: DEFAULT_REVERSE_SIZE,
+            reversed: OnceLock::new(),
+        }
+    }
+
+    /// Use `size` buckets in the reversed table
+    ///
+    /// Larger tables make inversion faster on long curves at the cost of
+    /// memory; the result is the same for every size.
+    pub fn with_reverse_size(mut self, size: usize) -> Self {
+        let size = size.max(1);
+        if size != self.reverse_size {
+            self.reverse_size = size;
+            self.reversed = OnceLock::new();
+        }
+        self
+    }
+
+    /// Build the reversed table now instead of on the first inversion
+    pub fn with_reversed_table(self) -> Self {
+        self.reversed_table();
+        self
+    }
+
+    /// Curve samples
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Number of samples
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the curve has no samples
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Whether every sample is within `tolerance` of the straight ramp
+    ///
+    /// A curve with no samples counts as identity, one with a single sample
+    /// does not.
+    pub fn is_identity(&self, tolerance: f64) -> bool {
+        if self.values.len() < 2 {
+            return self.values.is_empty();
+        }
+        let last = (self.values.len() - 1) as f64;
+        self.values
+            .iter()
+            .enumerate()
+            .all(|(i, &v)| (v - i as f64 / last).abs() <= tolerance)
+    }
+
+    /// Number of buckets in the reversed table
+    pub fn reverse_size(&self) -> usize {
+        self.reverse_size
+    }
+
+    /// Whether the reversed table has been built
+    pub fn has_reversed_table(&self) -> bool {
+        self.reversed.get().is_some()
+    }
+
+    /// Evaluate the curve at `x` in [0, 1]
+    ///
+    /// An empty curve is the identity and a single sample is a constant.
+    pub fn eval(&self, x: f64) -> f64 {
+        let x = x.clamp(0.0, 1.0);
+        let table = &self.values;
+        match table.len() {
+            0 => return x,
+            1 => return table[0],
+            _ => {}
+        }
+
+        let pos = x * (table.len() - 1) as f64;
+        let idx = pos.floor() as usize;
+        if idx >= table.len() - 1 {
+            return table[table.len() - 1];
+        }
+        let frac = pos - idx as f64;
+        table[idx] + frac * (table[idx + 1] - table[idx])
+    }
+
+    /// Input at which the curve reaches `y`
+    ///
+    /// Curves with fewer than two samples, or no finite ones, are inverted
+    /// as the identity.
+    pub fn eval_inverse(&self, y: f64) -> f64 {
+        let table = &self.values;
+        if table.len() < 2 {
+            return y;
+        }
+        let reversed = self.reversed_table();
+        if reversed.min > reversed.max {
+            return y;
+        }
+        let y = y.clamp(reversed.min, reversed.max);
+        let last = (table.len() - 1) as f64;
+        for &segment in reversed.candidates(y) {
+            let j = segment as usize;
+            if let Some(x) = solve_segment(table[j], table[j + 1], j, last, y, reversed.ascending) {
+                return x;
+            }
+        }
+        // Bucket edges are rounded; fall back to the full scan
+        invert_samples(table, y)
+    }
+
+    fn reversed_table(&self) -> &ReversedTable {
+        self.reversed
+            .get_or_init(|| Box::new(ReversedTable::new(&self.values, self.reverse_size)))
+    }
+}
+
+impl From<Vec<f64>> for SampledCurve {
+    fn from(values: Vec<f64>) -> Self {
+        Self::new(values)
+    }
+}
+
+/// Invert a sampled curve at `y` without a reversed table
+///
+/// Gives the same result as [`SampledCurve::eval_inverse`] by scanning every
+/// segment, so it suits one-off evaluations. Tables with fewer than two
+/// samples, or no finite ones, are inverted as the identity.
+pub fn invert_samples(table: &[f64], y: f64) -> f64 {
+    invert_with(table.len(), |i| table[i], y)
+}
+
+/// [`invert_samples`] over `len` samples read through `sample`
+///
+/// Lets integer tables be inverted in place, without normalizing a copy.
+pub(crate) fn invert_with(len: usize, sample: impl Fn(usize) -> f64, y: f64) -> f64 {
+    if len < 2 {
+        return y;
+    }
+    let Some((min, max)) = range((0..len).map(&sample)) else {
+        return y;
+    };
+    let y = y.clamp(min, max);
+    let ascending = sample(0) < sample(len - 1);
+    let last = (len - 1) as f64;
+    let solve = |j: usize| solve_segment(sample(j), sample(j + 1), j, last, y, ascending);
+    let found = if ascending {
+        (0..len - 1).rev().find_map(solve)
+    } else {
+        (0..len - 1).find_map(solve)
+    };
+    // Every value in range is reached unless non-finite samples break the curve
+    found.unwrap_or(0.0)
+}
+
+/// [`invert_with`] over samples that never change direction, by bisection
+///
+/// The caller checks that the samples are monotonic; the result is then the
+/// same as [`invert_with`]'s but found in O(log n).
+pub(crate) fn invert_monotonic(len: usize, sample: impl Fn(usize) -> f64, y: f64) -> f64 {
+    if len < 2 || sample(0) == sample(len - 1) {
+        return invert_with(len, sample, y);
+    }
+    let ascending = sample(0) < sample(len - 1);
+    let (min, max) = min_max(sample(0), sample(len - 1));
+    let y = y.clamp(min, max);
+
+    // Count the samples before the first one past `y` in the curve's direction
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let before = if ascending {
+            sample(mid) <= y
+        } else {
+            sample(mid) > y
+        };
+        if before {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    // Highest segment reaching `y` when ascending, lowest when descending
+    let j = lo.clamp(1, len - 1) - 1;
+    let last = (len - 1) as f64;
+    solve_segment(sample(j), sample(j + 1), j, last, y, ascending).unwrap_or(0.0)
+}
+
+/// Segments of a curve listed by the output bucket they reach
+///
+/// Each bucket lists, in the order they are preferred, the segments that
+/// reach it up to and including the first one spanning the whole bucket.
+/// Later segments could never be chosen there, so every segment appears in
+/// at most two partially covered buckets besides those it spans, and the
+/// table stays linear in the curve and bucket count.
+#[derive(Debug, Clone)]
+struct ReversedTable {
+    min: f64,
+    max: f64,
+    /// Buckets per unit of output
+    scale: f64,
+    ascending: bool,
+    /// Start of each bucket's list in `segments`, plus the end
+    offsets: Vec<u32>,
+    segments: Vec<u32>,
+}
+
+impl ReversedTable {
+    fn new(table: &[f64], size: usize) -> Self {
+        let size = size.max(1);
+        // An empty range (min > max) marks a curve with no finite samples
+        let (min, max) = range(table.iter().copied()).unwrap_or((1.0, 0.0));
+        let scale = if max > min {
+            size as f64 / (max - min)
+        } else {
+            0.0
+        };
+        let ascending = table.first() < table.last();
+        let mut reversed = Self {
+            min,
+            max,
+            scale,
+            ascending,
+            offsets: vec![0; size + 1],
+            segments: Vec::new(),
+        };
+        if table.len() < 2 || min > max {
+            return reversed;
+        }
+
+        // Visit segments in order of preference, skipping buckets that an
+        // earlier segment already spans
+        let mut next_open: Vec<usize> = (0..=size).collect();
+        let segments = table.len() - 1;
+        let order: Box<dyn Iterator<Item = usize>> = if ascending {
+            Box::new((0..segments).rev())
+        } else {
+            Box::new(0..segments)
+        };
+        let mut entries: Vec<(u32, u32)> = Vec::new();
+        for j in order {
+            let (lo, hi) = min_max(table[j], table[j + 1]);
+            if !(lo.is_finite() && hi.is_finite()) {
+                continue;
+            }
+            let last = reversed.bucket(hi);
+            let mut b = next_open_bucket(&mut next_open, reversed.bucket(lo));
+            while b <= last {
+                entries.push((b as u32, j as u32));
+                if lo <= reversed.edge(b) && hi >= reversed.edge(b + 1) {
+                    next_open[b] = b + 1;
+                }
+                b = next_open_bucket(&mut next_open, b + 1);
+            }
+        }
+
+        // Stable sort keeps the preference order within each bucket
+        entries.sort_by_key(|&(b, _)| b);
+        for &(b, _) in &entries {
+            reversed.offsets[b as usize + 1] += 1;
+        }
+        for b in 0..size {
+            reversed.offsets[b + 1] += reversed.offsets[b];
+        }
+        reversed.segments = entries.into_iter().map(|(_, j)| j).collect();
+        reversed
+    }
+
+    fn size(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn bucket(&self, y: f64) -> usize {
+        (((y - self.min) * self.scale).max(0.0) as usize).min(self.size() - 1)
+    }
+
+    /// Lower output edge of bucket `b`
+    fn edge(&self, b: usize) -> f64 {
+        if b >= self.size() || self.scale == 0.0 {
+            return self.max;
+        }
+        self.min + b as f64 / self.scale
+    }
+
+    fn candidates(&self, y: f64) -> &[u32] {
+        let b = self.bucket(y);
+        &self.segments[self.offsets[b] as usize..self.offsets[b + 1] as usize]
+    }
+}
+
+/// First bucket from `b` not yet spanned, with path halving
+fn next_open_bucket(next_open: &mut [usize], mut b: usize) -> usize {
+    while next_open[b] != b {
+        next_open[b] = next_open[next_open[b]];
+        b = next_open[b];
+    }
+    b
+}
+
+/// Input in segment `j`, from `y0` to `y1`, at which the curve reaches `y`
+///
+/// `last` is the index of the last sample. Segments with a non-finite end
+/// never match.
+fn solve_segment(y0: f64, y1: f64, j: usize, last: f64, y: f64, ascending: bool) -> Option<f64> {
+    let (lo, hi) = min_max(y0, y1);
+    if !(lo.is_finite() && hi.is_finite() && lo <= y && y <= hi) {
+        return None;
+    }
+    if y0 == y1 {
+        let end = if ascending { j + 1 } else { j };
+        return Some(end as f64 / last);
+    }
+    Some((j as f64 + (y - y0) / (y1 - y0)) / last)
+}
+
+/// Smallest and largest finite value, if there is one
+fn range(values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    values
+        .filter(|v| v.is_finite())
+        .fold(None, |acc, v| match acc {
+            None => Some((v, v)),
+            Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+        })
+}
+
+fn min_max(a: f64, b: f64) -> (f64, f64) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_inverse() {
+        // Gamma 2.2 is steep near black, where a sampled inverse is worst
+        let table: Vec<f64> = (0..1024).map(|i| (i as f64 / 1023.0).powf(2.2)).collect();
+        let curve = SampledCurve::new(table);
+        for i in 0..=4096 {
+            let x = i as f64 / 4096.0;
+            let roundtrip = curve.eval_inverse(curve.eval(x));
+            assert!((roundtrip - x).abs() < 1e-12, "{}: {}", x, roundtrip);
+        }
+    }
+
+    #[test]
+    fn test_size_does_not_change_result() {
+        let table: Vec<f64> = (0..256)
+            .map(|i| {
+                let x = i as f64 / 255.0;
+                x * x + 0.1 * (12.0 * x).sin()
+            })
+            .collect();
+        let reference = SampledCurve::new(table.clone());
+        for size in [1, 7, 256, 65536] {
+            let curve = SampledCurve::new(table.clone()).with_reverse_size(size);
+            assert_eq!(curve.reverse_size(), size);
+            for i in 0..=1000 {
+                let y = i as f64 / 1000.0 * 1.2 - 0.1;
+                assert_eq!(curve.eval_inverse(y), reference.eval_inverse(y));
+                assert_eq!(curve.eval_inverse(y), invert_samples(&table, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_monotonic() {
+        // Rises to 0.8, dips to 0.4, then rises to 1: the last branch wins
+        let curve = SampledCurve::new(vec![0.0, 0.8, 0.4, 1.0]);
+        assert!((curve.eval_inverse(0.6) - (2.0 + 1.0 / 3.0) / 3.0).abs() < 1e-12);
+        assert!((curve.eval_inverse(0.2) - 0.25 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_flat_and_descending() {
+        // Flat segments invert to their upper end on ascending curves
+        let curve = SampledCurve::new(vec![0.0, 0.5, 0.5, 1.0]);
+        assert_eq!(curve.eval_inverse(0.5), 2.0 / 3.0);
+
+        // ... and their lower end on descending ones
+        let curve = SampledCurve::new(vec![1.0, 0.5, 0.5, 0.0]);
+        assert_eq!(curve.eval_inverse(0.5), 1.0 / 3.0);
+        assert!((curve.eval_inverse(0.25) - 2.5 / 3.0).abs() < 1e-12);
+
+        // Outside the range of the curve values are clamped
+        let curve = SampledCurve::new(vec![0.2, 0.9]);
+        assert_eq!(curve.eval_inverse(0.0), 0.0);
+        assert_eq!(curve.eval_inverse(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_non_finite_samples() {
+        // No finite samples: inverted as the identity instead of panicking
+        let curve = SampledCurve::new(vec![f64::NAN, f64::NAN]);
+        assert_eq!(curve.eval_inverse(0.5), 0.5);
+        assert_eq!(invert_samples(&[f64::NAN, f64::INFINITY], 0.5), 0.5);
+
+        // Segments touching a NaN are skipped
+        let table = vec![0.0, 0.5, f64::NAN, 0.75, 1.0];
+        let curve = SampledCurve::new(table.clone());
+        for y in [0.25, 0.8, 1.0] {
+            let x = curve.eval_inverse(y);
+            assert!(x.is_finite(), "{}: {}", y, x);
+            assert_eq!(x, invert_samples(&table, y));
+        }
+        assert!((curve.eval_inverse(0.25) - 0.125).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_reversed_table_built_on_demand() {
+        let curve = SampledCurve::new(vec![0.0, 0.25, 1.0]).with_reverse_size(16);
+        assert!(!curve.has_reversed_table());
+        curve.eval(0.5);
+        assert!(!curve.has_reversed_table());
+        curve.eval_inverse(0.5);
+        assert!(curve.has_reversed_table());
+        assert!(
+            SampledCurve::new(vec![0.0, 1.0])
+                .with_reversed_table()
+                .has_reversed_table()
+        );
+    }
+}
@@ -4,6 +4,7 @@
 //! - sRGB gamma encode/decode
 //! - ICC parametric curve types 0-4
 //! - General gamma power functions
+//! - Analytic CICP transfer functions (sRGB, BT.709, PQ, HLG, ...)
 
 /// sRGB gamma decode (encoded → linear)
 ///
@@ -368,6 +369,193 @@ pub fn build_curve_lut_inverse(curve: &ParametricCurve, size: usize) -> Vec<f64>
     lut
 }
 
+// ============================================================================
+// Analytic transfer functions (CICP / ITU-T H.273)
+// ============================================================================
+
+/// Transfer function described by a CICP transfer characteristics code
+///
+/// These are evaluated analytically rather than through a sampled TRC table,
+/// which matters for HDR curves (PQ, HLG) where a 1024-entry table loses
+/// precision near black.
+///
+/// Linear values are relative: for PQ, 1.0 corresponds to 10000 cd/m²;
+/// for HLG, 1.0 is the nominal peak after the reference OOTF (system gamma 1.2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferFunction {
+    /// Rec. ITU-R BT.709 / BT.601 / BT.2020 (codes 1, 6, 14, 15)
+    Bt709,
+    /// Pure gamma 2.2 (BT.470 System M, code 4)
+    Gamma22,
+    /// Pure gamma 2.8 (BT.470 System B/G, code 5)
+    Gamma28,
+    /// SMPTE 240M (code 7)
+    Smpte240,
+    /// Linear (code 8)
+    Linear,
+    /// IEC 61966-2-1 sRGB (code 13)
+    Srgb,
+    /// SMPTE ST 2084 perceptual quantizer (code 16)
+    Pq,
+    /// SMPTE ST 428-1 (code 17)
+    Smpte428,
+    /// ARIB STD-B67 hybrid log-gamma (code 18)
+    Hlg,
+}
+
+impl TransferFunction {
+    /// Map an ITU-T H.273 transfer characteristics code
+    ///
+    /// Returns `None` for reserved, unspecified and logarithmic codes.
+    pub fn from_cicp(code: u8) -> Option<Self> {
+        match code {
+            1 | 6 | 14 | 15 => Some(Self::Bt709),
+            4 => Some(Self::Gamma22),
+            5 => Some(Self::Gamma28),
+            7 => Some(Self::Smpte240),
+            8 => Some(Self::Linear),
+            13 => Some(Self::Srgb),
+            16 => Some(Self::Pq),
+            17 => Some(Self::Smpte428),
+            18 => Some(Self::Hlg),
+            _ => None,
+        }
+    }
+
+    /// Check if this is an HDR transfer function (PQ or HLG)
+    pub fn is_hdr(&self) -> bool {
+        matches!(self, Self::Pq | Self::Hlg)
+    }
+
+    /// Decode (encoded → linear)
+    pub fn decode(&self, x: f64) -> f64 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Self::Bt709 => {
+                if x < 4.5 * BT709_BETA {
+                    x / 4.5
+                } else {
+                    ((x + BT709_ALPHA - 1.0) / BT709_ALPHA).powf(1.0 / 0.45)
+                }
+            }
+            Self::Gamma22 => x.powf(2.2),
+            Self::Gamma28 => x.powf(2.8),
+            Self::Smpte240 => {
+                if x < 4.0 * SMPTE240_BETA {
+                    x / 4.0
+                } else {
+                    ((x + SMPTE240_ALPHA - 1.0) / SMPTE240_ALPHA).powf(1.0 / 0.45)
+                }
+            }
+            Self::Linear => x,
+            Self::Srgb => srgb_gamma_decode(x),
+            Self::Pq => pq_decode(x),
+            Self::Smpte428 => x.powf(2.6) / SMPTE428_SCALE,
+            Self::Hlg => hlg_decode(x),
+        }
+    }
+
+    /// Encode (linear → encoded)
+    pub fn encode(&self, y: f64) -> f64 {
+        // Only clamp below: SMPTE 428 decodes slightly above 1.0 at full code value
+        let y = y.max(0.0);
+        let encoded = match self {
+            Self::Bt709 => {
+                if y < BT709_BETA {
+                    4.5 * y
+                } else {
+                    BT709_ALPHA * y.powf(0.45) - (BT709_ALPHA - 1.0)
+                }
+            }
+            Self::Gamma22 => y.powf(1.0 / 2.2),
+            Self::Gamma28 => y.powf(1.0 / 2.8),
+            Self::Smpte240 => {
+                if y < SMPTE240_BETA {
+                    4.0 * y
+                } else {
+                    SMPTE240_ALPHA * y.powf(0.45) - (SMPTE240_ALPHA - 1.0)
+                }
+            }
+            Self::Linear => y,
+            Self::Srgb => srgb_gamma_encode(y),
+            Self::Pq => pq_encode(y),
+            Self::Smpte428 => (SMPTE428_SCALE * y).powf(1.0 / 2.6),
+            Self::Hlg => hlg_encode(y),
+        };
+        encoded.min(1.0)
+    }
+}
+
+const BT709_ALPHA: f64 = 1.099_296_826_809_44;
+const BT709_BETA: f64 = 0.018_053_968_510_807;
+const SMPTE240_ALPHA: f64 = 1.111_572_195_921_731;
+const SMPTE240_BETA: f64 = 0.022_821_585_529_445;
+const SMPTE428_SCALE: f64 = 0.916_555_279_740_309_3;
+
+const PQ_M1: f64 = 0.159_301_757_812_5;
+const PQ_M2: f64 = 78.843_75;
+const PQ_C1: f64 = 0.835_937_5;
+const PQ_C2: f64 = 18.851_562_5;
+const PQ_C3: f64 = 18.687_5;
+
+const HLG_A: f64 = 0.178_832_77;
+const HLG_B: f64 = 0.284_668_92;
+const HLG_C: f64 = 0.559_910_73;
+const HLG_SYSTEM_GAMMA: f64 = 1.2;
+
+/// SMPTE ST 2084 (PQ) EOTF: encoded → linear (1.0 = 10000 cd/m²)
+#[inline]
+pub fn pq_decode(encoded: f64) -> f64 {
+    if encoded <= 0.0 {
+        return 0.0;
+    }
+    let p = encoded.powf(1.0 / PQ_M2);
+    let num = (p - PQ_C1).max(0.0);
+    let den = PQ_C2 - PQ_C3 * p;
+    if den <= 0.0 {
+        return 1.0;
+    }
+    (num / den).powf(1.0 / PQ_M1)
+}
+
+/// SMPTE ST 2084 (PQ) inverse EOTF: linear (1.0 = 10000 cd/m²) → encoded
+#[inline]
+pub fn pq_encode(linear: f64) -> f64 {
+    if linear <= 0.0 {
+        return 0.0;
+    }
+    let l = linear.powf(PQ_M1);
+    ((PQ_C1 + PQ_C2 * l) / (1.0 + PQ_C3 * l)).powf(PQ_M2)
+}
+
+/// ARIB STD-B67 (HLG) inverse OETF followed by the reference OOTF
+#[inline]
+pub fn hlg_decode(encoded: f64) -> f64 {
+    if encoded <= 0.0 {
+        return 0.0;
+    }
+    let scene = if encoded <= 0.5 {
+        encoded * encoded / 3.0
+    } else {
+        (((encoded - HLG_C) / HLG_A).exp() + HLG_B) / 12.0
+    };
+    scene.powf(HLG_SYSTEM_GAMMA)
+}
+
+/// Inverse of [`hlg_decode`]: inverse OOTF followed by the HLG OETF
+#[inline]
+pub fn hlg_encode(linear: f64) -> f64 {
+    if linear <= 0.0 {
+        return 0.0;
+    }
+    let scene = linear.powf(1.0 / HLG_SYSTEM_GAMMA);
+    if scene <= 1.0 / 12.0 {
+        (3.0 * scene).sqrt()
+    } else {
+        HLG_A * (12.0 * scene - HLG_B).ln() + HLG_C
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -485,6 +673,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_transfer_function_roundtrip() {
+        let all = [
+            TransferFunction::Bt709,
+            TransferFunction::Gamma22,
+            TransferFunction::Gamma28,
+            TransferFunction::Smpte240,
+            TransferFunction::Linear,
+            TransferFunction::Srgb,
+            TransferFunction::Pq,
+            TransferFunction::Smpte428,
+            TransferFunction::Hlg,
+        ];
+        for tf in all {
+            for i in 0..=255 {
+                let x = i as f64 / 255.0;
+                let roundtrip = tf.encode(tf.decode(x));
+                assert!(
+                    (roundtrip - x).abs() < 1e-6,
+                    "{:?} roundtrip failed at {}: {}",
+                    tf,
+                    x,
+                    roundtrip
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_pq_reference_points() {
+        // 100 cd/m² (SDR reference white) encodes to ~0.508
+        assert!((pq_encode(0.01) - 0.5081).abs() < 1e-3);
+        // Peak 10000 cd/m² encodes to 1.0
+        assert!((pq_encode(1.0) - 1.0).abs() < 1e-9);
+        assert!((pq_decode(1.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_transfer_from_cicp() {
+        assert_eq!(
+            TransferFunction::from_cicp(13),
+            Some(TransferFunction::Srgb)
+        );
+        assert_eq!(TransferFunction::from_cicp(16), Some(TransferFunction::Pq));
+        assert_eq!(TransferFunction::from_cicp(18), Some(TransferFunction::Hlg));
+        assert_eq!(
+            TransferFunction::from_cicp(6),
+            Some(TransferFunction::Bt709)
+        );
+        assert_eq!(TransferFunction::from_cicp(2), None);
+        assert_eq!(TransferFunction::from_cicp(0), None);
+    }
+
     #[test]
     fn test_param_count() {
         assert_eq!(ParametricCurveType::Gamma.param_count(), 1);
@@ -3,20 +3,24 @@
 //! This module provides foundational math operations used throughout oxcms:
 //! - 3x3 matrix operations for RGB↔XYZ transforms
 //! - Gamma and transfer function evaluation
-//! - Chromatic adaptation (Bradford)
+//! - Sampled tone curves with cached inverses
+//! - Chromatic adaptation (Bradford, CAT02, CAT16, ...)
 //! - Interpolation for LUT evaluation
 
 pub mod chromatic_adaptation;
+pub mod curve;
 pub mod gamma;
 pub mod interpolation;
 pub mod matrix;
 
 pub use chromatic_adaptation::{
-    ChromaticAdaptationMethod, adapt_xyz, adaptation_matrix, bradford_matrix,
+    ChromaticAdaptationMethod, adapt_xyz, adaptation_matrix, bradford_matrix, degree_of_adaptation,
+    partial_adaptation_matrix,
 };
+pub use curve::{DEFAULT_REVERSE_SIZE, SampledCurve};
 pub use gamma::{
-    ParametricCurve, ParametricCurveType, parametric_curve_eval, srgb_gamma_decode,
-    srgb_gamma_encode,
+    ParametricCurve, ParametricCurveType, TransferFunction, parametric_curve_eval,
+    srgb_gamma_decode, srgb_gamma_encode,
 };
 pub use interpolation::{lerp, tetrahedral_interp, trilinear_interp};
 pub use matrix::Matrix3x3;
@@ -0,0 +1,278 @@
+//! Display Calibration Curves
+//!
+//! Applies (or removes) the video card calibration stored in a display
+//! profile's `vcgt` tag. This lets an application emulate calibrated output
+//! when drawing into a surface whose video card LUT is not loaded.
+
+use crate::icc::{IccProfile, VcgtData};
+use crate::{Error, Result};
+
+/// Number of samples used when inverting calibration curves
+const INVERSE_SAMPLES: usize = 4096;
+
+/// Per-channel calibration curves (R, G, B)
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    curves: [Vec<f64>; 3],
+}
+
+impl Calibration {
+    /// Create from a parsed vcgt tag
+    pub fn from_vcgt(vcgt: &VcgtData) -> Self {
+        let curves = std::array::from_fn(|channel| match vcgt {
+            VcgtData::Table(tables) => tables
+                .get(channel)
+                .or_else(|| tables.first())
+                .cloned()
+                .unwrap_or_else(|| vec![0.0, 1.0]),
+            VcgtData::Formula { .. } => (0..INVERSE_SAMPLES)
+                .map(|i| vcgt.eval(channel, i as f64 / (INVERSE_SAMPLES - 1) as f64))
+                .collect(),
+        });
+        Self { curves }
+    }
+
+    /// Create from a display profile, if it carries a vcgt tag
+    pub fn from_profile(profile: &IccProfile) -> Option<Self> {
+        profile.vcgt().map(Self::from_vcgt)
+    }
+
+    /// Create an identity calibration
+    pub fn identity() -> Self {
+        Self {
+            curves: std::array::from_fn(|_| vec![0.0, 1.0]),
+        }
+    }
+
+    /// Build the inverse calibration
+    ///
+    /// Undoes calibration that is already baked into pixel values. Flat
+    /// regions of the forward curves map to their first input.
+    pub fn inverse(&self) -> Self {
+        let curves = std::array::from_fn(|channel| {
+            (0..INVERSE_SAMPLES)
+                .map(|i| {
+                    let y = i as f64 / (INVERSE_SAMPLES - 1) as f64;
+                    invert_table(&self.curves[channel], y)
+                })
+                .collect()
+        });
+        Self { curves }
+    }
+
+    /// Apply calibration to one normalized RGB value
+    pub fn apply(&self, rgb: [f64; 3]) -> [f64; 3] {
+        [
+            eval_table(&self.curves[0], rgb[0]),
+            eval_table(&self.curves[1], rgb[1]),
+            eval_table(&self.curves[2], rgb[2]),
+        ]
+    }
+
+    /// Remove calibration from one normalized RGB value
+    pub fn invert(&self, rgb: [f64; 3]) -> [f64; 3] {
+        [
+            invert_table(&self.curves[0], rgb[0]),
+            invert_table(&self.curves[1], rgb[1]),
+            invert_table(&self.curves[2], rgb[2]),
+        ]
+    }
+
+    /// Apply calibration in place to 8-bit RGB pixels
+    pub fn apply_rgb8(&self, pixels: &mut [u8]) -> Result<()> {
+        self.apply_u8(pixels, 3)
+    }
+
+    /// Apply calibration in place to 8-bit RGBA pixels (alpha preserved)
+    pub fn apply_rgba8(&self, pixels: &mut [u8]) -> Result<()> {
+        self.apply_u8(pixels, 4)
+    }
+
+    /// Apply calibration in place to 16-bit RGB pixels
+    pub fn apply_rgb16(&self, pixels: &mut [u16]) -> Result<()> {
+        self.apply_u16(pixels, 3)
+    }
+
+    /// Apply calibration in place to 16-bit RGBA pixels (alpha preserved)
+    pub fn apply_rgba16(&self, pixels: &mut [u16]) -> Result<()> {
+        self.apply_u16(pixels, 4)
+    }
+
+    /// Apply calibration in place to f32 RGB pixels
+    pub fn apply_rgb_f32(&self, pixels: &mut [f32]) -> Result<()> {
+        check_whole_pixels(pixels.len(), 3)?;
+
+        for chunk in pixels.chunks_exact_mut(3) {
+            let result = self.apply([chunk[0] as f64, chunk[1] as f64, chunk[2] as f64]);
+            chunk[0] = result[0] as f32;
+            chunk[1] = result[1] as f32;
+            chunk[2] = result[2] as f32;
+        }
+        Ok(())
+    }
+
+    fn apply_u8(&self, pixels: &mut [u8], stride: usize) -> Result<()> {
+        check_whole_pixels(pixels.len(), stride)?;
+
+        // 8-bit input has only 256 codes per channel; tabulate them once
+        let luts: [[u8; 256]; 3] = std::array::from_fn(|c| {
+            std::array::from_fn(|i| {
+                (eval_table(&self.curves[c], i as f64 / 255.0) * 255.0 + 0.5) as u8
+            })
+        });
+
+        for chunk in pixels.chunks_exact_mut(stride) {
+            for (value, lut) in chunk.iter_mut().zip(luts.iter()) {
+                *value = lut[*value as usize];
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_u16(&self, pixels: &mut [u16], stride: usize) -> Result<()> {
+        check_whole_pixels(pixels.len(), stride)?;
+
+        for chunk in pixels.chunks_exact_mut(stride) {
+            for (value, curve) in chunk.iter_mut().zip(self.curves.iter()) {
+                let v = eval_table(curve, *value as f64 / 65535.0);
+                *value = (v * 65535.0 + 0.5) as u16;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fail for buffers that end in a partial pixel
+fn check_whole_pixels(len: usize, stride: usize) -> Result<()> {
+    if len % stride == 0 {
+        Ok(())
+    } else {
+        Err(Error::BufferSize {
+            expected: len - len % stride,
+            actual: len,
+        })
+    }
+}
+
+/// Evaluate a normalized table with linear interpolation
+fn eval_table(table: &[f64], x: f64) -> f64 {
+    let x = x.clamp(0.0, 1.0);
+    if table.len() < 2 {
+        return x;
+    }
+
+    let pos = x * (table.len() - 1) as f64;
+    let idx = pos.floor() as usize;
+    if idx >= table.len() - 1 {
+        return table[table.len() - 1].clamp(0.0, 1.0);
+    }
+    let frac = pos - idx as f64;
+    (table[idx] + frac * (table[idx + 1] - table[idx])).clamp(0.0, 1.0)
+}
+
+/// Invert a normalized table, handling ascending and descending curves
+fn invert_table(table: &[f64], y: f64) -> f64 {
+    let y = y.clamp(0.0, 1.0);
+    if table.len() < 2 {
+        return y;
+    }
+
+    let n = table.len() - 1;
+    let descending = table[n] < table[0];
+
+    for i in 0..n {
+        let (v0, v1) = (table[i], table[i + 1]);
+        let (lo, hi) = if v0 <= v1 { (v0, v1) } else { (v1, v0) };
+        if y >= lo && y <= hi {
+            let t = if (v1 - v0).abs() > 1e-12 {
+                (y - v0) / (v1 - v0)
+            } else {
+                0.0
+            };
+            return (i as f64 + t) / n as f64;
+        }
+    }
+
+    // Outside the curve's range: clamp to the nearest end
+    let below_start = if descending {
+        y > table[0]
+    } else {
+        y < table[0]
+    };
+    if below_start { 0.0 } else { 1.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gamma_vcgt(g: f64) -> VcgtData {
+        VcgtData::Formula {
+            gamma: [g; 3],
+            min: [0.0; 3],
+            max: [1.0; 3],
+        }
+    }
+
+    #[test]
+    fn test_identity_calibration() {
+        let cal = Calibration::identity();
+        let mut pixels = [0u8, 64, 128, 255, 10, 20];
+        let expected = pixels;
+        cal.apply_rgb8(&mut pixels).unwrap();
+        assert_eq!(pixels, expected);
+    }
+
+    #[test]
+    fn test_apply_and_invert_roundtrip() {
+        let cal = Calibration::from_vcgt(&gamma_vcgt(1.8));
+        for i in 0..=100 {
+            let x = i as f64 / 100.0;
+            let y = cal.apply([x, x, x]);
+            assert!((y[0] - x.powf(1.8)).abs() < 1e-4);
+            let back = cal.invert(y);
+            assert!((back[1] - x).abs() < 1e-3, "x={} back={}", x, back[1]);
+        }
+    }
+
+    #[test]
+    fn test_inverse_calibration() {
+        let cal = Calibration::from_vcgt(&gamma_vcgt(2.0));
+        let inv = cal.inverse();
+
+        let mut pixels = [0u16, 16384, 32768, 65535, 49152, 8192, 1234, 60000];
+        let original = pixels;
+        cal.apply_rgba16(&mut pixels).unwrap();
+        assert_ne!(pixels, original);
+        assert_eq!(pixels[7], 60000); // alpha untouched
+        inv.apply_rgba16(&mut pixels).unwrap();
+        for (a, b) in pixels.iter().zip(original.iter()) {
+            assert!((*a as i32 - *b as i32).abs() <= 64, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_partial_pixel_rejected() {
+        let cal = Calibration::from_vcgt(&gamma_vcgt(2.0));
+        let mut pixels = [10u8, 20, 30, 40, 50];
+        assert!(matches!(
+            cal.apply_rgb8(&mut pixels),
+            Err(Error::BufferSize {
+                expected: 3,
+                actual: 5
+            })
+        ));
+        // Nothing is written when the buffer is rejected
+        assert_eq!(pixels, [10, 20, 30, 40, 50]);
+        assert!(cal.apply_rgba16(&mut [0u16; 6]).is_err());
+        assert!(cal.apply_rgb_f32(&mut [0.5f32; 4]).is_err());
+        assert!(cal.apply_rgb_f32(&mut [0.5f32; 6]).is_ok());
+    }
+
+    #[test]
+    fn test_invert_descending_table() {
+        let table = vec![1.0, 0.5, 0.0];
+        assert!((invert_table(&table, 0.75) - 0.25).abs() < 1e-9);
+        assert!((invert_table(&table, 0.0) - 1.0).abs() < 1e-9);
+    }
+}
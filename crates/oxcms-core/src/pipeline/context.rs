@@ -2,8 +2,9 @@
 //!
 //! Configuration options for color transforms.
 
+use super::gamut::GamutMapping;
 use crate::color::WhitePoint;
-use crate::math::ChromaticAdaptationMethod;
+use crate::math::{ChromaticAdaptationMethod, DEFAULT_REVERSE_SIZE};
 
 /// Rendering intent for color conversion
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -88,10 +89,23 @@ pub struct TransformContext {
     pub flags: TransformFlags,
     /// Chromatic adaptation method
     pub adaptation_method: ChromaticAdaptationMethod,
+    /// Degree of adaptation D (1 = complete, 0 = none)
+    pub adaptation_degree: f64,
     /// PCS white point (usually D50)
     pub pcs_white: WhitePoint,
     /// Gamut warning color (for gamut check mode)
     pub gamut_warning_color: [f64; 3],
+    /// Prefer the analytic transfer function from a `cicp` tag over the TRC tags
+    pub prefer_cicp_transfer: bool,
+    /// Grid size for inverting the destination A2B table when it has no B2A
+    ///
+    /// `None` keeps the strict behaviour of failing with a missing-tag error.
+    /// See [`with_a2b_inversion`](Self::with_a2b_inversion) for the limitations.
+    pub a2b_inversion_grid: Option<usize>,
+    /// How matrix-shaper outputs bring out-of-gamut colors into range
+    pub gamut_mapping: GamutMapping,
+    /// Buckets in the reversed tables built for inverting sampled curves
+    pub reverse_table_size: usize,
 }
 
 impl Default for TransformContext {
@@ -102,8 +116,13 @@ impl Default for TransformContext {
             intent: RenderIntent::default(),
             flags: TransformFlags::new(),
             adaptation_method: ChromaticAdaptationMethod::Bradford,
+            adaptation_degree: 1.0,
             pcs_white: D50,
             gamut_warning_color: [1.0, 0.0, 1.0], // Magenta
+            prefer_cicp_transfer: false,
+            a2b_inversion_grid: None,
+            gamut_mapping: GamutMapping::default(),
+            reverse_table_size: DEFAULT_REVERSE_SIZE,
         }
     }
 }
@@ -137,6 +156,45 @@ impl TransformContext {
         self.adaptation_method = method;
         self
     }
+
+    /// Set the degree of adaptation D for incomplete adaptation
+    pub fn with_adaptation_degree(mut self, degree: f64) -> Self {
+        self.adaptation_degree = degree;
+        self
+    }
+
+    /// Prefer the `cicp` transfer function (PQ, HLG, sRGB, ...) when present
+    pub fn with_cicp_transfer(mut self, enabled: bool) -> Self {
+        self.prefer_cicp_transfer = enabled;
+        self
+    }
+
+    /// Build a missing destination B2A by inverting A2B into a CLUT of this grid size
+    ///
+    /// Inverses are cached, so later pipelines for the same table, grid size
+    /// and reverse table size skip the search.
+    ///
+    /// Only the first three device channels are solved for. Any further
+    /// channels, such as K in a CMYK destination, are held at 0: there is no
+    /// black generation, so output relies on CMY alone.
+    pub fn with_a2b_inversion(mut self, grid_points: usize) -> Self {
+        self.a2b_inversion_grid = Some(grid_points);
+        self
+    }
+
+    /// Map out-of-gamut colors with `mapping` instead of clipping them
+    ///
+    /// Mapped output is in range whether or not `clamp_output` is set.
+    pub fn with_gamut_mapping(mut self, mapping: GamutMapping) -> Self {
+        self.gamut_mapping = mapping;
+        self
+    }
+
+    /// Size the reversed curve tables; larger tables invert long curves faster
+    pub fn with_reverse_table_size(mut self, size: usize) -> Self {
+        self.reverse_table_size = size;
+        self
+    }
 }
 
 #[cfg(test)]
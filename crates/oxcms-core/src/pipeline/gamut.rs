@@ -0,0 +1,421 @@
+//! Gamut Mapping
+//!
+//! Brings colors that fall outside the destination RGB gamut back inside.
+//! Clipping each channel separately (the default) shifts the hue and
+//! lightness of saturated colors, e.g. wide gamut greens turn yellowish in
+//! sRGB. The other strategies keep the hue:
+//! - [`GamutMapping::FilmlikeClip`]: moxcms' soft highlight clip, which
+//!   keeps the ratios between channels
+//! - [`GamutMapping::OklchChroma`]: the CSS Color 4 algorithm, reducing
+//!   Oklch chroma at constant lightness and hue until clipping the rest is
+//!   below one just noticeable difference
+//! - [`GamutMapping::Cam16Chroma`]: reduce CAM16 chroma at constant
+//!   lightness and hue
+//! - [`GamutMapping::Cusp`]: project towards the lightness of the gamut cusp
+//!   at that hue in Oklch (Ottosson), trading some lightness for chroma
+//!
+//! A [`GamutMapper`] works on linear destination RGB, between the output
+//! matrix and the output TRCs of a matrix-shaper pipeline. Its results are
+//! always in [0, 1], so selecting any strategy but [`GamutMapping::Clip`]
+//! clamps the output even when `clamp_output` is off.
+
+use crate::color::cam::{CamAppearance, ColorAppearanceModel, ViewingConditions};
+use crate::color::white_point::{D50, D65};
+use crate::color::{Oklch, Xyz};
+use crate::icc::IccError;
+use crate::math::{Matrix3x3, bradford_matrix};
+
+/// Just noticeable ΔEOK used by CSS Color 4
+const OKLCH_JND: f64 = 0.02;
+
+/// Oklch chroma resolution of the CSS Color 4 search
+const OKLCH_EPSILON: f64 = 1e-4;
+
+/// Bisection steps of the CAM16 and cusp searches
+const SEARCH_STEPS: usize = 24;
+
+/// Samples on each edge of the RGB cube when locating the cusps
+const CUSP_SAMPLES_PER_EDGE: usize = 128;
+
+/// Linear RGB this far outside [0, 1] still counts as in gamut
+const GAMUT_TOLERANCE: f64 = 1e-9;
+
+/// Primaries and secondaries around the hue circle (R, Y, G, C, B, M, R)
+const CUBE_EDGE_CORNERS: [[f64; 3]; 7] = [
+    [1.0, 0.0, 0.0],
+    [1.0, 1.0, 0.0],
+    [0.0, 1.0, 0.0],
+    [0.0, 1.0, 1.0],
+    [0.0, 0.0, 1.0],
+    [1.0, 0.0, 1.0],
+    [1.0, 0.0, 0.0],
+];
+
+/// Strategy for out-of-gamut colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GamutMapping {
+    /// Clip each channel to [0, 1]
+    #[default]
+    Clip,
+    /// Clip highlights while keeping channel ratios (moxcms' `filmlike_clip`)
+    FilmlikeClip,
+    /// Reduce Oklch chroma at constant lightness and hue (CSS Color 4)
+    OklchChroma,
+    /// Reduce CAM16 chroma at constant lightness and hue
+    Cam16Chroma,
+    /// Project towards the cusp lightness at constant Oklch hue
+    Cusp,
+}
+
+/// Gamut mapping for one destination RGB space
+#[derive(Debug, Clone)]
+pub struct GamutMapper {
+    method: GamutMapping,
+    /// Linear RGB → PCS XYZ
+    rgb_to_pcs: Matrix3x3,
+    pcs_to_rgb: Matrix3x3,
+    /// Linear RGB → D65 XYZ, for Oklab
+    rgb_to_d65: Matrix3x3,
+    d65_to_rgb: Matrix3x3,
+    /// CAM16 viewed under the destination white
+    cam: Option<Box<ColorAppearanceModel>>,
+    /// Oklch (hue, lightness) of the gamut cusps, sorted by hue
+    cusps: Vec<[f64; 2]>,
+}
+
+impl GamutMapper {
+    /// Create a mapper for a destination whose linear RGB → PCS XYZ matrix is `rgb_to_pcs`
+    pub fn new(method: GamutMapping, rgb_to_pcs: Matrix3x3) -> Result<Self, IccError> {
+        let pcs_to_rgb = rgb_to_pcs
+            .inverse()
+            .ok_or_else(|| IccError::CorruptedData("Destination matrix is singular".to_string()))?;
+        let rgb_to_d65 = bradford_matrix(&D50, &D65).multiply(&rgb_to_pcs);
+        let d65_to_rgb = pcs_to_rgb.multiply(&bradford_matrix(&D65, &D50));
+
+        let cam = (method == GamutMapping::Cam16Chroma).then(|| {
+            let white = Xyz::from_array(rgb_to_pcs.multiply_vec([1.0; 3]));
+            Box::new(ColorAppearanceModel::cam16(ViewingConditions::new(white)))
+        });
+
+        let mut mapper = Self {
+            method,
+            rgb_to_pcs,
+            pcs_to_rgb,
+            rgb_to_d65,
+            d65_to_rgb,
+            cam,
+            cusps: Vec::new(),
+        };
+        if method == GamutMapping::Cusp {
+            mapper.cusps = mapper.find_cusps();
+        }
+        Ok(mapper)
+    }
+
+    /// The mapping strategy
+    pub fn method(&self) -> GamutMapping {
+        self.method
+    }
+
+    /// Map linear destination RGB into [0, 1]
+    ///
+    /// Colors already in gamut are only clipped, which removes rounding
+    /// noise just outside [0, 1].
+    pub fn map(&self, rgb: [f64; 3]) -> [f64; 3] {
+        if in_gamut(rgb) {
+            return clip(rgb);
+        }
+        match self.method {
+            GamutMapping::Clip => clip(rgb),
+            GamutMapping::FilmlikeClip => clip(filmlike_clip(rgb)),
+            GamutMapping::OklchChroma => self.map_oklch_chroma(rgb),
+            GamutMapping::Cam16Chroma => self.map_cam16_chroma(rgb),
+            GamutMapping::Cusp => self.map_cusp(rgb),
+        }
+    }
+
+    fn rgb_to_oklch(&self, rgb: [f64; 3]) -> Oklch {
+        Oklch::from_xyz(Xyz::from_array(self.rgb_to_d65.multiply_vec(rgb)))
+    }
+
+    fn oklch_to_rgb(&self, lch: &Oklch) -> [f64; 3] {
+        self.d65_to_rgb.multiply_vec(lch.to_xyz().to_array())
+    }
+
+    /// Binary search on chroma, accepting a clipped result within one JND
+    fn map_oklch_chroma(&self, rgb: [f64; 3]) -> [f64; 3] {
+        let origin = self.rgb_to_oklch(rgb);
+        if origin.l >= 1.0 {
+            return [1.0; 3];
+        }
+        if origin.l <= 0.0 {
+            return [0.0; 3];
+        }
+        let delta = |lch: &Oklch, rgb: [f64; 3]| {
+            lch.to_oklab().distance(&self.rgb_to_oklch(rgb).to_oklab())
+        };
+
+        let clipped = clip(rgb);
+        if delta(&origin, clipped) < OKLCH_JND {
+            return clipped;
+        }
+
+        let (mut lo, mut hi) = (0.0, origin.c);
+        let mut lo_in_gamut = true;
+        let mut current = origin;
+        while hi - lo > OKLCH_EPSILON {
+            current.c = (lo + hi) / 2.0;
+            let candidate = self.oklch_to_rgb(&current);
+            if lo_in_gamut && in_gamut(candidate) {
+                lo = current.c;
+                continue;
+            }
+            let clipped = clip(candidate);
+            let e = delta(&current, clipped);
+            if e < OKLCH_JND {
+                if OKLCH_JND - e < OKLCH_EPSILON {
+                    return clipped;
+                }
+                lo_in_gamut = false;
+                lo = current.c;
+            } else {
+                hi = current.c;
+            }
+        }
+        clip(self.oklch_to_rgb(&current))
+    }
+
+    /// Largest in-gamut CAM16 chroma at the same J and h
+    fn map_cam16_chroma(&self, rgb: [f64; 3]) -> [f64; 3] {
+        let Some(cam) = &self.cam else {
+            return clip(rgb);
+        };
+        let xyz = Xyz::from_array(self.rgb_to_pcs.multiply_vec(rgb));
+        if xyz.y >= cam.conditions().white.y {
+            return [1.0; 3];
+        }
+        if xyz.y <= 0.0 {
+            return [0.0; 3];
+        }
+
+        let mut appearance = cam.forward(xyz);
+        let to_rgb = |appearance: &CamAppearance| {
+            self.pcs_to_rgb
+                .multiply_vec(cam.inverse(appearance).to_array())
+        };
+        let (mut lo, mut hi) = (0.0, appearance.c);
+        for _ in 0..SEARCH_STEPS {
+            appearance.c = (lo + hi) / 2.0;
+            if in_gamut(to_rgb(&appearance)) {
+                lo = appearance.c;
+            } else {
+                hi = appearance.c;
+            }
+        }
+        appearance.c = lo;
+        clip(to_rgb(&appearance))
+    }
+
+    /// Move along the line to the gray at the cusp lightness until in gamut
+    fn map_cusp(&self, rgb: [f64; 3]) -> [f64; 3] {
+        let origin = self.rgb_to_oklch(rgb);
+        if origin.l >= 1.0 {
+            return [1.0; 3];
+        }
+        if origin.l <= 0.0 {
+            return [0.0; 3];
+        }
+
+        let l0 = self.cusp_lightness(origin.h);
+        let point = |t: f64| Oklch::new(l0 + t * (origin.l - l0), t * origin.c, origin.h);
+        let (mut lo, mut hi) = (0.0, 1.0);
+        for _ in 0..SEARCH_STEPS {
+            let t = (lo + hi) / 2.0;
+            if in_gamut(self.oklch_to_rgb(&point(t))) {
+                lo = t;
+            } else {
+                hi = t;
+            }
+        }
+        clip(self.oklch_to_rgb(&point(lo)))
+    }
+
+    /// Sample the cube edges between primaries and secondaries
+    ///
+    /// The most chromatic color of every hue has one channel at 0 and
+    /// another at 1, so it lies on one of these six edges.
+    fn find_cusps(&self) -> Vec<[f64; 2]> {
+        let mut cusps: Vec<[f64; 2]> = CUBE_EDGE_CORNERS
+            .windows(2)
+            .flat_map(|edge| {
+                (0..CUSP_SAMPLES_PER_EDGE).map(move |i| {
+                    let t = i as f64 / CUSP_SAMPLES_PER_EDGE as f64;
+                    [0, 1, 2].map(|c| edge[0][c] + (edge[1][c] - edge[0][c]) * t)
+                })
+            })
+            .map(|rgb| {
+                let lch = self.rgb_to_oklch(rgb);
+                [lch.h, lch.l]
+            })
+            .collect();
+        cusps.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        cusps
+    }
+
+    /// Oklch lightness of the cusp at a hue
+    fn cusp_lightness(&self, hue: f64) -> f64 {
+        let n = self.cusps.len();
+        let i = self.cusps.partition_point(|c| c[0] < hue);
+        let (a, b) = (self.cusps[(i + n - 1) % n], self.cusps[i % n]);
+        let span = (b[0] - a[0]).rem_euclid(360.0);
+        let t = if span > 0.0 {
+            (hue - a[0]).rem_euclid(360.0) / span
+        } else {
+            0.0
+        };
+        a[1] + (b[1] - a[1]) * t
+    }
+}
+
+/// Limit highlights to 1 while keeping the hue, as moxcms' `filmlike_clip`
+///
+/// The largest channel becomes 1 and the middle one keeps its relative
+/// position between the smallest and largest. Negative values are left for
+/// the final clip.
+fn filmlike_clip(rgb: [f64; 3]) -> [f64; 3] {
+    let mut order = [0, 1, 2];
+    order.sort_by(|&a, &b| rgb[b].total_cmp(&rgb[a]));
+    let [hi, mid, lo] = order;
+    if rgb[hi] <= 1.0 || rgb[hi] == rgb[lo] {
+        return rgb;
+    }
+
+    let mut out = rgb;
+    out[hi] = 1.0;
+    out[lo] = rgb[lo].min(1.0);
+    out[mid] = out[lo] + (1.0 - out[lo]) * (rgb[mid] - rgb[lo]) / (rgb[hi] - rgb[lo]);
+    out
+}
+
+#[inline]
+fn in_gamut(rgb: [f64; 3]) -> bool {
+    rgb.iter()
+        .all(|v| (-GAMUT_TOLERANCE..=1.0 + GAMUT_TOLERANCE).contains(v))
+}
+
+#[inline]
+fn clip(rgb: [f64; 3]) -> [f64; 3] {
+    rgb.map(|v| v.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::lch::hue_difference;
+    use crate::math::matrix::{DISPLAY_P3_TO_XYZ, SRGB_TO_XYZ, XYZ_TO_SRGB};
+
+    fn srgb_mapper(method: GamutMapping) -> GamutMapper {
+        let rgb_to_pcs = bradford_matrix(&D65, &D50).multiply(&SRGB_TO_XYZ);
+        GamutMapper::new(method, rgb_to_pcs).unwrap()
+    }
+
+    /// Linear Display P3 as linear sRGB
+    fn p3_in_srgb(rgb: [f64; 3]) -> [f64; 3] {
+        XYZ_TO_SRGB.multiply_vec(DISPLAY_P3_TO_XYZ.multiply_vec(rgb))
+    }
+
+    const METHODS: [GamutMapping; 5] = [
+        GamutMapping::Clip,
+        GamutMapping::FilmlikeClip,
+        GamutMapping::OklchChroma,
+        GamutMapping::Cam16Chroma,
+        GamutMapping::Cusp,
+    ];
+
+    #[test]
+    fn test_in_gamut_unchanged() {
+        for method in METHODS {
+            let mapper = srgb_mapper(method);
+            for rgb in [[0.2, 0.5, 0.9], [1.0, 1.0, 1.0], [0.0, 0.0, 0.0]] {
+                assert_eq!(mapper.map(rgb), rgb, "{:?}", method);
+            }
+        }
+    }
+
+    #[test]
+    fn test_results_in_gamut() {
+        for method in METHODS {
+            let mapper = srgb_mapper(method);
+            for rgb in [
+                [1.4, -0.2, 0.3],
+                [-0.5, 1.2, -0.1],
+                [2.0, 2.0, 2.0],
+                [0.1, -0.3, 1.1],
+            ] {
+                let out = mapper.map(rgb);
+                assert!(in_gamut(out), "{:?}: {:?}", method, out);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hue_preserved() {
+        let reference = srgb_mapper(GamutMapping::Clip);
+        for p3 in [
+            [0.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.1, 0.3, 1.0],
+            [1.0, 0.0, 1.0],
+        ] {
+            let rgb = p3_in_srgb(p3);
+            assert!(!in_gamut(rgb));
+            let hue = reference.rgb_to_oklch(rgb).h;
+            let shift = |method| {
+                let mapped = reference.rgb_to_oklch(srgb_mapper(method).map(rgb));
+                hue_difference(hue, mapped.h).abs()
+            };
+            let clipped = shift(GamutMapping::Clip);
+
+            // The projection is exact; CSS Color 4 accepts a clip within one JND
+            let cusp = shift(GamutMapping::Cusp);
+            assert!(cusp < 0.1, "{:?}: {}", p3, cusp);
+            let css = shift(GamutMapping::OklchChroma);
+            assert!(
+                css < 3.0 && css <= clipped,
+                "{:?}: {} vs {}",
+                p3,
+                css,
+                clipped
+            );
+        }
+
+        // CSS Color 4 keeps lightness too
+        let rgb = p3_in_srgb([0.0, 1.0, 0.0]);
+        let origin = reference.rgb_to_oklch(rgb);
+        let mapped = reference.rgb_to_oklch(srgb_mapper(GamutMapping::OklchChroma).map(rgb));
+        assert!((mapped.l - origin.l).abs() < OKLCH_JND, "{:?}", mapped);
+    }
+
+    #[test]
+    fn test_cam16_hue_preserved() {
+        let mapper = srgb_mapper(GamutMapping::Cam16Chroma);
+        let cam = mapper.cam.as_ref().unwrap();
+        let to_cam = |rgb| cam.forward(Xyz::from_array(mapper.rgb_to_pcs.multiply_vec(rgb)));
+
+        let rgb = p3_in_srgb([0.0, 1.0, 0.0]);
+        let (before, after) = (to_cam(rgb), to_cam(mapper.map(rgb)));
+        assert!(hue_difference(before.h, after.h).abs() < 0.5, "{:?}", after);
+        assert!((before.j - after.j).abs() < 0.5, "{:?}", after);
+        assert!(after.c < before.c);
+    }
+
+    #[test]
+    fn test_filmlike_clip() {
+        let out = filmlike_clip([1.5, 0.75, 0.0]);
+        assert_eq!(out, [1.0, 0.5, 0.0]);
+        let out = filmlike_clip([0.2, 0.6, 1.4]);
+        assert!((out[0] - 0.2).abs() < 1e-12 && (out[1] - (0.2 + 0.8 / 3.0)).abs() < 1e-12);
+        // Only highlights are touched
+        assert_eq!(filmlike_clip([0.5, -0.1, 0.2]), [0.5, -0.1, 0.2]);
+    }
+}
@@ -7,14 +7,18 @@
 //!
 //! The LUT pipeline can include:
 //! - A curves (input)
-//! - CLUT (3D lookup table)
+//! - CLUT (lookup table with 1 to 15 inputs)
 //! - M curves (middle)
 //! - Matrix (optional)
 //! - B curves (output)
 
-use crate::icc::IccError;
-use crate::icc::tags::{CurveSegment, Lut8Data, Lut16Data, LutAToBData, LutBToAData, TagData};
-use crate::math::{tetrahedral_interp, trilinear_interp};
+use crate::icc::tags::{
+    CurveSegment, Lut8Data, Lut16Data, LutAToBData, LutBToAData, LutClut, TagData,
+};
+use crate::icc::{IccError, S15Fixed16};
+use crate::math::{Matrix3x3, SampledCurve, tetrahedral_interp, trilinear_interp};
+
+use super::optimize::{COMPOSED_CURVE_SAMPLES, IDENTITY_TOLERANCE};
 
 /// A LUT-based transform pipeline
 #[derive(Debug, Clone)]
@@ -23,6 +27,8 @@ pub struct LutPipeline {
     pub input_channels: usize,
     /// Number of output channels
     pub output_channels: usize,
+    /// lut8/lut16 matrix applied before the input curves (None when identity)
+    matrix: Option<Matrix3x3>,
     /// Input curves
     input_curves: Vec<LutCurve>,
     /// CLUT data (normalized to f64)
@@ -44,6 +50,173 @@ pub struct ClutData {
     pub data: Vec<f64>,
 }
 
+impl ClutData {
+    /// Sample `f` at every node of a uniform grid
+    ///
+    /// Node coordinates are in [0, 1] and the first input varies slowest,
+    /// matching the ICC CLUT layout. `f` must return `output_channels` values.
+    pub fn sample(
+        input_channels: usize,
+        grid_points: usize,
+        output_channels: usize,
+        f: impl Fn(&[f64]) -> Vec<f64>,
+    ) -> Self {
+        let nodes = grid_points.pow(input_channels as u32);
+        let mut data = Vec::with_capacity(nodes * output_channels);
+        let mut point = vec![0.0; input_channels];
+        for node in 0..nodes {
+            let mut rest = node;
+            for d in (0..input_channels).rev() {
+                point[d] = (rest % grid_points) as f64 / (grid_points - 1) as f64;
+                rest /= grid_points;
+            }
+            let out = f(&point);
+            debug_assert_eq!(out.len(), output_channels);
+            data.extend(out.into_iter().take(output_channels));
+        }
+        Self {
+            grid_points: vec![grid_points; input_channels],
+            output_channels,
+            data,
+        }
+    }
+
+    /// Multilinear interpolation at `input` (one value in [0, 1] per dimension)
+    pub fn interpolate(&self, input: &[f64]) -> Vec<f64> {
+        let dims = self.grid_points.len();
+        let out = self.output_channels;
+
+        let mut base = 0usize;
+        let mut stride = out;
+        let mut strides = vec![0usize; dims];
+        let mut fracs = vec![0.0f64; dims];
+        for d in (0..dims).rev() {
+            let grid = self.grid_points[d];
+            let pos = input[d].clamp(0.0, 1.0) * (grid - 1) as f64;
+            let i = (pos.floor() as usize).min(grid.saturating_sub(2));
+            fracs[d] = pos - i as f64;
+            strides[d] = if grid > 1 { stride } else { 0 };
+            base += i * stride;
+            stride *= grid;
+        }
+
+        let mut result = vec![0.0; out];
+        for corner in 0..1usize << dims {
+            let mut weight = 1.0;
+            let mut index = base;
+            for d in 0..dims {
+                if corner & (1 << d) != 0 {
+                    weight *= fracs[d];
+                    index += strides[d];
+                } else {
+                    weight *= 1.0 - fracs[d];
+                }
+            }
+            if weight == 0.0 {
+                continue;
+            }
+            for (r, &v) in result.iter_mut().zip(&self.data[index..index + out]) {
+                *r += weight * v;
+            }
+        }
+        result
+    }
+
+    /// Tetrahedral interpolation at `input`, extended to any dimension
+    ///
+    /// Follows lcms2: tables of up to two inputs are interpolated linearly,
+    /// 3D tables tetrahedrally, and each further input (first input first)
+    /// blends linearly between two evaluations of the remaining dimensions.
+    /// The ICC limit is 15 inputs.
+    pub fn interpolate_tetrahedral(&self, input: &[f64]) -> Vec<f64> {
+        let dims = self.grid_points.len();
+        if dims < 3 {
+            return self.interpolate(input);
+        }
+
+        let mut strides = vec![0usize; dims];
+        let mut stride = self.output_channels;
+        for d in (0..dims).rev() {
+            strides[d] = stride;
+            stride *= self.grid_points[d];
+        }
+        let mut result = vec![0.0; self.output_channels];
+        self.eval_recursive(0, 0, &strides, input, &mut result);
+        result
+    }
+
+    /// Grid cell and fraction along dimension `d`, plus the step to the next node
+    fn locate(&self, d: usize, x: f64, stride: usize) -> (usize, f64, usize) {
+        let grid = self.grid_points[d];
+        if grid < 2 {
+            return (0, 0.0, 0);
+        }
+        let pos = x.clamp(0.0, 1.0) * (grid - 1) as f64;
+        let i = (pos.floor() as usize).min(grid - 2);
+        (i * stride, pos - i as f64, stride)
+    }
+
+    /// Evaluate the sub-table at `offset` over dimensions `dim..`
+    fn eval_recursive(
+        &self,
+        offset: usize,
+        dim: usize,
+        strides: &[usize],
+        input: &[f64],
+        out: &mut [f64],
+    ) {
+        let remaining = self.grid_points.len() - dim;
+        if remaining == 3 {
+            let mut axes =
+                [0, 1, 2].map(|k| self.locate(dim + k, input[dim + k], strides[dim + k]));
+            let base = offset + axes.iter().map(|a| a.0).sum::<usize>();
+            // Walk the cube diagonal along the axes in order of decreasing fraction
+            axes.sort_by(|a, b| b.1.total_cmp(&a.1));
+            let c0 = base;
+            let c1 = c0 + axes[0].2;
+            let c2 = c1 + axes[1].2;
+            let c3 = c2 + axes[2].2;
+            let d = &self.data;
+            for (ch, v) in out.iter_mut().enumerate() {
+                *v = d[c0 + ch]
+                    + axes[0].1 * (d[c1 + ch] - d[c0 + ch])
+                    + axes[1].1 * (d[c2 + ch] - d[c1 + ch])
+                    + axes[2].1 * (d[c3 + ch] - d[c2 + ch]);
+            }
+            return;
+        }
+
+        let (base, frac, step) = self.locate(dim, input[dim], strides[dim]);
+        self.eval_recursive(offset + base, dim + 1, strides, input, out);
+        if frac > 0.0 {
+            let mut upper = vec![0.0; out.len()];
+            self.eval_recursive(offset + base + step, dim + 1, strides, input, &mut upper);
+            for (v, u) in out.iter_mut().zip(upper) {
+                *v += frac * (u - *v);
+            }
+        }
+    }
+
+    /// Normalized copy of a lutAToB/lutBToA CLUT element
+    pub fn from_lut_clut(clut: &LutClut) -> Self {
+        Self {
+            grid_points: clut.grid_points.iter().map(|&g| g as usize).collect(),
+            output_channels: clut.output_channels as usize,
+            data: clut.data.clone(),
+        }
+    }
+
+    /// Convert to a 16-bit CLUT element for lutAToB/lutBToA tags
+    pub fn to_lut_clut(&self) -> LutClut {
+        LutClut {
+            grid_points: self.grid_points.iter().map(|&g| g as u8).collect(),
+            precision: 2,
+            data: self.data.iter().map(|v| v.clamp(0.0, 1.0)).collect(),
+            output_channels: self.output_channels as u8,
+        }
+    }
+}
+
 /// A single LUT curve
 #[derive(Debug, Clone)]
 pub enum LutCurve {
@@ -52,7 +225,9 @@ pub enum LutCurve {
     /// Gamma curve
     Gamma(f64),
     /// Lookup table (normalized)
-    Table(Vec<f64>),
+    Table(SampledCurve),
+    /// Inverse of a lookup table, evaluated through its reversed table
+    Reversed(SampledCurve),
 }
 
 impl Default for LutCurve {
@@ -68,7 +243,7 @@ impl LutCurve {
             return LutCurve::Identity;
         }
         let normalized: Vec<f64> = table.iter().map(|&v| v as f64 / 255.0).collect();
-        LutCurve::Table(normalized)
+        LutCurve::Table(SampledCurve::new(normalized))
     }
 
     /// Create from 16-bit table
@@ -77,7 +252,7 @@ impl LutCurve {
             return LutCurve::Identity;
         }
         let normalized: Vec<f64> = table.iter().map(|&v| v as f64 / 65535.0).collect();
-        LutCurve::Table(normalized)
+        LutCurve::Table(SampledCurve::new(normalized))
     }
 
     /// Create from curve segment
@@ -88,7 +263,7 @@ impl LutCurve {
                 if table.is_empty() {
                     LutCurve::Identity
                 } else {
-                    LutCurve::Table(table.clone())
+                    LutCurve::Table(SampledCurve::new(table.clone()))
                 }
             }
             CurveSegment::Parametric { curve_type, params } => {
@@ -103,42 +278,63 @@ impl LutCurve {
                         let y = eval_parametric(*curve_type, params, x);
                         table.push(y);
                     }
-                    LutCurve::Table(table)
+                    LutCurve::Table(SampledCurve::new(table))
                 }
             }
         }
     }
 
+    /// The inverse curve
+    ///
+    /// Tables are inverted exactly through their reversed table, and a
+    /// reversed table turns back into the table.
+    pub fn reversed(&self) -> Self {
+        match self {
+            LutCurve::Identity => LutCurve::Identity,
+            LutCurve::Gamma(g) if *g != 0.0 => LutCurve::Gamma(1.0 / g),
+            LutCurve::Gamma(_) => LutCurve::Identity,
+            LutCurve::Table(table) => LutCurve::Reversed(table.clone().with_reversed_table()),
+            LutCurve::Reversed(table) => LutCurve::Table(table.clone()),
+        }
+    }
+
+    /// Use `size` buckets for the reversed table of a table curve
+    ///
+    /// Only a [`LutCurve::Reversed`] builds its table right away; a forward
+    /// table waits until it is inverted.
+    pub fn with_reverse_size(self, size: usize) -> Self {
+        match self {
+            LutCurve::Table(table) => LutCurve::Table(table.with_reverse_size(size)),
+            LutCurve::Reversed(table) => {
+                LutCurve::Reversed(table.with_reverse_size(size).with_reversed_table())
+            }
+            other => other,
+        }
+    }
+
+    /// Whether the curve maps every input to itself within `tolerance`
+    pub fn is_identity(&self, tolerance: f64) -> bool {
+        match self {
+            LutCurve::Identity => true,
+            LutCurve::Gamma(g) => (g - 1.0).abs() <= tolerance,
+            LutCurve::Table(table) | LutCurve::Reversed(table) => table.is_identity(tolerance),
+        }
+    }
+
     /// Evaluate the curve at input x (0.0-1.0)
     pub fn eval(&self, x: f64) -> f64 {
         let x = x.clamp(0.0, 1.0);
         match self {
             LutCurve::Identity => x,
             LutCurve::Gamma(g) => x.powf(*g),
-            LutCurve::Table(table) => {
-                if table.is_empty() {
-                    return x;
-                }
-                if table.len() == 1 {
-                    return table[0];
-                }
-
-                let pos = x * (table.len() - 1) as f64;
-                let idx = pos.floor() as usize;
-                let frac = pos - idx as f64;
-
-                if idx >= table.len() - 1 {
-                    return table[table.len() - 1];
-                }
-
-                table[idx] + frac * (table[idx + 1] - table[idx])
-            }
+            LutCurve::Table(table) => table.eval(x),
+            LutCurve::Reversed(table) => table.eval_inverse(x),
         }
     }
 }
 
 /// Evaluate parametric curve
-fn eval_parametric(curve_type: u16, params: &[f64], x: f64) -> f64 {
+pub(crate) fn eval_parametric(curve_type: u16, params: &[f64], x: f64) -> f64 {
     let x = x.clamp(0.0, 1.0);
     let g = params.first().copied().unwrap_or(1.0);
 
@@ -208,6 +404,7 @@ impl LutPipeline {
         Self {
             input_channels,
             output_channels,
+            matrix: None,
             input_curves: vec![LutCurve::Identity; input_channels],
             clut: None,
             output_curves: vec![LutCurve::Identity; output_channels],
@@ -259,6 +456,7 @@ impl LutPipeline {
         Self {
             input_channels: lut.input_channels as usize,
             output_channels: lut.output_channels as usize,
+            matrix: lut_matrix(&lut.matrix, lut.input_channels),
             input_curves,
             clut,
             output_curves,
@@ -297,6 +495,7 @@ impl LutPipeline {
         Self {
             input_channels: lut.input_channels as usize,
             output_channels: lut.output_channels as usize,
+            matrix: lut_matrix(&lut.matrix, lut.input_channels),
             input_curves,
             clut,
             output_curves,
@@ -314,11 +513,7 @@ impl LutPipeline {
             .unwrap_or_else(|| vec![LutCurve::Identity; lut.input_channels as usize]);
 
         // Parse CLUT if present
-        let clut = lut.clut.as_ref().map(|c| ClutData {
-            grid_points: c.grid_points.iter().map(|&g| g as usize).collect(),
-            output_channels: c.output_channels as usize,
-            data: c.data.clone(),
-        });
+        let clut = lut.clut.as_ref().map(ClutData::from_lut_clut);
 
         // B curves are output curves (for A2B)
         let output_curves = lut
@@ -330,6 +525,7 @@ impl LutPipeline {
         Self {
             input_channels: lut.input_channels as usize,
             output_channels: lut.output_channels as usize,
+            matrix: None,
             input_curves,
             clut,
             output_curves,
@@ -347,11 +543,7 @@ impl LutPipeline {
             .unwrap_or_else(|| vec![LutCurve::Identity; lut.input_channels as usize]);
 
         // Parse CLUT if present
-        let clut = lut.clut.as_ref().map(|c| ClutData {
-            grid_points: c.grid_points.iter().map(|&g| g as usize).collect(),
-            output_channels: c.output_channels as usize,
-            data: c.data.clone(),
-        });
+        let clut = lut.clut.as_ref().map(ClutData::from_lut_clut);
 
         // A curves are output curves (for B2A)
         let output_curves = lut
@@ -363,6 +555,7 @@ impl LutPipeline {
         Self {
             input_channels: lut.input_channels as usize,
             output_channels: lut.output_channels as usize,
+            matrix: None,
             input_curves,
             clut,
             output_curves,
@@ -370,230 +563,362 @@ impl LutPipeline {
         }
     }
 
-    /// Transform input values through the LUT pipeline
-    pub fn transform(&self, input: &[f64]) -> Vec<f64> {
-        // Apply input curves
-        let mut values: Vec<f64> = input
-            .iter()
-            .zip(self.input_curves.iter().cycle())
-            .map(|(&x, curve)| curve.eval(x))
+    /// Create a pipeline that is a single sampled CLUT with identity curves
+    pub fn from_clut(clut: ClutData) -> Self {
+        let input_channels = clut.grid_points.len();
+        let output_channels = clut.output_channels;
+        Self {
+            input_channels,
+            output_channels,
+            matrix: None,
+            input_curves: vec![LutCurve::Identity; input_channels],
+            clut: Some(clut),
+            output_curves: vec![LutCurve::Identity; output_channels],
+            use_tetrahedral: true,
+        }
+    }
+
+    /// Find the input that this pipeline maps to `target`
+    ///
+    /// This is the numerical inverse used when a profile has no reverse table,
+    /// in the style of lcms2's `cmsPipelineEvalReverseFloat`: damped
+    /// Newton–Raphson on the whole pipeline with a finite-difference
+    /// Jacobian, clamping the input to [0, 1] after every step. The search
+    /// starts at `hint`, or mid-grey without one. Inputs beyond the output
+    /// count (K of a CMYK→PCS table) are not searched and keep their `hint`
+    /// value, 0 without a hint.
+    ///
+    /// Returns the best input found and the remaining Euclidean error in
+    /// output units; the error is non-zero for out-of-gamut targets.
+    pub fn transform_reverse(&self, target: &[f64], hint: Option<&[f64]>) -> (Vec<f64>, f64) {
+        const ITERATIONS: usize = 30;
+        const STEP: f64 = 1e-3;
+        const TOLERANCE: f64 = 1e-6;
+
+        let outputs = self.output_channels.min(target.len());
+        let free = self.input_channels.min(outputs);
+        let mut input: Vec<f64> = (0..self.input_channels)
+            .map(|i| match hint.and_then(|h| h.get(i)) {
+                Some(&v) => v.clamp(0.0, 1.0),
+                None if i < free => 0.5,
+                None => 0.0,
+            })
             .collect();
 
-        // Apply CLUT
-        if let Some(ref clut) = self.clut {
-            values = self.apply_clut(&values, clut);
-        }
+        let residual = |input: &[f64]| -> Vec<f64> {
+            let out = self.transform(input);
+            (0..outputs)
+                .map(|ch| out.get(ch).copied().unwrap_or(0.0) - target[ch])
+                .collect()
+        };
+        let norm = |r: &[f64]| r.iter().map(|v| v * v).sum::<f64>().sqrt();
+
+        let mut r = residual(&input);
+        let mut error = norm(&r);
+        let mut damping = 1e-6;
+        for _ in 0..ITERATIONS {
+            if error < TOLERANCE {
+                break;
+            }
 
-        // Apply output curves
-        values
-            .iter()
-            .zip(self.output_curves.iter().cycle())
-            .map(|(&x, curve)| curve.eval(x))
-            .collect()
-    }
+            // Forward differences, stepping inward at the upper bound
+            let mut jacobian = vec![vec![0.0; outputs]; free];
+            for (j, column) in jacobian.iter_mut().enumerate() {
+                let h = if input[j] + STEP > 1.0 { -STEP } else { STEP };
+                let mut probe = input.clone();
+                probe[j] += h;
+                for (c, (p, q)) in column.iter_mut().zip(residual(&probe).iter().zip(&r)) {
+                    *c = (p - q) / h;
+                }
+            }
 
-    /// Apply CLUT interpolation
-    fn apply_clut(&self, input: &[f64], clut: &ClutData) -> Vec<f64> {
-        // For 3-channel input, use optimized interpolation
-        if input.len() == 3 && clut.grid_points.len() >= 3 {
-            let grid_size = clut.grid_points[0];
-            if self.use_tetrahedral {
-                let result =
-                    tetrahedral_interp(&clut.data, grid_size, [input[0], input[1], input[2]]);
-                return result.to_vec();
+            // (JᵀJ + λI) δ = -Jᵀr, which is plain Newton for square J
+            let mut a = vec![vec![0.0; free]; free];
+            let mut g = vec![0.0; free];
+            for i in 0..free {
+                for j in 0..free {
+                    a[i][j] = jacobian[i]
+                        .iter()
+                        .zip(&jacobian[j])
+                        .map(|(p, q)| p * q)
+                        .sum();
+                }
+                a[i][i] += damping;
+                g[i] = -jacobian[i].iter().zip(&r).map(|(p, q)| p * q).sum::<f64>();
+            }
+            let Some(delta) = solve_linear(a, g) else {
+                break;
+            };
+
+            let mut candidate = input.clone();
+            for (v, d) in candidate.iter_mut().zip(&delta) {
+                *v = (*v + d).clamp(0.0, 1.0);
+            }
+            let candidate_r = residual(&candidate);
+            let candidate_error = norm(&candidate_r);
+            if candidate_error < error {
+                let step: f64 = candidate
+                    .iter()
+                    .zip(&input)
+                    .map(|(a, b)| (a - b).abs())
+                    .sum();
+                input = candidate;
+                r = candidate_r;
+                error = candidate_error;
+                damping = (damping / 10.0).max(1e-12);
+                if step < 1e-9 {
+                    break;
+                }
             } else {
-                let result =
-                    trilinear_interp(&clut.data, grid_size, [input[0], input[1], input[2]]);
-                return result.to_vec();
+                damping *= 10.0;
+                if damping > 1e6 {
+                    break;
+                }
             }
         }
+        (input, error)
+    }
+
+    /// Sample the numerical inverse of this pipeline into a CLUT
+    ///
+    /// The result maps this pipeline's output space back to its input
+    /// space, so an A2B table can stand in for a missing B2A. The curves
+    /// around the CLUT are inverted exactly through their reversed tables
+    /// and only the CLUT is searched numerically; a pipeline of curves alone
+    /// needs no CLUT at all. A lut8/lut16 matrix ahead of the input curves
+    /// is not split off, and the whole pipeline is searched instead. Extra
+    /// inputs (K for CMYK) are held at 0, i.e. the inverse uses no black
+    /// generation.
+    pub fn inverse(&self, grid_points: usize) -> Result<Self, IccError> {
+        if !(2..=255).contains(&grid_points) {
+            return Err(IccError::Unsupported(format!(
+                "inverse CLUT grid of {} points",
+                grid_points
+            )));
+        }
+        if self.output_channels == 0 || self.output_channels > 4 {
+            return Err(IccError::Unsupported(format!(
+                "inverting a LUT with {} outputs",
+                self.output_channels
+            )));
+        }
 
-        // For 4-channel input (CMYK), use quadrilinear interpolation
-        if input.len() == 4 && clut.grid_points.len() >= 4 {
-            return self.apply_clut_4d(input, clut);
+        let reversed = |curves: &[LutCurve]| curves.iter().map(LutCurve::reversed).collect();
+        if self.matrix.is_some() {
+            let held = vec![0.0; self.input_channels];
+            return Ok(Self::from_clut(self.sample_inverse(grid_points, &held)));
+        }
+        if self.clut.is_none() && self.input_channels == self.output_channels {
+            return Ok(Self {
+                input_curves: reversed(&self.output_curves),
+                output_curves: reversed(&self.input_curves),
+                ..self.clone()
+            });
         }
 
-        // For other dimensions, fall back to nearest-neighbor
-        self.apply_clut_generic(input, clut)
+        // Held inputs are 0 on the device side of the input curves
+        let held: Vec<f64> = (0..self.input_channels)
+            .map(|ch| self.input_curves.get(ch).map_or(0.0, |c| c.eval(0.0)))
+            .collect();
+        let core = Self {
+            input_curves: vec![LutCurve::Identity; self.input_channels],
+            output_curves: vec![LutCurve::Identity; self.output_channels],
+            ..self.clone()
+        };
+        Ok(Self::from_clut(core.sample_inverse(grid_points, &held))
+            .with_input_curves(reversed(&self.output_curves))
+            .with_output_curves(reversed(&self.input_curves)))
     }
 
-    /// Apply 4D CLUT interpolation (for CMYK)
-    fn apply_clut_4d(&self, input: &[f64], clut: &ClutData) -> Vec<f64> {
-        // Quadrilinear interpolation for 4D CLUT
-        let g0 = clut.grid_points[0];
-        let g1 = clut.grid_points[1];
-        let g2 = clut.grid_points[2];
-        let g3 = clut.grid_points[3];
-        let out_ch = clut.output_channels;
+    /// Sample [`Self::transform_reverse`] over a grid of this pipeline's outputs
+    ///
+    /// Inputs that are not searched start from, and keep, their `held` value.
+    fn sample_inverse(&self, grid_points: usize, held: &[f64]) -> ClutData {
+        let free = self.input_channels.min(self.output_channels);
+        let cold: Vec<f64> = (0..self.input_channels)
+            .map(|ch| if ch < free { 0.5 } else { held[ch] })
+            .collect();
 
-        // Calculate positions and fractions for each dimension
-        let p0 = (input[0].clamp(0.0, 1.0) * (g0 - 1) as f64).min((g0 - 1) as f64);
-        let p1 = (input[1].clamp(0.0, 1.0) * (g1 - 1) as f64).min((g1 - 1) as f64);
-        let p2 = (input[2].clamp(0.0, 1.0) * (g2 - 1) as f64).min((g2 - 1) as f64);
-        let p3 = (input[3].clamp(0.0, 1.0) * (g3 - 1) as f64).min((g3 - 1) as f64);
+        // Neighbouring nodes have close solutions, so warm-start from the
+        // previous one and only fall back to a cold start when that fails
+        let previous = std::cell::RefCell::new(None::<Vec<f64>>);
+        ClutData::sample(
+            self.output_channels,
+            grid_points,
+            self.input_channels,
+            |target| {
+                let warm = previous.borrow().clone();
+                let (mut best, error) =
+                    self.transform_reverse(target, Some(warm.as_deref().unwrap_or(&cold)));
+                if warm.is_some() && error > 1e-4 {
+                    let (cold, cold_error) = self.transform_reverse(target, Some(&cold));
+                    if cold_error < error {
+                        best = cold;
+                    }
+                }
+                *previous.borrow_mut() = Some(best.clone());
+                best
+            },
+        )
+    }
 
-        let i0 = p0.floor() as usize;
-        let i1 = p1.floor() as usize;
-        let i2 = p2.floor() as usize;
-        let i3 = p3.floor() as usize;
+    /// Use `size` buckets for the reversed tables of every table curve
+    pub fn with_reverse_size(mut self, size: usize) -> Self {
+        for curve in self
+            .input_curves
+            .iter_mut()
+            .chain(self.output_curves.iter_mut())
+        {
+            *curve = std::mem::take(curve).with_reverse_size(size);
+        }
+        self
+    }
 
-        let f0 = p0 - i0 as f64;
-        let f1 = p1 - i1 as f64;
-        let f2 = p2 - i2 as f64;
-        let f3 = p3 - i3 as f64;
+    /// Replace the input curves
+    pub fn with_input_curves(mut self, curves: Vec<LutCurve>) -> Self {
+        self.input_curves = curves;
+        self
+    }
 
-        // Clamp indices
-        let i0_1 = (i0 + 1).min(g0 - 1);
-        let i1_1 = (i1 + 1).min(g1 - 1);
-        let i2_1 = (i2 + 1).min(g2 - 1);
-        let i3_1 = (i3 + 1).min(g3 - 1);
+    /// Replace the output curves
+    pub fn with_output_curves(mut self, curves: Vec<LutCurve>) -> Self {
+        self.output_curves = curves;
+        self
+    }
 
-        // Calculate strides
-        let s3 = out_ch;
-        let s2 = s3 * g3;
-        let s1 = s2 * g2;
-        let s0 = s1 * g1;
+    /// Input curves, one per input channel
+    pub(crate) fn input_curves(&self) -> &[LutCurve] {
+        &self.input_curves
+    }
 
-        // Index function
-        let idx = |c, m, y, k| c * s0 + m * s1 + y * s2 + k * s3;
+    /// Whether a lut8/lut16 matrix runs ahead of the input curves
+    pub(crate) fn has_matrix(&self) -> bool {
+        self.matrix.is_some()
+    }
 
-        // Interpolate in all 4 dimensions
-        let mut output = vec![0.0f64; out_ch];
+    /// Simplify the pipeline without changing what it computes
+    ///
+    /// Curves that are identity within one 16-bit code become
+    /// [`LutCurve::Identity`], and an identity matrix is dropped. Without a
+    /// CLUT the input and output curves are adjacent, so they are composed
+    /// into one sampled input table per channel.
+    pub fn optimize(mut self) -> Self {
+        if self
+            .matrix
+            .is_some_and(|m| m.is_identity(IDENTITY_TOLERANCE))
+        {
+            self.matrix = None;
+        }
 
-        for ch in 0..out_ch {
-            // 16 corner values
-            let v0000 = clut
-                .data
-                .get(idx(i0, i1, i2, i3) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v0001 = clut
-                .data
-                .get(idx(i0, i1, i2, i3_1) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v0010 = clut
-                .data
-                .get(idx(i0, i1, i2_1, i3) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v0011 = clut
-                .data
-                .get(idx(i0, i1, i2_1, i3_1) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v0100 = clut
-                .data
-                .get(idx(i0, i1_1, i2, i3) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v0101 = clut
-                .data
-                .get(idx(i0, i1_1, i2, i3_1) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v0110 = clut
-                .data
-                .get(idx(i0, i1_1, i2_1, i3) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v0111 = clut
-                .data
-                .get(idx(i0, i1_1, i2_1, i3_1) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v1000 = clut
-                .data
-                .get(idx(i0_1, i1, i2, i3) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v1001 = clut
-                .data
-                .get(idx(i0_1, i1, i2, i3_1) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v1010 = clut
-                .data
-                .get(idx(i0_1, i1, i2_1, i3) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v1011 = clut
-                .data
-                .get(idx(i0_1, i1, i2_1, i3_1) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v1100 = clut
-                .data
-                .get(idx(i0_1, i1_1, i2, i3) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v1101 = clut
-                .data
-                .get(idx(i0_1, i1_1, i2, i3_1) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v1110 = clut
-                .data
-                .get(idx(i0_1, i1_1, i2_1, i3) + ch)
-                .copied()
-                .unwrap_or(0.0);
-            let v1111 = clut
-                .data
-                .get(idx(i0_1, i1_1, i2_1, i3_1) + ch)
-                .copied()
-                .unwrap_or(0.0);
+        let curve_pair = |ch: usize| {
+            (
+                &self.input_curves[ch % self.input_curves.len()],
+                &self.output_curves[ch % self.output_curves.len()],
+            )
+        };
+        if self.clut.is_none()
+            && self.input_channels == self.output_channels
+            && !self.input_curves.is_empty()
+            && !self.output_curves.is_empty()
+        {
+            let composable = (0..self.input_channels).any(|ch| {
+                let (first, second) = curve_pair(ch);
+                !first.is_identity(IDENTITY_TOLERANCE) && !second.is_identity(IDENTITY_TOLERANCE)
+            });
+            if composable {
+                let input_curves = (0..self.input_channels)
+                    .map(|ch| {
+                        let (first, second) = curve_pair(ch);
+                        LutCurve::Table(SampledCurve::new(
+                            (0..COMPOSED_CURVE_SAMPLES)
+                                .map(|i| {
+                                    let x = i as f64 / (COMPOSED_CURVE_SAMPLES - 1) as f64;
+                                    second.eval(first.eval(x))
+                                })
+                                .collect(),
+                        ))
+                    })
+                    .collect();
+                self.input_curves = input_curves;
+                self.output_curves = vec![LutCurve::Identity; self.output_channels];
+            }
+        }
 
-            // Interpolate along dimension 3 (K)
-            let v000 = v0000 + f3 * (v0001 - v0000);
-            let v001 = v0010 + f3 * (v0011 - v0010);
-            let v010 = v0100 + f3 * (v0101 - v0100);
-            let v011 = v0110 + f3 * (v0111 - v0110);
-            let v100 = v1000 + f3 * (v1001 - v1000);
-            let v101 = v1010 + f3 * (v1011 - v1010);
-            let v110 = v1100 + f3 * (v1101 - v1100);
-            let v111 = v1110 + f3 * (v1111 - v1110);
+        for curve in self
+            .input_curves
+            .iter_mut()
+            .chain(self.output_curves.iter_mut())
+        {
+            if curve.is_identity(IDENTITY_TOLERANCE) {
+                *curve = LutCurve::Identity;
+            }
+        }
+        self
+    }
 
-            // Interpolate along dimension 2 (Y)
-            let v00 = v000 + f2 * (v001 - v000);
-            let v01 = v010 + f2 * (v011 - v010);
-            let v10 = v100 + f2 * (v101 - v100);
-            let v11 = v110 + f2 * (v111 - v110);
+    /// Transform input values through the LUT pipeline
+    pub fn transform(&self, input: &[f64]) -> Vec<f64> {
+        // Apply the lut8/lut16 matrix
+        let matrixed;
+        let input = match (&self.matrix, input) {
+            (Some(matrix), &[a, b, c]) => {
+                matrixed = matrix.multiply_vec([a, b, c]);
+                &matrixed[..]
+            }
+            _ => input,
+        };
 
-            // Interpolate along dimension 1 (M)
-            let v0 = v00 + f1 * (v01 - v00);
-            let v1 = v10 + f1 * (v11 - v10);
+        // Apply input curves
+        let values: Vec<f64> = input
+            .iter()
+            .zip(self.input_curves.iter().cycle())
+            .map(|(&x, curve)| curve.eval(x))
+            .collect();
 
-            // Interpolate along dimension 0 (C)
-            output[ch] = v0 + f0 * (v1 - v0);
-        }
+        self.transform_from_clut(&values)
+    }
 
-        output
+    /// Run the stages after the input curves on already-curved values
+    pub(crate) fn transform_from_clut(&self, values: &[f64]) -> Vec<f64> {
+        // Apply CLUT
+        let values = match self.clut {
+            Some(ref clut) => self.apply_clut(values, clut),
+            None => values.to_vec(),
+        };
+
+        // Apply output curves
+        values
+            .iter()
+            .zip(self.output_curves.iter().cycle())
+            .map(|(&x, curve)| curve.eval(x))
+            .collect()
     }
 
-    /// Generic CLUT interpolation for arbitrary dimensions
-    fn apply_clut_generic(&self, input: &[f64], clut: &ClutData) -> Vec<f64> {
-        // Simplified: just find nearest grid point
-        let mut idx = 0usize;
-        let mut stride = 1usize;
+    /// Apply CLUT interpolation
+    fn apply_clut(&self, input: &[f64], clut: &ClutData) -> Vec<f64> {
+        let dims = clut.grid_points.len();
 
-        for (i, &x) in input.iter().enumerate().rev() {
-            if i >= clut.grid_points.len() {
-                continue;
-            }
-            let grid = clut.grid_points[i];
-            let pos = (x * (grid - 1) as f64).round() as usize;
-            let pos = pos.min(grid - 1);
-            idx += pos * stride;
-            stride *= grid;
+        // Uniform RGB-style 3D tables take the fixed-size fast path
+        let uniform = clut.grid_points.iter().all(|&g| g == clut.grid_points[0]);
+        if input.len() == 3 && dims == 3 && clut.output_channels == 3 && uniform {
+            let grid_size = clut.grid_points[0];
+            let point = [input[0], input[1], input[2]];
+            let result = if self.use_tetrahedral {
+                tetrahedral_interp(&clut.data, grid_size, point)
+            } else {
+                trilinear_interp(&clut.data, grid_size, point)
+            };
+            return result.to_vec();
         }
 
-        // Extract output values
-        let base = idx * clut.output_channels;
-        let mut output = Vec::with_capacity(clut.output_channels);
-        for i in 0..clut.output_channels {
-            output.push(clut.data.get(base + i).copied().unwrap_or(0.0));
+        // Missing channels read as 0, extra ones are ignored
+        let mut point = input.to_vec();
+        point.resize(dims, 0.0);
+        if self.use_tetrahedral {
+            clut.interpolate_tetrahedral(&point)
+        } else {
+            clut.interpolate(&point)
         }
-        output
     }
 
     /// Transform 3-channel input to 3-channel output (common case)
@@ -618,6 +943,39 @@ impl LutPipeline {
     }
 }
 
+/// The lut8/lut16 matrix, kept only for 3-input tables where it is not identity
+fn lut_matrix(matrix: &[[S15Fixed16; 3]; 3], input_channels: u8) -> Option<Matrix3x3> {
+    let matrix = Matrix3x3::new(matrix.map(|row| row.map(S15Fixed16::to_f64)));
+    (input_channels == 3 && !matrix.is_identity(IDENTITY_TOLERANCE)).then_some(matrix)
+}
+
+/// Solve `a·x = b` by Gaussian elimination with partial pivoting
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-18 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..n {
+            let f = a[row][col] / a[col][col];
+            let (upper, lower) = a.split_at_mut(row);
+            for (v, p) in lower[0][col..].iter_mut().zip(&upper[col][col..]) {
+                *v -= f * p;
+            }
+            b[row] -= f * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|c| a[row][c] * x[c]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -642,7 +1000,7 @@ mod tests {
     fn test_lut_curve_table() {
         // Linear table
         let table: Vec<f64> = (0..256).map(|i| i as f64 / 255.0).collect();
-        let curve = LutCurve::Table(table);
+        let curve = LutCurve::Table(table.into());
 
         assert!((curve.eval(0.0) - 0.0).abs() < 0.01);
         assert!((curve.eval(0.5) - 0.5).abs() < 0.01);
@@ -654,6 +1012,7 @@ mod tests {
         let pipeline = LutPipeline {
             input_channels: 3,
             output_channels: 3,
+            matrix: None,
             input_curves: vec![LutCurve::Identity; 3],
             clut: None,
             output_curves: vec![LutCurve::Identity; 3],
@@ -668,6 +1027,206 @@ mod tests {
         assert!((output[2] - 0.7).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_optimize_collapses_curves() {
+        let ramp: Vec<f64> = (0..256).map(|i| i as f64 / 255.0).collect();
+        let mut pipeline = LutPipeline::identity(3, 3).with_input_curves(vec![
+            LutCurve::Gamma(2.0),
+            LutCurve::Table(ramp.into()),
+            LutCurve::Identity,
+        ]);
+        pipeline.output_curves = vec![LutCurve::Gamma(0.5); 3];
+
+        let optimized = pipeline.clone().optimize();
+        // x^2 then x^0.5 composes to identity on red; green and blue keep
+        // only the output curve
+        assert!(matches!(optimized.input_curves[0], LutCurve::Identity));
+        assert!(matches!(optimized.input_curves[1], LutCurve::Table(_)));
+        assert!(
+            optimized
+                .output_curves
+                .iter()
+                .all(|c| matches!(c, LutCurve::Identity))
+        );
+        for x in [0.1, 0.4, 0.9] {
+            let a = pipeline.transform(&[x, x, x]);
+            let b = optimized.transform(&[x, x, x]);
+            for c in 0..3 {
+                assert!(
+                    (a[c] - b[c]).abs() < 1e-3,
+                    "{} ch{}: {} vs {}",
+                    x,
+                    c,
+                    a[c],
+                    b[c]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lut16_matrix() {
+        let ramp: Vec<u16> = vec![0, 65535];
+        let mut matrix = [[S15Fixed16::from_f64(0.0); 3]; 3];
+        matrix[0][1] = S15Fixed16::from_f64(1.0);
+        matrix[1][0] = S15Fixed16::from_f64(1.0);
+        matrix[2][2] = S15Fixed16::from_f64(1.0);
+        let mut lut = Lut16Data {
+            input_channels: 3,
+            output_channels: 3,
+            grid_points: 2,
+            matrix,
+            input_entries: 2,
+            output_entries: 2,
+            input_curves: vec![ramp.clone(); 3],
+            clut: ClutData::sample(3, 2, 3, |p| p.to_vec())
+                .data
+                .iter()
+                .map(|&v| (v * 65535.0) as u16)
+                .collect(),
+            output_curves: vec![ramp; 3],
+        };
+
+        // The swap matrix runs ahead of the identity CLUT
+        let out = LutPipeline::from_lut16(&lut).transform(&[0.2, 0.6, 0.9]);
+        assert!((out[0] - 0.6).abs() < 1e-4 && (out[1] - 0.2).abs() < 1e-4);
+
+        lut.matrix = [[S15Fixed16::from_f64(0.0); 3]; 3];
+        for i in 0..3 {
+            lut.matrix[i][i] = S15Fixed16::from_f64(1.0);
+        }
+        assert!(!LutPipeline::from_lut16(&lut).has_matrix());
+    }
+
+    #[test]
+    fn test_clut_interpolation_any_dimension() {
+        // Both schemes are exact on linear functions, whatever the dimension
+        for dims in [1, 2, 3, 4, 5, 6, 8] {
+            let linear = |p: &[f64]| {
+                let sum: f64 = p.iter().enumerate().map(|(i, v)| (i + 1) as f64 * v).sum();
+                vec![sum / 36.0, 1.0 - p[0]]
+            };
+            let clut = ClutData::sample(dims, 3, 2, linear);
+            let point: Vec<f64> = (0..dims).map(|i| (i as f64 * 0.37 + 0.1) % 1.0).collect();
+            let expected = linear(&point);
+            for result in [
+                clut.interpolate(&point),
+                clut.interpolate_tetrahedral(&point),
+            ] {
+                for (a, b) in result.iter().zip(&expected) {
+                    assert!((a - b).abs() < 1e-12, "{} inputs: {:?}", dims, result);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tetrahedral_matches_3d_kernel() {
+        let clut = ClutData::sample(3, 5, 3, |p| vec![p[0] * p[1], p[2].powi(2), p[0] + p[2]]);
+        let point = [0.33, 0.71, 0.58];
+        let kernel = tetrahedral_interp(&clut.data, 5, point);
+        let general = clut.interpolate_tetrahedral(&point);
+        for (a, b) in kernel.iter().zip(&general) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_duotone_clut_is_not_quantized() {
+        let pipeline =
+            LutPipeline::from_clut(ClutData::sample(2, 2, 1, |p| vec![(p[0] + p[1]) / 2.0]));
+        let out = pipeline.transform(&[0.3, 0.4]);
+        assert!((out[0] - 0.35).abs() < 1e-12, "{:?}", out);
+    }
+
+    #[test]
+    fn test_transform_reverse() {
+        let forward = LutPipeline::from_clut(ClutData::sample(3, 9, 3, |p| {
+            vec![
+                0.8 * p[0] * p[0] + 0.2 * p[1],
+                p[1].sqrt() * 0.9 + 0.1 * p[2],
+                0.5 * (p[2] + p[0] * p[2]),
+            ]
+        }));
+
+        let input = [0.3, 0.6, 0.8];
+        let target = forward.transform(&input);
+        let (found, error) = forward.transform_reverse(&target, None);
+        assert!(error < 1e-5, "error {}", error);
+        for (a, b) in found.iter().zip(input) {
+            assert!((a - b).abs() < 1e-3, "{:?}", found);
+        }
+
+        // Out-of-range targets settle on the boundary
+        let (found, error) = forward.transform_reverse(&[2.0, 2.0, 2.0], None);
+        assert!(error > 0.5);
+        assert!(found.iter().all(|v| (0.0..=1.0).contains(v)));
+    }
+
+    #[test]
+    fn test_inverse_reverses_curves() {
+        let gamma: Vec<f64> = (0..256).map(|i| (i as f64 / 255.0).powf(2.2)).collect();
+        let curves = vec![LutCurve::Table(gamma.into()); 3];
+
+        // Curves alone invert exactly, without a CLUT
+        let forward = LutPipeline::identity(3, 3).with_input_curves(curves.clone());
+        let inverse = forward.inverse(2).unwrap();
+        assert!(inverse.clut.is_none());
+        let input = [0.001, 0.3, 0.9];
+        for (a, b) in inverse
+            .transform(&forward.transform(&input))
+            .iter()
+            .zip(input)
+        {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // Around a CLUT only the CLUT is sampled
+        let forward = LutPipeline::from_clut(ClutData::sample(3, 2, 3, |p| p.to_vec()))
+            .with_input_curves(curves)
+            .with_output_curves(vec![LutCurve::Gamma(0.5); 3]);
+        let inverse = forward.inverse(5).unwrap();
+        assert!(matches!(inverse.input_curves[0], LutCurve::Gamma(g) if g == 2.0));
+        // Reversed tables are built up front; the forward curves never had one
+        assert!(
+            matches!(&inverse.output_curves[0], LutCurve::Reversed(t) if t.has_reversed_table())
+        );
+        assert!(matches!(&forward.input_curves[0], LutCurve::Table(t) if !t.has_reversed_table()));
+        for (a, b) in inverse
+            .transform(&forward.transform(&input))
+            .iter()
+            .zip(input)
+        {
+            assert!((a - b).abs() < 1e-6, "{} {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_inverse_of_cmyk_table() {
+        // Simple subtractive model: PCS = (1 - ink) * (1 - K)
+        let forward = LutPipeline::from_clut(ClutData::sample(4, 5, 3, |p| {
+            (0..3).map(|i| (1.0 - p[i]) * (1.0 - p[3])).collect()
+        }));
+        let inverse = forward.inverse(9).unwrap();
+        assert_eq!(inverse.input_channels, 3);
+        assert_eq!(inverse.output_channels, 4);
+
+        let cmyk = inverse.transform(&[0.75, 0.5, 0.25]);
+        assert!((cmyk[0] - 0.25).abs() < 0.01, "{:?}", cmyk);
+        assert!((cmyk[1] - 0.5).abs() < 0.01, "{:?}", cmyk);
+        assert!((cmyk[2] - 0.75).abs() < 0.01, "{:?}", cmyk);
+        assert_eq!(cmyk[3], 0.0);
+
+        // Chained back through the forward table
+        let pcs = forward.transform(&cmyk);
+        for (a, b) in pcs.iter().zip([0.75, 0.5, 0.25]) {
+            assert!((a - b).abs() < 0.01, "{:?}", pcs);
+        }
+
+        assert!(forward.inverse(1).is_err());
+        assert!(LutPipeline::identity(3, 6).inverse(5).is_err());
+    }
+
     #[test]
     fn test_parametric_curve_eval() {
         // Type 0: Y = X^g
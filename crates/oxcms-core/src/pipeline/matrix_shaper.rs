@@ -8,14 +8,16 @@
 //!
 //! # Transform Pipeline
 //!
-//! Source RGB → TRC decode → Matrix to XYZ → [Chromatic adaptation] → Matrix from XYZ → TRC encode → Dest RGB
+//! Source RGB → TRC decode → Matrix to XYZ → [Chromatic adaptation] → Matrix from XYZ → [Gamut mapping] → TRC encode → Dest RGB
 
 use crate::color::{WhitePoint, white_point::D50};
 use crate::icc::{IccError, IccProfile};
-use crate::math::{Matrix3x3, adaptation_matrix};
+use crate::math::{Matrix3x3, partial_adaptation_matrix};
 
 use super::context::TransformContext;
-use super::stages::TrcStage;
+use super::gamut::{GamutMapper, GamutMapping};
+use super::optimize::is_identity_chain;
+use super::stages::{MatrixStage, PipelineStage, TrcStage};
 
 /// A matrix-shaper transform pipeline
 #[derive(Debug, Clone)]
@@ -28,10 +30,14 @@ pub struct MatrixShaperPipeline {
     adaptation: Option<Matrix3x3>,
     /// Output matrix (PCS to device)
     output_matrix: Matrix3x3,
+    /// Gamut mapping of linear output (clipping when absent)
+    gamut: Option<Box<GamutMapper>>,
     /// Output TRCs (encode)
     output_trc: TrcStage,
     /// Clamp output
     clamp: bool,
+    /// Whole chain is identity, so buffers are copied unchanged
+    identity: bool,
 }
 
 impl MatrixShaperPipeline {
@@ -52,10 +58,10 @@ impl MatrixShaperPipeline {
             .inverse()
             .ok_or_else(|| IccError::CorruptedData("Destination matrix is singular".to_string()))?;
 
-        // Get TRCs
-        let src_trc = TrcStage::from_curves(src.red_trc(), src.green_trc(), src.blue_trc());
+        // Get TRCs; the destination ones are inverted, so size their reversed tables
+        let src_trc = Self::extract_trc(src, ctx);
 
-        let dst_trc = TrcStage::from_curves(dst.red_trc(), dst.green_trc(), dst.blue_trc());
+        let dst_trc = Self::extract_trc(dst, ctx).with_reverse_size(ctx.reverse_table_size);
 
         // Check if chromatic adaptation is needed
         let src_white = src.media_white_point().unwrap_or(D50.xyz);
@@ -65,19 +71,71 @@ impl MatrixShaperPipeline {
             // Need chromatic adaptation
             let src_wp = WhitePoint::from_xyz(src_white);
             let dst_wp = WhitePoint::from_xyz(dst_white);
-            Some(adaptation_matrix(&src_wp, &dst_wp, ctx.adaptation_method))
+            Some(partial_adaptation_matrix(
+                &src_wp,
+                &dst_wp,
+                ctx.adaptation_method,
+                ctx.adaptation_degree,
+            ))
         } else {
             None
         };
 
-        Ok(Self {
+        let gamut = match ctx.gamut_mapping {
+            GamutMapping::Clip => None,
+            method => Some(Box::new(GamutMapper::new(method, dst_matrix)?)),
+        };
+
+        let mut pipeline = Self {
             input_trc: src_trc,
             input_matrix: src_matrix,
             adaptation,
             output_matrix: dst_matrix_inv,
+            gamut,
             output_trc: dst_trc,
             clamp: ctx.flags.clamp_output,
-        })
+            identity: false,
+        };
+        pipeline.identity = is_identity_chain(&pipeline.stages());
+        Ok(pipeline)
+    }
+
+    /// The pipeline as a list of stages, in evaluation order
+    pub fn stages(&self) -> Vec<PipelineStage> {
+        let mut stages = vec![
+            PipelineStage::Trc(self.input_trc.clone()),
+            PipelineStage::Matrix(MatrixStage::new(self.input_matrix)),
+        ];
+        if let Some(adapt) = self.adaptation {
+            stages.push(PipelineStage::ChromaticAdaptation(adapt));
+        }
+        stages.push(PipelineStage::Matrix(MatrixStage::new(self.output_matrix)));
+        if let Some(gamut) = &self.gamut {
+            stages.push(PipelineStage::GamutMap(gamut.as_ref().clone()));
+        }
+        stages.push(PipelineStage::TrcInverse(self.output_trc.clone()));
+        if self.clamp {
+            stages.push(PipelineStage::Clamp);
+        }
+        stages
+    }
+
+    /// Whether the transform leaves in-range pixels unchanged
+    ///
+    /// True when source and destination describe the same color space (e.g.
+    /// sRGB → sRGB); the integer buffer transforms then copy their input.
+    pub fn is_identity(&self) -> bool {
+        self.identity
+    }
+
+    /// Extract the TRCs from a profile, honouring the cicp preference
+    fn extract_trc(profile: &IccProfile, ctx: &TransformContext) -> TrcStage {
+        if ctx.prefer_cicp_transfer {
+            if let Some(transfer) = profile.cicp().and_then(|c| c.transfer_function()) {
+                return TrcStage::from_transfer(transfer);
+            }
+        }
+        TrcStage::from_curves(profile.red_trc(), profile.green_trc(), profile.blue_trc())
     }
 
     /// Extract the colorant matrix from a profile
@@ -114,7 +172,10 @@ impl MatrixShaperPipeline {
         }
 
         // 4. Apply output matrix (XYZ → device)
-        let linear_out = self.output_matrix.multiply_vec(xyz);
+        let mut linear_out = self.output_matrix.multiply_vec(xyz);
+        if let Some(ref gamut) = self.gamut {
+            linear_out = gamut.map(linear_out);
+        }
 
         // 5. Apply output TRC (encode from linear)
         let mut result = self.output_trc.apply_inverse(linear_out);
@@ -136,6 +197,11 @@ impl MatrixShaperPipeline {
         assert!(src.len() % 3 == 0);
         assert!(dst.len() >= src.len());
 
+        if self.identity {
+            dst[..src.len()].copy_from_slice(src);
+            return;
+        }
+
         for (src_chunk, dst_chunk) in src.chunks_exact(3).zip(dst.chunks_exact_mut(3)) {
             let rgb = [
                 src_chunk[0] as f64 / 255.0,
@@ -156,6 +222,11 @@ impl MatrixShaperPipeline {
         assert!(src.len() % 3 == 0);
         assert!(dst.len() >= src.len());
 
+        if self.identity {
+            dst[..src.len()].copy_from_slice(src);
+            return;
+        }
+
         for (src_chunk, dst_chunk) in src.chunks_exact(3).zip(dst.chunks_exact_mut(3)) {
             let rgb = [
                 src_chunk[0] as f64 / 65535.0,
@@ -176,6 +247,11 @@ impl MatrixShaperPipeline {
         assert!(src.len() % 4 == 0);
         assert!(dst.len() >= src.len());
 
+        if self.identity {
+            dst[..src.len()].copy_from_slice(src);
+            return;
+        }
+
         for (src_chunk, dst_chunk) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
             let rgb = [
                 src_chunk[0] as f64 / 255.0,
@@ -203,6 +279,8 @@ pub struct MatrixShaperTransform {
     pub combined_matrix: Matrix3x3,
     /// Input TRCs
     pub input_trc: TrcStage,
+    /// Gamut mapping of linear output (clipping when absent)
+    pub gamut: Option<GamutMapper>,
     /// Output TRCs
     pub output_trc: TrcStage,
     /// Clamp output
@@ -230,6 +308,7 @@ impl MatrixShaperTransform {
         Ok(Self {
             combined_matrix: combined,
             input_trc: pipeline.input_trc,
+            gamut: pipeline.gamut.map(|gamut| *gamut),
             output_trc: pipeline.output_trc,
             clamp: pipeline.clamp,
         })
@@ -241,7 +320,10 @@ impl MatrixShaperTransform {
         let linear = self.input_trc.apply(rgb);
 
         // 2. Apply combined matrix
-        let linear_out = self.combined_matrix.multiply_vec(linear);
+        let mut linear_out = self.combined_matrix.multiply_vec(linear);
+        if let Some(ref gamut) = self.gamut {
+            linear_out = gamut.map(linear_out);
+        }
 
         // 3. Apply output TRC
         let mut result = self.output_trc.apply_inverse(linear_out);
@@ -277,12 +359,14 @@ mod tests {
             input_matrix: SRGB_TO_XYZ,
             adaptation: None,
             output_matrix: XYZ_TO_SRGB,
+            gamut: None,
             output_trc: TrcStage {
                 red: TrcCurve::Gamma(2.2),
                 green: TrcCurve::Gamma(2.2),
                 blue: TrcCurve::Gamma(2.2),
             },
             clamp: true,
+            identity: false,
         };
 
         // White should stay white
@@ -318,12 +402,14 @@ mod tests {
             input_matrix: Matrix3x3::identity(),
             adaptation: None,
             output_matrix: Matrix3x3::identity(),
+            gamut: None,
             output_trc: TrcStage {
                 red: TrcCurve::Identity,
                 green: TrcCurve::Identity,
                 blue: TrcCurve::Identity,
             },
             clamp: true,
+            identity: false,
         };
 
         let src = [255u8, 128, 64, 0, 255, 128];
@@ -350,12 +436,14 @@ mod tests {
             input_matrix: Matrix3x3::identity(),
             adaptation: None,
             output_matrix: Matrix3x3::identity(),
+            gamut: None,
             output_trc: TrcStage {
                 red: TrcCurve::Identity,
                 green: TrcCurve::Identity,
                 blue: TrcCurve::Identity,
             },
             clamp: true,
+            identity: false,
         };
 
         let src = [255u8, 128, 64, 200]; // RGBA with alpha=200
@@ -375,6 +463,7 @@ mod tests {
                 green: TrcCurve::Identity,
                 blue: TrcCurve::Identity,
             },
+            gamut: None,
             output_trc: TrcStage {
                 red: TrcCurve::Identity,
                 green: TrcCurve::Identity,
@@ -390,4 +479,70 @@ mod tests {
         assert!((result[1] - 0.3).abs() < 1e-10);
         assert!((result[2] - 0.7).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_same_profile_is_copied() {
+        use crate::icc::{RgbProfileBuilder, Trc};
+
+        let srgb = RgbProfileBuilder::new().build().unwrap();
+        let ctx = TransformContext::default();
+        let pipeline = MatrixShaperPipeline::from_profiles(&srgb, &srgb, &ctx).unwrap();
+        assert!(pipeline.is_identity());
+
+        let src: Vec<u16> = (0..300).map(|i| (i * 217) as u16).collect();
+        let mut dst = vec![0u16; src.len()];
+        pipeline.transform_rgb16(&src, &mut dst);
+        assert_eq!(src, dst);
+
+        let gamma = RgbProfileBuilder::new()
+            .with_trc(Trc::Gamma(2.2))
+            .build()
+            .unwrap();
+        let pipeline = MatrixShaperPipeline::from_profiles(&srgb, &gamma, &ctx).unwrap();
+        assert!(!pipeline.is_identity());
+    }
+
+    #[test]
+    fn test_gamut_mapping_stage() {
+        use crate::icc::RgbProfileBuilder;
+        use crate::pipeline::GamutMapping;
+
+        let srgb = RgbProfileBuilder::new().build().unwrap();
+        let p3 = RgbProfileBuilder::new()
+            .with_primaries((0.680, 0.320), (0.265, 0.690), (0.150, 0.060))
+            .build()
+            .unwrap();
+
+        let clip = TransformContext::default();
+        let mapped = TransformContext::default().with_gamut_mapping(GamutMapping::OklchChroma);
+        let clipping = MatrixShaperPipeline::from_profiles(&p3, &srgb, &clip).unwrap();
+        let mapping = MatrixShaperPipeline::from_profiles(&p3, &srgb, &mapped).unwrap();
+        assert!(
+            mapping
+                .stages()
+                .iter()
+                .any(|s| matches!(s, PipelineStage::GamutMap(_)))
+        );
+
+        // In-gamut colors are untouched
+        let gray = [0.5, 0.5, 0.5];
+        let (a, b) = (clipping.transform_rgb(gray), mapping.transform_rgb(gray));
+        assert!((0..3).all(|c| (a[c] - b[c]).abs() < 1e-12));
+
+        // P3 green is reduced in chroma instead of clipped: red stays at 0
+        let green = mapping.transform_rgb([0.0, 1.0, 0.0]);
+        assert!(green.iter().all(|v| (0.0..=1.0).contains(v)));
+        assert!(green[0] < 0.3, "{:?}", green);
+        assert!(green[0] > 0.0 || green[2] > 0.0);
+
+        // Mapping implies clamping, even with clamp_output off
+        let mut unclamped = mapped.clone();
+        unclamped.flags.clamp_output = false;
+        let mapping = MatrixShaperPipeline::from_profiles(&p3, &srgb, &unclamped).unwrap();
+        assert_eq!(mapping.transform_rgb([0.0, 1.0, 0.0]), green);
+
+        // Same image, same profile: still an identity chain
+        let same = MatrixShaperPipeline::from_profiles(&srgb, &srgb, &mapped).unwrap();
+        assert!(same.is_identity());
+    }
 }
@@ -19,20 +19,39 @@
 //!
 //! For LUT profiles:
 //! - Use A2B/B2A lookup tables directly
+//! - Optionally invert A2B numerically when a destination has no B2A
+//! - Optionally precalculate the whole chain into one 16-bit device link
+//!
+//! Matrix-shaper outputs can be gamut mapped with a hue-preserving
+//! [`GamutMapping`] instead of clipped.
+//!
+//! Stage lists can be simplified with [`optimize_stages`]; a chain that
+//! reduces to identity (e.g. sRGB → sRGB) is executed as a copy.
 
 mod bpc;
+mod calibration;
 mod context;
+mod gamut;
 mod lut;
 mod matrix_shaper;
+mod optimize;
+mod precalc;
 mod stages;
 
 pub use bpc::{BpcParams, detect_black_point};
+pub use calibration::Calibration;
 pub use context::{RenderIntent, TransformContext, TransformFlags};
+pub use gamut::{GamutMapper, GamutMapping};
+pub(crate) use lut::eval_parametric;
 pub use lut::{ClutData, LutCurve, LutPipeline};
 pub use matrix_shaper::{MatrixShaperPipeline, MatrixShaperTransform};
-pub use stages::{MatrixStage, PipelineStage, TrcStage};
+pub use optimize::{IDENTITY_TOLERANCE, is_identity_chain, optimize_stages};
+pub use precalc::PrecalculatedLut;
+pub use stages::{MatrixStage, PipelineStage, TrcCurve, TrcStage};
+
+use std::sync::{Arc, Mutex};
 
-use crate::icc::{IccError, IccProfile, IccRenderingIntent, TagSignature};
+use crate::icc::{IccError, IccProfile, IccRenderingIntent, TagData, TagSignature, write_tag};
 
 /// A complete color transform pipeline
 #[derive(Debug, Clone)]
@@ -48,6 +67,8 @@ pub enum Pipeline {
         /// Destination profile LUT (PCS → device)
         destination: LutPipeline,
     },
+    /// LUT pipeline sampled into one fixed-point device link
+    Precalculated(PrecalculatedLut),
 }
 
 impl Pipeline {
@@ -86,18 +107,52 @@ impl Pipeline {
             .ok_or(IccError::MissingTag(TagSignature::A2B0.0))?;
         let source_lut = LutPipeline::from_tag_data(src_tag)?;
 
-        // Get destination B2A LUT (PCS → device)
-        let dst_tag = dst
-            .b2a_for_intent(intent)
-            .ok_or(IccError::MissingTag(TagSignature::B2A0.0))?;
-        let destination_lut = LutPipeline::from_tag_data(dst_tag)?;
+        // Get destination B2A LUT (PCS → device), or invert A2B if allowed
+        let destination_lut = match (dst.b2a_for_intent(intent), ctx.a2b_inversion_grid) {
+            (Some(dst_tag), _) => LutPipeline::from_tag_data(dst_tag)?,
+            (None, Some(grid_points)) => {
+                let dst_tag = dst
+                    .a2b_for_intent(intent)
+                    .ok_or(IccError::MissingTag(TagSignature::B2A0.0))?;
+                cached_a2b_inverse(dst, dst_tag, grid_points, ctx.reverse_table_size)?
+                    .as_ref()
+                    .clone()
+            }
+            (None, None) => return Err(IccError::MissingTag(TagSignature::B2A0.0)),
+        };
 
         Ok(Pipeline::ChainedLut {
-            source: source_lut,
-            destination: destination_lut,
+            source: source_lut.optimize(),
+            destination: destination_lut.optimize(),
         })
     }
 
+    /// Sample a LUT-based pipeline into a precalculated device link
+    ///
+    /// The grid size follows `ctx.flags.high_precision`. Matrix-shaper and
+    /// already precalculated pipelines are returned unchanged, as are LUT
+    /// pipelines with other than three or four inputs (gray, two-channel and
+    /// multi-ink sources).
+    pub fn precalculate(&self, ctx: &TransformContext) -> Result<Self, IccError> {
+        let stages = match self {
+            Pipeline::Lut(p) => vec![p],
+            Pipeline::ChainedLut {
+                source,
+                destination,
+            } => vec![source, destination],
+            Pipeline::MatrixShaper(_) | Pipeline::Precalculated(_) => return Ok(self.clone()),
+        };
+        if !(3..=4).contains(&stages[0].input_channels) {
+            return Ok(self.clone());
+        }
+        let grid_points =
+            PrecalculatedLut::grid_points_for(stages[0].input_channels, ctx.flags.high_precision);
+        Ok(Pipeline::Precalculated(PrecalculatedLut::from_stages(
+            &stages,
+            grid_points,
+        )?))
+    }
+
     /// Create a pipeline for CMYK → RGB transform
     pub fn cmyk_to_rgb(
         cmyk_profile: &IccProfile,
@@ -144,6 +199,14 @@ impl Pipeline {
                 // Destination: PCS → device (3 channels for RGB)
                 destination.transform_rgb(pcs)
             }
+            Pipeline::Precalculated(p) => {
+                let result = p.eval(&rgb);
+                [
+                    result.first().copied().unwrap_or(0.0),
+                    result.get(1).copied().unwrap_or(0.0),
+                    result.get(2).copied().unwrap_or(0.0),
+                ]
+            }
         }
     }
 
@@ -167,6 +230,14 @@ impl Pipeline {
                 // Destination: PCS → RGB (via B2A LUT)
                 destination.transform_rgb(pcs_rgb)
             }
+            Pipeline::Precalculated(p) => {
+                let result = p.eval(&cmyk);
+                [
+                    result.first().copied().unwrap_or(0.0),
+                    result.get(1).copied().unwrap_or(0.0),
+                    result.get(2).copied().unwrap_or(0.0),
+                ]
+            }
             Pipeline::Lut(p) => {
                 // Direct CMYK → RGB LUT
                 let result = p.transform(&cmyk);
@@ -204,6 +275,15 @@ impl Pipeline {
                     result.get(3).copied().unwrap_or(0.0),
                 ]
             }
+            Pipeline::Precalculated(p) => {
+                let result = p.eval(&rgb);
+                [
+                    result.first().copied().unwrap_or(0.0),
+                    result.get(1).copied().unwrap_or(0.0),
+                    result.get(2).copied().unwrap_or(0.0),
+                    result.get(3).copied().unwrap_or(0.0),
+                ]
+            }
             Pipeline::Lut(p) => {
                 // Direct RGB → CMYK LUT
                 let result = p.transform(&rgb);
@@ -228,6 +308,7 @@ impl Pipeline {
 
         match self {
             Pipeline::MatrixShaper(p) => p.transform_rgb8(src, dst),
+            Pipeline::Precalculated(p) => p.transform8(src, dst),
             Pipeline::Lut(_) | Pipeline::ChainedLut { .. } => {
                 for (src_chunk, dst_chunk) in src.chunks_exact(3).zip(dst.chunks_exact_mut(3)) {
                     let rgb = [
@@ -251,6 +332,7 @@ impl Pipeline {
 
         match self {
             Pipeline::MatrixShaper(p) => p.transform_rgb16(src, dst),
+            Pipeline::Precalculated(p) => p.transform16(src, dst),
             Pipeline::Lut(_) | Pipeline::ChainedLut { .. } => {
                 for (src_chunk, dst_chunk) in src.chunks_exact(3).zip(dst.chunks_exact_mut(3)) {
                     let rgb = [
@@ -274,6 +356,14 @@ impl Pipeline {
 
         match self {
             Pipeline::MatrixShaper(p) => p.transform_rgba8(src, dst),
+            Pipeline::Precalculated(p) => {
+                let mut out = [0u8; 3];
+                for (src_chunk, dst_chunk) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+                    p.transform8(&src_chunk[..3], &mut out);
+                    dst_chunk[..3].copy_from_slice(&out);
+                    dst_chunk[3] = src_chunk[3]; // Preserve alpha
+                }
+            }
             Pipeline::Lut(_) | Pipeline::ChainedLut { .. } => {
                 for (src_chunk, dst_chunk) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
                     let rgb = [
@@ -297,6 +387,11 @@ impl Pipeline {
         let pixel_count = src.len() / 4;
         assert!(dst.len() >= pixel_count * 3);
 
+        if let Pipeline::Precalculated(p) = self {
+            p.transform8(src, dst);
+            return;
+        }
+
         for (src_chunk, dst_chunk) in src.chunks_exact(4).zip(dst.chunks_exact_mut(3)) {
             let cmyk = [
                 src_chunk[0] as f64 / 255.0,
@@ -317,6 +412,11 @@ impl Pipeline {
         let pixel_count = src.len() / 3;
         assert!(dst.len() >= pixel_count * 4);
 
+        if let Pipeline::Precalculated(p) = self {
+            p.transform8(src, dst);
+            return;
+        }
+
         for (src_chunk, dst_chunk) in src.chunks_exact(3).zip(dst.chunks_exact_mut(4)) {
             let rgb = [
                 src_chunk[0] as f64 / 255.0,
@@ -337,6 +437,11 @@ impl Pipeline {
         let pixel_count = src.len() / 4;
         assert!(dst.len() >= pixel_count * 3);
 
+        if let Pipeline::Precalculated(p) = self {
+            p.transform16(src, dst);
+            return;
+        }
+
         for (src_chunk, dst_chunk) in src.chunks_exact(4).zip(dst.chunks_exact_mut(3)) {
             let cmyk = [
                 src_chunk[0] as f64 / 65535.0,
@@ -357,6 +462,11 @@ impl Pipeline {
         let pixel_count = src.len() / 3;
         assert!(dst.len() >= pixel_count * 4);
 
+        if let Pipeline::Precalculated(p) = self {
+            p.transform16(src, dst);
+            return;
+        }
+
         for (src_chunk, dst_chunk) in src.chunks_exact(3).zip(dst.chunks_exact_mut(4)) {
             let rgb = [
                 src_chunk[0] as f64 / 65535.0,
@@ -372,6 +482,59 @@ impl Pipeline {
     }
 }
 
+/// Inverted A2B tables kept by [`cached_a2b_inverse`]
+const INVERSE_CACHE_SIZE: usize = 8;
+
+/// Serialized A2B tag, grid size and reverse table size
+type InverseKey = (Vec<u8>, usize, usize);
+
+/// Recently inverted A2B tables, oldest first
+static INVERSE_CACHE: Mutex<Vec<(InverseKey, Arc<LutPipeline>)>> = Mutex::new(Vec::new());
+
+/// Invert an A2B tag into a B2A stand-in, reusing earlier results
+///
+/// The numerical search takes seconds for large grids, so inverses are
+/// cached by tag content, grid size and reverse table size. Identical
+/// tables in different profiles, or in different intents of one profile,
+/// share an entry.
+fn cached_a2b_inverse(
+    profile: &IccProfile,
+    tag: &TagData,
+    grid_points: usize,
+    reverse_size: usize,
+) -> Result<Arc<LutPipeline>, IccError> {
+    let key = (
+        write_tag(tag, TagSignature::A2B0, profile.header.version)?,
+        grid_points,
+        reverse_size,
+    );
+    let lookup = |cache: &Vec<(InverseKey, Arc<LutPipeline>)>| {
+        cache
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, inverse)| Arc::clone(inverse))
+    };
+    if let Some(inverse) = lookup(&INVERSE_CACHE.lock().unwrap_or_else(|e| e.into_inner())) {
+        return Ok(inverse);
+    }
+
+    // Search without holding the lock; a concurrent miss just repeats the work
+    let inverse = Arc::new(
+        LutPipeline::from_tag_data(tag)?
+            .with_reverse_size(reverse_size)
+            .inverse(grid_points)?,
+    );
+    let mut cache = INVERSE_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = lookup(&cache) {
+        return Ok(existing);
+    }
+    if cache.len() == INVERSE_CACHE_SIZE {
+        cache.remove(0);
+    }
+    cache.push((key, Arc::clone(&inverse)));
+    Ok(inverse)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -494,6 +657,86 @@ mod tests {
         assert_eq!(cmyk8[3], 0); // K channel from identity is 0
     }
 
+    #[test]
+    fn test_destination_from_inverted_a2b() {
+        use crate::color::Lab;
+        use crate::icc::{AbstractProfileBuilder, LabIdentityBuilder};
+
+        let src = LabIdentityBuilder::new().build().unwrap();
+        // Abstract profiles carry only A2B0
+        let dst = AbstractProfileBuilder::new(|lab: Lab| Lab::new(lab.l, lab.a * 0.5, lab.b * 0.5))
+            .with_grid_points(9)
+            .build()
+            .unwrap();
+
+        let ctx = TransformContext::new().with_intent(RenderIntent::Perceptual);
+        assert!(matches!(
+            Pipeline::from_profiles(&src, &dst, &ctx),
+            Err(IccError::MissingTag(_))
+        ));
+
+        let pipeline = Pipeline::from_profiles(&src, &dst, &ctx.with_a2b_inversion(17)).unwrap();
+        let encode = |l: f64, a: f64, b: f64| [l / 100.0, (a + 128.0) / 255.0, (b + 128.0) / 255.0];
+        let out = pipeline.transform_rgb(encode(50.0, 20.0, -10.0));
+        let expected = encode(50.0, 40.0, -20.0);
+        for (a, b) in out.iter().zip(expected) {
+            assert!((a - b).abs() < 0.01, "{:?} vs {:?}", out, expected);
+        }
+    }
+
+    #[test]
+    fn test_a2b_inverse_cached() {
+        use crate::color::Lab;
+        use crate::icc::AbstractProfileBuilder;
+
+        // A transform no other test uses, so the entries are ours
+        let dst = AbstractProfileBuilder::new(|lab: Lab| Lab::new(lab.l, lab.b, lab.a))
+            .with_grid_points(5)
+            .build()
+            .unwrap();
+        let tag = dst.a2b_for_intent(IccRenderingIntent::Perceptual).unwrap();
+
+        let first = cached_a2b_inverse(&dst, tag, 9, 256).unwrap();
+        let again = cached_a2b_inverse(&dst, tag, 9, 256).unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+
+        let finer = cached_a2b_inverse(&dst, tag, 11, 256).unwrap();
+        assert!(!Arc::ptr_eq(&first, &finer));
+    }
+
+    #[test]
+    fn test_precalculated_cmyk_pipeline() {
+        let source = LutPipeline::from_clut(ClutData::sample(4, 9, 3, |p| {
+            (0..3).map(|i| (1.0 - p[i]) * (1.0 - p[3])).collect()
+        }));
+        let pipeline = Pipeline::ChainedLut {
+            source,
+            destination: LutPipeline::identity(3, 3),
+        };
+        let fast = pipeline.precalculate(&TransformContext::new()).unwrap();
+        assert!(matches!(&fast, Pipeline::Precalculated(p) if p.grid_points() == 17));
+
+        let src: Vec<u8> = (0..64u8)
+            .flat_map(|i| [i * 4, 255 - i * 4, i * 2, i])
+            .collect();
+        let mut expected = vec![0u8; 64 * 3];
+        let mut actual = vec![0u8; 64 * 3];
+        pipeline.transform_cmyk8_to_rgb8(&src, &mut expected);
+        fast.transform_cmyk8_to_rgb8(&src, &mut actual);
+        for (a, e) in actual.iter().zip(&expected) {
+            assert!((*a as i32 - *e as i32).abs() <= 1, "{} vs {}", a, e);
+        }
+    }
+
+    #[test]
+    fn test_precalculate_keeps_other_input_counts() {
+        for inputs in [1, 2, 6] {
+            let pipeline = Pipeline::Lut(LutPipeline::identity(inputs, 3));
+            let kept = pipeline.precalculate(&TransformContext::new()).unwrap();
+            assert!(matches!(kept, Pipeline::Lut(ref p) if p.input_channels == inputs));
+        }
+    }
+
     #[test]
     fn test_16bit_cmyk_transforms() {
         // Test 16-bit transforms
@@ -514,4 +757,60 @@ mod tests {
         assert_eq!(rgb16[1], 16384);
         assert_eq!(rgb16[2], 8192);
     }
+
+    #[test]
+    fn test_cicp_transfer_preferred() {
+        use crate::icc::tags::CicpData;
+        use crate::icc::{RgbProfileBuilder, TagData, Trc};
+        use crate::math::TransferFunction;
+
+        // BT.2020 primaries with a gamma 2.2 TRC but a cicp tag naming PQ or HLG
+        let primaries = ((0.708, 0.292), (0.170, 0.797), (0.131, 0.046));
+        let tagged = |transfer_characteristics| {
+            let mut profile = RgbProfileBuilder::new()
+                .with_primaries(primaries.0, primaries.1, primaries.2)
+                .with_trc(Trc::Gamma(2.2))
+                .build()
+                .unwrap();
+            profile.set_tag(
+                TagSignature::CICP,
+                TagData::Cicp(CicpData {
+                    color_primaries: 9,
+                    transfer_characteristics,
+                    matrix_coefficients: 0,
+                    full_range: true,
+                }),
+            );
+            IccProfile::parse(&profile.to_bytes().unwrap()).unwrap()
+        };
+        let linear = RgbProfileBuilder::new()
+            .with_primaries(primaries.0, primaries.1, primaries.2)
+            .with_trc(Trc::Gamma(1.0))
+            .build()
+            .unwrap();
+
+        let ctx = TransformContext::default().with_cicp_transfer(true);
+        for (code, transfer) in [(16, TransferFunction::Pq), (18, TransferFunction::Hlg)] {
+            let src = tagged(code);
+            let pipeline = Pipeline::from_profiles(&src, &linear, &ctx).unwrap();
+            for x in [0.1, 0.5, 0.75] {
+                let out = pipeline.transform_rgb([x, x, x]);
+                let expected = transfer.decode(x);
+                assert!(
+                    out.iter().all(|v| (v - expected).abs() < 1e-3),
+                    "{:?} {}: {:?} vs {}",
+                    transfer,
+                    x,
+                    out,
+                    expected
+                );
+            }
+
+            // Without the preference the TRC tags are used
+            let pipeline =
+                Pipeline::from_profiles(&src, &linear, &TransformContext::default()).unwrap();
+            let out = pipeline.transform_rgb([0.5, 0.5, 0.5]);
+            assert!((out[1] - 0.5f64.powf(2.2)).abs() < 1e-3, "{:?}", out);
+        }
+    }
 }
@@ -0,0 +1,247 @@
+//! Pipeline Optimization
+//!
+//! Simplifies a stage list before it is evaluated, in the spirit of lcms2's
+//! `_cmsOptimizePipeline`:
+//! - adjacent matrices (including chromatic adaptation) are multiplied into one
+//! - adjacent per-channel curves are composed into one sampled table
+//! - identity matrices and repeated clamps are dropped
+//! - identity curves are replaced by a clamp, since curves clamp their input
+//!
+//! A chain that optimizes down to nothing but clamps is an identity
+//! transform for in-range data and can be replaced by a copy.
+
+use crate::math::Matrix3x3;
+
+use super::stages::{MatrixStage, PipelineStage, TrcCurve, TrcStage};
+
+/// Largest deviation that still counts as identity (one 16-bit code value)
+pub const IDENTITY_TOLERANCE: f64 = 1.0 / 65535.0;
+
+/// Entries of the table two composed curves are sampled into
+pub const COMPOSED_CURVE_SAMPLES: usize = 4096;
+
+/// Simplify a stage list without changing what it computes
+///
+/// Composed curves are resampled into a 4096-entry table, so the result is
+/// exact only up to that table's interpolation error.
+pub fn optimize_stages(stages: &[PipelineStage]) -> Vec<PipelineStage> {
+    let mut out: Vec<PipelineStage> = Vec::with_capacity(stages.len());
+
+    for stage in stages {
+        if is_identity_stage(stage) {
+            if is_curve(stage) {
+                push_clamp(&mut out);
+            }
+            continue;
+        }
+        match out.last().and_then(|prev| merge(prev, stage)) {
+            Some(merged) => {
+                out.pop();
+                if !is_identity_stage(&merged) {
+                    out.push(merged);
+                } else if is_curve(&merged) {
+                    push_clamp(&mut out);
+                }
+            }
+            None => out.push(stage.clone()),
+        }
+    }
+
+    out
+}
+
+/// Keep the [0, 1] clamp of a dropped curve, unless one is already last
+fn push_clamp(out: &mut Vec<PipelineStage>) {
+    if !matches!(out.last(), Some(PipelineStage::Clamp)) {
+        out.push(PipelineStage::Clamp);
+    }
+}
+
+/// Whether a stage list is an identity transform for data in [0, 1]
+///
+/// Clamps and gamut mapping that survive optimization are ignored since
+/// they do not change in-range values.
+pub fn is_identity_chain(stages: &[PipelineStage]) -> bool {
+    optimize_stages(stages)
+        .iter()
+        .all(|stage| matches!(stage, PipelineStage::Clamp | PipelineStage::GamutMap(_)))
+}
+
+/// Whether a single stage leaves its input unchanged
+fn is_identity_stage(stage: &PipelineStage) -> bool {
+    match stage {
+        PipelineStage::Trc(trc) | PipelineStage::TrcInverse(trc) => {
+            trc.is_identity(IDENTITY_TOLERANCE)
+        }
+        PipelineStage::Matrix(mat) => mat.is_identity(IDENTITY_TOLERANCE),
+        PipelineStage::ChromaticAdaptation(mat) => mat.is_identity(IDENTITY_TOLERANCE),
+        PipelineStage::Clamp | PipelineStage::GamutMap(_) => false,
+    }
+}
+
+/// Join two adjacent stages into one, if they are of compatible kinds
+fn merge(first: &PipelineStage, second: &PipelineStage) -> Option<PipelineStage> {
+    if matches!(
+        (first, second),
+        (PipelineStage::Clamp, PipelineStage::Clamp)
+    ) {
+        return Some(PipelineStage::Clamp);
+    }
+    if let (Some(a), Some(b)) = (matrix_of(first), matrix_of(second)) {
+        return Some(PipelineStage::Matrix(MatrixStage::new(b.multiply(&a))));
+    }
+    if is_curve(first) && is_curve(second) {
+        return Some(PipelineStage::Trc(compose_curves(first, second)));
+    }
+    None
+}
+
+/// The forward matrix of a matrix-like stage
+fn matrix_of(stage: &PipelineStage) -> Option<Matrix3x3> {
+    match stage {
+        PipelineStage::Matrix(mat) => Some(mat.matrix),
+        PipelineStage::ChromaticAdaptation(mat) => Some(*mat),
+        _ => None,
+    }
+}
+
+fn is_curve(stage: &PipelineStage) -> bool {
+    matches!(stage, PipelineStage::Trc(_) | PipelineStage::TrcInverse(_))
+}
+
+/// Sample `second ∘ first` per channel into one decode table
+fn compose_curves(first: &PipelineStage, second: &PipelineStage) -> TrcStage {
+    let last = (COMPOSED_CURVE_SAMPLES - 1) as f64;
+    let mut tables = [
+        Vec::with_capacity(COMPOSED_CURVE_SAMPLES),
+        Vec::with_capacity(COMPOSED_CURVE_SAMPLES),
+        Vec::with_capacity(COMPOSED_CURVE_SAMPLES),
+    ];
+    for i in 0..COMPOSED_CURVE_SAMPLES {
+        let x = i as f64 / last;
+        let y = second.apply_rgb(first.apply_rgb([x, x, x]));
+        for (table, v) in tables.iter_mut().zip(y) {
+            table.push(v);
+        }
+    }
+    let [red, green, blue] = tables.map(|table| TrcCurve::Table(table.into()));
+    TrcStage { red, green, blue }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::TransferFunction;
+    use crate::math::matrix::{SRGB_TO_XYZ, XYZ_TO_SRGB};
+
+    fn gamma(g: f64) -> TrcStage {
+        TrcStage {
+            red: TrcCurve::Gamma(g),
+            green: TrcCurve::Gamma(g),
+            blue: TrcCurve::Gamma(g),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_chain_is_identity() {
+        let stages = [
+            PipelineStage::Trc(TrcStage::from_transfer(TransferFunction::Srgb)),
+            PipelineStage::Matrix(MatrixStage::new(SRGB_TO_XYZ)),
+            PipelineStage::Matrix(MatrixStage::new(XYZ_TO_SRGB)),
+            PipelineStage::TrcInverse(TrcStage::from_transfer(TransferFunction::Srgb)),
+            PipelineStage::Clamp,
+        ];
+        assert!(is_identity_chain(&stages));
+        assert!(matches!(
+            optimize_stages(&stages).as_slice(),
+            [PipelineStage::Clamp]
+        ));
+    }
+
+    #[test]
+    fn test_matrices_merge() {
+        let scale = Matrix3x3::new([[2.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+        let stages = [
+            PipelineStage::Matrix(MatrixStage::new(SRGB_TO_XYZ)),
+            PipelineStage::ChromaticAdaptation(scale),
+        ];
+        let optimized = optimize_stages(&stages);
+        assert_eq!(optimized.len(), 1);
+
+        let rgb = [0.2, 0.5, 0.8];
+        let expected = stages.iter().fold(rgb, |acc, stage| stage.apply_rgb(acc));
+        let got = optimized[0].apply_rgb(rgb);
+        for c in 0..3 {
+            assert!((got[c] - expected[c]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_curves_compose() {
+        let stages = [
+            PipelineStage::Trc(gamma(2.0)),
+            PipelineStage::TrcInverse(gamma(4.0)),
+        ];
+        let optimized = optimize_stages(&stages);
+        assert_eq!(optimized.len(), 1);
+        assert!(!is_identity_chain(&stages));
+
+        // x^2 then x^(1/4) is x^0.5
+        for i in 1..=10 {
+            let x = i as f64 / 10.0;
+            let got = optimized[0].apply_rgb([x, x, x]);
+            assert!((got[1] - x.sqrt()).abs() < 1e-3, "{}: {}", x, got[1]);
+        }
+    }
+
+    #[test]
+    fn test_identity_stages_dropped() {
+        let ramp: Vec<f64> = (0..256).map(|i| i as f64 / 255.0).collect();
+        let stages = [
+            PipelineStage::Trc(TrcStage {
+                red: TrcCurve::Identity,
+                green: TrcCurve::Table(ramp.into()),
+                blue: TrcCurve::Gamma(1.0),
+            }),
+            PipelineStage::Matrix(MatrixStage::new(Matrix3x3::identity())),
+            PipelineStage::Clamp,
+            PipelineStage::Clamp,
+        ];
+        assert!(matches!(
+            optimize_stages(&stages).as_slice(),
+            [PipelineStage::Clamp]
+        ));
+    }
+
+    #[test]
+    fn test_non_identity_kept() {
+        let stages = [
+            PipelineStage::Trc(gamma(2.2)),
+            PipelineStage::Clamp,
+            PipelineStage::TrcInverse(gamma(2.2)),
+        ];
+        // The clamp keeps the curves apart
+        assert_eq!(optimize_stages(&stages).len(), 3);
+        assert!(!is_identity_chain(&stages));
+    }
+
+    #[test]
+    fn test_identity_curve_keeps_clamp() {
+        let stages = [
+            PipelineStage::Matrix(MatrixStage::new(SRGB_TO_XYZ)),
+            PipelineStage::TrcInverse(gamma(1.0)),
+        ];
+        let optimized = optimize_stages(&stages);
+        assert!(matches!(
+            optimized.as_slice(),
+            [PipelineStage::Matrix(_), PipelineStage::Clamp]
+        ));
+
+        let rgb = [1.0, 1.0, 1.0];
+        let expected = stages.iter().fold(rgb, |acc, stage| stage.apply_rgb(acc));
+        let got = optimized
+            .iter()
+            .fold(rgb, |acc, stage| stage.apply_rgb(acc));
+        assert_eq!(got, expected);
+    }
+}
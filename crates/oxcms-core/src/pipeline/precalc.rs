@@ -0,0 +1,416 @@
+//! Precalculated Device Links
+//!
+//! A LUT-based pipeline is sampled once into a single device-to-device
+//! CLUT of 16-bit nodes, the way lcms2 builds precalculated transforms.
+//! Pixels are then evaluated with fixed-point tetrahedral interpolation
+//! (plus a linear blend over the first input for 4D tables), so each pixel
+//! costs a few integer operations instead of the full f64 stage chain.
+//!
+//! When the first stage starts with non-identity input curves they are kept
+//! as 16-bit prelinearization tables in front of the grid, and the grid is
+//! sampled in the linearized space. Strongly nonlinear encodings then do not
+//! waste grid resolution.
+
+use super::lut::{ClutData, LutCurve, LutPipeline};
+use crate::icc::IccError;
+
+/// Entries in each prelinearization table
+const PRELINEARIZATION_ENTRIES: usize = 4096;
+
+/// Largest output count of an ICC CLUT
+const MAX_OUTPUTS: usize = 15;
+
+/// A whole pipeline sampled into one 16-bit CLUT
+#[derive(Debug, Clone)]
+pub struct PrecalculatedLut {
+    input_channels: usize,
+    output_channels: usize,
+    grid_points: usize,
+    /// Per-input curves applied before the grid lookup
+    prelinearization: Option<Vec<Vec<u16>>>,
+    /// Grid nodes, first input slowest, `output_channels` values per node
+    table: Vec<u16>,
+    /// Distance in `table` between neighbouring nodes along each input
+    strides: Vec<usize>,
+}
+
+impl PrecalculatedLut {
+    /// Grid size for a number of inputs, following lcms2's choices
+    ///
+    /// 33 points for three inputs and 17 for four, or 49 and 23 when
+    /// `high_precision` is set (as for 16-bit and float data). Only three and
+    /// four inputs are precalculated, so any other count gets the 3-input size.
+    pub fn grid_points_for(input_channels: usize, high_precision: bool) -> usize {
+        match (input_channels == 4, high_precision) {
+            (true, true) => 23,
+            (true, false) => 17,
+            (false, true) => 49,
+            (false, false) => 33,
+        }
+    }
+
+    /// Sample a chain of LUT stages (device → device) into one table
+    ///
+    /// The stages run in order, each feeding the next. Three and four
+    /// inputs are supported.
+    pub fn from_stages(stages: &[&LutPipeline], grid_points: usize) -> Result<Self, IccError> {
+        let (Some(first), Some(last)) = (stages.first(), stages.last()) else {
+            return Err(IccError::Unsupported(
+                "precalculating an empty pipeline".to_string(),
+            ));
+        };
+        let input_channels = first.input_channels;
+        let output_channels = last.output_channels;
+        if !(3..=4).contains(&input_channels) {
+            return Err(IccError::Unsupported(format!(
+                "precalculated LUT with {} inputs",
+                input_channels
+            )));
+        }
+        if !(1..=MAX_OUTPUTS).contains(&output_channels) {
+            return Err(IccError::Unsupported(format!(
+                "precalculated LUT with {} outputs",
+                output_channels
+            )));
+        }
+        if !(2..=255).contains(&grid_points) {
+            return Err(IccError::Unsupported(format!(
+                "precalculated LUT grid of {} points",
+                grid_points
+            )));
+        }
+
+        let curves = first.input_curves();
+        let prelinearized =
+            !first.has_matrix() && curves.iter().any(|c| !matches!(c, LutCurve::Identity));
+        let prelinearization = prelinearized.then(|| {
+            (0..input_channels)
+                .map(|ch| {
+                    let curve = &curves[ch % curves.len()];
+                    (0..PRELINEARIZATION_ENTRIES)
+                        .map(|i| {
+                            let x = i as f64 / (PRELINEARIZATION_ENTRIES - 1) as f64;
+                            to_u16(curve.eval(x))
+                        })
+                        .collect()
+                })
+                .collect()
+        });
+
+        let clut = ClutData::sample(input_channels, grid_points, output_channels, |node| {
+            let mut values = if prelinearized {
+                first.transform_from_clut(node)
+            } else {
+                first.transform(node)
+            };
+            for stage in &stages[1..] {
+                values = stage.transform(&values);
+            }
+            values.resize(output_channels, 0.0);
+            values
+        });
+
+        let mut strides = vec![0; input_channels];
+        let mut stride = output_channels;
+        for d in (0..input_channels).rev() {
+            strides[d] = stride;
+            stride *= grid_points;
+        }
+
+        Ok(Self {
+            input_channels,
+            output_channels,
+            grid_points,
+            prelinearization,
+            table: clut.data.into_iter().map(to_u16).collect(),
+            strides,
+        })
+    }
+
+    /// Number of input channels
+    pub fn input_channels(&self) -> usize {
+        self.input_channels
+    }
+
+    /// Number of output channels
+    pub fn output_channels(&self) -> usize {
+        self.output_channels
+    }
+
+    /// Grid points per input
+    pub fn grid_points(&self) -> usize {
+        self.grid_points
+    }
+
+    /// Whether input curves are applied ahead of the grid
+    pub fn is_prelinearized(&self) -> bool {
+        self.prelinearization.is_some()
+    }
+
+    /// Evaluate one pixel in the 16-bit domain
+    ///
+    /// `input` holds at least `input_channels` values and `output` at least
+    /// `output_channels`.
+    pub fn eval16(&self, input: &[u16], output: &mut [u16]) {
+        let mut x = [0u16; 4];
+        for (ch, v) in x.iter_mut().take(self.input_channels).enumerate() {
+            *v = match &self.prelinearization {
+                Some(tables) => prelinearize(&tables[ch], input[ch]),
+                None => input[ch],
+            };
+        }
+        let output = &mut output[..self.output_channels];
+
+        if self.input_channels == 3 {
+            self.tetrahedral(0, &x[..3], output);
+            return;
+        }
+
+        // 4D: blend two 3D lookups along the first (slowest) input
+        let (k0, k1, rk) = self.locate(x[0], self.strides[0]);
+        self.tetrahedral(k0, &x[1..4], output);
+        if rk == 0 {
+            return;
+        }
+        let mut upper = [0u16; MAX_OUTPUTS];
+        let upper = &mut upper[..self.output_channels];
+        self.tetrahedral(k1, &x[1..4], upper);
+        for (v, &u) in output.iter_mut().zip(upper.iter()) {
+            let lo = *v as i64;
+            *v = (lo + (((u as i64 - lo) * rk + 0x8000) >> 16)) as u16;
+        }
+    }
+
+    /// Evaluate one pixel of normalized [0, 1] values
+    pub fn eval(&self, input: &[f64]) -> Vec<f64> {
+        let mut x = [0u16; 4];
+        for (v, &i) in x.iter_mut().zip(input) {
+            *v = to_u16(i);
+        }
+        let mut out = [0u16; MAX_OUTPUTS];
+        self.eval16(&x, &mut out);
+        out[..self.output_channels]
+            .iter()
+            .map(|&v| v as f64 / 65535.0)
+            .collect()
+    }
+
+    /// Transform packed 8-bit pixels of exactly the input and output channels
+    pub fn transform8(&self, src: &[u8], dst: &mut [u8]) {
+        let mut x = [0u16; 4];
+        let mut out = [0u16; MAX_OUTPUTS];
+        for (s, d) in src
+            .chunks_exact(self.input_channels)
+            .zip(dst.chunks_exact_mut(self.output_channels))
+        {
+            for (v, &i) in x.iter_mut().zip(s) {
+                *v = i as u16 * 257;
+            }
+            self.eval16(&x, &mut out);
+            for (o, &v) in d.iter_mut().zip(out.iter()) {
+                *o = from_16_to_8(v);
+            }
+        }
+    }
+
+    /// Transform packed 16-bit pixels of exactly the input and output channels
+    pub fn transform16(&self, src: &[u16], dst: &mut [u16]) {
+        for (s, d) in src
+            .chunks_exact(self.input_channels)
+            .zip(dst.chunks_exact_mut(self.output_channels))
+        {
+            self.eval16(s, d);
+        }
+    }
+
+    /// Base offset, next-node offset and 16-bit fraction along one input
+    #[inline]
+    fn locate(&self, v: u16, stride: usize) -> (usize, usize, i64) {
+        let domain = (self.grid_points - 1) as u32;
+        // v · domain in 16.16 fixed point, scaled so 0xffff lands on the last node
+        let a = v as u32 * domain;
+        let fixed = a + (a + 0x7fff) / 0xffff;
+        let base = (fixed >> 16) as usize * stride;
+        let next = if v == 0xffff { base } else { base + stride };
+        (base, next, (fixed & 0xffff) as i64)
+    }
+
+    /// Fixed-point tetrahedral interpolation in the 3D sub-table at `offset`
+    #[inline]
+    fn tetrahedral(&self, offset: usize, x: &[u16], out: &mut [u16]) {
+        let n = x.len();
+        let s = &self.strides[self.strides.len() - n..];
+        let (x0, x1, rx) = self.locate(x[0], s[0]);
+        let (y0, y1, ry) = self.locate(x[1], s[1]);
+        let (z0, z1, rz) = self.locate(x[2], s[2]);
+        let (dx, dy, dz) = (x1 - x0, y1 - y0, z1 - z0);
+
+        // Walk the cube diagonal along the axes in order of decreasing fraction
+        let ((r1, d1), (r2, d2), (r3, d3)) = if rx >= ry {
+            if ry >= rz {
+                ((rx, dx), (ry, dy), (rz, dz))
+            } else if rx >= rz {
+                ((rx, dx), (rz, dz), (ry, dy))
+            } else {
+                ((rz, dz), (rx, dx), (ry, dy))
+            }
+        } else if rx >= rz {
+            ((ry, dy), (rx, dx), (rz, dz))
+        } else if ry >= rz {
+            ((ry, dy), (rz, dz), (rx, dx))
+        } else {
+            ((rz, dz), (ry, dy), (rx, dx))
+        };
+
+        let c0 = offset + x0 + y0 + z0;
+        let c1 = c0 + d1;
+        let c2 = c1 + d2;
+        let c3 = c2 + d3;
+        let t = &self.table;
+        for (ch, o) in out.iter_mut().enumerate() {
+            let v0 = t[c0 + ch] as i64;
+            let v1 = t[c1 + ch] as i64;
+            let v2 = t[c2 + ch] as i64;
+            let v3 = t[c3 + ch] as i64;
+            let rest = (v1 - v0) * r1 + (v2 - v1) * r2 + (v3 - v2) * r3;
+            *o = (v0 + ((rest + 0x8000) >> 16)).clamp(0, 0xffff) as u16;
+        }
+    }
+}
+
+/// Linear interpolation in a 16-bit curve table
+#[inline]
+fn prelinearize(table: &[u16], v: u16) -> u16 {
+    let a = v as u32 * (table.len() - 1) as u32;
+    let i = (a / 0xffff) as usize;
+    let r = (a % 0xffff) as i64;
+    if r == 0 {
+        return table[i];
+    }
+    let (y0, y1) = (table[i] as i64, table[i + 1] as i64);
+    ((y0 * 0xffff + (y1 - y0) * r + 0x7fff) / 0xffff) as u16
+}
+
+fn to_u16(v: f64) -> u16 {
+    (v.clamp(0.0, 1.0) * 65535.0).round() as u16
+}
+
+/// Exact rounding of a 16-bit value to 8 bits
+#[inline]
+fn from_16_to_8(v: u16) -> u8 {
+    ((v as u32 * 65281 + 8388608) >> 24) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmyk_source() -> LutPipeline {
+        // Subtractive CMYK → RGB-like PCS with a curved K response
+        LutPipeline::from_clut(ClutData::sample(4, 9, 3, |p| {
+            let k = 1.0 - p[0].powf(1.5);
+            (1..4).map(|i| (1.0 - p[i]) * k).collect()
+        }))
+    }
+
+    #[test]
+    fn test_grid_points_for() {
+        assert_eq!(PrecalculatedLut::grid_points_for(3, false), 33);
+        assert_eq!(PrecalculatedLut::grid_points_for(4, false), 17);
+        assert_eq!(PrecalculatedLut::grid_points_for(3, true), 49);
+        assert_eq!(PrecalculatedLut::grid_points_for(4, true), 23);
+    }
+
+    #[test]
+    fn test_precalculated_matches_pipeline() {
+        let source = cmyk_source();
+        let destination = LutPipeline::identity(3, 3);
+        let lut = PrecalculatedLut::from_stages(&[&source, &destination], 17).unwrap();
+        assert_eq!((lut.input_channels(), lut.output_channels()), (4, 3));
+        assert!(!lut.is_prelinearized());
+
+        let mut worst = 0i32;
+        let mut out = [0u8; 3];
+        for c in (0..=255).step_by(15) {
+            for k in (0..=255).step_by(17) {
+                let src = [k as u8, c as u8, (255 - c) as u8, 128];
+                lut.transform8(&src, &mut out);
+                let x: Vec<f64> = src.iter().map(|&v| v as f64 / 255.0).collect();
+                let expected = source.transform(&x);
+                for (&o, e) in out.iter().zip(expected) {
+                    worst = worst.max((o as i32 - (e * 255.0).round() as i32).abs());
+                }
+            }
+        }
+        assert!(worst <= 1, "8-bit error {}", worst);
+
+        // Grid nodes and the extremes are exact
+        let mut out16 = [0u16; 3];
+        lut.transform16(&[0xffff, 0, 0, 0], &mut out16);
+        assert_eq!(out16, [0, 0, 0]);
+        lut.transform16(&[0, 0, 0, 0], &mut out16);
+        assert_eq!(out16, [0xffff; 3]);
+    }
+
+    #[test]
+    fn test_prelinearization() {
+        // A steep gamma in front of a small grid loses accuracy without prelinearization
+        let source = LutPipeline::identity(3, 3).with_input_curves(vec![LutCurve::Gamma(3.0); 3]);
+        let lut = PrecalculatedLut::from_stages(&[&source], 5).unwrap();
+        assert!(lut.is_prelinearized());
+
+        for x in [0.05, 0.2, 0.5, 0.8] {
+            let out = lut.eval(&[x, x, x]);
+            assert!((out[0] - x.powf(3.0)).abs() < 1e-3, "{} -> {:?}", x, out);
+        }
+    }
+
+    #[test]
+    fn test_matrix_disables_prelinearization() {
+        use crate::icc::S15Fixed16;
+        use crate::icc::tags::Lut16Data;
+
+        // The input curves follow the lut16 matrix, so they cannot be pulled
+        // ahead of the grid
+        let gamma: Vec<u16> = (0..256)
+            .map(|i| ((i as f64 / 255.0).powi(2) * 65535.0).round() as u16)
+            .collect();
+        let mut matrix = [[S15Fixed16::from_f64(0.0); 3]; 3];
+        matrix[0][1] = S15Fixed16::from_f64(1.0);
+        matrix[1][0] = S15Fixed16::from_f64(1.0);
+        matrix[2][2] = S15Fixed16::from_f64(1.0);
+        let source = LutPipeline::from_lut16(&Lut16Data {
+            input_channels: 3,
+            output_channels: 3,
+            grid_points: 2,
+            matrix,
+            input_entries: 256,
+            output_entries: 2,
+            input_curves: vec![gamma; 3],
+            clut: ClutData::sample(3, 2, 3, |p| p.to_vec())
+                .data
+                .iter()
+                .map(|&v| (v * 65535.0) as u16)
+                .collect(),
+            output_curves: vec![vec![0, 65535]; 3],
+        });
+        let lut = PrecalculatedLut::from_stages(&[&source], 33).unwrap();
+        assert!(!lut.is_prelinearized());
+
+        let x = [0.2, 0.6, 0.9];
+        let expected = source.transform(&x);
+        assert!((expected[0] - 0.36).abs() < 1e-3, "{:?}", expected);
+        for (got, want) in lut.eval(&x).iter().zip(&expected) {
+            assert!((got - want).abs() < 2e-3, "{} vs {}", got, want);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_shapes() {
+        let gray = LutPipeline::identity(1, 1);
+        assert!(PrecalculatedLut::from_stages(&[&gray], 17).is_err());
+        assert!(PrecalculatedLut::from_stages(&[], 17).is_err());
+        let rgb = LutPipeline::identity(3, 3);
+        assert!(PrecalculatedLut::from_stages(&[&rgb], 1).is_err());
+    }
+}
@@ -2,20 +2,25 @@
 //!
 //! Individual components of a color transform pipeline.
 
+use super::gamut::GamutMapper;
 use crate::icc::CurveData;
-use crate::math::Matrix3x3;
+use crate::math::{Matrix3x3, SampledCurve, TransferFunction};
 
 /// A pipeline stage
 #[derive(Debug, Clone)]
 pub enum PipelineStage {
-    /// TRC (Tone Reproduction Curve) stage
+    /// TRC (Tone Reproduction Curve) stage (encoded → linear)
     Trc(TrcStage),
+    /// Inverse TRC stage (linear → encoded)
+    TrcInverse(TrcStage),
     /// Matrix stage
     Matrix(MatrixStage),
     /// Chromatic adaptation stage
     ChromaticAdaptation(Matrix3x3),
     /// Clamp stage
     Clamp,
+    /// Gamut mapping of linear RGB into [0, 1]
+    GamutMap(GamutMapper),
 }
 
 impl PipelineStage {
@@ -23,6 +28,7 @@ impl PipelineStage {
     pub fn apply_rgb(&self, rgb: [f64; 3]) -> [f64; 3] {
         match self {
             PipelineStage::Trc(trc) => trc.apply(rgb),
+            PipelineStage::TrcInverse(trc) => trc.apply_inverse(rgb),
             PipelineStage::Matrix(mat) => mat.apply(rgb),
             PipelineStage::ChromaticAdaptation(mat) => mat.multiply_vec(rgb),
             PipelineStage::Clamp => [
@@ -30,6 +36,7 @@ impl PipelineStage {
                 rgb[1].clamp(0.0, 1.0),
                 rgb[2].clamp(0.0, 1.0),
             ],
+            PipelineStage::GamutMap(mapper) => mapper.map(rgb),
         }
     }
 
@@ -37,11 +44,12 @@ impl PipelineStage {
     pub fn apply_inverse_rgb(&self, rgb: [f64; 3]) -> [f64; 3] {
         match self {
             PipelineStage::Trc(trc) => trc.apply_inverse(rgb),
+            PipelineStage::TrcInverse(trc) => trc.apply(rgb),
             PipelineStage::Matrix(mat) => mat.apply_inverse(rgb),
             PipelineStage::ChromaticAdaptation(mat) => {
                 mat.inverse().map_or(rgb, |inv| inv.multiply_vec(rgb))
             }
-            PipelineStage::Clamp => rgb, // No inverse for clamp
+            PipelineStage::Clamp | PipelineStage::GamutMap(_) => rgb, // No inverse
         }
     }
 }
@@ -73,6 +81,15 @@ impl TrcStage {
         }
     }
 
+    /// Create with the same analytic transfer function on every channel
+    pub fn from_transfer(transfer: TransferFunction) -> Self {
+        Self {
+            red: TrcCurve::Transfer(transfer),
+            green: TrcCurve::Transfer(transfer),
+            blue: TrcCurve::Transfer(transfer),
+        }
+    }
+
     /// Apply TRCs to decode (encoded → linear)
     pub fn apply(&self, rgb: [f64; 3]) -> [f64; 3] {
         [
@@ -90,6 +107,22 @@ impl TrcStage {
             self.blue.encode(rgb[2]),
         ]
     }
+
+    /// Build the reversed tables of table curves, with `size` buckets, for encoding
+    pub fn with_reverse_size(self, size: usize) -> Self {
+        Self {
+            red: self.red.with_reverse_size(size),
+            green: self.green.with_reverse_size(size),
+            blue: self.blue.with_reverse_size(size),
+        }
+    }
+
+    /// Whether every channel is an identity curve within `tolerance`
+    pub fn is_identity(&self, tolerance: f64) -> bool {
+        self.red.is_identity(tolerance)
+            && self.green.is_identity(tolerance)
+            && self.blue.is_identity(tolerance)
+    }
 }
 
 /// A single TRC curve
@@ -99,8 +132,10 @@ pub enum TrcCurve {
     Identity,
     /// Simple gamma
     Gamma(f64),
-    /// Lookup table (normalized to f64)
-    Table(Vec<f64>),
+    /// Lookup table (normalized to f64), with its reversed table for encoding
+    Table(SampledCurve),
+    /// Analytic transfer function (from a `cicp` tag)
+    Transfer(TransferFunction),
 }
 
 impl Default for TrcCurve {
@@ -118,36 +153,39 @@ impl TrcCurve {
             CurveData::Table(table) => {
                 // Normalize to f64
                 let normalized: Vec<f64> = table.iter().map(|&v| v as f64 / 65535.0).collect();
-                TrcCurve::Table(normalized)
+                TrcCurve::Table(SampledCurve::new(normalized))
             }
         }
     }
 
+    /// Build the reversed table of a table curve, with `size` buckets, for encoding
+    pub fn with_reverse_size(self, size: usize) -> Self {
+        match self {
+            TrcCurve::Table(table) => {
+                TrcCurve::Table(table.with_reverse_size(size).with_reversed_table())
+            }
+            other => other,
+        }
+    }
+
+    /// Whether the curve maps every input to itself within `tolerance`
+    pub fn is_identity(&self, tolerance: f64) -> bool {
+        match self {
+            TrcCurve::Identity => true,
+            TrcCurve::Gamma(g) => (g - 1.0).abs() <= tolerance,
+            TrcCurve::Table(table) => table.is_identity(tolerance),
+            TrcCurve::Transfer(tf) => *tf == TransferFunction::Linear,
+        }
+    }
+
     /// Decode (apply forward curve: encoded → linear)
     pub fn decode(&self, x: f64) -> f64 {
         let x = x.clamp(0.0, 1.0);
         match self {
             TrcCurve::Identity => x,
             TrcCurve::Gamma(g) => x.powf(*g),
-            TrcCurve::Table(table) => {
-                if table.is_empty() {
-                    return x;
-                }
-                if table.len() == 1 {
-                    return table[0];
-                }
-
-                // Linear interpolation
-                let pos = x * (table.len() - 1) as f64;
-                let idx = pos.floor() as usize;
-                let frac = pos - idx as f64;
-
-                if idx >= table.len() - 1 {
-                    return table[table.len() - 1];
-                }
-
-                table[idx] + frac * (table[idx + 1] - table[idx])
-            }
+            TrcCurve::Table(table) => table.eval(x),
+            TrcCurve::Transfer(tf) => tf.decode(x),
         }
     }
 
@@ -163,40 +201,8 @@ impl TrcCurve {
                     y.powf(1.0 / *g)
                 }
             }
-            TrcCurve::Table(table) => {
-                // Binary search for inverse
-                if table.is_empty() || table.len() == 1 {
-                    return y;
-                }
-
-                // Find position where value would be
-                let mut lo = 0usize;
-                let mut hi = table.len() - 1;
-
-                while lo < hi {
-                    let mid = (lo + hi) / 2;
-                    if table[mid] < y {
-                        lo = mid + 1;
-                    } else {
-                        hi = mid;
-                    }
-                }
-
-                if lo == 0 {
-                    return 0.0;
-                }
-
-                // Interpolate
-                let v0 = table[lo - 1];
-                let v1 = table[lo];
-                let t = if (v1 - v0).abs() > 1e-10 {
-                    (y - v0) / (v1 - v0)
-                } else {
-                    0.0
-                };
-
-                ((lo - 1) as f64 + t) / (table.len() - 1) as f64
-            }
+            TrcCurve::Table(table) => table.eval_inverse(y),
+            TrcCurve::Transfer(tf) => tf.encode(y),
         }
     }
 }
@@ -219,6 +225,11 @@ impl MatrixStage {
         Self { matrix, inverse }
     }
 
+    /// Whether the forward matrix is the identity within `epsilon`
+    pub fn is_identity(&self, epsilon: f64) -> bool {
+        self.matrix.is_identity(epsilon)
+    }
+
     /// Apply forward matrix
     pub fn apply(&self, rgb: [f64; 3]) -> [f64; 3] {
         self.matrix.multiply_vec(rgb)
@@ -275,7 +286,7 @@ mod tests {
     fn test_trc_table() {
         // Simple linear table
         let table: Vec<f64> = (0..256).map(|i| i as f64 / 255.0).collect();
-        let trc = TrcCurve::Table(table);
+        let trc = TrcCurve::Table(table.into());
 
         // Should be identity
         assert!((trc.decode(0.5) - 0.5).abs() < 0.01);
@@ -311,4 +322,14 @@ mod tests {
         assert!((roundtrip[1] - rgb[1]).abs() < 1e-9);
         assert!((roundtrip[2] - rgb[2]).abs() < 1e-9);
     }
+
+    #[test]
+    fn test_trc_transfer() {
+        let trc = TrcCurve::Transfer(TransferFunction::Srgb);
+        for i in 0..=255 {
+            let x = i as f64 / 255.0;
+            assert!((trc.decode(x) - crate::math::srgb_gamma_decode(x)).abs() < 1e-12);
+            assert!((trc.encode(trc.decode(x)) - x).abs() < 1e-9);
+        }
+    }
 }
@@ -3,6 +3,9 @@
 //! This module provides ICC profile parsing and manipulation.
 //! It wraps moxcms::ColorProfile with additional validation.
 
+use crate::color::{WhitePoint, Xyz, white_point::D50};
+use crate::icc::{IccProfile, ParseOptions, ParseReport};
+use crate::math::{ChromaticAdaptationMethod, bradford_matrix, partial_adaptation_matrix};
 use crate::types::{
     ColorSpace, Matrix3x3, ProfileClass, ProfileVersion, RenderingIntent, XyzColor,
 };
@@ -27,6 +30,25 @@ impl ColorProfile {
         Ok(Self { inner })
     }
 
+    /// Create a profile from raw ICC data, with structural diagnostics
+    ///
+    /// The header and tag table are first checked by the native parser under
+    /// `options`; in strict mode any reported issue rejects the profile. Tag
+    /// data is decoded once, by [`ColorProfile::from_bytes`].
+    pub fn from_bytes_with_options(
+        data: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(Self, ParseReport)> {
+        let report = IccProfile::check_with_options(data, options)?;
+        Ok((Self::from_bytes(data)?, report))
+    }
+
+    /// Create a profile from a native ICC profile, e.g. one built with
+    /// [`RgbProfileBuilder`](crate::icc::RgbProfileBuilder)
+    pub fn from_icc(profile: &IccProfile) -> Result<Self> {
+        Self::from_bytes(&profile.to_bytes()?)
+    }
+
     /// Create a built-in sRGB profile
     pub fn new_srgb() -> Self {
         Self {
@@ -183,6 +205,77 @@ impl ColorProfile {
         self.inner.colorant_matrix().into()
     }
 
+    /// Re-adapt the colorants to the D50 PCS with another adaptation transform
+    ///
+    /// Matrix-shaper colorants are stored already adapted to D50, normally
+    /// with Bradford. The native white is recovered from `chad`, or from a
+    /// media white point other than D50; the stored adaptation is undone and
+    /// redone with `method` and degree of adaptation `degree`. Other
+    /// profiles, and ones without a recoverable native white, are returned
+    /// unchanged.
+    pub fn readapted(&self, method: ChromaticAdaptationMethod, degree: f64) -> Self {
+        let inner = &self.inner;
+        if inner.color_space != moxcms::DataColorSpace::Rgb || !inner.is_matrix_shaper() {
+            return self.clone();
+        }
+
+        let (native_white, applied) = match inner.chromatic_adaptation {
+            Some(chad) => {
+                let chad = crate::math::Matrix3x3::new(chad.v);
+                let Some(inverse) = chad.inverse() else {
+                    return self.clone();
+                };
+                (
+                    Xyz::from_array(inverse.multiply_vec(D50.xyz.to_array())),
+                    chad,
+                )
+            }
+            None => match inner.media_white_point {
+                Some(w) if !Xyz::new(w.x, w.y, w.z).approx_eq(&D50.xyz, 1e-4) => {
+                    let white = Xyz::new(w.x, w.y, w.z);
+                    (white, bradford_matrix(&WhitePoint::from_xyz(white), &D50))
+                }
+                _ => return self.clone(),
+            },
+        };
+        let Some(undo) = applied.inverse() else {
+            return self.clone();
+        };
+        let redo =
+            partial_adaptation_matrix(&WhitePoint::from_xyz(native_white), &D50, method, degree);
+        let colorants = redo
+            .multiply(&undo)
+            .multiply(&crate::math::Matrix3x3::new(inner.colorant_matrix().v));
+
+        let mut profile = inner.clone();
+        let column = |c: usize| moxcms::Xyzd {
+            x: colorants.m[0][c],
+            y: colorants.m[1][c],
+            z: colorants.m[2][c],
+        };
+        profile.red_colorant = column(0);
+        profile.green_colorant = column(1);
+        profile.blue_colorant = column(2);
+        profile.chromatic_adaptation = Some(moxcms::Matrix3d { v: redo.m });
+        Self { inner: profile }
+    }
+
+    /// Copy of an RGB matrix-shaper profile with linear tone curves
+    ///
+    /// Returns `None` for other profiles.
+    pub fn linearized(&self) -> Option<Self> {
+        if self.inner.color_space != moxcms::DataColorSpace::Rgb || !self.inner.is_matrix_shaper() {
+            return None;
+        }
+        let mut profile = self.inner.clone();
+        let linear = moxcms::ToneReprCurve::Parametric(vec![1.0]);
+        profile.red_trc = Some(linear.clone());
+        profile.green_trc = Some(linear.clone());
+        profile.blue_trc = Some(linear);
+        profile.cicp = None;
+        Some(Self { inner: profile })
+    }
+
     /// Get the white point
     pub fn white_point(&self) -> XyzColor {
         self.inner.white_point.into()
@@ -285,6 +378,39 @@ mod tests {
         assert!(ColorProfile::from_bytes(&small_data).is_err());
     }
 
+    #[test]
+    fn test_from_bytes_with_options() {
+        let mut data = ColorProfile::new_srgb().inner().encode().unwrap();
+        data.extend_from_slice(&[0u8; 4]);
+
+        let (profile, report) =
+            ColorProfile::from_bytes_with_options(&data, &ParseOptions::lenient()).unwrap();
+        assert!(profile.is_matrix_shaper());
+        assert!(report.has(crate::icc::ParseIssueKind::HeaderSizeMismatch));
+
+        assert!(ColorProfile::from_bytes_with_options(&data, &ParseOptions::strict()).is_err());
+    }
+
+    #[test]
+    fn test_from_icc_builder() {
+        let icc = crate::icc::RgbProfileBuilder::new()
+            .with_primaries((0.708, 0.292), (0.170, 0.797), (0.131, 0.046))
+            .build()
+            .unwrap();
+        let profile = ColorProfile::from_icc(&icc).unwrap();
+        assert!(profile.is_matrix_shaper());
+        assert_eq!(profile.description().as_deref(), Some("Custom RGB"));
+
+        // Same primaries and white as the built-in BT.2020 profile
+        let a = profile.colorant_matrix();
+        let b = ColorProfile::new_bt2020().colorant_matrix();
+        for (ra, rb) in a.m.iter().zip(b.m.iter()) {
+            for (va, vb) in ra.iter().zip(rb) {
+                assert!((va - vb).abs() < 2e-3, "{:?} vs {:?}", a, b);
+            }
+        }
+    }
+
     #[test]
     fn test_lab_profile() {
         let profile = ColorProfile::new_lab();
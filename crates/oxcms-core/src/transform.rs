@@ -3,6 +3,11 @@
 //! This module provides color space transformations between profiles.
 //! It wraps moxcms transforms with additional validation.
 
+use std::borrow::Cow;
+
+use crate::color::xyb::{Xyb, XybParams};
+use crate::math::ChromaticAdaptationMethod;
+use crate::pipeline::{RenderIntent, TransformContext};
 use crate::profile::ColorProfile;
 use crate::{Error, Result};
 
@@ -56,6 +61,12 @@ pub enum Layout {
     Gray,
     /// Grayscale + Alpha, 2 channels
     GrayAlpha,
+    /// Device channels with no alpha, for nCLR (multi-ink) profiles
+    ///
+    /// The count must match the profile's channel count. Transforms accept
+    /// 1, 3 and 5 to 15 channels; moxcms only reads 2 and 4 channel data
+    /// through its gray+alpha and RGBA layouts, so those counts are rejected.
+    Channels(u8),
 }
 
 impl Layout {
@@ -66,6 +77,7 @@ impl Layout {
             Layout::Rgba | Layout::Cmyk => 4,
             Layout::Gray => 1,
             Layout::GrayAlpha => 2,
+            Layout::Channels(n) => *n as usize,
         }
     }
 
@@ -83,8 +95,31 @@ impl Layout {
     pub fn is_cmyk(&self) -> bool {
         matches!(self, Layout::Cmyk)
     }
+
+    /// Check if this is a multi-ink layout
+    pub fn is_multi_ink(&self) -> bool {
+        matches!(self, Layout::Channels(_))
+    }
+
+    /// Fail for channel counts with no alpha-free moxcms layout
+    fn validate(&self) -> Result<()> {
+        match self {
+            Layout::Channels(n @ (2 | 4)) => Err(Error::InvalidLayout(format!(
+                "{} channels (moxcms treats the last of 2 or 4 channels as alpha)",
+                n
+            ))),
+            Layout::Channels(n) if !(1..=15).contains(n) => Err(Error::InvalidLayout(format!(
+                "{} channels (expected 1 to 15)",
+                n
+            ))),
+            _ => Ok(()),
+        }
+    }
 }
 
+/// `Layout::Channels` counts that [`Transform`] rejects have no alpha-free
+/// moxcms layout: 2 and 4 map to the alpha layouts of the same width, any
+/// other invalid count to `Rgba`.
 impl From<Layout> for moxcms::Layout {
     fn from(layout: Layout) -> Self {
         match layout {
@@ -93,6 +128,22 @@ impl From<Layout> for moxcms::Layout {
             Layout::Cmyk => moxcms::Layout::Cmyka, // 4-channel CMYK uses Cmyka in moxcms
             Layout::Gray => moxcms::Layout::Gray,
             Layout::GrayAlpha => moxcms::Layout::GrayAlpha,
+            // moxcms reuses the gray and RGB layouts for 1 and 3 channels
+            Layout::Channels(1) => moxcms::Layout::Gray,
+            Layout::Channels(3) => moxcms::Layout::Rgb,
+            Layout::Channels(5) => moxcms::Layout::Inks5,
+            Layout::Channels(6) => moxcms::Layout::Inks6,
+            Layout::Channels(7) => moxcms::Layout::Inks7,
+            Layout::Channels(8) => moxcms::Layout::Inks8,
+            Layout::Channels(9) => moxcms::Layout::Inks9,
+            Layout::Channels(10) => moxcms::Layout::Inks10,
+            Layout::Channels(11) => moxcms::Layout::Inks11,
+            Layout::Channels(12) => moxcms::Layout::Inks12,
+            Layout::Channels(13) => moxcms::Layout::Inks13,
+            Layout::Channels(14) => moxcms::Layout::Inks14,
+            Layout::Channels(15) => moxcms::Layout::Inks15,
+            Layout::Channels(2) => moxcms::Layout::GrayAlpha,
+            Layout::Channels(_) => moxcms::Layout::Rgba,
         }
     }
 }
@@ -105,13 +156,27 @@ impl From<moxcms::Layout> for Layout {
             moxcms::Layout::Cmyka => Layout::Cmyk,
             moxcms::Layout::Gray => Layout::Gray,
             moxcms::Layout::GrayAlpha => Layout::GrayAlpha,
-            _ => Layout::Rgba, // Multi-ink layouts map to RGBA
+            moxcms::Layout::Inks5 => Layout::Channels(5),
+            moxcms::Layout::Inks6 => Layout::Channels(6),
+            moxcms::Layout::Inks7 => Layout::Channels(7),
+            moxcms::Layout::Inks8 => Layout::Channels(8),
+            moxcms::Layout::Inks9 => Layout::Channels(9),
+            moxcms::Layout::Inks10 => Layout::Channels(10),
+            moxcms::Layout::Inks11 => Layout::Channels(11),
+            moxcms::Layout::Inks12 => Layout::Channels(12),
+            moxcms::Layout::Inks13 => Layout::Channels(13),
+            moxcms::Layout::Inks14 => Layout::Channels(14),
+            moxcms::Layout::Inks15 => Layout::Channels(15),
         }
     }
 }
 
 /// Options for transform creation
-#[derive(Debug, Clone, Copy, Default)]
+///
+/// Outside this crate, start from [`TransformOptions::default`] and use the
+/// `with_*` methods, so new options can be added without breaking callers.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
 pub struct TransformOptions {
     /// Rendering intent
     pub intent: RenderingIntent,
@@ -125,6 +190,93 @@ pub struct TransformOptions {
     pub allow_use_cicp_transfer: bool,
     /// Prefer fixed-point math for performance
     pub prefer_fixed_point: bool,
+    /// Chromatic adaptation transform used to adapt to the D50 PCS
+    ///
+    /// Anything other than Bradford with complete adaptation re-adapts the
+    /// colorants of matrix-shaper profiles (see [`ColorProfile::readapted`]).
+    pub adaptation_method: ChromaticAdaptationMethod,
+    /// Degree of adaptation D (1 = complete, 0 = none)
+    pub adaptation_degree: f64,
+}
+
+impl Default for TransformOptions {
+    fn default() -> Self {
+        Self {
+            intent: RenderingIntent::default(),
+            black_point_compensation: false,
+            allow_use_cicp_transfer: false,
+            prefer_fixed_point: false,
+            adaptation_method: ChromaticAdaptationMethod::Bradford,
+            adaptation_degree: 1.0,
+        }
+    }
+}
+
+impl TransformOptions {
+    /// Set rendering intent
+    pub fn with_intent(mut self, intent: RenderingIntent) -> Self {
+        self.intent = intent;
+        self
+    }
+
+    /// Enable or disable black point compensation
+    pub fn with_black_point_compensation(mut self, enabled: bool) -> Self {
+        self.black_point_compensation = enabled;
+        self
+    }
+
+    /// Use CICP transfer functions when available
+    pub fn with_cicp_transfer(mut self, enabled: bool) -> Self {
+        self.allow_use_cicp_transfer = enabled;
+        self
+    }
+
+    /// Prefer fixed-point math for performance
+    pub fn with_fixed_point(mut self, enabled: bool) -> Self {
+        self.prefer_fixed_point = enabled;
+        self
+    }
+
+    /// Set chromatic adaptation method
+    pub fn with_adaptation(mut self, method: ChromaticAdaptationMethod) -> Self {
+        self.adaptation_method = method;
+        self
+    }
+
+    /// Set the degree of adaptation D for incomplete adaptation
+    pub fn with_adaptation_degree(mut self, degree: f64) -> Self {
+        self.adaptation_degree = degree;
+        self
+    }
+
+    /// The profile as it should be linked under these options
+    fn adapted<'a>(&self, profile: &'a ColorProfile) -> Cow<'a, ColorProfile> {
+        if self.adaptation_method == ChromaticAdaptationMethod::Bradford
+            && self.adaptation_degree == 1.0
+        {
+            Cow::Borrowed(profile)
+        } else {
+            Cow::Owned(profile.readapted(self.adaptation_method, self.adaptation_degree))
+        }
+    }
+}
+
+impl From<TransformOptions> for TransformContext {
+    fn from(opts: TransformOptions) -> Self {
+        let intent = match opts.intent {
+            RenderingIntent::Perceptual => RenderIntent::Perceptual,
+            RenderingIntent::RelativeColorimetric => RenderIntent::RelativeColorimetric,
+            RenderingIntent::Saturation => RenderIntent::Saturation,
+            RenderingIntent::AbsoluteColorimetric => RenderIntent::AbsoluteColorimetric,
+        };
+        let mut ctx = TransformContext::new()
+            .with_intent(intent)
+            .with_cicp_transfer(opts.allow_use_cicp_transfer)
+            .with_adaptation(opts.adaptation_method)
+            .with_adaptation_degree(opts.adaptation_degree);
+        ctx.flags.black_point_compensation = opts.black_point_compensation;
+        ctx
+    }
 }
 
 impl From<TransformOptions> for moxcms::TransformOptions {
@@ -152,6 +304,10 @@ enum TransformInner {
     U8(std::sync::Arc<moxcms::Transform8BitExecutor>),
     U16(std::sync::Arc<moxcms::Transform16BitExecutor>),
     F32(std::sync::Arc<moxcms::TransformF32Executor>),
+    /// XYB decoded to linear RGB, then a float transform to the destination
+    FromXyb(XybParams, Box<Transform>),
+    /// Float transform to linear RGB, then XYB encoded
+    ToXyb(Box<Transform>, XybParams),
 }
 
 impl Transform {
@@ -163,6 +319,10 @@ impl Transform {
         dst_layout: Layout,
         options: TransformOptions,
     ) -> Result<Self> {
+        src_layout.validate()?;
+        dst_layout.validate()?;
+        let src_profile = options.adapted(src_profile);
+        let dst_profile = options.adapted(dst_profile);
         let inner = src_profile
             .inner()
             .create_transform_8bit(
@@ -188,6 +348,10 @@ impl Transform {
         dst_layout: Layout,
         options: TransformOptions,
     ) -> Result<Self> {
+        src_layout.validate()?;
+        dst_layout.validate()?;
+        let src_profile = options.adapted(src_profile);
+        let dst_profile = options.adapted(dst_profile);
         let inner = src_profile
             .inner()
             .create_transform_16bit(
@@ -213,6 +377,10 @@ impl Transform {
         dst_layout: Layout,
         options: TransformOptions,
     ) -> Result<Self> {
+        src_layout.validate()?;
+        dst_layout.validate()?;
+        let src_profile = options.adapted(src_profile);
+        let dst_profile = options.adapted(dst_profile);
         let inner = src_profile
             .inner()
             .create_transform_f32(
@@ -230,6 +398,56 @@ impl Transform {
         })
     }
 
+    /// Create a floating-point transform from JPEG XL XYB to a profile
+    ///
+    /// XYB is decoded straight into the linear primaries of an RGB
+    /// matrix-shaper destination, or into linear BT.2020 otherwise, so wide
+    /// gamut content is not clipped before it reaches the destination.
+    /// `src_layout` must be `Rgb` or `Rgba`.
+    pub fn new_from_xyb(
+        params: &XybParams,
+        src_layout: Layout,
+        dst_profile: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Self> {
+        validate_xyb_layout(src_layout)?;
+        // `new_f32` adapts both ends of the link, so the intermediate is
+        // built from the profile as given
+        let intermediate = xyb_intermediate(dst_profile);
+        let params = params.for_profile(&intermediate)?;
+        let link = Self::new_f32(&intermediate, src_layout, dst_profile, dst_layout, options)?;
+
+        Ok(Self {
+            inner: TransformInner::FromXyb(params, Box::new(link)),
+            src_layout,
+            dst_layout,
+        })
+    }
+
+    /// Create a floating-point transform from a profile to JPEG XL XYB
+    ///
+    /// The inverse of [`Transform::new_from_xyb`]; `dst_layout` must be `Rgb`
+    /// or `Rgba`.
+    pub fn new_to_xyb(
+        src_profile: &ColorProfile,
+        src_layout: Layout,
+        params: &XybParams,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Self> {
+        validate_xyb_layout(dst_layout)?;
+        let intermediate = xyb_intermediate(src_profile);
+        let params = params.for_profile(&intermediate)?;
+        let link = Self::new_f32(src_profile, src_layout, &intermediate, dst_layout, options)?;
+
+        Ok(Self {
+            inner: TransformInner::ToXyb(Box::new(link), params),
+            src_layout,
+            dst_layout,
+        })
+    }
+
     /// Get source layout
     pub fn src_layout(&self) -> Layout {
         self.src_layout
@@ -266,11 +484,74 @@ impl Transform {
             TransformInner::F32(t) => t
                 .transform(src, dst)
                 .map_err(|e| Error::Transform(format!("{:?}", e))),
+            TransformInner::FromXyb(params, link) => {
+                let src_channels = self.src_layout.channels();
+                let dst_channels = self.dst_layout.channels();
+                let pixels = src.len() / src_channels;
+                if src.len() % src_channels != 0 || dst.len() != pixels * dst_channels {
+                    return Err(Error::BufferSize {
+                        expected: pixels * dst_channels,
+                        actual: dst.len(),
+                    });
+                }
+
+                // Decode a stack-sized run of pixels at a time
+                let mut buffer = [0f32; XYB_CHUNK_PIXELS * 4];
+                for (src, dst) in src
+                    .chunks(XYB_CHUNK_PIXELS * src_channels)
+                    .zip(dst.chunks_mut(XYB_CHUNK_PIXELS * dst_channels))
+                {
+                    let linear = &mut buffer[..src.len()];
+                    linear.copy_from_slice(src);
+                    for px in linear.chunks_exact_mut(src_channels) {
+                        let xyb = Xyb::new(px[0] as f64, px[1] as f64, px[2] as f64);
+                        let rgb = params.xyb_to_linear_rgb(&xyb);
+                        for (v, c) in px.iter_mut().zip(rgb) {
+                            *v = c as f32;
+                        }
+                    }
+                    link.transform_f32(linear, dst)?;
+                }
+                Ok(())
+            }
+            TransformInner::ToXyb(link, params) => {
+                link.transform_f32(src, dst)?;
+                for px in dst.chunks_exact_mut(self.dst_layout.channels()) {
+                    let rgb = [px[0] as f64, px[1] as f64, px[2] as f64];
+                    let xyb = params.linear_rgb_to_xyb(rgb);
+                    for (v, c) in px.iter_mut().zip(xyb.to_array()) {
+                        *v = c as f32;
+                    }
+                }
+                Ok(())
+            }
             _ => Err(Error::Transform("Wrong bit depth for transform".into())),
         }
     }
 }
 
+/// XYB is carried as three channels, optionally with alpha
+fn validate_xyb_layout(layout: Layout) -> Result<()> {
+    match layout {
+        Layout::Rgb | Layout::Rgba => Ok(()),
+        other => Err(Error::InvalidLayout(format!(
+            "{:?} cannot carry XYB (expected Rgb or Rgba)",
+            other
+        ))),
+    }
+}
+
+/// Pixels decoded from XYB per call into the linking transform
+const XYB_CHUNK_PIXELS: usize = 256;
+
+/// Linear RGB space XYB is decoded into before linking with `profile`
+fn xyb_intermediate(profile: &ColorProfile) -> ColorProfile {
+    profile
+        .linearized()
+        .or_else(|| ColorProfile::new_bt2020().linearized())
+        .expect("BT.2020 is a matrix-shaper profile")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -283,6 +564,115 @@ mod tests {
         assert_eq!(Layout::GrayAlpha.channels(), 2);
     }
 
+    /// 7-ink Lab-PCS output profile where lightness falls with mean ink
+    fn seven_ink_profile() -> ColorProfile {
+        use crate::icc::tags::{CurveSegment, LutAToBData, LutBToAData, XyzTagData};
+        use crate::icc::{
+            ColorSpace as IccColorSpace, IccHeader, IccProfile, ProfileClass as IccClass, TagData,
+            TagSignature, XyzNumber,
+        };
+        use crate::pipeline::ClutData;
+
+        let neutral = 128.0 / 255.0;
+        let a2b = ClutData::sample(7, 2, 3, |p| {
+            vec![1.0 - p.iter().sum::<f64>() / 7.0, neutral, neutral]
+        });
+        let b2a = ClutData::sample(3, 2, 7, |p| vec![1.0 - p[0]; 7]);
+
+        let header = IccHeader::new(IccClass::Output, IccColorSpace::Color7, IccColorSpace::Lab);
+        let mut profile = IccProfile::new(header);
+        profile.set_tag(
+            TagSignature::MEDIA_WHITE,
+            TagData::Xyz(XyzTagData {
+                values: vec![XyzNumber::D50],
+            }),
+        );
+        profile.set_tag(
+            TagSignature::A2B0,
+            TagData::LutAToB(LutAToBData {
+                input_channels: 7,
+                output_channels: 3,
+                b_curves: Some(vec![CurveSegment::Identity; 3]),
+                matrix: None,
+                m_curves: None,
+                clut: Some(a2b.to_lut_clut()),
+                a_curves: Some(vec![CurveSegment::Identity; 7]),
+            }),
+        );
+        profile.set_tag(
+            TagSignature::B2A0,
+            TagData::LutBToA(LutBToAData {
+                input_channels: 3,
+                output_channels: 7,
+                b_curves: Some(vec![CurveSegment::Identity; 3]),
+                matrix: None,
+                m_curves: None,
+                clut: Some(b2a.to_lut_clut()),
+                a_curves: Some(vec![CurveSegment::Identity; 7]),
+            }),
+        );
+        ColorProfile::from_bytes(&profile.to_bytes().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_multi_ink_layout() {
+        assert_eq!(Layout::Channels(7).channels(), 7);
+        assert!(Layout::Channels(7).is_multi_ink());
+        assert!(!Layout::Channels(7).has_alpha());
+        assert_eq!(
+            Layout::from(moxcms::Layout::from(Layout::Channels(7))),
+            Layout::Channels(7)
+        );
+
+        let srgb = ColorProfile::new_srgb();
+        let opts = TransformOptions::default();
+        for n in [0, 2, 4, 16] {
+            let ink = Layout::Channels(n);
+            // The conversion itself is total; the constructors reject the count
+            let _ = moxcms::Layout::from(ink);
+            assert!(matches!(
+                Transform::new_8bit(&srgb, Layout::Rgb, &srgb, ink, opts),
+                Err(Error::InvalidLayout(_))
+            ));
+            assert!(matches!(
+                Transform::new_16bit(&srgb, ink, &srgb, Layout::Rgb, opts),
+                Err(Error::InvalidLayout(_))
+            ));
+            assert!(matches!(
+                Transform::new_f32(&srgb, Layout::Rgb, &srgb, ink, opts),
+                Err(Error::InvalidLayout(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_multi_ink_transforms() {
+        let inks = seven_ink_profile();
+        let srgb = ColorProfile::new_srgb();
+        let opts = TransformOptions::default();
+        let ink7 = Layout::Channels(7);
+
+        // No ink is paper white, full ink is black
+        let t = Transform::new_8bit(&inks, ink7, &srgb, Layout::Rgb, opts).unwrap();
+        let src = [[0u8; 7], [255u8; 7]].concat();
+        let mut dst = [0u8; 6];
+        t.transform(&src, &mut dst).unwrap();
+        assert!(dst[..3].iter().all(|&v| v >= 250), "{:?}", dst);
+        assert!(dst[3..].iter().all(|&v| v <= 5), "{:?}", dst);
+
+        let t = Transform::new_16bit(&inks, ink7, &srgb, Layout::Rgb, opts).unwrap();
+        let mut dst16 = [0u16; 3];
+        t.transform_u16(&[0u16; 7], &mut dst16).unwrap();
+        assert!(dst16.iter().all(|&v| v >= 64000), "{:?}", dst16);
+
+        // Grey separates into equal amounts of every ink
+        let t = Transform::new_f32(&srgb, Layout::Rgb, &inks, ink7, opts).unwrap();
+        let mut out = [0f32; 7];
+        t.transform_f32(&[0.5, 0.5, 0.5], &mut out).unwrap();
+        assert!(out.iter().all(|&v| (v - out[0]).abs() < 0.01), "{:?}", out);
+        assert!(out[0] > 0.2 && out[0] < 0.8, "{:?}", out);
+    }
+
     #[test]
     fn test_transform_identity() {
         let profile = ColorProfile::new_srgb();
@@ -329,4 +719,158 @@ mod tests {
         assert!(dst[1] > 0); // Will have some green
         assert!(dst[2] > 0); // Will have some blue
     }
+
+    #[test]
+    fn test_adaptation_options() {
+        use crate::color::white_point::D50;
+        use crate::icc::RgbProfileBuilder;
+
+        let srgb = ColorProfile::new_srgb();
+        let d50_rgb = ColorProfile::from_icc(
+            &RgbProfileBuilder::new()
+                .with_white_point(&D50)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let run = |options: TransformOptions, src: [f32; 3]| {
+            let transform =
+                Transform::new_f32(&srgb, Layout::Rgb, &d50_rgb, Layout::Rgb, options).unwrap();
+            let mut dst = [0f32; 3];
+            transform.transform_f32(&src, &mut dst).unwrap();
+            dst
+        };
+        let with = |method, degree| {
+            TransformOptions::default()
+                .with_adaptation(method)
+                .with_adaptation_degree(degree)
+        };
+
+        // Complete adaptation keeps white neutral whatever the method
+        for method in [
+            ChromaticAdaptationMethod::Cat02,
+            ChromaticAdaptationMethod::Cat16,
+            ChromaticAdaptationMethod::Sharp,
+            ChromaticAdaptationMethod::Cmccat2000,
+            ChromaticAdaptationMethod::BiancoSchettini,
+            ChromaticAdaptationMethod::XyzScaling,
+        ] {
+            let white = run(with(method, 1.0), [1.0, 1.0, 1.0]);
+            for c in white {
+                assert!((c - 1.0).abs() < 2e-3, "{:?}: {:?}", method, white);
+            }
+        }
+
+        // Methods disagree away from white
+        let bradford = run(TransformOptions::default(), [0.2, 0.6, 0.3]);
+        let scaling = run(
+            with(ChromaticAdaptationMethod::XyzScaling, 1.0),
+            [0.2, 0.6, 0.3],
+        );
+        assert!(
+            (bradford[0] - scaling[0]).abs() > 1e-3,
+            "{:?} {:?}",
+            bradford,
+            scaling
+        );
+
+        // Without adaptation the D65 white looks blue under D50
+        let white = run(
+            with(ChromaticAdaptationMethod::Bradford, 0.0),
+            [1.0, 1.0, 1.0],
+        );
+        assert!(white[2] > white[0], "{:?}", white);
+
+        let ctx = TransformContext::from(with(ChromaticAdaptationMethod::Cat16, 0.7));
+        assert_eq!(ctx.adaptation_method, ChromaticAdaptationMethod::Cat16);
+        assert_eq!(ctx.adaptation_degree, 0.7);
+    }
+
+    #[test]
+    fn test_xyb_transforms() {
+        use crate::color::xyb::{srgb_to_xyb, xyb_to_srgb};
+
+        let srgb = ColorProfile::new_srgb();
+        let params = XybParams::default();
+        let colors = [
+            (255u8, 0u8, 0u8),
+            (30, 200, 90),
+            (128, 128, 128),
+            (0, 0, 255),
+        ];
+
+        let xyb: Vec<f32> = colors
+            .iter()
+            .flat_map(|&(r, g, b)| srgb_to_xyb(r, g, b).to_array().map(|v| v as f32))
+            .collect();
+        let decode = Transform::new_from_xyb(
+            &params,
+            Layout::Rgb,
+            &srgb,
+            Layout::Rgb,
+            TransformOptions::default(),
+        )
+        .unwrap();
+        let mut rgb = vec![0.0f32; xyb.len()];
+        decode.transform_f32(&xyb, &mut rgb).unwrap();
+        for (px, &(r, g, b)) in rgb.chunks_exact(3).zip(&colors) {
+            let expected = xyb_to_srgb(&srgb_to_xyb(r, g, b));
+            let got = px
+                .iter()
+                .map(|v| (v * 255.0).round() as u8)
+                .collect::<Vec<_>>();
+            assert_eq!(got, [expected.0, expected.1, expected.2]);
+        }
+
+        let encode = Transform::new_to_xyb(
+            &srgb,
+            Layout::Rgb,
+            &params,
+            Layout::Rgb,
+            TransformOptions::default(),
+        )
+        .unwrap();
+        let mut back = vec![0.0f32; xyb.len()];
+        encode.transform_f32(&rgb, &mut back).unwrap();
+        for (a, b) in back.iter().zip(&xyb) {
+            assert!((a - b).abs() < 2e-3, "{} vs {}", a, b);
+        }
+
+        // Adapting to the PCS another way leaves the XYB link unchanged
+        let cat16 = Transform::new_from_xyb(
+            &params,
+            Layout::Rgb,
+            &srgb,
+            Layout::Rgb,
+            TransformOptions::default().with_adaptation(ChromaticAdaptationMethod::Cat16),
+        )
+        .unwrap();
+        let mut adapted = vec![0.0f32; xyb.len()];
+        cat16.transform_f32(&xyb, &mut adapted).unwrap();
+        for (a, b) in adapted.iter().zip(&rgb) {
+            assert!((a - b).abs() < 1e-3, "{} vs {}", a, b);
+        }
+
+        // Buffers longer than one decode chunk, and a mismatched one
+        let long: Vec<f32> = xyb.iter().copied().cycle().take(xyb.len() * 100).collect();
+        let mut out = vec![0.0f32; long.len()];
+        decode.transform_f32(&long, &mut out).unwrap();
+        assert_eq!(&out[out.len() - rgb.len()..], &rgb[..]);
+        assert!(matches!(
+            decode.transform_f32(&long, &mut out[1..]),
+            Err(Error::BufferSize { .. })
+        ));
+
+        assert!(matches!(
+            Transform::new_from_xyb(
+                &params,
+                Layout::Gray,
+                &srgb,
+                Layout::Rgb,
+                TransformOptions::default()
+            ),
+            Err(Error::InvalidLayout(_))
+        ));
+    }
 }